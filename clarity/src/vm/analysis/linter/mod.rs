@@ -0,0 +1,632 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::vm::analysis::types::ContractAnalysis;
+use crate::vm::diagnostic::{DiagnosableError, Diagnostic, Level};
+use crate::vm::functions::define::DefineFunctionsParsed;
+use crate::vm::functions::NativeFunctions;
+use crate::vm::representations::SymbolicExpressionType::{Atom, AtomValue, List, LiteralValue};
+use crate::vm::representations::{ClarityName, SymbolicExpression};
+use crate::vm::variables::NativeVariables;
+use crate::vm::{is_reserved, ClarityVersion};
+
+#[cfg(test)]
+mod tests;
+
+define_named_enum!(LintRule {
+    UncheckedUnwrapPanic("unchecked-unwrap-panic"),
+    TxSenderInCallableFunction("tx-sender-in-callable-function"),
+    UnusedPrivateFunction("unused-private-function"),
+    UnusedConstant("unused-constant"),
+    UnboundedAsContractTransfer("unbounded-as-contract-transfer"),
+    Shadowing("shadowing"),
+});
+
+/// What to do when a lint rule matches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintLevel {
+    /// Ignore the rule
+    Allow,
+    /// Report a warning
+    Warn,
+    /// Report an error
+    Deny,
+}
+
+/// The level of each lint rule. Rules which are not configured are reported as warnings.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LintConfig {
+    levels: BTreeMap<String, LintLevel>,
+}
+
+impl LintConfig {
+    /// Parse a JSON object which maps rule names to levels, like
+    /// `{ "unused-constant": "allow", "unchecked-unwrap-panic": "deny" }`
+    pub fn from_json(json: &str) -> Result<LintConfig, String> {
+        let levels: BTreeMap<String, LintLevel> =
+            serde_json::from_str(json).map_err(|e| format!("invalid lint config: {}", e))?;
+        let mut config = LintConfig::default();
+        for (rule_name, level) in levels.into_iter() {
+            let rule = LintRule::lookup_by_name(&rule_name)
+                .ok_or_else(|| format!("unknown lint rule '{}'", rule_name))?;
+            config.set_level(rule, level);
+        }
+        Ok(config)
+    }
+
+    pub fn set_level(&mut self, rule: LintRule, level: LintLevel) {
+        self.levels.insert(rule.get_name(), level);
+    }
+
+    pub fn level(&self, rule: LintRule) -> LintLevel {
+        self.levels
+            .get(rule.get_name_str())
+            .copied()
+            .unwrap_or(LintLevel::Warn)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum LintError {
+    UncheckedUnwrapPanic(String),
+    TxSenderInCallableFunction(String),
+    UnusedPrivateFunction(String),
+    UnusedConstant(String),
+    UnboundedAsContractTransfer(String),
+    ShadowsBuiltIn(String),
+    ShadowsDefinition(String),
+    ShadowsBinding(String),
+}
+
+impl LintError {
+    pub fn rule(&self) -> LintRule {
+        match self {
+            LintError::UncheckedUnwrapPanic(_) => LintRule::UncheckedUnwrapPanic,
+            LintError::TxSenderInCallableFunction(_) => LintRule::TxSenderInCallableFunction,
+            LintError::UnusedPrivateFunction(_) => LintRule::UnusedPrivateFunction,
+            LintError::UnusedConstant(_) => LintRule::UnusedConstant,
+            LintError::UnboundedAsContractTransfer(_) => LintRule::UnboundedAsContractTransfer,
+            LintError::ShadowsBuiltIn(_)
+            | LintError::ShadowsDefinition(_)
+            | LintError::ShadowsBinding(_) => LintRule::Shadowing,
+        }
+    }
+}
+
+impl DiagnosableError for LintError {
+    fn message(&self) -> String {
+        let message = match self {
+            LintError::UncheckedUnwrapPanic(function) => format!(
+                "'{}' aborts the transaction when given a value which was not checked first",
+                function
+            ),
+            LintError::TxSenderInCallableFunction(function) => format!(
+                "'tx-sender' is used in '{}', which other contracts can call with 'contract-call?'",
+                function
+            ),
+            LintError::UnusedPrivateFunction(name) => {
+                format!("private function '{}' is never called", name)
+            }
+            LintError::UnusedConstant(name) => format!("constant '{}' is never used", name),
+            LintError::UnboundedAsContractTransfer(function) => format!(
+                "'{}' within 'as-contract' moves an amount of the contract's own assets which is not bounded",
+                function
+            ),
+            LintError::ShadowsBuiltIn(name) => {
+                format!("'{}' shadows a built-in function or keyword", name)
+            }
+            LintError::ShadowsDefinition(name) => {
+                format!("'{}' shadows a contract-level definition", name)
+            }
+            LintError::ShadowsBinding(name) => format!("'{}' shadows an enclosing binding", name),
+        };
+        format!("{} [{}]", message, self.rule())
+    }
+
+    fn suggestion(&self) -> Option<String> {
+        match self {
+            LintError::UncheckedUnwrapPanic(_) => Some(
+                "use 'unwrap!' with an error code, or check the value with 'is-some' or 'is-ok' first"
+                    .into(),
+            ),
+            LintError::TxSenderInCallableFunction(_) => Some(
+                "'tx-sender' is the origin of the transaction, not the caller: use 'contract-caller' to authorize the caller"
+                    .into(),
+            ),
+            LintError::UnboundedAsContractTransfer(_) => Some(
+                "assert an upper bound on the amount, e.g. (asserts! (<= amount max-amount) (err u1))"
+                    .into(),
+            ),
+            _ => None,
+        }
+    }
+
+    fn level(&self) -> Level {
+        Level::Warning
+    }
+}
+
+/// Something known to hold while evaluating an expression, because of an enclosing `if` or a
+/// preceding `asserts!`. Expressions are identified by their printed form.
+#[derive(Debug, PartialEq, Eq, Clone)]
+enum Fact {
+    IsSome(String),
+    IsOk(String),
+    IsErr(String),
+    /// The expression is less than (or equal to) some other value
+    Bounded(String),
+}
+
+#[derive(Default)]
+struct Scope {
+    /// Names bound by the enclosing function signature, `let`s and `match`es
+    bindings: Vec<ClarityName>,
+    facts: Vec<Fact>,
+    in_as_contract: bool,
+    /// The function being checked, if it can be reached through `contract-call?`
+    callable_function: Option<String>,
+}
+
+///
+/// A static-analysis pass which warns about risky but valid code: values unwrapped without a
+/// check, authorization with `tx-sender`, unused private functions and constants, transfers of
+/// unbounded amounts out of the contract, and names which shadow other names.
+///
+/// Unlike the other passes, the linter never rejects a contract. Each rule reports
+/// `Diagnostic`s at the level configured in its `LintConfig`.
+///
+pub struct Linter<'a> {
+    config: &'a LintConfig,
+    clarity_version: ClarityVersion,
+    /// Names of all contract-level definitions
+    definitions: BTreeSet<ClarityName>,
+    constants: BTreeSet<ClarityName>,
+    /// Names referenced anywhere, outside of the positions which bind them
+    references: BTreeSet<ClarityName>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> Linter<'a> {
+    pub fn run(contract_analysis: &ContractAnalysis, config: &LintConfig) -> Vec<Diagnostic> {
+        let mut linter = Linter {
+            config,
+            clarity_version: contract_analysis.clarity_version,
+            definitions: BTreeSet::new(),
+            constants: BTreeSet::new(),
+            references: BTreeSet::new(),
+            diagnostics: vec![],
+        };
+
+        // malformed definitions are left to the other passes, and linted as plain expressions
+        let top_levels: Vec<_> = contract_analysis
+            .expressions
+            .iter()
+            .map(|expr| (expr, DefineFunctionsParsed::try_parse(expr).ok().flatten()))
+            .collect();
+
+        let mut private_functions = BTreeMap::new();
+        let mut callable_roots = vec![];
+        let mut calls = BTreeMap::new();
+        for (expr, define) in top_levels.iter() {
+            let Some(define) = define else {
+                continue;
+            };
+            let name = match define {
+                DefineFunctionsParsed::PrivateFunction { signature, body }
+                | DefineFunctionsParsed::ReadOnlyFunction { signature, body }
+                | DefineFunctionsParsed::PublicFunction { signature, body } => {
+                    let Some(name) = signature.first().and_then(|name| name.match_atom()) else {
+                        continue;
+                    };
+                    if matches!(define, DefineFunctionsParsed::PrivateFunction { .. }) {
+                        private_functions.insert(name.clone(), *expr);
+                    } else {
+                        callable_roots.push(name.clone());
+                    }
+                    let mut called = BTreeSet::new();
+                    collect_atoms(body, &mut called);
+                    calls.insert(name.clone(), called);
+                    name
+                }
+                DefineFunctionsParsed::Constant { name, .. } => {
+                    linter.constants.insert((*name).clone());
+                    *name
+                }
+                DefineFunctionsParsed::NonFungibleToken { name, .. }
+                | DefineFunctionsParsed::BoundedFungibleToken { name, .. }
+                | DefineFunctionsParsed::UnboundedFungibleToken { name }
+                | DefineFunctionsParsed::Map { name, .. }
+                | DefineFunctionsParsed::PersistedVariable { name, .. }
+                | DefineFunctionsParsed::Trait { name, .. }
+                | DefineFunctionsParsed::UseTrait { name, .. } => *name,
+                DefineFunctionsParsed::ImplTrait { .. } => continue,
+            };
+            if is_reserved(name, &ClarityVersion::latest()) {
+                linter.report(LintError::ShadowsBuiltIn(name.to_string()), expr);
+            }
+            linter.definitions.insert(name.clone());
+        }
+
+        // private functions called, directly or not, by public and read-only functions
+        let mut callable = BTreeSet::new();
+        while let Some(name) = callable_roots.pop() {
+            if !callable.insert(name.clone()) {
+                continue;
+            }
+            if let Some(called) = calls.get(&name) {
+                callable_roots.extend(
+                    called
+                        .iter()
+                        .filter(|called| private_functions.contains_key(*called))
+                        .cloned(),
+                );
+            }
+        }
+
+        for (expr, define) in top_levels.iter() {
+            let mut scope = Scope::default();
+            match define {
+                Some(DefineFunctionsParsed::PrivateFunction { signature, body })
+                | Some(DefineFunctionsParsed::ReadOnlyFunction { signature, body })
+                | Some(DefineFunctionsParsed::PublicFunction { signature, body }) => {
+                    if let Some(name) = signature.first().and_then(|name| name.match_atom()) {
+                        if callable.contains(name) {
+                            scope.callable_function = Some(name.to_string());
+                        }
+                    }
+                    for arg in signature.iter().skip(1) {
+                        if let Some(arg_name) = arg
+                            .match_list()
+                            .and_then(|arg| arg.first())
+                            .and_then(|arg_name| arg_name.match_atom())
+                        {
+                            linter.check_binding(arg_name, arg, &mut scope);
+                        }
+                    }
+                    linter.check_expression(body, &mut scope);
+                }
+                Some(DefineFunctionsParsed::Constant { value, .. }) => {
+                    linter.check_expression(value, &mut scope);
+                }
+                Some(DefineFunctionsParsed::PersistedVariable { initial, .. }) => {
+                    linter.check_expression(initial, &mut scope);
+                }
+                Some(DefineFunctionsParsed::BoundedFungibleToken { max_supply, .. }) => {
+                    linter.check_expression(max_supply, &mut scope);
+                }
+                Some(_) => {}
+                None => {
+                    linter.check_expression(expr, &mut scope);
+                }
+            }
+        }
+
+        for (name, expr) in private_functions.iter() {
+            if !linter.references.contains(name) {
+                linter.report(LintError::UnusedPrivateFunction(name.to_string()), expr);
+            }
+        }
+        for (expr, define) in top_levels.iter() {
+            if let Some(DefineFunctionsParsed::Constant { name, .. }) = define {
+                if !linter.references.contains(*name) {
+                    linter.report(LintError::UnusedConstant(name.to_string()), expr);
+                }
+            }
+        }
+
+        linter.diagnostics
+    }
+
+    fn report(&mut self, error: LintError, expr: &SymbolicExpression) {
+        let level = match self.config.level(error.rule()) {
+            LintLevel::Allow => return,
+            LintLevel::Warn => Level::Warning,
+            LintLevel::Deny => Level::Error,
+        };
+        let mut diagnostic = Diagnostic::err(&error);
+        diagnostic.level = level;
+        diagnostic.spans = vec![expr.span().clone()];
+        self.diagnostics.push(diagnostic);
+    }
+
+    fn native_function(&self, expr: &SymbolicExpression) -> Option<NativeFunctions> {
+        expr.match_atom().and_then(|name| {
+            NativeFunctions::lookup_by_name_at_version(name, &self.clarity_version)
+        })
+    }
+
+    fn is_tx_sender(&self, expr: &SymbolicExpression) -> bool {
+        expr.match_atom().and_then(|name| {
+            NativeVariables::lookup_by_name_at_version(name, &self.clarity_version)
+        }) == Some(NativeVariables::TxSender)
+    }
+
+    /// Check that a new local binding does not shadow another name, and add it to `scope`
+    fn check_binding(&mut self, name: &ClarityName, expr: &SymbolicExpression, scope: &mut Scope) {
+        if is_reserved(name, &ClarityVersion::latest()) {
+            self.report(LintError::ShadowsBuiltIn(name.to_string()), expr);
+        } else if self.definitions.contains(name) {
+            self.report(LintError::ShadowsDefinition(name.to_string()), expr);
+        } else if scope.bindings.contains(name) {
+            self.report(LintError::ShadowsBinding(name.to_string()), expr);
+        }
+        scope.bindings.push(name.clone());
+    }
+
+    /// Check an expression, returning the facts which hold after it is evaluated
+    fn check_expression(&mut self, expr: &SymbolicExpression, scope: &mut Scope) -> Vec<Fact> {
+        match expr.expr {
+            Atom(ref name) => {
+                self.references.insert(name.clone());
+                if self.is_tx_sender(expr) && !scope.in_as_contract {
+                    if let Some(function) = scope.callable_function.clone() {
+                        self.report(LintError::TxSenderInCallableFunction(function), expr);
+                    }
+                }
+                vec![]
+            }
+            List(ref list) => self.check_application(expr, list, scope),
+            _ => vec![],
+        }
+    }
+
+    fn check_all(&mut self, exprs: &[SymbolicExpression], scope: &mut Scope) {
+        for expr in exprs.iter() {
+            self.check_expression(expr, scope);
+        }
+    }
+
+    /// Check expressions which are evaluated in order, so that facts established by an
+    /// `asserts!` hold for the expressions which follow it
+    fn check_sequence(&mut self, exprs: &[SymbolicExpression], scope: &mut Scope) {
+        let facts_len = scope.facts.len();
+        for expr in exprs.iter() {
+            let facts = self.check_expression(expr, scope);
+            scope.facts.extend(facts);
+        }
+        scope.facts.truncate(facts_len);
+    }
+
+    fn check_with_facts(&mut self, expr: &SymbolicExpression, facts: Vec<Fact>, scope: &mut Scope) {
+        let facts_len = scope.facts.len();
+        scope.facts.extend(facts);
+        self.check_expression(expr, scope);
+        scope.facts.truncate(facts_len);
+    }
+
+    fn check_application(
+        &mut self,
+        expr: &SymbolicExpression,
+        list: &[SymbolicExpression],
+        scope: &mut Scope,
+    ) -> Vec<Fact> {
+        use crate::vm::functions::NativeFunctions::*;
+
+        let Some((function, args)) = list.split_first() else {
+            return vec![];
+        };
+        let Some(native) = self.native_function(function) else {
+            // user-defined functions and malformed applications
+            self.check_all(list, scope);
+            return vec![];
+        };
+        match native {
+            Let => self.check_let(args, scope),
+            Match => self.check_match(args, scope),
+            TupleCons => {
+                for pair in args.iter() {
+                    match pair.match_list() {
+                        Some([_key, value]) => {
+                            self.check_expression(value, scope);
+                        }
+                        _ => {
+                            self.check_expression(pair, scope);
+                        }
+                    }
+                }
+            }
+            TupleGet => self.check_all(args.get(1..).unwrap_or_default(), scope),
+            If => match args {
+                [condition, then_branch, else_branch] => {
+                    self.check_expression(condition, scope);
+                    let then_facts = self.condition_facts(condition, true);
+                    self.check_with_facts(then_branch, then_facts, scope);
+                    let else_facts = self.condition_facts(condition, false);
+                    self.check_with_facts(else_branch, else_facts, scope);
+                }
+                _ => self.check_all(args, scope),
+            },
+            Begin => self.check_sequence(args, scope),
+            Asserts => {
+                self.check_all(args, scope);
+                return args
+                    .first()
+                    .map(|condition| self.condition_facts(condition, true))
+                    .unwrap_or_default();
+            }
+            Unwrap | UnwrapErr => {
+                if let Some(value) = args.first() {
+                    let key = value.to_string();
+                    let checked = scope.facts.iter().any(|fact| match (native, fact) {
+                        (Unwrap, Fact::IsSome(k)) | (Unwrap, Fact::IsOk(k)) => k == &key,
+                        (UnwrapErr, Fact::IsErr(k)) => k == &key,
+                        _ => false,
+                    });
+                    if !checked {
+                        self.report(LintError::UncheckedUnwrapPanic(native.get_name()), expr);
+                    }
+                }
+                self.check_all(args, scope);
+            }
+            AsContract => {
+                let in_as_contract = scope.in_as_contract;
+                scope.in_as_contract = true;
+                self.check_all(args, scope);
+                scope.in_as_contract = in_as_contract;
+            }
+            StxTransfer | StxTransferMemo | StxBurn | TransferToken | BurnToken => {
+                let (amount_index, sender_index) = match native {
+                    TransferToken | BurnToken => (1, 2),
+                    _ => (0, 1),
+                };
+                if scope.in_as_contract {
+                    if let Some(amount) = args.get(amount_index) {
+                        if !self.is_bounded(amount, scope) {
+                            self.report(
+                                LintError::UnboundedAsContractTransfer(native.get_name()),
+                                expr,
+                            );
+                        }
+                    }
+                }
+                for (i, arg) in args.iter().enumerate() {
+                    // the VM requires the sender of STX to be `tx-sender`
+                    if i == sender_index
+                        && matches!(native, StxTransfer | StxTransferMemo | StxBurn)
+                        && self.is_tx_sender(arg)
+                    {
+                        continue;
+                    }
+                    self.check_expression(arg, scope);
+                }
+            }
+            _ => self.check_all(args, scope),
+        }
+        vec![]
+    }
+
+    fn check_let(&mut self, args: &[SymbolicExpression], scope: &mut Scope) {
+        let Some((bindings, body)) = args.split_first() else {
+            return;
+        };
+        let bindings_len = scope.bindings.len();
+        for binding in bindings.match_list().unwrap_or_default() {
+            match binding.match_list() {
+                Some([name, value]) => {
+                    self.check_expression(value, scope);
+                    if let Some(name) = name.match_atom() {
+                        self.check_binding(name, binding, scope);
+                    }
+                }
+                _ => {
+                    self.check_expression(binding, scope);
+                }
+            }
+        }
+        self.check_sequence(body, scope);
+        scope.bindings.truncate(bindings_len);
+    }
+
+    fn check_match(&mut self, args: &[SymbolicExpression], scope: &mut Scope) {
+        let branches = match args {
+            [input, some_name, some_branch, none_branch] => {
+                self.check_expression(input, scope);
+                vec![(Some(some_name), some_branch), (None, none_branch)]
+            }
+            [input, ok_name, ok_branch, err_name, err_branch] => {
+                self.check_expression(input, scope);
+                vec![(Some(ok_name), ok_branch), (Some(err_name), err_branch)]
+            }
+            _ => {
+                self.check_all(args, scope);
+                return;
+            }
+        };
+        for (name, branch) in branches.into_iter() {
+            let bindings_len = scope.bindings.len();
+            if let Some(name) = name {
+                if let Some(atom) = name.match_atom() {
+                    self.check_binding(atom, name, scope);
+                }
+            }
+            self.check_expression(branch, scope);
+            scope.bindings.truncate(bindings_len);
+        }
+    }
+
+    /// The facts which hold when `condition` evaluates to `holds`
+    fn condition_facts(&self, condition: &SymbolicExpression, holds: bool) -> Vec<Fact> {
+        use crate::vm::functions::NativeFunctions::*;
+
+        let Some((function, args)) = condition.match_list().and_then(|list| list.split_first())
+        else {
+            return vec![];
+        };
+        let Some(native) = self.native_function(function) else {
+            return vec![];
+        };
+        match (native, args) {
+            (Not, [inner]) => self.condition_facts(inner, !holds),
+            (And, _) if holds => args
+                .iter()
+                .flat_map(|arg| self.condition_facts(arg, true))
+                .collect(),
+            (Or, _) if !holds => args
+                .iter()
+                .flat_map(|arg| self.condition_facts(arg, false))
+                .collect(),
+            (IsSome, [value]) if holds => vec![Fact::IsSome(value.to_string())],
+            (IsNone, [value]) if !holds => vec![Fact::IsSome(value.to_string())],
+            (IsOkay, [value]) | (IsErr, [value]) => {
+                if (native == IsOkay) == holds {
+                    vec![Fact::IsOk(value.to_string())]
+                } else {
+                    vec![Fact::IsErr(value.to_string())]
+                }
+            }
+            (CmpLess, [lesser, greater])
+            | (CmpLeq, [lesser, greater])
+            | (CmpGreater, [greater, lesser])
+            | (CmpGeq, [greater, lesser]) => {
+                let bounded = if holds { lesser } else { greater };
+                vec![Fact::Bounded(bounded.to_string())]
+            }
+            _ => vec![],
+        }
+    }
+
+    /// Is `amount` a literal, a constant, or known to be less than some other value?
+    fn is_bounded(&self, amount: &SymbolicExpression, scope: &Scope) -> bool {
+        match amount.expr {
+            AtomValue(_) | LiteralValue(_) => return true,
+            Atom(ref name) if self.constants.contains(name) => return true,
+            _ => {}
+        }
+        let key = amount.to_string();
+        scope
+            .facts
+            .iter()
+            .any(|fact| fact == &Fact::Bounded(key.clone()))
+    }
+}
+
+/// Collect every atom in `expr`
+fn collect_atoms(expr: &SymbolicExpression, atoms: &mut BTreeSet<ClarityName>) {
+    match expr.expr {
+        Atom(ref name) => {
+            atoms.insert(name.clone());
+        }
+        List(ref list) => {
+            for expr in list.iter() {
+                collect_atoms(expr, atoms);
+            }
+        }
+        _ => {}
+    }
+}
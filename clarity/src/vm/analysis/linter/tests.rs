@@ -0,0 +1,207 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use stacks_common::types::StacksEpochId;
+
+use crate::vm::analysis::linter::{LintConfig, LintError, LintLevel, LintRule, Linter};
+use crate::vm::analysis::ContractAnalysis;
+use crate::vm::ast::parse;
+use crate::vm::costs::LimitedCostTracker;
+use crate::vm::diagnostic::{DiagnosableError, Diagnostic, Level};
+use crate::vm::types::QualifiedContractIdentifier;
+use crate::vm::ClarityVersion;
+
+fn lint_with_config(contract: &str, config: &LintConfig) -> Vec<Diagnostic> {
+    let contract_identifier = QualifiedContractIdentifier::transient();
+    let expressions = parse(
+        &contract_identifier,
+        contract,
+        ClarityVersion::Clarity2,
+        StacksEpochId::Epoch25,
+    )
+    .unwrap();
+
+    let analysis = ContractAnalysis::new(
+        contract_identifier,
+        expressions,
+        LimitedCostTracker::new_free(),
+        StacksEpochId::Epoch25,
+        ClarityVersion::Clarity2,
+    );
+
+    Linter::run(&analysis, config)
+}
+
+/// The messages of the lints reported for `contract` with the default config, sorted
+fn lint(contract: &str) -> Vec<String> {
+    let mut messages: Vec<_> = lint_with_config(contract, &LintConfig::default())
+        .into_iter()
+        .map(|diagnostic| diagnostic.message)
+        .collect();
+    messages.sort();
+    messages
+}
+
+fn messages(errors: &[LintError]) -> Vec<String> {
+    let mut messages: Vec<_> = errors.iter().map(|error| error.message()).collect();
+    messages.sort();
+    messages
+}
+
+#[test]
+fn test_unchecked_unwrap_panic() {
+    let contract = "
+        (define-read-only (unchecked (x (optional uint)))
+            (unwrap-panic x))
+        (define-read-only (checked-by-if (x (optional uint)))
+            (if (is-some x) (unwrap-panic x) u0))
+        (define-read-only (checked-by-else (x (response uint uint)))
+            (if (or (is-none (some u1)) (is-err x)) u0 (unwrap-panic x)))
+        (define-read-only (checked-by-asserts (x (response uint uint)))
+            (begin
+                (asserts! (and (is-ok x) true) u0)
+                (unwrap-panic x)))
+        (define-read-only (checked-too-late (x (response uint uint)))
+            (begin
+                (unwrap-err-panic x)
+                (asserts! (is-err x) u0)
+                (unwrap-err-panic x)))";
+    assert_eq!(
+        lint(contract),
+        messages(&[
+            LintError::UncheckedUnwrapPanic("unwrap-panic".into()),
+            LintError::UncheckedUnwrapPanic("unwrap-err-panic".into()),
+        ])
+    );
+}
+
+#[test]
+fn test_tx_sender_in_callable_function() {
+    let contract = "
+        (define-data-var owner principal tx-sender)
+        (define-private (is-owner)
+            (is-eq tx-sender (var-get owner)))
+        (define-private (contract-owner)
+            (as-contract tx-sender))
+        (define-public (set-owner (new-owner principal))
+            (begin
+                (asserts! (is-owner) (err u1))
+                (try! (stx-transfer? u100 tx-sender (contract-owner)))
+                (ok (var-set owner new-owner))))
+        (define-read-only (get-owner)
+            (if (is-eq contract-caller (var-get owner)) (some tx-sender) none))";
+    assert_eq!(
+        lint(contract),
+        messages(&[
+            LintError::TxSenderInCallableFunction("is-owner".into()),
+            LintError::TxSenderInCallableFunction("get-owner".into()),
+        ])
+    );
+}
+
+#[test]
+fn test_unused_definitions() {
+    let contract = "
+        (define-constant USED u1)
+        (define-constant USED_BY_CONSTANT u2)
+        (define-constant UNUSED (+ USED_BY_CONSTANT u1))
+        (define-private (called (x uint)) (+ x USED))
+        (define-private (mapped (x uint)) (* x u2))
+        (define-private (uncalled) u3)
+        (define-read-only (get-values)
+            (map mapped (list (called u1))))";
+    assert_eq!(
+        lint(contract),
+        messages(&[
+            LintError::UnusedConstant("UNUSED".into()),
+            LintError::UnusedPrivateFunction("uncalled".into()),
+        ])
+    );
+}
+
+#[test]
+fn test_unbounded_as_contract_transfer() {
+    let contract = "
+        (define-fungible-token token)
+        (define-constant MAX_WITHDRAWAL u1000)
+        (define-public (withdraw (amount uint))
+            (let ((recipient contract-caller))
+                (try! (as-contract (stx-transfer? amount contract-caller recipient)))
+                (as-contract (ft-transfer? token amount contract-caller recipient))))
+        (define-public (withdraw-bounded (amount uint))
+            (let ((recipient contract-caller))
+                (asserts! (>= MAX_WITHDRAWAL amount) (err u1))
+                (try! (as-contract (stx-transfer? amount contract-caller recipient)))
+                (try! (as-contract (stx-transfer? MAX_WITHDRAWAL contract-caller recipient)))
+                (try! (stx-transfer? amount contract-caller recipient))
+                (as-contract (stx-transfer? u10 contract-caller recipient))))";
+    assert_eq!(
+        lint(contract),
+        messages(&[
+            LintError::UnboundedAsContractTransfer("stx-transfer?".into()),
+            LintError::UnboundedAsContractTransfer("ft-transfer?".into()),
+        ])
+    );
+}
+
+#[test]
+fn test_shadowing() {
+    let contract = "
+        (define-data-var counter uint u0)
+        (define-read-only (shadows (list uint) (x uint))
+            (let ((counter u1)
+                  (y (let ((x u2)) x)))
+                (match (some y) z (+ z list counter) u0)))";
+    assert_eq!(
+        lint(contract),
+        messages(&[
+            LintError::ShadowsBuiltIn("list".into()),
+            LintError::ShadowsDefinition("counter".into()),
+            LintError::ShadowsBinding("x".into()),
+        ])
+    );
+}
+
+#[test]
+fn test_lint_config() {
+    let contract = "
+        (define-constant UNUSED u1)
+        (define-read-only (get-value (x (optional uint))) (unwrap-panic x))";
+
+    let diagnostics = lint_with_config(contract, &LintConfig::default());
+    assert_eq!(diagnostics.len(), 2);
+    assert!(diagnostics
+        .iter()
+        .all(|diagnostic| diagnostic.level == Level::Warning));
+
+    let config = LintConfig::from_json(
+        r#"{ "unused-constant": "allow", "unchecked-unwrap-panic": "deny" }"#,
+    )
+    .unwrap();
+    assert_eq!(config.level(LintRule::UnusedConstant), LintLevel::Allow);
+    assert_eq!(config.level(LintRule::Shadowing), LintLevel::Warn);
+    let diagnostics = lint_with_config(contract, &config);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].level, Level::Error);
+    assert_eq!(
+        diagnostics[0].message,
+        LintError::UncheckedUnwrapPanic("unwrap-panic".into()).message()
+    );
+    assert!(diagnostics[0].suggestion.is_some());
+
+    assert!(LintConfig::from_json(r#"{ "no-such-rule": "deny" }"#).is_err());
+    assert!(LintConfig::from_json(r#"{ "shadowing": "forbid" }"#).is_err());
+}
@@ -19,6 +19,7 @@ pub mod arithmetic_checker;
 pub mod contract_interface_builder;
 #[allow(clippy::result_large_err)]
 pub mod errors;
+pub mod linter;
 pub mod read_only_checker;
 pub mod trait_checker;
 pub mod type_checker;
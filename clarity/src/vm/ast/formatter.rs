@@ -0,0 +1,538 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Deterministic pretty-printer for Clarity source code.
+//!
+//! The source is first validated with the v2 parser, and then re-lexed so that comments, line
+//! breaks and the exact spelling of each literal are available. Expressions which fit on one
+//! line are printed on one line. Longer expressions keep their operator (and, for special forms
+//! like `define-public` or `let`, their first argument) on the opening line, and put each
+//! remaining argument on its own line. Comments and single blank lines between expressions are
+//! preserved.
+
+use super::errors::{ParseErrors, ParseResult};
+use super::parser::v2::lexer::token::{PlacedToken, Token};
+use super::parser::v2::lexer::Lexer;
+use super::parser::v2::parse;
+use crate::vm::representations::Span;
+
+/// Lines longer than this are broken up, when possible
+pub const MAX_LINE_WIDTH: usize = 80;
+
+/// Number of spaces per nesting level
+pub const INDENT_WIDTH: usize = 2;
+
+#[derive(Debug)]
+enum Node {
+    /// An atom or literal, spelled exactly as in the source
+    Leaf { text: String, line: u32 },
+    /// A comment, including its leading semicolons
+    Comment { text: String, line: u32 },
+    List {
+        children: Vec<Node>,
+        start_line: u32,
+        end_line: u32,
+    },
+    Tuple {
+        children: Vec<Node>,
+        start_line: u32,
+        end_line: u32,
+    },
+}
+
+impl Node {
+    fn start_line(&self) -> u32 {
+        match self {
+            Node::Leaf { line, .. } | Node::Comment { line, .. } => *line,
+            Node::List { start_line, .. } | Node::Tuple { start_line, .. } => *start_line,
+        }
+    }
+
+    fn end_line(&self) -> u32 {
+        match self {
+            Node::Leaf { line, .. } | Node::Comment { line, .. } => *line,
+            Node::List { end_line, .. } | Node::Tuple { end_line, .. } => *end_line,
+        }
+    }
+
+    fn is_comment(&self) -> bool {
+        matches!(self, Node::Comment { .. })
+    }
+}
+
+/// Format a Clarity program. Fails if the program cannot be parsed by the v2 parser.
+pub fn format_source(source: &str) -> ParseResult<String> {
+    parse(source)?;
+
+    let mut lexer = Lexer::new(source, true).map_err(ParseErrors::Lexer)?;
+    let mut tokens = vec![];
+    loop {
+        let token = lexer.read_token().map_err(ParseErrors::Lexer)?;
+        match token.token {
+            Token::Eof => break,
+            Token::Whitespace | Token::Comma | Token::Colon => {}
+            _ => tokens.push(token),
+        }
+    }
+
+    let reader = NodeReader {
+        source,
+        line_offsets: line_offsets(source),
+        tokens,
+        index: 0,
+    };
+    let nodes = reader.read_all()?;
+
+    let mut output = render_sequence(&nodes, 0, 0);
+    if !output.is_empty() {
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+/// Byte offset of the start of each line
+fn line_offsets(source: &str) -> Vec<usize> {
+    let mut offsets = vec![0];
+    offsets.extend(source.match_indices('\n').map(|(i, _)| i + 1));
+    offsets
+}
+
+/// Groups the significant tokens of a program into lists, tuples, comments and leaves.
+/// The v2 parser has already validated the program, so the tokens are well-nested.
+struct NodeReader<'a> {
+    source: &'a str,
+    line_offsets: Vec<usize>,
+    tokens: Vec<PlacedToken>,
+    index: usize,
+}
+
+impl<'a> NodeReader<'a> {
+    fn read_all(mut self) -> ParseResult<Vec<Node>> {
+        let nodes = self.read_sequence()?;
+        if self.index < self.tokens.len() {
+            return Err(ParseErrors::UnexpectedParserFailure.into());
+        }
+        Ok(nodes)
+    }
+
+    /// Read nodes up to the next unmatched closing token, or the end of the program
+    fn read_sequence(&mut self) -> ParseResult<Vec<Node>> {
+        let mut nodes = vec![];
+        while let Some(token) = self.tokens.get(self.index) {
+            let node = match token.token {
+                Token::Rparen | Token::Rbrace => break,
+                Token::Lparen | Token::Lbrace => {
+                    let is_list = token.token == Token::Lparen;
+                    let start_line = token.span.start_line;
+                    self.index += 1;
+                    let children = self.read_sequence()?;
+                    let end_line = match self.tokens.get(self.index) {
+                        Some(PlacedToken {
+                            token: Token::Rparen,
+                            span,
+                        }) if is_list => span.end_line,
+                        Some(PlacedToken {
+                            token: Token::Rbrace,
+                            span,
+                        }) if !is_list => span.end_line,
+                        _ => return Err(ParseErrors::UnexpectedParserFailure.into()),
+                    };
+                    self.index += 1;
+                    if is_list {
+                        Node::List {
+                            children,
+                            start_line,
+                            end_line,
+                        }
+                    } else {
+                        Node::Tuple {
+                            children,
+                            start_line,
+                            end_line,
+                        }
+                    }
+                }
+                Token::Comment(_) => {
+                    let line = token.span.start_line;
+                    let text = self.source_line_from(&token.span)?.trim_end().to_string();
+                    self.index += 1;
+                    Node::Comment { text, line }
+                }
+                _ => self.read_leaf()?,
+            };
+            nodes.push(node);
+        }
+        Ok(nodes)
+    }
+
+    /// Read a run of adjacent tokens, like `.contract.trait`, as a single leaf
+    fn read_leaf(&mut self) -> ParseResult<Node> {
+        let first = self.index;
+        self.index += 1;
+        while let Some(token) = self.tokens.get(self.index) {
+            let previous = &self.tokens[self.index - 1].span;
+            let adjacent = token.span.start_line == previous.end_line
+                && token.span.start_column == previous.end_column + 1;
+            if !adjacent || is_structural(&token.token) {
+                break;
+            }
+            self.index += 1;
+        }
+        let start = &self.tokens[first].span;
+        let end = &self.tokens[self.index - 1].span;
+        let text = self.source_text(start, end)?.to_string();
+        Ok(Node::Leaf {
+            text,
+            line: start.start_line,
+        })
+    }
+
+    fn offset(&self, line: u32, column: u32) -> ParseResult<usize> {
+        let line_offset = (line as usize)
+            .checked_sub(1)
+            .and_then(|line| self.line_offsets.get(line))
+            .ok_or(ParseErrors::UnexpectedParserFailure)?;
+        let column = (column as usize)
+            .checked_sub(1)
+            .ok_or(ParseErrors::UnexpectedParserFailure)?;
+        Ok(line_offset + column)
+    }
+
+    /// The source text from the start of `start` to the end of `end`
+    fn source_text(&self, start: &Span, end: &Span) -> ParseResult<&'a str> {
+        let start = self.offset(start.start_line, start.start_column)?;
+        let end = self.offset(end.end_line, end.end_column)? + 1;
+        self.source
+            .get(start..end)
+            .ok_or_else(|| ParseErrors::UnexpectedParserFailure.into())
+    }
+
+    /// The source text from the start of `span` to the end of its line
+    fn source_line_from(&self, span: &Span) -> ParseResult<&'a str> {
+        let start = self.offset(span.start_line, span.start_column)?;
+        let rest = self
+            .source
+            .get(start..)
+            .ok_or(ParseErrors::UnexpectedParserFailure)?;
+        Ok(rest.split('\n').next().unwrap_or(rest))
+    }
+}
+
+fn is_structural(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::Lparen
+            | Token::Rparen
+            | Token::Lbrace
+            | Token::Rbrace
+            | Token::Comment(_)
+            | Token::Whitespace
+            | Token::Comma
+            | Token::Colon
+            | Token::Eof
+    )
+}
+
+/// Number of arguments kept on the opening line of a broken list, after its operator
+fn header_len(operator: &str, first_arg: Option<&Node>) -> usize {
+    match operator {
+        "define-public"
+        | "define-private"
+        | "define-read-only"
+        | "define-constant"
+        | "define-map"
+        | "define-fungible-token"
+        | "define-non-fungible-token"
+        | "define-trait"
+        | "let"
+        | "if"
+        | "match"
+        | "asserts!" => 1,
+        "define-data-var" => 2,
+        "begin" | "and" | "or" | "list" | "tuple" => 0,
+        _ => match first_arg {
+            Some(Node::Leaf { .. }) => 1,
+            _ => 0,
+        },
+    }
+}
+
+fn indentation(width: usize) -> String {
+    " ".repeat(width)
+}
+
+/// Width of the last line of `rendered`, which starts at `column`
+fn end_column(rendered: &str, column: usize) -> usize {
+    match rendered.rfind('\n') {
+        Some(i) => rendered.len() - i - 1,
+        None => column + rendered.len(),
+    }
+}
+
+/// Render `node` on a single line, unless it contains a comment
+fn render_flat(node: &Node) -> Option<String> {
+    match node {
+        Node::Leaf { text, .. } => Some(text.clone()),
+        Node::Comment { .. } => None,
+        Node::List { children, .. } => {
+            let children: Option<Vec<_>> = children.iter().map(render_flat).collect();
+            Some(format!("({})", children?.join(" ")))
+        }
+        Node::Tuple { children, .. } => {
+            if children.is_empty() {
+                return Some("{}".to_string());
+            }
+            let children: Option<Vec<_>> = children.iter().map(render_flat).collect();
+            let entries: Vec<_> = children?.chunks(2).map(|entry| entry.join(": ")).collect();
+            Some(format!("{{ {} }}", entries.join(", ")))
+        }
+    }
+}
+
+/// Render `node`, which starts at `column` on a line indented by `indent`. Lines after the first
+/// include their indentation.
+fn render(node: &Node, indent: usize, column: usize) -> String {
+    if let Some(flat) = render_flat(node) {
+        if column + flat.len() <= MAX_LINE_WIDTH || matches!(node, Node::Leaf { .. }) {
+            return flat;
+        }
+    }
+    match node {
+        Node::Leaf { text, .. } | Node::Comment { text, .. } => text.clone(),
+        Node::List {
+            children,
+            start_line,
+            ..
+        } => render_list(children, *start_line, indent, column),
+        Node::Tuple {
+            children,
+            start_line,
+            ..
+        } => render_tuple(children, *start_line, indent),
+    }
+}
+
+/// Render nodes one per line at `indent`, to be appended to a line which ends at
+/// `previous_line` of the source. Comments on the same line as the preceding node stay on that
+/// line, and a single blank line is kept between nodes which were separated by blank lines.
+fn render_sequence(nodes: &[Node], indent: usize, previous_line: u32) -> String {
+    let mut output = String::new();
+    let mut previous_line = previous_line;
+    for (i, node) in nodes.iter().enumerate() {
+        if node.is_comment() && node.start_line() == previous_line {
+            output.push(' ');
+        } else {
+            if i > 0 || indent > 0 {
+                output.push('\n');
+            }
+            if i > 0 && node.start_line() > previous_line + 1 {
+                output.push('\n');
+            }
+            output.push_str(&indentation(indent));
+        }
+        output.push_str(&render(node, indent, indent));
+        previous_line = node.end_line();
+    }
+    output
+}
+
+fn render_list(children: &[Node], start_line: u32, indent: usize, column: usize) -> String {
+    let mut output = "(".to_string();
+    let mut body_start = 0;
+    if let Some(first) = children.first().filter(|first| !first.is_comment()) {
+        output.push_str(&render(first, indent + INDENT_WIDTH, column + 1));
+        body_start = 1;
+        if let Node::Leaf { text, .. } = first {
+            let header = header_len(text, children.get(1));
+            for (i, arg) in children.iter().skip(1).take(header).enumerate() {
+                if arg.is_comment() {
+                    break;
+                }
+                let arg_column = end_column(&output, column) + 1;
+                match render_flat(arg) {
+                    Some(flat) if arg_column + flat.len() <= MAX_LINE_WIDTH => {
+                        output.push(' ');
+                        output.push_str(&flat);
+                        body_start += 1;
+                    }
+                    // a long signature or binding list is broken in place, indented past the
+                    // body
+                    _ if i == 0 && !matches!(arg, Node::Leaf { .. }) => {
+                        output.push(' ');
+                        output.push_str(&render(arg, indent + INDENT_WIDTH, arg_column));
+                        body_start += 1;
+                        break;
+                    }
+                    _ => break,
+                }
+            }
+        }
+    }
+
+    let previous_line = match body_start {
+        0 => start_line,
+        n => children[n - 1].end_line(),
+    };
+    let body = &children[body_start..];
+    let rendered_body = render_sequence(body, indent + INDENT_WIDTH, previous_line);
+    output.push_str(&rendered_body);
+    if body.last().map(Node::is_comment).unwrap_or(false) {
+        output.push('\n');
+        output.push_str(&indentation(indent));
+    }
+    output.push(')');
+    output
+}
+
+fn render_tuple(children: &[Node], start_line: u32, indent: usize) -> String {
+    let entry_indent = indent + INDENT_WIDTH;
+    let mut output = "{".to_string();
+    let mut previous_line = start_line;
+    let mut key: Option<&Node> = None;
+    for node in children.iter() {
+        // comments between a key and its value are moved before the entry
+        if !node.is_comment() && key.is_none() {
+            key = Some(node);
+            continue;
+        }
+        let entry_start = key.unwrap_or(node);
+        if node.is_comment() && key.is_none() && node.start_line() == previous_line {
+            output.push(' ');
+        } else {
+            output.push('\n');
+            if output != "{\n" && entry_start.start_line() > previous_line + 1 {
+                output.push('\n');
+            }
+            output.push_str(&indentation(entry_indent));
+        }
+        if node.is_comment() {
+            output.push_str(&render(node, entry_indent, 0));
+        } else if let Some(entry_key) = key.take() {
+            let key_text = render(entry_key, entry_indent, entry_indent);
+            let value_column = entry_indent + key_text.len() + 2;
+            output.push_str(&key_text);
+            output.push_str(": ");
+            output.push_str(&render(node, entry_indent, value_column));
+            output.push(',');
+        }
+        previous_line = node.end_line();
+    }
+    output.push('\n');
+    output.push_str(&indentation(indent));
+    output.push('}');
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_formats_to(source: &str, expected: &str) {
+        let formatted = format_source(source).unwrap();
+        assert_eq!(formatted, expected);
+        // formatting is idempotent
+        assert_eq!(format_source(&formatted).unwrap(), formatted);
+    }
+
+    #[test]
+    fn test_format_short_expressions() {
+        assert_formats_to("", "");
+        assert_formats_to(
+            "(define-constant   ERR_UNAUTHORIZED\n (err u401))",
+            "(define-constant ERR_UNAUTHORIZED (err u401))\n",
+        );
+        assert_formats_to(
+            "(define-data-var counter uint u0)(define-read-only (get-counter) (var-get counter))",
+            "(define-data-var counter uint u0)\n(define-read-only (get-counter) (var-get counter))\n",
+        );
+        assert_formats_to("{a:1,b: \"x \\\" y\"  ,}", "{ a: 1, b: \"x \\\" y\" }\n");
+        assert_formats_to(
+            "(contract-call?  .token   transfer u1 'SP000000000000000000002Q6VF78 .pool.trait)",
+            "(contract-call? .token transfer u1 'SP000000000000000000002Q6VF78 .pool.trait)\n",
+        );
+    }
+
+    #[test]
+    fn test_format_long_expressions() {
+        assert_formats_to(
+            "(define-public (increment (amount uint)) (begin (asserts! (> amount u0) (err u1)) (var-set counter (+ (var-get counter) amount)) (ok true)))",
+            "(define-public (increment (amount uint))
+  (begin
+    (asserts! (> amount u0) (err u1))
+    (var-set counter (+ (var-get counter) amount))
+    (ok true)))
+",
+        );
+        assert_formats_to(
+            "(define-map balances { owner: principal, token-id: uint, extension: (optional (buff 32)), memo: (buff 34) } uint)",
+            "(define-map balances
+  {
+    owner: principal,
+    token-id: uint,
+    extension: (optional (buff 32)),
+    memo: (buff 34),
+  }
+  uint)
+",
+        );
+        assert_formats_to(
+            "(define-read-only (get-balance-for-owner-and-token (owner principal) (token-id uint)) u0)",
+            "(define-read-only (get-balance-for-owner-and-token
+    (owner principal)
+    (token-id uint))
+  u0)
+",
+        );
+    }
+
+    #[test]
+    fn test_format_preserves_comments_and_blank_lines() {
+        assert_formats_to(
+            ";; counter contract
+(define-data-var counter uint u0) ;; the count
+
+
+;; read it
+(define-read-only (get-counter)
+  ;; no checks needed
+  (var-get counter))
+",
+            ";; counter contract
+(define-data-var counter uint u0) ;; the count
+
+;; read it
+(define-read-only (get-counter)
+  ;; no checks needed
+  (var-get counter))
+",
+        );
+        assert_formats_to(
+            ";;;; header\n(begin (foo) ;; trailing\n)\n",
+            ";;;; header\n(begin\n  (foo) ;; trailing\n)\n",
+        );
+        assert_formats_to(
+            "{ a: 1, ;; first\n b: 2 }",
+            "{\n  a: 1, ;; first\n  b: 2,\n}\n",
+        );
+    }
+
+    #[test]
+    fn test_format_invalid_source() {
+        assert!(format_source("(define-constant A").is_err());
+        assert!(format_source("(ok u1))").is_err());
+        assert!(format_source("; single semicolon").is_err());
+    }
+}
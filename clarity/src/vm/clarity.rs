@@ -11,7 +11,9 @@ use crate::vm::database::ClarityDatabase;
 use crate::vm::errors::Error as InterpreterError;
 use crate::vm::events::StacksTransactionEvent;
 use crate::vm::types::{BuffData, PrincipalData, QualifiedContractIdentifier};
-use crate::vm::{analysis, ast, ClarityVersion, ContractContext, SymbolicExpression, Value};
+use crate::vm::{
+    analysis, ast, ClarityVersion, ContractContext, EvalHook, SymbolicExpression, Value,
+};
 
 #[derive(Debug)]
 pub enum Error {
@@ -123,6 +125,35 @@ pub trait ClarityConnection {
         cost_track: LimitedCostTracker,
         to_do: F,
     ) -> Result<R, InterpreterError>
+    where
+        F: FnOnce(&mut Environment) -> Result<R, InterpreterError>,
+    {
+        self.with_readonly_clarity_env_and_hook(
+            mainnet,
+            chain_id,
+            clarity_version,
+            sender,
+            sponsor,
+            cost_track,
+            None,
+            to_do,
+        )
+    }
+
+    /// Like `with_readonly_clarity_env`, but if `eval_hook` is given, it is installed in the
+    /// environment so that it observes the evaluation done by `to_do`.
+    #[allow(clippy::too_many_arguments)]
+    fn with_readonly_clarity_env_and_hook<F, R>(
+        &mut self,
+        mainnet: bool,
+        chain_id: u32,
+        clarity_version: ClarityVersion,
+        sender: PrincipalData,
+        sponsor: Option<PrincipalData>,
+        cost_track: LimitedCostTracker,
+        eval_hook: Option<&mut dyn EvalHook>,
+        to_do: F,
+    ) -> Result<R, InterpreterError>
     where
         F: FnOnce(&mut Environment) -> Result<R, InterpreterError>,
     {
@@ -133,6 +164,9 @@ pub trait ClarityConnection {
             let mut vm_env = OwnedEnvironment::new_cost_limited(
                 mainnet, chain_id, clarity_db, cost_track, epoch_id,
             );
+            if let Some(eval_hook) = eval_hook {
+                vm_env.add_eval_hook(eval_hook);
+            }
             let result = vm_env
                 .execute_in_env(sender, sponsor, Some(initial_context), to_do)
                 .map(|(result, _, _)| result);
@@ -0,0 +1,533 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! An `EvalHook` which pauses evaluation at breakpoints, or after a step, and then reads
+//! debugger commands from an input stream until it is told to resume.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+
+use super::EvalHook;
+use crate::vm::ast::{build_ast_with_rules, ASTRules};
+use crate::vm::callables::FunctionIdentifier;
+use crate::vm::contexts::{Environment, LocalContext};
+use crate::vm::errors::Error;
+use crate::vm::representations::SymbolicExpressionType::{
+    Atom, AtomValue, Field, List, LiteralValue, TraitReference,
+};
+use crate::vm::types::QualifiedContractIdentifier;
+use crate::vm::{eval, ExecutionResult, SymbolicExpression, Value};
+
+/// Expressions are shortened to this many characters when the debugger shows where it stopped
+const MAX_EXPR_WIDTH: usize = 80;
+
+const HELP: &str = "Commands:
+  step (s)                    evaluate up to the next expression, entering function calls
+  next (n)                    evaluate up to the next expression, skipping over function calls
+  finish (f)                  evaluate until the current function returns
+  continue (c)                evaluate until a breakpoint is reached or a watch changes
+  break (b) <line|function>   stop at a source line, or when a function is called
+  delete (d) <line|function>  remove a breakpoint
+  watch (w) <expression>      show an expression at every stop, and stop when it changes
+  print (p) <expression>      evaluate an expression in the current scope
+  locals (l)                  show the variables in the current scope
+  backtrace (bt)              show the function call stack
+  quit (q)                    stop debugging and finish evaluation
+  help (h)                    show this message";
+
+/// A place where the debugger stops evaluation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Breakpoint {
+    /// The outermost expression which starts on a source line.  Source lines are only known
+    /// when Clarity is built with the `developer-mode` feature.
+    Line(u32),
+    /// The start of a call to a user-defined function, given either by its name or as
+    /// `<contract-identifier>:<name>`
+    Function(String),
+}
+
+impl Breakpoint {
+    fn matches_function(&self, function: &FunctionIdentifier) -> bool {
+        let Breakpoint::Function(ref name) = self else {
+            return false;
+        };
+        let identifier = function.to_string();
+        identifier == *name
+            || identifier
+                .rsplit_once(':')
+                .map_or(false, |(_, function_name)| function_name == name)
+    }
+}
+
+impl FromStr for Breakpoint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err("Expected a line number or a function name".into());
+        }
+        match s.parse::<u32>() {
+            Ok(0) => Err("Line numbers start at 1".into()),
+            Ok(line) => Ok(Breakpoint::Line(line)),
+            Err(_) => Ok(Breakpoint::Function(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Breakpoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Breakpoint::Line(line) => write!(f, "line {}", line),
+            Breakpoint::Function(name) => write!(f, "function {}", name),
+        }
+    }
+}
+
+/// When the debugger will next stop, besides at breakpoints
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mode {
+    /// At the next list expression
+    Step,
+    /// At the next list expression which is nested at most this deep
+    Next(usize),
+    /// At the next list expression after the function call stack is shallower than this
+    Finish(usize),
+    /// Only at breakpoints and watch changes
+    Continue,
+    /// Never
+    Detached,
+}
+
+/// An expression whose evaluation has begun but not finished
+struct PendingExpr {
+    contract: QualifiedContractIdentifier,
+    expr_id: u64,
+    line: u32,
+}
+
+struct Watch {
+    source: String,
+    /// The last value of the expression, or None if it has not been evaluated yet
+    value: Option<String>,
+}
+
+/// An interactive debugger for Clarity code.  Evaluation stops before the first expression, and
+/// from then on according to the commands read from `input`.  Everything the debugger shows is
+/// written to `output`.
+pub struct Debugger<R: BufRead, W: Write> {
+    input: R,
+    output: W,
+    breakpoints: Vec<Breakpoint>,
+    watches: Vec<Watch>,
+    mode: Mode,
+    exprs: Vec<PendingExpr>,
+    /// The depth of the function call stack when the last expression began or finished
+    function_depth: usize,
+    /// The nesting depth of the expression at which the debugger last stopped
+    stop_depth: usize,
+}
+
+/// Clarity source for `expr`, shortened to `MAX_EXPR_WIDTH` characters
+fn expr_source(expr: &SymbolicExpression) -> String {
+    fn write_expr(expr: &SymbolicExpression, out: &mut String) {
+        match expr.expr {
+            List(ref children) => {
+                out.push('(');
+                for (i, child) in children.iter().enumerate() {
+                    if i > 0 {
+                        out.push(' ');
+                    }
+                    write_expr(child, out);
+                }
+                out.push(')');
+            }
+            Atom(ref name) => out.push_str(name),
+            AtomValue(ref value) | LiteralValue(ref value) => out.push_str(&value.to_string()),
+            TraitReference(ref name, _) => out.push_str(&format!("<{}>", name)),
+            Field(ref field) => out.push_str(&format!("<{}>", field)),
+        }
+    }
+
+    let mut source = String::new();
+    write_expr(expr, &mut source);
+    if source.chars().count() > MAX_EXPR_WIDTH {
+        source = source.chars().take(MAX_EXPR_WIDTH - 3).collect();
+        source.push_str("...");
+    }
+    source
+}
+
+/// Whether `expr` writes to a data-var or a map
+fn writes_state(expr: &SymbolicExpression) -> bool {
+    let List(ref children) = expr.expr else {
+        return false;
+    };
+    matches!(
+        children
+            .first()
+            .and_then(|head| head.match_atom())
+            .map(|name| name.as_str()),
+        Some("var-set" | "map-set" | "map-insert" | "map-delete")
+    )
+}
+
+/// The user-defined functions being evaluated, innermost last.  Some native functions, such
+/// as `let`, are also placed on the call stack, but they are not of interest here.
+fn user_functions<'a>(env: &'a Environment) -> Vec<&'a FunctionIdentifier> {
+    env.call_stack
+        .functions()
+        .iter()
+        .filter(|function| !function.to_string().starts_with("_native_:"))
+        .collect()
+}
+
+/// Evaluate `source` in read-only mode and free of cost, as if it appeared in `context`
+fn evaluate(env: &mut Environment, context: &LocalContext, source: &str) -> Result<Value, String> {
+    let contract_identifier = env.contract_context.contract_identifier.clone();
+    let clarity_version = *env.contract_context.get_clarity_version();
+    let epoch = *env.epoch();
+    let parsed = build_ast_with_rules(
+        &contract_identifier,
+        source,
+        &mut (),
+        clarity_version,
+        epoch,
+        ASTRules::PrecheckSize,
+    )
+    .map_err(|e| e.to_string())?;
+    let [expr] = parsed.expressions.as_slice() else {
+        return Err("Expected a single expression".into());
+    };
+
+    env.global_context.begin_read_only();
+    let result = env.run_free(|env| eval(expr, env, context));
+    env.global_context.roll_back().map_err(|e| e.to_string())?;
+    result.map_err(|e| e.to_string())
+}
+
+fn result_string(result: Result<Value, String>) -> String {
+    match result {
+        Ok(value) => value.to_string(),
+        Err(e) => format!("<error: {}>", e),
+    }
+}
+
+impl<R: BufRead, W: Write> Debugger<R, W> {
+    pub fn new(input: R, output: W) -> Self {
+        Debugger {
+            input,
+            output,
+            breakpoints: Vec::new(),
+            watches: Vec::new(),
+            mode: Mode::Step,
+            exprs: Vec::new(),
+            function_depth: 0,
+            stop_depth: 0,
+        }
+    }
+
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) {
+        if !self.breakpoints.contains(&breakpoint) {
+            self.breakpoints.push(breakpoint);
+        }
+    }
+
+    /// Show the value of `source` whenever evaluation stops, and stop whenever a data-var or map
+    /// write changes it
+    pub fn add_watch(&mut self, source: &str) {
+        self.watches.push(Watch {
+            source: source.trim().to_string(),
+            value: None,
+        });
+    }
+
+    pub fn into_output(self) -> W {
+        self.output
+    }
+
+    fn print(&mut self, text: &str) {
+        let _ = writeln!(self.output, "{}", text);
+    }
+
+    /// Re-evaluate the watches, and describe the ones whose values changed
+    fn update_watches(&mut self, env: &mut Environment, context: &LocalContext) -> Vec<String> {
+        let mut changes = Vec::new();
+        for watch in self.watches.iter_mut() {
+            let value = result_string(evaluate(env, context, &watch.source));
+            match watch.value.replace(value.clone()) {
+                Some(old_value) if old_value != value => {
+                    changes.push(format!("{}: {} -> {}", watch.source, old_value, value))
+                }
+                _ => {}
+            }
+        }
+        changes
+    }
+
+    fn show_stop(&mut self, env: &Environment, expr: &SymbolicExpression) {
+        let location = match user_functions(env).last() {
+            Some(function) => function.to_string(),
+            None => format!("{} (top level)", env.contract_context.contract_identifier),
+        };
+        let span = expr.span();
+        let message = if span.start_line > 0 {
+            format!(
+                "Stopped at line {}, column {} in {}",
+                span.start_line, span.start_column, location
+            )
+        } else {
+            format!("Stopped in {}", location)
+        };
+        self.print(&message);
+        self.print(&format!("  {}", expr_source(expr)));
+
+        let watches: Vec<_> = self
+            .watches
+            .iter()
+            .map(|watch| {
+                format!(
+                    "  watch {} = {}",
+                    watch.source,
+                    watch.value.as_deref().unwrap_or("<not evaluated>")
+                )
+            })
+            .collect();
+        for watch in watches {
+            self.print(&watch);
+        }
+    }
+
+    fn show_locals(&mut self, context: &LocalContext) {
+        let mut seen = HashSet::new();
+        let mut lines = Vec::new();
+        let mut scope = Some(context);
+        // innermost bindings first, skipping the ones they shadow
+        while let Some(current) = scope {
+            let mut variables: Vec<_> = current
+                .variables
+                .iter()
+                .filter(|(name, _)| seen.insert(name.to_string()))
+                .map(|(name, value)| format!("  {} = {}", name, value))
+                .collect();
+            variables.sort();
+            lines.extend(variables);
+            scope = current.parent;
+        }
+        if lines.is_empty() {
+            self.print("No local variables.");
+        }
+        for line in lines {
+            self.print(&line);
+        }
+    }
+
+    fn show_backtrace(&mut self, env: &Environment) {
+        let frames: Vec<_> = user_functions(env)
+            .into_iter()
+            .rev()
+            .enumerate()
+            .map(|(i, function)| format!("  #{} {}", i, function))
+            .collect();
+        if frames.is_empty() {
+            self.print("Not in a function call.");
+        }
+        for frame in frames {
+            self.print(&frame);
+        }
+    }
+
+    /// Read and run commands until one of them resumes evaluation
+    fn prompt(&mut self, env: &mut Environment, context: &LocalContext) {
+        loop {
+            let _ = write!(self.output, "(debug) ");
+            let _ = self.output.flush();
+
+            let mut line = String::new();
+            match self.input.read_line(&mut line) {
+                Ok(0) | Err(_) => {
+                    // nobody left to ask
+                    self.mode = Mode::Detached;
+                    return;
+                }
+                Ok(_) => {}
+            }
+            let line = line.trim();
+            let (command, argument) = line
+                .split_once(char::is_whitespace)
+                .map(|(command, argument)| (command, argument.trim()))
+                .unwrap_or((line, ""));
+
+            match command {
+                "" => {}
+                "s" | "step" => {
+                    self.mode = Mode::Step;
+                    return;
+                }
+                "n" | "next" => {
+                    self.mode = Mode::Next(self.stop_depth);
+                    return;
+                }
+                "f" | "finish" => {
+                    self.mode = Mode::Finish(self.function_depth);
+                    return;
+                }
+                "c" | "continue" => {
+                    self.mode = Mode::Continue;
+                    return;
+                }
+                "q" | "quit" => {
+                    self.mode = Mode::Detached;
+                    return;
+                }
+                "b" | "break" => match argument.parse::<Breakpoint>() {
+                    Ok(breakpoint) => {
+                        self.print(&format!("Breakpoint set at {}", breakpoint));
+                        self.add_breakpoint(breakpoint);
+                    }
+                    Err(e) => self.print(&e),
+                },
+                "d" | "delete" => match argument.parse::<Breakpoint>() {
+                    Ok(breakpoint) if self.breakpoints.contains(&breakpoint) => {
+                        self.breakpoints.retain(|existing| *existing != breakpoint);
+                        self.print(&format!("Breakpoint at {} deleted", breakpoint));
+                    }
+                    Ok(breakpoint) => self.print(&format!("No breakpoint at {}", breakpoint)),
+                    Err(e) => self.print(&e),
+                },
+                "w" | "watch" | "p" | "print" if argument.is_empty() => {
+                    self.print("Expected an expression");
+                }
+                "w" | "watch" => {
+                    let value = result_string(evaluate(env, context, argument));
+                    self.print(&format!("Watching {} = {}", argument, value));
+                    self.watches.push(Watch {
+                        source: argument.to_string(),
+                        value: Some(value),
+                    });
+                }
+                "p" | "print" => {
+                    let value = result_string(evaluate(env, context, argument));
+                    self.print(&value);
+                }
+                "l" | "locals" => self.show_locals(context),
+                "bt" | "backtrace" => self.show_backtrace(env),
+                "h" | "help" => self.print(HELP),
+                _ => self.print(&format!(
+                    "Unknown command `{}`. Type `help` for a list of commands.",
+                    command
+                )),
+            }
+        }
+    }
+}
+
+impl<R: BufRead, W: Write> EvalHook for Debugger<R, W> {
+    fn will_begin_eval(
+        &mut self,
+        env: &mut Environment,
+        context: &LocalContext,
+        expr: &SymbolicExpression,
+    ) {
+        let contract = env.contract_context.contract_identifier.clone();
+        let line = expr.span().start_line;
+        let starts_line = line > 0
+            && self.exprs.last().map_or(true, |parent| {
+                parent.line != line || parent.contract != contract
+            });
+        self.exprs.push(PendingExpr {
+            contract,
+            expr_id: expr.id,
+            line,
+        });
+
+        let functions = user_functions(env);
+        let entered_function = if functions.len() > self.function_depth {
+            functions.last().map(|function| (*function).clone())
+        } else {
+            None
+        };
+        self.function_depth = functions.len();
+
+        if self.mode == Mode::Detached {
+            return;
+        }
+        if self.watches.iter().any(|watch| watch.value.is_none()) {
+            self.update_watches(env, context);
+        }
+
+        let at_breakpoint = self.breakpoints.iter().any(|breakpoint| match breakpoint {
+            Breakpoint::Line(breakpoint_line) => starts_line && *breakpoint_line == line,
+            Breakpoint::Function(_) => entered_function
+                .as_ref()
+                .map_or(false, |function| breakpoint.matches_function(function)),
+        });
+        let is_list = matches!(expr.expr, List(_));
+        let stop = at_breakpoint
+            || match self.mode {
+                Mode::Step => is_list,
+                Mode::Next(depth) => is_list && self.exprs.len() <= depth,
+                Mode::Finish(depth) => is_list && self.function_depth < depth,
+                Mode::Continue | Mode::Detached => false,
+            };
+        if stop {
+            self.stop_depth = self.exprs.len();
+            self.show_stop(env, expr);
+            self.prompt(env, context);
+        }
+    }
+
+    fn did_finish_eval(
+        &mut self,
+        env: &mut Environment,
+        context: &LocalContext,
+        expr: &SymbolicExpression,
+        res: &Result<Value, Error>,
+    ) {
+        let contract = &env.contract_context.contract_identifier;
+        // An expression that fails before it is evaluated is never reported as finished, so
+        // look past any such expressions.
+        if let Some(index) = self
+            .exprs
+            .iter()
+            .rposition(|pending| pending.expr_id == expr.id && &pending.contract == contract)
+        {
+            self.exprs.truncate(index);
+        }
+        self.function_depth = user_functions(env).len();
+
+        if self.mode == Mode::Detached
+            || self.watches.is_empty()
+            || res.is_err()
+            || !writes_state(expr)
+        {
+            return;
+        }
+        let changes = self.update_watches(env, context);
+        if !changes.is_empty() {
+            for change in changes {
+                self.print(&format!("Watch changed: {}", change));
+            }
+            // the next expression is a sibling of this one
+            self.stop_depth = self.exprs.len() + 1;
+            self.show_stop(env, expr);
+            self.prompt(env, context);
+        }
+    }
+
+    fn did_complete(&mut self, _result: core::result::Result<&mut ExecutionResult, String>) {}
+}
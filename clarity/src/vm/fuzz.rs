@@ -0,0 +1,330 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Generation and shrinking of random well-typed values and call sequences, used to
+//! property-test a contract's public functions against its invariants.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use stacks_common::types::StacksEpochId;
+
+use crate::vm::types::{
+    CharType, OptionalData, PrincipalData, SequenceData, SequenceSubtype, StringSubtype, TupleData,
+    TypeSignature, Value,
+};
+
+/// Generated buffers, strings and lists have at most this many elements, which keeps calls
+/// cheap and failures readable
+pub const MAX_GENERATED_LENGTH: u32 = 8;
+
+/// Characters used for generated `string-utf8` values, including multi-byte ones
+const UTF8_CHARS: &[char] = &['a', 'Z', '0', ' ', 'é', 'ß', '→', '字', '🦊'];
+
+/// Edge cases tried for `int` values
+const INT_EDGES: &[i128] = &[0, 1, -1, i128::MAX, i128::MIN];
+
+/// Edge cases tried for `uint` values
+const UINT_EDGES: &[u128] = &[0, 1, u128::MAX];
+
+fn random_length<R: Rng>(rng: &mut R, max_len: u32) -> usize {
+    rng.gen_range(0..=max_len.min(MAX_GENERATED_LENGTH)) as usize
+}
+
+/// Generate a random value of type `type_sig`. Integers are often taken from the edges of
+/// their range, and principals are chosen from `principals`. Fails for types which have no
+/// values that can be passed as arguments, like traits.
+pub fn random_value<R: Rng>(
+    rng: &mut R,
+    epoch: &StacksEpochId,
+    type_sig: &TypeSignature,
+    principals: &[PrincipalData],
+) -> Result<Value, String> {
+    let value = match type_sig {
+        TypeSignature::IntType => Ok(Value::Int(match rng.gen_range(0..4) {
+            0 => *INT_EDGES.choose(rng).expect("non-empty"),
+            1 => rng.gen_range(-100..=100),
+            _ => rng.gen(),
+        })),
+        TypeSignature::UIntType => Ok(Value::UInt(match rng.gen_range(0..4) {
+            0 => *UINT_EDGES.choose(rng).expect("non-empty"),
+            1 => rng.gen_range(0..=100),
+            _ => rng.gen(),
+        })),
+        TypeSignature::BoolType => Ok(Value::Bool(rng.gen())),
+        TypeSignature::PrincipalType => {
+            let principal = principals
+                .choose(rng)
+                .ok_or("No principals to choose from")?;
+            Ok(Value::Principal(principal.clone()))
+        }
+        TypeSignature::SequenceType(SequenceSubtype::BufferType(len)) => {
+            let len = random_length(rng, u32::from(len));
+            Value::buff_from((0..len).map(|_| rng.gen()).collect())
+        }
+        TypeSignature::SequenceType(SequenceSubtype::StringType(StringSubtype::ASCII(len))) => {
+            let len = random_length(rng, u32::from(len));
+            Value::string_ascii_from_bytes((0..len).map(|_| rng.gen_range(0x20..0x7f)).collect())
+        }
+        TypeSignature::SequenceType(SequenceSubtype::StringType(StringSubtype::UTF8(len))) => {
+            let len = random_length(rng, u32::from(len));
+            let string: String = (0..len)
+                .map(|_| *UTF8_CHARS.choose(rng).expect("non-empty"))
+                .collect();
+            Value::string_utf8_from_bytes(string.into_bytes())
+        }
+        TypeSignature::SequenceType(SequenceSubtype::ListType(list_type)) => {
+            let len = random_length(rng, list_type.get_max_len());
+            let items = (0..len)
+                .map(|_| random_value(rng, epoch, list_type.get_list_item_type(), principals))
+                .collect::<Result<Vec<_>, _>>()?;
+            Value::list_with_type(epoch, items, list_type.clone())
+        }
+        TypeSignature::TupleType(tuple_type) => {
+            let data = tuple_type
+                .get_type_map()
+                .iter()
+                .map(|(name, field_type)| {
+                    Ok((
+                        name.clone(),
+                        random_value(rng, epoch, field_type, principals)?,
+                    ))
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+            TupleData::from_data_typed(epoch, data, tuple_type).map(Value::from)
+        }
+        TypeSignature::OptionalType(inner_type) => {
+            if rng.gen_bool(0.25) {
+                Ok(Value::none())
+            } else {
+                Value::some(random_value(rng, epoch, inner_type, principals)?)
+            }
+        }
+        TypeSignature::ResponseType(types) => {
+            if rng.gen() {
+                Value::okay(random_value(rng, epoch, &types.0, principals)?)
+            } else {
+                Value::error(random_value(rng, epoch, &types.1, principals)?)
+            }
+        }
+        TypeSignature::NoType
+        | TypeSignature::CallableType(_)
+        | TypeSignature::ListUnionType(_)
+        | TypeSignature::TraitReferenceType(_) => {
+            return Err(format!("Cannot generate values of type {type_sig}"));
+        }
+    };
+    value.map_err(|e| e.to_string())
+}
+
+/// Shorter prefixes of `items`, shortest first
+fn shorter_prefixes<T: Clone>(items: &[T]) -> Vec<Vec<T>> {
+    let mut prefixes: Vec<Vec<T>> = vec![];
+    for len in [0, items.len() / 2, items.len().saturating_sub(1)] {
+        if len < items.len() && prefixes.last().map_or(true, |prefix| prefix.len() < len) {
+            prefixes.push(items[..len].to_vec());
+        }
+    }
+    prefixes
+}
+
+/// Simpler values of type `type_sig` than `value`, simplest first. Integers shrink towards
+/// zero, sequences towards their shorter prefixes, and optionals and responses towards their
+/// contents.
+pub fn shrink_value(epoch: &StacksEpochId, type_sig: &TypeSignature, value: &Value) -> Vec<Value> {
+    let candidates = match (type_sig, value) {
+        (_, Value::Int(i)) if *i != 0 => {
+            let mut candidates = vec![Ok(Value::Int(0))];
+            if i / 2 != 0 {
+                candidates.push(Ok(Value::Int(i / 2)));
+            }
+            candidates
+        }
+        (_, Value::UInt(u)) if *u != 0 => {
+            let mut candidates = vec![Ok(Value::UInt(0))];
+            if u / 2 != 0 {
+                candidates.push(Ok(Value::UInt(u / 2)));
+            }
+            candidates
+        }
+        (_, Value::Bool(true)) => vec![Ok(Value::Bool(false))],
+        (_, Value::Sequence(SequenceData::Buffer(buff))) => shorter_prefixes(&buff.data)
+            .into_iter()
+            .map(Value::buff_from)
+            .collect(),
+        (_, Value::Sequence(SequenceData::String(CharType::ASCII(string)))) => {
+            shorter_prefixes(&string.data)
+                .into_iter()
+                .map(Value::string_ascii_from_bytes)
+                .collect()
+        }
+        (_, Value::Sequence(SequenceData::String(CharType::UTF8(string)))) => {
+            shorter_prefixes(&string.data)
+                .into_iter()
+                .map(|chars| Value::string_utf8_from_bytes(chars.concat()))
+                .collect()
+        }
+        (
+            TypeSignature::SequenceType(SequenceSubtype::ListType(list_type)),
+            Value::Sequence(SequenceData::List(list)),
+        ) => {
+            let mut candidates: Vec<_> = shorter_prefixes(&list.data)
+                .into_iter()
+                .map(|items| Value::list_with_type(epoch, items, list_type.clone()))
+                .collect();
+            for (i, item) in list.data.iter().enumerate() {
+                for smaller in shrink_value(epoch, list_type.get_list_item_type(), item) {
+                    let mut items = list.data.clone();
+                    items[i] = smaller;
+                    candidates.push(Value::list_with_type(epoch, items, list_type.clone()));
+                }
+            }
+            candidates
+        }
+        (TypeSignature::TupleType(tuple_type), Value::Tuple(tuple)) => {
+            let mut candidates = vec![];
+            for (name, field_type) in tuple_type.get_type_map() {
+                let Some(field) = tuple.data_map.get(name) else {
+                    continue;
+                };
+                for smaller in shrink_value(epoch, field_type, field) {
+                    let mut data: Vec<_> = tuple
+                        .data_map
+                        .iter()
+                        .map(|(name, value)| (name.clone(), value.clone()))
+                        .collect();
+                    if let Some(entry) = data.iter_mut().find(|(field_name, _)| field_name == name)
+                    {
+                        entry.1 = smaller;
+                    }
+                    candidates
+                        .push(TupleData::from_data_typed(epoch, data, tuple_type).map(Value::from));
+                }
+            }
+            candidates
+        }
+        (
+            TypeSignature::OptionalType(inner_type),
+            Value::Optional(OptionalData { data: Some(inner) }),
+        ) => {
+            let mut candidates = vec![Ok(Value::none())];
+            candidates.extend(
+                shrink_value(epoch, inner_type, inner)
+                    .into_iter()
+                    .map(Value::some),
+            );
+            candidates
+        }
+        (TypeSignature::ResponseType(types), Value::Response(response)) => {
+            if response.committed {
+                shrink_value(epoch, &types.0, &response.data)
+                    .into_iter()
+                    .map(Value::okay)
+                    .collect()
+            } else {
+                shrink_value(epoch, &types.1, &response.data)
+                    .into_iter()
+                    .map(Value::error)
+                    .collect()
+            }
+        }
+        _ => vec![],
+    };
+    candidates.into_iter().filter_map(Result::ok).collect()
+}
+
+/// A call to a public function made by the fuzzer
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzCall {
+    pub sender: PrincipalData,
+    pub function: String,
+    pub arguments: Vec<Value>,
+}
+
+impl fmt::Display for FuzzCall {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}", self.function)?;
+        for argument in self.arguments.iter() {
+            write!(f, " {argument}")?;
+        }
+        write!(f, ") from {}", self.sender)
+    }
+}
+
+/// Shrink a sequence of calls for which `fails` returns true into a smaller one for which it
+/// still does, first by dropping calls and then by simplifying their arguments.
+/// `argument_types` maps each function to the types of its arguments. At most `max_attempts`
+/// candidate sequences are tried, so the result may not be minimal.
+pub fn shrink_calls<F>(
+    epoch: &StacksEpochId,
+    mut calls: Vec<FuzzCall>,
+    argument_types: &HashMap<String, Vec<TypeSignature>>,
+    max_attempts: usize,
+    mut fails: F,
+) -> Vec<FuzzCall>
+where
+    F: FnMut(&[FuzzCall]) -> bool,
+{
+    let mut attempts = 0;
+    loop {
+        let mut progress = false;
+
+        let mut i = 0;
+        while i < calls.len() {
+            if attempts >= max_attempts {
+                return calls;
+            }
+            attempts += 1;
+            let mut candidate = calls.clone();
+            candidate.remove(i);
+            if fails(&candidate) {
+                calls = candidate;
+                progress = true;
+            } else {
+                i += 1;
+            }
+        }
+
+        for c in 0..calls.len() {
+            for a in 0..calls[c].arguments.len() {
+                let Some(type_sig) = argument_types
+                    .get(&calls[c].function)
+                    .and_then(|types| types.get(a))
+                else {
+                    continue;
+                };
+                for smaller in shrink_value(epoch, type_sig, &calls[c].arguments[a]) {
+                    if attempts >= max_attempts {
+                        return calls;
+                    }
+                    attempts += 1;
+                    let mut candidate = calls.clone();
+                    candidate[c].arguments[a] = smaller;
+                    if fails(&candidate) {
+                        calls = candidate;
+                        progress = true;
+                        break;
+                    }
+                }
+            }
+        }
+
+        if !progress {
+            return calls;
+        }
+    }
+}
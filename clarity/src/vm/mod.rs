@@ -38,6 +38,9 @@ pub mod docs;
 pub mod version;
 
 pub mod coverage;
+pub mod debugger;
+pub mod fuzz;
+pub mod trace;
 
 pub mod events;
 
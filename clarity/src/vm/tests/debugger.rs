@@ -0,0 +1,157 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::io::Cursor;
+
+#[cfg(any(test, feature = "testing"))]
+use rstest::rstest;
+use stacks_common::types::StacksEpochId;
+
+use crate::vm::ast::ASTRules;
+use crate::vm::debugger::{Breakpoint, Debugger};
+use crate::vm::tests::{env_factory, MemoryEnvironmentGenerator};
+use crate::vm::types::QualifiedContractIdentifier;
+use crate::vm::{ClarityVersion, ContractContext, SymbolicExpression, Value};
+
+const COUNTER_CONTRACT: &str = "(define-data-var counter uint u0)
+    (define-private (double (x uint)) (* x u2))
+    (define-public (increment (by uint))
+      (let ((amount (double by)))
+        (var-set counter (+ (var-get counter) amount))
+        (ok (var-get counter))))";
+
+/// Call `(increment u3)` under a debugger which runs `commands`, and return the call's result
+/// along with everything the debugger printed
+fn debug_increment(
+    mut env_factory: MemoryEnvironmentGenerator,
+    configure: impl FnOnce(&mut Debugger<Cursor<&str>, Vec<u8>>),
+    commands: &str,
+) -> (Value, String) {
+    let contract_id = QualifiedContractIdentifier::local("counter").unwrap();
+    let mut placeholder_context = ContractContext::new(
+        QualifiedContractIdentifier::transient(),
+        ClarityVersion::Clarity2,
+    );
+    let mut debugger = Debugger::new(Cursor::new(commands), Vec::new());
+    configure(&mut debugger);
+
+    let mut owned_env = env_factory.get_env(StacksEpochId::latest());
+    {
+        let mut env = owned_env.get_exec_environment(None, None, &mut placeholder_context);
+        env.initialize_contract(
+            contract_id.clone(),
+            COUNTER_CONTRACT,
+            ASTRules::PrecheckSize,
+        )
+        .unwrap();
+    }
+
+    owned_env.add_eval_hook(&mut debugger);
+    let result = {
+        let mut env = owned_env.get_exec_environment(None, None, &mut placeholder_context);
+        env.execute_contract(
+            &contract_id,
+            "increment",
+            &[SymbolicExpression::atom_value(Value::UInt(3))],
+            false,
+        )
+        .unwrap()
+    };
+    drop(owned_env);
+
+    let output = String::from_utf8(debugger.into_output()).unwrap();
+    (result, output)
+}
+
+/// The expressions the debugger stopped at, in order
+fn stops(output: &str) -> Vec<&str> {
+    let lines: Vec<_> = output.lines().collect();
+    lines
+        .iter()
+        .zip(lines.iter().skip(1))
+        .filter(|(line, _)| line.contains("Stopped "))
+        .map(|(_, expr)| expr.trim())
+        .collect()
+}
+
+#[test]
+fn test_parse_breakpoint() {
+    assert_eq!("12".parse::<Breakpoint>(), Ok(Breakpoint::Line(12)));
+    assert_eq!(
+        " double ".parse::<Breakpoint>(),
+        Ok(Breakpoint::Function("double".into()))
+    );
+    assert!("0".parse::<Breakpoint>().is_err());
+    assert!("".parse::<Breakpoint>().is_err());
+}
+
+#[rstest]
+fn test_debugger_stepping(env_factory: MemoryEnvironmentGenerator) {
+    let (result, output) = debug_increment(env_factory, |_| {}, "s\ns\nn\nn\n");
+    assert_eq!(result, Value::okay(Value::UInt(6)).unwrap());
+    assert_eq!(
+        stops(&output),
+        vec![
+            "(let ((amount (double by))) (var-set counter (+ (var-get counter) amount)) (o...",
+            "(double by)",
+            "(* x u2)",
+            "(var-set counter (+ (var-get counter) amount))",
+            "(ok (var-get counter))",
+        ]
+    );
+}
+
+#[rstest]
+fn test_debugger_breakpoints(env_factory: MemoryEnvironmentGenerator) {
+    let (result, output) = debug_increment(
+        env_factory,
+        |debugger| debugger.add_breakpoint(Breakpoint::Function("double".into())),
+        "c\nlocals\nbt\nf\nlocals\nd double\nc\n",
+    );
+    assert_eq!(result, Value::okay(Value::UInt(6)).unwrap());
+    assert_eq!(
+        stops(&output),
+        vec![
+            "(let ((amount (double by))) (var-set counter (+ (var-get counter) amount)) (o...",
+            "(* x u2)",
+            "(var-set counter (+ (var-get counter) amount))",
+        ]
+    );
+    assert!(output
+        .contains("(debug) Stopped in S1G2081040G2081040G2081040G208105NK8PE5.counter:double\n"));
+    assert!(output.contains("(debug)   x = u3\n"));
+    assert!(output.contains(
+        "(debug)   #0 S1G2081040G2081040G2081040G208105NK8PE5.counter:double\n  #1 S1G2081040G2081040G2081040G208105NK8PE5.counter:increment\n"
+    ));
+    // after `double` returns, the `let` bindings are in scope along with the arguments
+    assert!(output.contains("(debug)   amount = u6\n  by = u3\n"));
+    assert!(output.contains("Breakpoint at function double deleted"));
+}
+
+#[rstest]
+fn test_debugger_watches(env_factory: MemoryEnvironmentGenerator) {
+    let (result, output) = debug_increment(
+        env_factory,
+        |debugger| debugger.add_watch("(var-get counter)"),
+        "c\np (+ amount u1)\np (var-set counter u100)\nw (is-eq (var-get counter) u6)\nc\n",
+    );
+    // the debugger can't change the contract's state
+    assert_eq!(result, Value::okay(Value::UInt(6)).unwrap());
+    assert!(output.contains("  watch (var-get counter) = u0\n"));
+    assert!(output.contains("Watch changed: (var-get counter): u0 -> u6\n"));
+    assert!(output.contains("(debug) u7\n"));
+    assert!(output.contains("(debug) <error: "));
+    assert!(output.contains("Watching (is-eq (var-get counter) u6) = true\n"));
+}
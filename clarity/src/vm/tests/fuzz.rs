@@ -0,0 +1,126 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::HashMap;
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use stacks_common::types::StacksEpochId;
+
+use crate::vm::fuzz::{random_value, shrink_calls, shrink_value, FuzzCall};
+use crate::vm::types::{PrincipalData, StandardPrincipalData, TypeSignature, Value};
+use crate::vm::ClarityVersion;
+
+const TYPES: &[&str] = &[
+    "int",
+    "uint",
+    "bool",
+    "principal",
+    "(buff 3)",
+    "(string-ascii 20)",
+    "(string-utf8 4)",
+    "(list 3 (optional (list 2 int)))",
+    "(tuple (a uint) (b (response (string-ascii 1) bool)))",
+];
+
+fn type_from_string(type_repr: &str) -> TypeSignature {
+    TypeSignature::from_string(type_repr, ClarityVersion::Clarity3, StacksEpochId::latest())
+}
+
+#[test]
+fn test_random_values_are_well_typed() {
+    let epoch = StacksEpochId::latest();
+    let mut rng = ChaCha8Rng::seed_from_u64(1);
+    let principals = vec![PrincipalData::Standard(StandardPrincipalData::transient())];
+    for type_repr in TYPES {
+        let type_sig = type_from_string(type_repr);
+        for _ in 0..50 {
+            let value = random_value(&mut rng, &epoch, &type_sig, &principals).unwrap();
+            assert!(
+                type_sig.admits(&epoch, &value).unwrap(),
+                "{value} is not a {type_repr}"
+            );
+            for smaller in shrink_value(&epoch, &type_sig, &value) {
+                assert!(type_sig.admits(&epoch, &smaller).unwrap());
+                assert_ne!(smaller, value);
+            }
+        }
+    }
+
+    assert!(random_value(&mut rng, &epoch, &TypeSignature::PrincipalType, &[]).is_err());
+}
+
+#[test]
+fn test_shrink_value() {
+    let epoch = StacksEpochId::latest();
+    assert_eq!(
+        shrink_value(&epoch, &TypeSignature::IntType, &Value::Int(-9)),
+        vec![Value::Int(0), Value::Int(-4)]
+    );
+    assert!(shrink_value(&epoch, &TypeSignature::UIntType, &Value::UInt(0)).is_empty());
+
+    let list_type = type_from_string("(list 4 uint)");
+    let list = Value::cons_list_unsanitized(vec![Value::UInt(3), Value::UInt(0)]).unwrap();
+    let shrunk: Vec<_> = shrink_value(&epoch, &list_type, &list)
+        .iter()
+        .map(|value| value.to_string())
+        .collect();
+    assert_eq!(shrunk, vec!["()", "(u3)", "(u0 u0)", "(u1 u0)"]);
+    assert_eq!(
+        shrink_value(&epoch, &TypeSignature::UIntType, &Value::UInt(1)),
+        vec![Value::UInt(0)]
+    );
+}
+
+#[test]
+fn test_shrink_calls() {
+    let epoch = StacksEpochId::latest();
+    let sender = PrincipalData::Standard(StandardPrincipalData::transient());
+    let call = |function: &str, amount| FuzzCall {
+        sender: sender.clone(),
+        function: function.into(),
+        arguments: vec![Value::UInt(amount)],
+    };
+    let argument_types = HashMap::from([
+        ("deposit".to_string(), vec![TypeSignature::UIntType]),
+        ("withdraw".to_string(), vec![TypeSignature::UIntType]),
+    ]);
+
+    // fails whenever a deposit of more than 10 is followed by any withdrawal
+    let fails = |calls: &[FuzzCall]| {
+        calls.iter().enumerate().any(|(i, deposit)| {
+            deposit.function == "deposit"
+                && matches!(deposit.arguments[0], Value::UInt(amount) if amount > 10)
+                && calls[i + 1..]
+                    .iter()
+                    .any(|call| call.function == "withdraw")
+        })
+    };
+    let calls = vec![
+        call("withdraw", 5),
+        call("deposit", 1000),
+        call("deposit", 3),
+        call("withdraw", 77),
+        call("deposit", 2),
+    ];
+    assert!(fails(&calls));
+
+    let minimal = shrink_calls(&epoch, calls, &argument_types, 1000, fails);
+    assert_eq!(minimal, vec![call("deposit", 15), call("withdraw", 0)]);
+    assert_eq!(
+        minimal[1].to_string(),
+        format!("(withdraw u0) from {sender}")
+    );
+}
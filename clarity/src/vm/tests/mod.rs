@@ -27,11 +27,15 @@ use crate::vm::types::Value;
 mod assets;
 mod contracts;
 mod datamaps;
+mod debugger;
 mod defines;
+#[cfg(test)]
+mod fuzz;
 mod principals;
 mod sequences;
 #[cfg(test)]
 mod simple_apply_eval;
+mod trace;
 mod traits;
 mod variables;
 
@@ -0,0 +1,119 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+#[cfg(any(test, feature = "testing"))]
+use rstest::rstest;
+use stacks_common::types::StacksEpochId;
+
+use crate::vm::ast::ASTRules;
+use crate::vm::tests::{env_factory, test_epochs, MemoryEnvironmentGenerator};
+use crate::vm::trace::{CallTracer, StateRead};
+use crate::vm::types::{PrincipalData, QualifiedContractIdentifier};
+use crate::vm::{ClarityVersion, ContractContext, SymbolicExpression, Value};
+
+const COUNTER_CONTRACT: &str = "(define-data-var counter uint u3)
+    (define-read-only (get-counter) (var-get counter))";
+
+const SUMMARY_CONTRACT: &str = "(define-map balances principal uint)
+    (map-set balances 'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR u10)
+    (define-private (double (x uint)) (* x u2))
+    (define-read-only (summary (who principal))
+      (+ (double (contract-call? .counter get-counter))
+         (default-to u0 (map-get? balances who))))";
+
+#[apply(test_epochs)]
+fn test_call_tracer(epoch: StacksEpochId, mut env_factory: MemoryEnvironmentGenerator) {
+    let counter_id = QualifiedContractIdentifier::local("counter").unwrap();
+    let summary_id = QualifiedContractIdentifier::local("summary").unwrap();
+    let who =
+        Value::from(PrincipalData::parse("SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR").unwrap());
+
+    let mut placeholder_context = ContractContext::new(
+        QualifiedContractIdentifier::transient(),
+        ClarityVersion::Clarity1,
+    );
+    let mut tracer = CallTracer::new();
+
+    let mut owned_env = env_factory.get_env(epoch);
+    {
+        let mut env = owned_env.get_exec_environment(None, None, &mut placeholder_context);
+        env.initialize_contract(counter_id.clone(), COUNTER_CONTRACT, ASTRules::PrecheckSize)
+            .unwrap();
+        env.initialize_contract(summary_id.clone(), SUMMARY_CONTRACT, ASTRules::PrecheckSize)
+            .unwrap();
+    }
+
+    owned_env.add_eval_hook(&mut tracer);
+    let cost_before = owned_env.get_cost_total();
+    let result = {
+        let mut env = owned_env.get_exec_environment(None, None, &mut placeholder_context);
+        env.execute_contract(
+            &summary_id,
+            "summary",
+            &[SymbolicExpression::atom_value(who.clone())],
+            false,
+        )
+    };
+    assert_eq!(result, Ok(Value::UInt(16)));
+    let mut execution_cost = owned_env.get_cost_total();
+    execution_cost.sub(&cost_before).unwrap();
+    drop(owned_env);
+
+    let trace = tracer.into_trace(
+        &summary_id,
+        "summary",
+        std::slice::from_ref(&who),
+        &result,
+        execution_cost,
+    );
+    assert_eq!(trace.contract, summary_id.to_string());
+    assert_eq!(trace.function, "summary");
+    assert_eq!(trace.arguments, vec![who.to_string()]);
+    assert_eq!(trace.result.as_deref(), Some("u16"));
+    assert!(trace.error.is_none());
+
+    // the map read is made by `summary` itself
+    assert_eq!(
+        trace.reads,
+        vec![StateRead::MapEntry {
+            name: "balances".into(),
+            key: who.to_string(),
+            value: "(some u10)".into(),
+        }]
+    );
+
+    // the contract-call is made by `summary` to compute the argument to `double`
+    assert_eq!(trace.calls.len(), 2);
+    let get_counter = &trace.calls[0];
+    assert_eq!(get_counter.contract, counter_id.to_string());
+    assert_eq!(get_counter.function, "get-counter");
+    assert!(get_counter.arguments.is_empty());
+    assert_eq!(get_counter.result.as_deref(), Some("u3"));
+    assert_eq!(
+        get_counter.reads,
+        vec![StateRead::DataVar {
+            name: "counter".into(),
+            value: "u3".into(),
+        }]
+    );
+
+    let double = &trace.calls[1];
+    assert_eq!(double.contract, summary_id.to_string());
+    assert_eq!(double.function, "double");
+    assert_eq!(double.arguments, vec!["u3".to_string()]);
+    assert_eq!(double.result.as_deref(), Some("u6"));
+    assert!(double.reads.is_empty());
+    assert!(double.calls.is_empty());
+}
@@ -0,0 +1,339 @@
+// Copyright (C) 2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! An `EvalHook` which records the tree of function calls made while evaluating Clarity code,
+//! along with each call's arguments, return value, data-var and map reads, and cost.
+
+use super::EvalHook;
+use crate::vm::contexts::{Environment, LocalContext};
+use crate::vm::costs::ExecutionCost;
+use crate::vm::errors::Error;
+use crate::vm::representations::SymbolicExpressionType::{Atom, List, LiteralValue};
+use crate::vm::types::{PrincipalData, QualifiedContractIdentifier};
+use crate::vm::{ExecutionResult, SymbolicExpression, Value};
+
+/// A read of contract state made by a function
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StateRead {
+    /// `(var-get name)`
+    DataVar { name: String, value: String },
+    /// `(map-get? name key)`
+    MapEntry {
+        name: String,
+        key: String,
+        value: String,
+    },
+}
+
+/// One function call in a call tree.  Clarity values are given in their Clarity syntax.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CallTraceFrame {
+    pub contract: String,
+    pub function: String,
+    pub arguments: Vec<String>,
+    /// The return value, if the call succeeded
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<String>,
+    /// Why the call failed, if it failed
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Reads made by this function itself, in the order they were made
+    pub reads: Vec<StateRead>,
+    /// Calls made by this function, in the order they were made
+    pub calls: Vec<CallTraceFrame>,
+    /// Cost of this call, including the calls it made
+    pub execution_cost: ExecutionCost,
+}
+
+/// An expression whose evaluation has begun but not finished
+struct PendingExpr {
+    contract: QualifiedContractIdentifier,
+    expr_id: u64,
+    /// Whether the values of the sub-expressions are needed
+    record_values: bool,
+    values: Vec<Value>,
+}
+
+/// A function call whose evaluation has begun but not finished
+struct PendingCall {
+    /// Index of the call's expression in `CallTracer::exprs`
+    expr_index: usize,
+    contract: QualifiedContractIdentifier,
+    function: String,
+    arg_count: usize,
+    cost_at_start: ExecutionCost,
+    reads: Vec<StateRead>,
+    calls: Vec<CallTraceFrame>,
+}
+
+/// Builds a `CallTraceFrame` tree for the calls to user-defined functions (including through
+/// `contract-call?`) made by the code it observes.
+#[derive(Default)]
+pub struct CallTracer {
+    exprs: Vec<PendingExpr>,
+    calls: Vec<PendingCall>,
+    /// Reads made outside of any traced call
+    reads: Vec<StateRead>,
+    /// Calls made outside of any traced call
+    top_level_calls: Vec<CallTraceFrame>,
+}
+
+fn cost_since(start: &ExecutionCost, now: ExecutionCost) -> ExecutionCost {
+    let mut cost = now;
+    match cost.sub(start) {
+        Ok(()) => cost,
+        Err(_) => ExecutionCost::ZERO,
+    }
+}
+
+fn result_strings(result: &Result<Value, Error>) -> (Option<String>, Option<String>) {
+    match result {
+        Ok(value) => (Some(value.to_string()), None),
+        Err(e) => (None, Some(e.to_string())),
+    }
+}
+
+impl CallTracer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Make the call tree for a call to `function` in `contract` whose evaluation this tracer
+    /// observed.  The calls and reads it observed become the children of this call.
+    pub fn into_trace(
+        mut self,
+        contract: &QualifiedContractIdentifier,
+        function: &str,
+        arguments: &[Value],
+        result: &Result<Value, Error>,
+        execution_cost: ExecutionCost,
+    ) -> CallTraceFrame {
+        // close out any calls that were interrupted by an error
+        while let Some(call) = self.calls.pop() {
+            self.finish_call(call, Vec::new(), None);
+        }
+        let (result, error) = result_strings(result);
+        CallTraceFrame {
+            contract: contract.to_string(),
+            function: function.to_string(),
+            arguments: arguments.iter().map(|arg| arg.to_string()).collect(),
+            result,
+            error,
+            reads: self.reads,
+            calls: self.top_level_calls,
+            execution_cost,
+        }
+    }
+
+    /// If `expr` calls a user-defined function, either directly or with `contract-call?`, then
+    /// return the contract and function that it calls, and the number of arguments it passes.
+    fn callee(
+        env: &Environment,
+        context: &LocalContext,
+        expr: &SymbolicExpression,
+    ) -> Option<(QualifiedContractIdentifier, String, usize)> {
+        let List(ref children) = expr.expr else {
+            return None;
+        };
+        let (head, rest) = children.split_first()?;
+        let name = head.match_atom()?;
+        if name.as_str() == "contract-call?" {
+            let contract = match rest.first().map(|arg| &arg.expr) {
+                Some(LiteralValue(Value::Principal(PrincipalData::Contract(contract)))) => {
+                    contract.clone()
+                }
+                Some(Atom(contract_ref)) => context
+                    .lookup_callable_contract(contract_ref)?
+                    .contract_identifier
+                    .clone(),
+                _ => return None,
+            };
+            let function = rest.get(1)?.match_atom()?;
+            Some((contract, function.to_string(), rest.len().saturating_sub(2)))
+        } else if env.contract_context.functions.contains_key(name) {
+            Some((
+                env.contract_context.contract_identifier.clone(),
+                name.to_string(),
+                rest.len(),
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Add a finished call to the tree.  `outcome` holds the call's result and the total cost
+    /// when it finished, or None if its evaluation was interrupted.
+    fn finish_call(
+        &mut self,
+        call: PendingCall,
+        mut values: Vec<Value>,
+        outcome: Option<(&Result<Value, Error>, ExecutionCost)>,
+    ) {
+        // the arguments are evaluated first, and then the function body
+        values.truncate(call.arg_count);
+        let ((result, error), execution_cost) = match outcome {
+            Some((result, cost_now)) => (
+                result_strings(result),
+                cost_since(&call.cost_at_start, cost_now),
+            ),
+            None => (
+                (None, Some("Evaluation did not finish".to_string())),
+                ExecutionCost::ZERO,
+            ),
+        };
+        let frame = CallTraceFrame {
+            contract: call.contract.to_string(),
+            function: call.function,
+            arguments: values.iter().map(|arg| arg.to_string()).collect(),
+            result,
+            error,
+            reads: call.reads,
+            calls: call.calls,
+            execution_cost,
+        };
+        match self.active_call() {
+            Some(parent) => parent.calls.push(frame),
+            None => self.top_level_calls.push(frame),
+        }
+    }
+
+    /// The call whose body is being evaluated.  A call's arguments are evaluated on behalf of
+    /// its caller, so a call which is still evaluating its arguments is skipped.
+    fn active_call(&mut self) -> Option<&mut PendingCall> {
+        let exprs = &self.exprs;
+        self.calls.iter_mut().rev().find(|call| {
+            exprs
+                .get(call.expr_index)
+                .map_or(true, |pending| pending.values.len() >= call.arg_count)
+        })
+    }
+
+    fn record_read(&mut self, read: StateRead) {
+        match self.active_call() {
+            Some(call) => call.reads.push(read),
+            None => self.reads.push(read),
+        }
+    }
+}
+
+impl EvalHook for CallTracer {
+    fn will_begin_eval(
+        &mut self,
+        env: &mut Environment,
+        context: &LocalContext,
+        expr: &SymbolicExpression,
+    ) {
+        let List(ref children) = expr.expr else {
+            return;
+        };
+        let callee = Self::callee(env, context, expr);
+        let is_map_get = children
+            .first()
+            .and_then(|head| head.match_atom())
+            .map(|name| name.as_str())
+            == Some("map-get?");
+        self.exprs.push(PendingExpr {
+            contract: env.contract_context.contract_identifier.clone(),
+            expr_id: expr.id,
+            record_values: callee.is_some() || is_map_get,
+            values: Vec::new(),
+        });
+        if let Some((contract, function, arg_count)) = callee {
+            self.calls.push(PendingCall {
+                expr_index: self.exprs.len() - 1,
+                contract,
+                function,
+                arg_count,
+                cost_at_start: env.global_context.cost_track.get_total(),
+                reads: Vec::new(),
+                calls: Vec::new(),
+            });
+        }
+    }
+
+    fn did_finish_eval(
+        &mut self,
+        env: &mut Environment,
+        _context: &LocalContext,
+        expr: &SymbolicExpression,
+        res: &Result<Value, Error>,
+    ) {
+        if let List(ref children) = expr.expr {
+            let contract = &env.contract_context.contract_identifier;
+            // An expression that fails before it is evaluated (e.g. because it names an unknown
+            // function) is never reported as finished, so look past any such expressions.
+            if let Some(index) = self
+                .exprs
+                .iter()
+                .rposition(|pending| pending.expr_id == expr.id && &pending.contract == contract)
+            {
+                let mut values = self
+                    .exprs
+                    .drain(index..)
+                    .next()
+                    .map(|pending| pending.values)
+                    .unwrap_or_default();
+
+                if let (Some(head), Some(name), Ok(value)) = (
+                    children.first().and_then(|head| head.match_atom()),
+                    children.get(1).and_then(|name| name.match_atom()),
+                    res,
+                ) {
+                    match head.as_str() {
+                        "var-get" => self.record_read(StateRead::DataVar {
+                            name: name.to_string(),
+                            value: value.to_string(),
+                        }),
+                        "map-get?" => {
+                            if let Some(key) = values.pop() {
+                                self.record_read(StateRead::MapEntry {
+                                    name: name.to_string(),
+                                    key: key.to_string(),
+                                    value: value.to_string(),
+                                })
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+
+                while let Some(call) = self.calls.pop() {
+                    if call.expr_index > index {
+                        self.finish_call(call, Vec::new(), None);
+                    } else if call.expr_index == index {
+                        let cost_now = env.global_context.cost_track.get_total();
+                        self.finish_call(call, values, Some((res, cost_now)));
+                        break;
+                    } else {
+                        self.calls.push(call);
+                        break;
+                    }
+                }
+            }
+        }
+
+        // pass the value up to the enclosing expression, if it needs it
+        if let (Some(parent), Ok(value)) = (self.exprs.last_mut(), res) {
+            if parent.record_values {
+                parent.values.push(value.clone());
+            }
+        }
+    }
+
+    fn did_complete(&mut self, _result: Result<&mut ExecutionResult, String>) {}
+}
@@ -298,6 +298,45 @@ lazy_static! {
     // Add an index for index block hash in nakamoto block headers
     "CREATE INDEX IF NOT EXISTS index_block_hash ON nakamoto_block_headers(index_block_hash);",
     ];
+
+    pub static ref NAKAMOTO_CHAINSTATE_SCHEMA_6: [&'static str; 8] = [
+    r#"
+        UPDATE db_config SET version = "9";
+    "#,
+    // Store the rest of each transaction's receipt in the transaction index, so it can be
+    // served over RPC.  These columns are NULL for rows logged before this migration.
+    "ALTER TABLE transactions ADD COLUMN tx_index INTEGER;",
+    "ALTER TABLE transactions ADD COLUMN raw_result TEXT;",
+    "ALTER TABLE transactions ADD COLUMN post_condition_aborted INTEGER;",
+    "ALTER TABLE transactions ADD COLUMN vm_error TEXT;",
+    "ALTER TABLE transactions ADD COLUMN stx_burned TEXT;",
+    "ALTER TABLE transactions ADD COLUMN execution_cost TEXT;",
+    "ALTER TABLE transactions ADD COLUMN events TEXT;",
+    ];
+
+    pub static ref NAKAMOTO_CHAINSTATE_SCHEMA_7: [&'static str; 4] = [
+    r#"
+        UPDATE db_config SET version = "10";
+    "#,
+    // Index of contract events emitted by processed transactions, so they can be queried
+    // by contract and topic over RPC.  Only populated if the event index is enabled.
+    r#"
+    CREATE TABLE contract_events(
+        contract_identifier TEXT NOT NULL,
+        topic TEXT NOT NULL,
+        block_height INTEGER NOT NULL,
+        index_block_hash TEXT NOT NULL,
+        txid TEXT NOT NULL,
+        tx_index INTEGER NOT NULL,
+        event_index INTEGER NOT NULL,
+        -- hex-encoded Clarity value
+        value TEXT NOT NULL,
+
+        PRIMARY KEY(index_block_hash,txid,event_index)
+    );"#,
+    "CREATE INDEX IF NOT EXISTS contract_events_by_contract ON contract_events(contract_identifier,block_height,topic);",
+    "CREATE INDEX IF NOT EXISTS contract_events_by_txid ON contract_events(txid);",
+    ];
 }
 
 #[cfg(test)]
@@ -4646,6 +4685,7 @@ impl NakamotoChainState {
 
         let new_block_id = new_tip.index_block_hash();
         chainstate_tx.log_transactions_processed(&new_block_id, &tx_receipts);
+        chainstate_tx.log_contract_events(&new_block_id, new_tip.stacks_block_height, &tx_receipts);
 
         let reward_cycle = pox_constants.block_height_to_reward_cycle(
             first_block_height.into(),
@@ -147,6 +147,21 @@ pub enum MemPoolRejection {
     DBError(db_error),
     EstimatorError(EstimatorError),
     TemporarilyBlacklisted,
+    /// The mempool is full, and this transaction's fee rate is too low to evict another
+    MemPoolFull,
+    TooManyPendingTransactions {
+        principal: PrincipalData,
+        max_txs: u64,
+    },
+    /// The node's admission policy rejects this transaction, by the named rule
+    DeniedByPolicy(String),
+    /// The named rule of the node's admission policy has admitted as many transactions as it
+    /// allows for now
+    RateLimitedByPolicy {
+        rule: String,
+        max_txs: u64,
+        window_secs: u64,
+    },
     Other(String),
 }
 
@@ -301,6 +316,25 @@ impl MemPoolRejection {
                 Some(json!({"message": e.to_string()})),
             ),
             TemporarilyBlacklisted => ("TemporarilyBlacklisted", None),
+            MemPoolFull => ("MempoolFull", None),
+            TooManyPendingTransactions { principal, max_txs } => (
+                "TooManyPendingTransactions",
+                Some(json!({
+                    "principal": principal.to_string(),
+                    "max": max_txs})),
+            ),
+            DeniedByPolicy(rule) => ("DeniedByPolicy", Some(json!({ "rule": rule }))),
+            RateLimitedByPolicy {
+                rule,
+                max_txs,
+                window_secs,
+            } => (
+                "RateLimitedByPolicy",
+                Some(json!({
+                    "rule": rule,
+                    "max": max_txs,
+                    "window_secs": window_secs})),
+            ),
             Other(s) => ("ServerFailureOther", Some(json!({ "message": s }))),
         };
         let mut result = json!({
@@ -5858,6 +5892,11 @@ impl StacksChainState {
         .expect("FATAL: failed to advance chain tip");
 
         chainstate_tx.log_transactions_processed(&new_tip.index_block_hash(), &tx_receipts);
+        chainstate_tx.log_contract_events(
+            &new_tip.index_block_hash(),
+            new_tip.stacks_block_height,
+            &tx_receipts,
+        );
 
         // store the reward set calculated during this block if it happened
         // NOTE: miner and proposal evaluation should not invoke this because
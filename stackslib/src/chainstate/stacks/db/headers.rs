@@ -287,6 +287,58 @@ impl StacksChainState {
         }
     }
 
+    /// Get the header of the ancestor of `tip_index_hash` at Stacks block height `height`.
+    /// Works for both Nakamoto and epoch2 blocks.
+    pub fn get_ancestor_header_at_height(
+        &self,
+        tip_index_hash: &StacksBlockId,
+        height: u64,
+    ) -> Result<Option<StacksHeaderInfo>, Error> {
+        match self
+            .index_conn()
+            .get_ancestor_block_hash(height, tip_index_hash)?
+        {
+            Some(block_id) => NakamotoChainState::get_block_header(self.db(), &block_id),
+            None => Ok(None),
+        }
+    }
+
+    /// Get the header of the highest ancestor of `tip_index_hash` (possibly `tip_index_hash`
+    /// itself) which was mined in a burnchain block at or below `burn_block_height`.  This is
+    /// the Stacks chain tip as of that burnchain block.  Returns None if the chain had not
+    /// started by then.  Works for both Nakamoto and epoch2 blocks.
+    pub fn get_ancestor_header_at_burn_height(
+        &self,
+        tip_index_hash: &StacksBlockId,
+        burn_block_height: u64,
+    ) -> Result<Option<StacksHeaderInfo>, Error> {
+        let Some(tip) = NakamotoChainState::get_block_header(self.db(), tip_index_hash)? else {
+            return Ok(None);
+        };
+        if u64::from(tip.burn_header_height) <= burn_block_height {
+            return Ok(Some(tip));
+        }
+
+        // burn header heights never decrease along a fork, so binary-search the ancestors for
+        // the last block mined at or below `burn_block_height`
+        let mut found = None;
+        let mut low = 0;
+        let mut high = tip.stacks_block_height;
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let Some(header) = self.get_ancestor_header_at_height(tip_index_hash, mid)? else {
+                return Ok(None);
+            };
+            if u64::from(header.burn_header_height) <= burn_block_height {
+                found = Some(header);
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        Ok(found)
+    }
+
     /// Get a segment of headers from the canonical chain
     pub fn get_ancestors_headers(
         conn: &Connection,
@@ -56,6 +56,7 @@ use crate::chainstate::nakamoto::{
     HeaderTypeNames, NakamotoBlock, NakamotoBlockHeader, NakamotoChainState,
     NakamotoStagingBlocksConn, NAKAMOTO_CHAINSTATE_SCHEMA_1, NAKAMOTO_CHAINSTATE_SCHEMA_2,
     NAKAMOTO_CHAINSTATE_SCHEMA_3, NAKAMOTO_CHAINSTATE_SCHEMA_4, NAKAMOTO_CHAINSTATE_SCHEMA_5,
+    NAKAMOTO_CHAINSTATE_SCHEMA_6, NAKAMOTO_CHAINSTATE_SCHEMA_7,
 };
 use crate::chainstate::stacks::address::StacksAddressExtensions;
 use crate::chainstate::stacks::boot::*;
@@ -86,8 +87,8 @@ use crate::net::atlas::BNS_CHARS_REGEX;
 use crate::net::Error as net_error;
 use crate::util_lib::boot::{boot_code_acc, boot_code_addr, boot_code_id, boot_code_tx_auth};
 use crate::util_lib::db::{
-    query_count, query_row, tx_begin_immediate, tx_busy_handler, DBConn, DBTx, Error as db_error,
-    FromColumn, FromRow, IndexDBConn, IndexDBTx,
+    query_count, query_row, query_rows, tx_begin_immediate, tx_busy_handler, u64_to_sql, DBConn,
+    DBTx, Error as db_error, FromColumn, FromRow, IndexDBConn, IndexDBTx,
 };
 
 pub mod accounts;
@@ -128,6 +129,12 @@ pub struct StacksChainState {
     pub unconfirmed_state: Option<UnconfirmedState>,
     pub fault_injection: StacksChainStateFaults,
     marf_opts: Option<MARFOpenOpts>,
+    /// If true, then store each processed transaction and its receipt in the `transactions`
+    /// table, so it can be looked up by txid.
+    pub txindex: bool,
+    /// If true, then store each contract event emitted by a processed transaction in the
+    /// `contract_events` table, so it can be looked up by contract and topic.
+    pub event_index: bool,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -612,6 +619,8 @@ pub struct ChainstateTx<'a> {
     pub blocks_path: String,
     pub tx: StacksDBTx<'a>,
     pub root_path: String,
+    pub txindex: bool,
+    pub event_index: bool,
 }
 
 impl<'a> ChainstateTx<'a> {
@@ -620,12 +629,16 @@ impl<'a> ChainstateTx<'a> {
         blocks_path: String,
         root_path: String,
         config: DBConfig,
+        txindex: bool,
+        event_index: bool,
     ) -> ChainstateTx<'a> {
         ChainstateTx {
             config,
             blocks_path,
             tx,
             root_path,
+            txindex,
+            event_index,
         }
     }
 
@@ -646,14 +659,44 @@ impl<'a> ChainstateTx<'a> {
         block_id: &StacksBlockId,
         events: &[StacksTransactionReceipt],
     ) {
-        if *TRANSACTION_LOG {
-            let insert =
-                "INSERT INTO transactions (txid, index_block_hash, tx_hex, result) VALUES (?, ?, ?, ?)";
+        if self.txindex || *TRANSACTION_LOG {
+            let insert = "INSERT INTO transactions
+                (txid, index_block_hash, tx_hex, result, tx_index, raw_result, post_condition_aborted, vm_error, stx_burned, execution_cost, events)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)";
             for tx_event in events.iter() {
                 let txid = tx_event.transaction.txid();
                 let tx_hex = tx_event.transaction.serialize_to_dbstring();
                 let result = tx_event.result.to_string();
-                let params = params![txid, block_id, tx_hex, result];
+                let raw_result = match tx_event.result.serialize_to_hex() {
+                    Ok(hex) => hex,
+                    Err(e) => {
+                        warn!("Failed to serialize TX result: {:?}", &e; "txid" => %txid);
+                        continue;
+                    }
+                };
+                let committed = !tx_event.post_condition_aborted;
+                let tx_events: Vec<_> = tx_event
+                    .events
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(event_index, event)| {
+                        event.json_serialize(event_index, &txid, committed).ok()
+                    })
+                    .collect();
+                let params = params![
+                    txid,
+                    block_id,
+                    tx_hex,
+                    result,
+                    tx_event.tx_index,
+                    raw_result,
+                    tx_event.post_condition_aborted,
+                    tx_event.vm_error,
+                    tx_event.stx_burned.to_string(),
+                    serde_json::to_string(&tx_event.execution_cost)
+                        .expect("FATAL: failed to serialize execution cost"),
+                    serde_json::Value::Array(tx_events).to_string(),
+                ];
                 if let Err(e) = self.tx.tx().execute(insert, params) {
                     warn!("Failed to log TX: {}", e);
                 }
@@ -666,6 +709,59 @@ impl<'a> ChainstateTx<'a> {
             }
         }
     }
+
+    /// Store the contract events emitted by a block's transactions in the event index, if it is
+    /// enabled.
+    pub fn log_contract_events(
+        &self,
+        block_id: &StacksBlockId,
+        block_height: u64,
+        receipts: &[StacksTransactionReceipt],
+    ) {
+        if !self.event_index {
+            return;
+        }
+        let Ok(block_height) = u64_to_sql(block_height) else {
+            warn!("Failed to log contract events: block height out of range"; "block_id" => %block_id);
+            return;
+        };
+        let insert = "INSERT OR REPLACE INTO contract_events
+            (contract_identifier, topic, block_height, index_block_hash, txid, tx_index, event_index, value)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)";
+        for receipt in receipts.iter() {
+            if receipt.post_condition_aborted {
+                // events from aborted transactions were never committed
+                continue;
+            }
+            let txid = receipt.transaction.txid();
+            for (event_index, event) in receipt.events.iter().enumerate() {
+                let StacksTransactionEvent::SmartContractEvent(event_data) = event else {
+                    continue;
+                };
+                let (contract_id, topic) = &event_data.key;
+                let value = match event_data.value.serialize_to_hex() {
+                    Ok(hex) => hex,
+                    Err(e) => {
+                        warn!("Failed to serialize contract event: {:?}", &e; "txid" => %txid);
+                        continue;
+                    }
+                };
+                let params = params![
+                    contract_id.to_string(),
+                    topic,
+                    block_height,
+                    block_id,
+                    txid,
+                    receipt.tx_index,
+                    event_index,
+                    value,
+                ];
+                if let Err(e) = self.tx.tx().execute(insert, params) {
+                    warn!("Failed to log contract event: {}", e; "txid" => %txid);
+                }
+            }
+        }
+    }
 }
 
 impl<'a> Deref for ChainstateTx<'a> {
@@ -681,7 +777,113 @@ impl<'a> DerefMut for ChainstateTx<'a> {
     }
 }
 
-pub const CHAINSTATE_VERSION: &'static str = "8";
+/// A processed transaction and its receipt, as stored in the `transactions` table.
+/// Fields which were added in chainstate schema version 9 are `None` for transactions that were
+/// logged before the node was upgraded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexedTransaction {
+    pub txid: Txid,
+    /// the block in which this transaction was processed
+    pub index_block_hash: StacksBlockId,
+    pub tx_hex: String,
+    /// the Clarity value returned by the transaction, as a string
+    pub result: String,
+    pub tx_index: Option<u32>,
+    /// the consensus-serialized Clarity value returned by the transaction
+    pub raw_result: Option<String>,
+    pub post_condition_aborted: Option<bool>,
+    pub vm_error: Option<String>,
+    pub stx_burned: Option<u128>,
+    pub execution_cost: Option<ExecutionCost>,
+    /// the events emitted by the transaction, encoded as they are for event observers
+    pub events: Option<serde_json::Value>,
+}
+
+impl FromRow<IndexedTransaction> for IndexedTransaction {
+    fn from_row<'a>(row: &'a Row) -> Result<IndexedTransaction, db_error> {
+        let txid = Txid::from_column(row, "txid")?;
+        let index_block_hash = StacksBlockId::from_column(row, "index_block_hash")?;
+        let tx_hex: String = row.get_unwrap("tx_hex");
+        let result: String = row.get_unwrap("result");
+        let tx_index: Option<u32> = row.get_unwrap("tx_index");
+        let raw_result: Option<String> = row.get_unwrap("raw_result");
+        let post_condition_aborted: Option<bool> = row.get_unwrap("post_condition_aborted");
+        let vm_error: Option<String> = row.get_unwrap("vm_error");
+
+        let stx_burned_str: Option<String> = row.get_unwrap("stx_burned");
+        let stx_burned = stx_burned_str
+            .map(|burned| burned.parse::<u128>().map_err(|_| db_error::ParseError))
+            .transpose()?;
+
+        let execution_cost_str: Option<String> = row.get_unwrap("execution_cost");
+        let execution_cost = execution_cost_str
+            .map(|cost| serde_json::from_str(&cost).map_err(|_| db_error::ParseError))
+            .transpose()?;
+
+        let events_str: Option<String> = row.get_unwrap("events");
+        let events = events_str
+            .map(|events| serde_json::from_str(&events).map_err(|_| db_error::ParseError))
+            .transpose()?;
+
+        Ok(IndexedTransaction {
+            txid,
+            index_block_hash,
+            tx_hex,
+            result,
+            tx_index,
+            raw_result,
+            post_condition_aborted,
+            vm_error,
+            stx_burned,
+            execution_cost,
+            events,
+        })
+    }
+}
+
+/// A contract event emitted by a processed transaction, as stored in the `contract_events` table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexedContractEvent {
+    pub contract_identifier: QualifiedContractIdentifier,
+    pub topic: String,
+    pub block_height: u64,
+    /// the block in which the emitting transaction was processed
+    pub index_block_hash: StacksBlockId,
+    pub txid: Txid,
+    pub tx_index: u32,
+    /// position of this event in the transaction receipt's list of events
+    pub event_index: u32,
+    /// the hex-encoded, consensus-serialized Clarity value
+    pub value: String,
+}
+
+impl FromRow<IndexedContractEvent> for IndexedContractEvent {
+    fn from_row<'a>(row: &'a Row) -> Result<IndexedContractEvent, db_error> {
+        let contract_identifier_str: String = row.get_unwrap("contract_identifier");
+        let contract_identifier = QualifiedContractIdentifier::parse(&contract_identifier_str)
+            .map_err(|_| db_error::ParseError)?;
+        let topic: String = row.get_unwrap("topic");
+        let block_height = u64::from_column(row, "block_height")?;
+        let index_block_hash = StacksBlockId::from_column(row, "index_block_hash")?;
+        let txid = Txid::from_column(row, "txid")?;
+        let tx_index: u32 = row.get_unwrap("tx_index");
+        let event_index: u32 = row.get_unwrap("event_index");
+        let value: String = row.get_unwrap("value");
+
+        Ok(IndexedContractEvent {
+            contract_identifier,
+            topic,
+            block_height,
+            index_block_hash,
+            txid,
+            tx_index,
+            event_index,
+            value,
+        })
+    }
+}
+
+pub const CHAINSTATE_VERSION: &'static str = "10";
 
 const CHAINSTATE_INITIAL_SCHEMA: &'static [&'static str] = &[
     "PRAGMA foreign_keys = ON;",
@@ -1135,6 +1337,22 @@ impl StacksChainState {
                         tx.execute_batch(cmd)?;
                     }
                 }
+                "8" => {
+                    info!(
+                        "Migrating chainstate schema from version 8 to 9: store transaction receipts in the transaction index"
+                    );
+                    for cmd in NAKAMOTO_CHAINSTATE_SCHEMA_6.iter() {
+                        tx.execute_batch(cmd)?;
+                    }
+                }
+                "9" => {
+                    info!(
+                        "Migrating chainstate schema from version 9 to 10: add the contract event index"
+                    );
+                    for cmd in NAKAMOTO_CHAINSTATE_SCHEMA_7.iter() {
+                        tx.execute_batch(cmd)?;
+                    }
+                }
                 _ => {
                     error!(
                         "Invalid chain state database: expected version = {}, got {}",
@@ -1881,6 +2099,8 @@ impl StacksChainState {
             unconfirmed_state: None,
             fault_injection: StacksChainStateFaults::new(),
             marf_opts: marf_opts,
+            txindex: false,
+            event_index: false,
         };
 
         let mut receipts = vec![];
@@ -1935,8 +2155,14 @@ impl StacksChainState {
         let clarity_instance = &mut self.clarity_state;
         let inner_tx = StacksDBTx::new(&mut self.state_index, ());
 
-        let chainstate_tx =
-            ChainstateTx::new(inner_tx, blocks_path, self.root_path.clone(), config);
+        let chainstate_tx = ChainstateTx::new(
+            inner_tx,
+            blocks_path,
+            self.root_path.clone(),
+            config,
+            self.txindex,
+            self.event_index,
+        );
 
         Ok((chainstate_tx, clarity_instance))
     }
@@ -2026,6 +2252,74 @@ impl StacksChainState {
         self.state_index.sqlite_conn()
     }
 
+    /// Get all recorded instances of a processed transaction, across all forks.
+    /// Only populated if the transaction index is enabled.
+    pub fn get_indexed_transactions(
+        conn: &Connection,
+        txid: &Txid,
+    ) -> Result<Vec<IndexedTransaction>, Error> {
+        let sql = "SELECT * FROM transactions WHERE txid = ?1";
+        let rows = query_rows(conn, sql, params![txid])?;
+        Ok(rows)
+    }
+
+    /// Get up to `limit` of the block heights between `start_height` and `end_height` inclusive
+    /// at which `contract_id` emitted events in any fork, optionally filtered by topic, lowest
+    /// first.
+    /// Only populated if the event index is enabled.
+    pub fn get_contract_event_heights(
+        conn: &Connection,
+        contract_id: &QualifiedContractIdentifier,
+        topic: Option<&str>,
+        start_height: u64,
+        end_height: u64,
+        limit: u32,
+    ) -> Result<Vec<u64>, Error> {
+        let sql = "SELECT DISTINCT block_height FROM contract_events
+            WHERE contract_identifier = ?1 AND (?2 IS NULL OR topic = ?2) AND block_height >= ?3 AND block_height <= ?4
+            ORDER BY block_height LIMIT ?5";
+        let args = params![
+            contract_id.to_string(),
+            topic,
+            u64_to_sql(start_height)?,
+            u64_to_sql(end_height)?,
+            limit,
+        ];
+        let heights = query_rows(conn, sql, args)?;
+        Ok(heights)
+    }
+
+    /// Get up to `limit` of the contract events emitted by `contract_id` in the block
+    /// `index_block_hash`, optionally filtered by topic, in the order in which they were emitted.
+    /// If `after` is given, only the events after that `(tx_index, event_index)` are returned.
+    /// Only populated if the event index is enabled.
+    pub fn get_indexed_contract_events(
+        conn: &Connection,
+        contract_id: &QualifiedContractIdentifier,
+        topic: Option<&str>,
+        index_block_hash: &StacksBlockId,
+        after: Option<(u32, u32)>,
+        limit: u32,
+    ) -> Result<Vec<IndexedContractEvent>, Error> {
+        let sql = "SELECT * FROM contract_events
+            WHERE index_block_hash = ?1 AND contract_identifier = ?2 AND (?3 IS NULL OR topic = ?3)
+            AND (tx_index > ?4 OR (tx_index = ?4 AND event_index > ?5))
+            ORDER BY tx_index, event_index LIMIT ?6";
+        let (after_tx_index, after_event_index) = after
+            .map(|(tx_index, event_index)| (i64::from(tx_index), i64::from(event_index)))
+            .unwrap_or((-1, -1));
+        let args = params![
+            index_block_hash,
+            contract_id.to_string(),
+            topic,
+            after_tx_index,
+            after_event_index,
+            limit,
+        ];
+        let rows = query_rows(conn, sql, args)?;
+        Ok(rows)
+    }
+
     /// Begin processing an epoch's transactions within the context of a chainstate transaction
     pub fn chainstate_block_begin<'a, 'b>(
         chainstate_tx: &'b ChainstateTx<'b>,
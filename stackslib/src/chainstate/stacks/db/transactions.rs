@@ -1453,8 +1453,8 @@ impl StacksChainState {
     }
 
     /// Deduce the Clarity version to run
-    pub fn get_tx_clarity_version(
-        clarity_block: &mut ClarityTx,
+    pub fn get_tx_clarity_version<T: ClarityConnection>(
+        clarity_block: &mut T,
         tx: &StacksTransaction,
     ) -> Result<ClarityVersion, Error> {
         let clarity_version = match &tx.payload {
@@ -1476,41 +1476,65 @@ impl StacksChainState {
         tx: &StacksTransaction,
         quiet: bool,
         ast_rules: ASTRules,
+    ) -> Result<(u64, StacksTransactionReceipt), Error> {
+        let config = clarity_block.config.clone();
+        let mut transaction = clarity_block.connection().start_transaction_processing();
+        let (fee, tx_receipt) = StacksChainState::process_transaction_uncommitted(
+            &mut transaction,
+            &config,
+            tx,
+            quiet,
+            ast_rules,
+        )?;
+
+        transaction
+            .commit()
+            .map_err(|e| Error::InvalidStacksTransaction(e.to_string(), false))?;
+
+        Ok((fee, tx_receipt))
+    }
+
+    /// Process a transaction in an open Clarity transaction, but do not commit it.
+    /// Return the fee and the transaction receipt.
+    pub fn process_transaction_uncommitted(
+        transaction: &mut ClarityTransactionConnection,
+        config: &DBConfig,
+        tx: &StacksTransaction,
+        quiet: bool,
+        ast_rules: ASTRules,
     ) -> Result<(u64, StacksTransactionReceipt), Error> {
         debug!("Process transaction {} ({})", tx.txid(), tx.payload.name());
-        let epoch = clarity_block.get_epoch();
+        let epoch = transaction.get_epoch();
 
-        StacksChainState::process_transaction_precheck(&clarity_block.config, tx, epoch)?;
+        StacksChainState::process_transaction_precheck(config, tx, epoch)?;
 
         // what version of Clarity did the transaction caller want? And, is it valid now?
-        let clarity_version = StacksChainState::get_tx_clarity_version(clarity_block, tx)?;
+        let clarity_version = StacksChainState::get_tx_clarity_version(transaction, tx)?;
         if clarity_version == ClarityVersion::Clarity2 {
             // requires 2.1 and higher
-            if clarity_block.get_epoch() < StacksEpochId::Epoch21 {
+            if epoch < StacksEpochId::Epoch21 {
                 let msg = format!("Invalid transaction {}: asks for Clarity2, but not in Stacks epoch 2.1 or later", tx.txid());
                 info!("{}", &msg);
                 return Err(Error::InvalidStacksTransaction(msg, false));
             }
         }
 
-        let mut transaction = clarity_block.connection().start_transaction_processing();
-
         let fee = tx.get_tx_fee();
         let tx_receipt = if epoch >= StacksEpochId::Epoch21 {
             // 2.1 and later: pay tx fee, then process transaction
             let (_origin_account, payer_account) =
-                StacksChainState::check_transaction_nonces(&mut transaction, tx, quiet)?;
+                StacksChainState::check_transaction_nonces(transaction, tx, quiet)?;
 
             let payer_address = payer_account.principal.clone();
             let payer_nonce = payer_account.nonce;
-            StacksChainState::pay_transaction_fee(&mut transaction, fee, payer_account)?;
+            StacksChainState::pay_transaction_fee(transaction, fee, payer_account)?;
 
             // origin balance may have changed (e.g. if the origin paid the tx fee), so reload the account
             let origin_account =
-                StacksChainState::get_account(&mut transaction, &tx.origin_address().into());
+                StacksChainState::get_account(transaction, &tx.origin_address().into());
 
             let tx_receipt = StacksChainState::process_transaction_payload(
-                &mut transaction,
+                transaction,
                 tx,
                 &origin_account,
                 ast_rules,
@@ -1518,44 +1542,40 @@ impl StacksChainState {
 
             // update the account nonces
             StacksChainState::update_account_nonce(
-                &mut transaction,
+                transaction,
                 &origin_account.principal,
                 origin_account.nonce,
             );
             if origin_account.principal != payer_address {
                 // payer is a different account, so update its nonce too
-                StacksChainState::update_account_nonce(
-                    &mut transaction,
-                    &payer_address,
-                    payer_nonce,
-                );
+                StacksChainState::update_account_nonce(transaction, &payer_address, payer_nonce);
             }
 
             tx_receipt
         } else {
             // pre-2.1: process transaction, then pay tx fee
             let (origin_account, payer_account) =
-                StacksChainState::check_transaction_nonces(&mut transaction, tx, quiet)?;
+                StacksChainState::check_transaction_nonces(transaction, tx, quiet)?;
 
             let tx_receipt = StacksChainState::process_transaction_payload(
-                &mut transaction,
+                transaction,
                 tx,
                 &origin_account,
                 ast_rules,
             )?;
 
-            let new_payer_account = StacksChainState::get_payer_account(&mut transaction, tx);
-            StacksChainState::pay_transaction_fee(&mut transaction, fee, new_payer_account)?;
+            let new_payer_account = StacksChainState::get_payer_account(transaction, tx);
+            StacksChainState::pay_transaction_fee(transaction, fee, new_payer_account)?;
 
             // update the account nonces
             StacksChainState::update_account_nonce(
-                &mut transaction,
+                transaction,
                 &origin_account.principal,
                 origin_account.nonce,
             );
             if origin_account != payer_account {
                 StacksChainState::update_account_nonce(
-                    &mut transaction,
+                    transaction,
                     &payer_account.principal,
                     payer_account.nonce,
                 );
@@ -1564,10 +1584,6 @@ impl StacksChainState {
             tx_receipt
         };
 
-        transaction
-            .commit()
-            .map_err(|e| Error::InvalidStacksTransaction(e.to_string(), false))?;
-
         Ok((fee, tx_receipt))
     }
 }
@@ -14,20 +14,26 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::collections::HashMap;
 use std::ffi::OsStr;
-use std::io::{Read, Write};
+use std::io::{BufRead, BufReader, Read, Write};
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::{env, fs, io, process};
 
 use clarity::vm::coverage::CoverageReporter;
 use lazy_static::lazy_static;
-use rand::Rng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 use rusqlite::types::ToSql;
 use rusqlite::{Connection, OpenFlags, Row, Transaction};
 use serde::Serialize;
 use serde_json::json;
 use stacks_common::address::c32::c32_address;
+use stacks_common::address::{
+    C32_ADDRESS_VERSION_MAINNET_SINGLESIG, C32_ADDRESS_VERSION_TESTNET_SINGLESIG,
+};
 use stacks_common::codec::StacksMessageCodec;
 use stacks_common::consts::{CHAIN_ID_MAINNET, CHAIN_ID_TESTNET};
 use stacks_common::types::chainstate::{
@@ -46,20 +52,29 @@ use crate::chainstate::stacks::boot::{
 };
 use crate::chainstate::stacks::index::storage::TrieFileStorage;
 use crate::chainstate::stacks::index::{ClarityMarfTrieId, MarfTrieId};
-use crate::clarity::vm::analysis::contract_interface_builder::build_contract_interface;
+use crate::clarity::vm::analysis::contract_interface_builder::{
+    build_contract_interface, ContractInterfaceFunctionAccess,
+};
 use crate::clarity::vm::analysis::errors::{CheckError, CheckResult};
+use crate::clarity::vm::analysis::linter::{LintConfig, Linter};
 use crate::clarity::vm::analysis::{AnalysisDatabase, ContractAnalysis};
+use crate::clarity::vm::ast::formatter::format_source;
 use crate::clarity::vm::ast::{build_ast_with_rules, ASTRules};
 use crate::clarity::vm::contexts::{AssetMap, GlobalContext, OwnedEnvironment};
 use crate::clarity::vm::costs::{ExecutionCost, LimitedCostTracker};
 use crate::clarity::vm::database::{
     BurnStateDB, ClarityDatabase, HeadersDB, STXBalance, SqliteConnection, NULL_BURN_STATE_DB,
 };
+use crate::clarity::vm::debugger::{Breakpoint, Debugger};
+use crate::clarity::vm::diagnostic::Level;
 use crate::clarity::vm::errors::{Error, InterpreterResult, RuntimeErrorType};
-use crate::clarity::vm::types::{OptionalData, PrincipalData, QualifiedContractIdentifier};
+use crate::clarity::vm::fuzz::{random_value, shrink_calls, FuzzCall};
+use crate::clarity::vm::types::{
+    FunctionType, OptionalData, PrincipalData, QualifiedContractIdentifier, StandardPrincipalData,
+};
 use crate::clarity::vm::{
-    analysis, ast, eval_all, ClarityVersion, ContractContext, ContractName, SymbolicExpression,
-    SymbolicExpressionType, Value,
+    analysis, ast, eval_all, ClarityVersion, ContractContext, ContractName, EvalHook,
+    SymbolicExpression, SymbolicExpressionType, Value,
 };
 use crate::clarity_vm::database::marf::{MarfedKV, WritableMarfStore};
 use crate::clarity_vm::database::MemoryBackingStore;
@@ -122,6 +137,11 @@ where command is one of:
   repl               to typecheck and evaluate expressions in a stdin/stdout loop.
   execute            to execute a public function of a defined contract.
   generate_address   to generate a random Stacks public address for testing purposes.
+  format             to pretty-print a program, or check that it is already formatted.
+  lint               to report risky patterns in a potential contract definition.
+  debug              to run `eval`, `eval_at_chaintip` or `execute` in an interactive debugger.
+  test               to run the `test-` functions of contracts deployed to a fresh database.
+  fuzz               to check a contract's `invariant-` functions against random calls to its public functions.
 ",
         invoked_by
     );
@@ -144,6 +164,12 @@ fn friendly_expect_opt<A>(input: Option<A>, msg: &str) -> A {
 
 pub const DEFAULT_CLI_EPOCH: StacksEpochId = StacksEpochId::Epoch25;
 
+/// The deployer of the contracts, and the default tx-sender, for the `test` command
+const DEFAULT_TEST_SENDER: &str = "S1G2081040G2081040G2081040G208105NK8PE5";
+
+/// The most call sequences the `fuzz` command will try while shrinking a failing run
+const MAX_FUZZ_SHRINK_ATTEMPTS: usize = 500;
+
 struct EvalInput {
     marf_kv: MarfedKV,
     contract_identifier: QualifiedContractIdentifier,
@@ -415,6 +441,7 @@ fn with_env_costs<F, R>(
     header_db: &CLIHeadersDB,
     marf: &mut WritableMarfStore,
     coverage: Option<&mut CoverageReporter>,
+    debugger: Option<&mut dyn EvalHook>,
     f: F,
 ) -> (R, ExecutionCost)
 where
@@ -443,6 +470,9 @@ where
     if let Some(coverage) = coverage {
         vm_env.add_eval_hook(coverage);
     }
+    if let Some(debugger) = debugger {
+        vm_env.add_eval_hook(debugger);
+    }
     let result = f(&mut vm_env);
     let cost = vm_env.get_cost_total();
     (result, cost)
@@ -864,6 +894,25 @@ fn consume_arg(
     }
 }
 
+/// Consume a numeric option and its value, which is `default` if the option isn't given
+fn consume_number_arg<T>(args: &mut Vec<String>, argname: &str, default: T) -> T
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    match consume_arg(args, &[argname], true) {
+        Ok(Some(value)) => friendly_expect(
+            value.parse::<T>(),
+            &format!("Failed to parse {} '{}'", argname, value),
+        ),
+        Ok(None) => default,
+        Err(_) => {
+            eprintln!("Expected argument for {}", argname);
+            panic_test!();
+        }
+    }
+}
+
 /// This function uses Clarity1 to parse the boot code.
 fn install_boot_code<C: ClarityStorage>(header_db: &CLIHeadersDB, marf: &mut C) {
     let mainnet = header_db.is_mainnet();
@@ -973,6 +1022,62 @@ fn install_boot_code<C: ClarityStorage>(header_db: &CLIHeadersDB, marf: &mut C)
         .unwrap();
 }
 
+fn parse_allocations(json_in: &str) -> Vec<(PrincipalData, u64)> {
+    let allocations: Vec<InitialAllocation> =
+        friendly_expect(serde_json::from_str(json_in), "Failure parsing JSON");
+
+    allocations
+        .into_iter()
+        .map(|a| {
+            (
+                friendly_expect(
+                    PrincipalData::parse(&a.principal),
+                    "Failed to parse principal in JSON",
+                ),
+                a.amount,
+            )
+        })
+        .collect()
+}
+
+/// Create a VM state database with the boot code installed and the given initial balances
+fn initialize_db(
+    db_name: &str,
+    mainnet: bool,
+    allocations: &[(PrincipalData, u64)],
+) -> (CLIHeadersDB, MarfedKV) {
+    let header_db = CLIHeadersDB::new(db_name, mainnet);
+    let marf_kv = friendly_expect(
+        MarfedKV::open(db_name, None, None),
+        "Failed to open VM database.",
+    );
+
+    // install bootcode
+    let (header_db, marf_kv, _) = in_block(header_db, marf_kv, |header_db, mut marf| {
+        install_boot_code(&header_db, &mut marf);
+        (header_db, marf, ())
+    });
+
+    // set initial balances
+    let (header_db, marf_kv, _) = in_block(header_db, marf_kv, |header_db, mut kv| {
+        {
+            let mut db = kv.as_clarity_db(&header_db, &NULL_BURN_STATE_DB);
+            db.begin();
+            for (principal, amount) in allocations.iter() {
+                let balance = STXBalance::initial(*amount as u128);
+
+                let mut snapshot = db.get_stx_balance_snapshot_genesis(principal).unwrap();
+                snapshot.set_balance(balance);
+                snapshot.save().unwrap();
+            }
+            db.commit().unwrap();
+        };
+        (header_db, kv, ())
+    });
+
+    (header_db, marf_kv)
+}
+
 pub fn add_costs(result: &mut serde_json::Value, costs: bool, runtime: ExecutionCost) {
     if costs {
         result["costs"] = serde_json::to_value(runtime).unwrap();
@@ -993,8 +1098,425 @@ pub fn add_serialized_output(result: &mut serde_json::Value, value: Value) {
     result["output_serialized"] = serde_json::to_value(result_raw.as_str()).unwrap();
 }
 
+/// Write an lcov report of the coverage files in `coverage_folder` to `lcov_output_file`
+fn make_lcov(coverage_folder: &str, lcov_output_file: &str) {
+    let mut register_files = vec![];
+    let mut coverage_files = vec![];
+    for folder_entry in fs::read_dir(coverage_folder).expect("Failed to read the coverage folder") {
+        let folder_entry = folder_entry.expect("Failed to read entry in the coverage folder");
+        let entry_path = folder_entry.path();
+        if entry_path.is_file() {
+            if entry_path.extension() == Some(OsStr::new("clarcovref")) {
+                register_files.push(entry_path)
+            } else if entry_path.extension() == Some(OsStr::new("clarcov")) {
+                coverage_files.push(entry_path)
+            }
+        }
+    }
+    CoverageReporter::produce_lcov(lcov_output_file, &register_files, &coverage_files)
+        .expect("Failed to produce an lcov output");
+}
+
+/// A directory under the system's temporary directory, for the scratch databases and
+/// coverage files of one command.  It is removed along with its contents when dropped.
+struct ScratchDir(PathBuf);
+
+impl ScratchDir {
+    fn new(prefix: &str) -> ScratchDir {
+        let path = env::temp_dir().join(format!("{}_{}", prefix, rand::thread_rng().gen::<u64>()));
+        friendly_expect(
+            fs::create_dir_all(&path),
+            "Failed to create a temporary directory",
+        );
+        ScratchDir(path)
+    }
+
+    /// The path of `name` within this directory
+    fn path(&self, name: &str) -> String {
+        self.0.join(name).to_string_lossy().into_owned()
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Settings for one test function, given in `;; @` comments directly above it
+#[derive(Default, Clone)]
+struct TestAnnotations {
+    /// `;; @sender <principal>`: the tx-sender for the test
+    sender: Option<PrincipalData>,
+    /// `;; @advance-blocks <count>`: how many blocks to mine before the test
+    advance_blocks: Option<u64>,
+}
+
+/// A `test-` function found by the `test` command
+struct ContractTest {
+    contract_identifier: QualifiedContractIdentifier,
+    name: String,
+    arg_count: usize,
+    annotations: TestAnnotations,
+}
+
+/// Read the `;; @` annotations above each public function in a contract's source
+fn parse_test_annotations(content: &str) -> HashMap<String, TestAnnotations> {
+    let mut annotations = HashMap::new();
+    let mut pending = TestAnnotations::default();
+    for line in content.lines().map(|line| line.trim()) {
+        if let Some(comment) = line.strip_prefix(";;") {
+            let mut words = comment.split_whitespace();
+            match (words.next(), words.next()) {
+                (Some("@sender"), Some(sender)) => {
+                    pending.sender = Some(friendly_expect(
+                        PrincipalData::parse(sender.trim_start_matches('\'')),
+                        &format!("Failed to parse @sender principal '{}'", sender),
+                    ));
+                }
+                (Some("@advance-blocks"), Some(count)) => {
+                    pending.advance_blocks = Some(friendly_expect(
+                        count.parse::<u64>(),
+                        &format!("Failed to parse @advance-blocks count '{}'", count),
+                    ));
+                }
+                _ => {}
+            }
+        } else if let Some(definition) = line.strip_prefix("(define-public") {
+            let name = definition
+                .trim_start()
+                .trim_start_matches('(')
+                .split(|c: char| c.is_whitespace() || c == ')')
+                .next()
+                .unwrap_or_default();
+            annotations.insert(name.to_string(), std::mem::take(&mut pending));
+        } else if !line.is_empty() {
+            pending = TestAnnotations::default();
+        }
+    }
+    annotations
+}
+
+/// Find the public functions whose names start with `test-`, in the order they are defined
+fn find_contract_tests(
+    contract_identifier: &QualifiedContractIdentifier,
+    content: &str,
+    ast: &[SymbolicExpression],
+) -> Vec<ContractTest> {
+    let annotations = parse_test_annotations(content);
+    ast.iter()
+        .filter_map(|expr| {
+            let definition = expr.match_list()?;
+            if definition.first()?.match_atom()?.as_str() != "define-public" {
+                return None;
+            }
+            let signature = definition.get(1)?.match_list()?;
+            let name = signature.first()?.match_atom()?;
+            if !name.starts_with("test-") {
+                return None;
+            }
+            Some(ContractTest {
+                contract_identifier: contract_identifier.clone(),
+                name: name.to_string(),
+                arg_count: signature.len() - 1,
+                annotations: annotations.get(name.as_str()).cloned().unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+/// Copy the files of a VM state database, so that each test can start from the same state
+fn copy_db(from: &str, to: &str) {
+    friendly_expect(fs::create_dir_all(to), "Failed to create test database");
+    for entry in friendly_expect(fs::read_dir(from), "Failed to read test database") {
+        let entry = friendly_expect(entry, "Failed to read test database");
+        friendly_expect(
+            fs::copy(entry.path(), PathBuf::from(to).join(entry.file_name())),
+            "Failed to copy test database",
+        );
+    }
+}
+
+/// A contract deployed by the `test` or `fuzz` commands
+struct DeployedContract {
+    contract_identifier: QualifiedContractIdentifier,
+    content: String,
+    ast: Vec<SymbolicExpression>,
+    analysis: ContractAnalysis,
+}
+
+/// Deploy contract files in the order given, each in its own block and named after its file.
+///  If `coverage_folder` is given, the contracts are registered there for coverage reports.
+///  The database is released afterwards, so that it can be copied. On failure, returns the
+///  command's JSON output.
+fn deploy_contract_files(
+    mut header_db: CLIHeadersDB,
+    mut marf_kv: MarfedKV,
+    deployer: &StandardPrincipalData,
+    contract_src_files: &[String],
+    coverage_folder: Option<&str>,
+) -> Result<Vec<DeployedContract>, serde_json::Value> {
+    let mainnet = header_db.is_mainnet();
+    let mut contracts = vec![];
+    for contract_src_file in contract_src_files.iter() {
+        let contract_name = friendly_expect_opt(
+            PathBuf::from(contract_src_file)
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .map(|stem| stem.to_string()),
+            &format!("Failed to get a contract name from '{}'", contract_src_file),
+        );
+        let contract_identifier = QualifiedContractIdentifier::new(
+            deployer.clone(),
+            friendly_expect(
+                ContractName::try_from(contract_name.clone()),
+                &format!("Invalid contract name '{}'", contract_name),
+            ),
+        );
+        let contract_content: String = friendly_expect(
+            fs::read_to_string(contract_src_file),
+            &format!("Error reading file: {}", contract_src_file),
+        );
+        let mut ast = friendly_expect(
+            parse(
+                &contract_identifier,
+                &contract_content,
+                ClarityVersion::Clarity2,
+            ),
+            "Failed to parse program.",
+        );
+
+        if let Some(coverage_folder) = coverage_folder {
+            let mut coverage_file = PathBuf::from(coverage_folder);
+            coverage_file.push(&format!("test_{}_{}", contract_name, get_epoch_time_ms()));
+            coverage_file.set_extension("clarcovref");
+            CoverageReporter::register_src_file(
+                &contract_identifier,
+                contract_src_file,
+                &ast,
+                &coverage_file,
+            )
+            .expect("Coverage reference file generation failure");
+        }
+
+        let deployment;
+        (header_db, marf_kv, deployment) = in_block(header_db, marf_kv, |header_db, mut marf| {
+            let analysis_result =
+                run_analysis(&contract_identifier, &mut ast, &header_db, &mut marf, true);
+            let deployment = match analysis_result {
+                Err((error, _)) => Err(error.to_string()),
+                Ok(analysis) => {
+                    let (result, _) =
+                        with_env_costs(mainnet, &header_db, &mut marf, None, None, |vm_env| {
+                            vm_env.initialize_versioned_contract(
+                                contract_identifier.clone(),
+                                ClarityVersion::Clarity2,
+                                &contract_content,
+                                None,
+                                ASTRules::PrecheckSize,
+                            )
+                        });
+                    result.map(|_| analysis).map_err(|error| error.to_string())
+                }
+            };
+            (header_db, marf, deployment)
+        });
+
+        match deployment {
+            Ok(analysis) => contracts.push(DeployedContract {
+                contract_identifier,
+                content: contract_content,
+                ast,
+                analysis,
+            }),
+            Err(error) => {
+                return Err(json!({
+                    "message": format!("Failed to deploy {}.", contract_identifier),
+                    "error": {
+                        "initialization": error
+                    }
+                }));
+            }
+        }
+    }
+    Ok(contracts)
+}
+
+/// Run one test against a copy of the database at `template_db`, which has the contracts
+///  deployed
+fn run_contract_test(
+    template_db: &str,
+    test: &ContractTest,
+    sender: &PrincipalData,
+    advance_blocks: u64,
+    coverage: Option<&mut CoverageReporter>,
+) -> serde_json::Value {
+    let mut result = json!({
+        "contract": test.contract_identifier.to_string(),
+        "test": test.name,
+    });
+    if test.arg_count > 0 {
+        result["success"] = json!(false);
+        result["error"] = json!("Test functions must not take arguments.");
+        return result;
+    }
+
+    let test_db = format!("{}_{}", template_db, rand::thread_rng().gen::<u64>());
+    copy_db(template_db, &test_db);
+    let mut header_db = friendly_expect(CLIHeadersDB::resume(&test_db), "Failed to open CLI DB");
+    let mut marf_kv = friendly_expect(
+        MarfedKV::open(&test_db, None, None),
+        "Failed to open VM database.",
+    );
+    let mainnet = header_db.is_mainnet();
+
+    for _ in 0..test.annotations.advance_blocks.unwrap_or(advance_blocks) {
+        (header_db, marf_kv, _) =
+            in_block(header_db, marf_kv, |header_db, marf| (header_db, marf, ()));
+    }
+
+    let sender = test.annotations.sender.as_ref().unwrap_or(sender).clone();
+    let (_, _, (outcome, _)) = in_block(header_db, marf_kv, |header_db, mut marf| {
+        let result_and_cost =
+            with_env_costs(mainnet, &header_db, &mut marf, coverage, None, |vm_env| {
+                vm_env.execute_transaction(
+                    sender,
+                    None,
+                    test.contract_identifier.clone(),
+                    &test.name,
+                    &[],
+                )
+            });
+        (header_db, marf, result_and_cost)
+    });
+    let _ = fs::remove_dir_all(&test_db);
+
+    match outcome {
+        Ok((Value::Response(data), ..)) => {
+            result["success"] = json!(data.committed);
+            result["result"] = json!(Value::Response(data).to_string());
+        }
+        Ok((value, ..)) => {
+            result["success"] = json!(false);
+            result["result"] = json!(value.to_string());
+        }
+        Err(error) => {
+            result["success"] = json!(false);
+            result["error"] = json!(error.to_string());
+        }
+    }
+    result
+}
+
+/// An `invariant-` function which did not hold during a fuzzing run
+struct InvariantFailure {
+    invariant: String,
+    result: String,
+    /// how many calls were made before the invariant failed
+    after_calls: usize,
+}
+
+/// An invariant holds if it returns `true` or `(ok true)`
+fn invariant_holds(result: &Value) -> bool {
+    match result {
+        Value::Bool(holds) => *holds,
+        Value::Response(data) => data.committed && *data.data == Value::Bool(true),
+        _ => false,
+    }
+}
+
+/// Make `calls` in turn, each in its own block, against a copy of the database at
+///  `template_db`. The `invariants` of `contract_identifier` are checked before the first call
+///  and after each one, stopping at the first which fails. Returns the result of each call
+///  made, and the failed invariant, if any.
+fn run_fuzz_calls(
+    template_db: &str,
+    contract_identifier: &QualifiedContractIdentifier,
+    invariants: &[String],
+    calls: &[FuzzCall],
+) -> (Vec<String>, Option<InvariantFailure>) {
+    let fuzz_db = format!("{}_{}", template_db, rand::thread_rng().gen::<u64>());
+    copy_db(template_db, &fuzz_db);
+    let mut header_db = friendly_expect(CLIHeadersDB::resume(&fuzz_db), "Failed to open CLI DB");
+    let mut marf_kv = friendly_expect(
+        MarfedKV::open(&fuzz_db, None, None),
+        "Failed to open VM database.",
+    );
+    let mainnet = header_db.is_mainnet();
+
+    let mut results = vec![];
+    let mut failure = None;
+    // the first block makes no call, and only checks that the invariants hold after deployment
+    for call in std::iter::once(None).chain(calls.iter().map(Some)) {
+        let (call_result, invariant_failure);
+        (header_db, marf_kv, (call_result, invariant_failure)) =
+            in_block(header_db, marf_kv, |header_db, mut marf| {
+                let (outcome, _) =
+                    with_env_costs(mainnet, &header_db, &mut marf, None, None, |vm_env| {
+                        let call_result = call.map(|call| {
+                            let arguments: Vec<_> = call
+                                .arguments
+                                .iter()
+                                .map(|argument| SymbolicExpression::atom_value(argument.clone()))
+                                .collect();
+                            match vm_env.execute_transaction(
+                                call.sender.clone(),
+                                None,
+                                contract_identifier.clone(),
+                                &call.function,
+                                &arguments,
+                            ) {
+                                Ok((value, ..)) => value.to_string(),
+                                Err(error) => format!("Runtime error: {}", error),
+                            }
+                        });
+                        let invariant_failure = invariants.iter().find_map(|invariant| {
+                            let result = vm_env.execute_in_env(
+                                contract_identifier.issuer.clone().into(),
+                                None,
+                                None,
+                                |env| {
+                                    env.execute_contract(contract_identifier, invariant, &[], true)
+                                },
+                            );
+                            match result {
+                                Ok((value, ..)) if invariant_holds(&value) => None,
+                                Ok((value, ..)) => Some((invariant.clone(), value.to_string())),
+                                Err(error) => {
+                                    Some((invariant.clone(), format!("Runtime error: {}", error)))
+                                }
+                            }
+                        });
+                        (call_result, invariant_failure)
+                    });
+                (header_db, marf, outcome)
+            });
+
+        results.extend(call_result);
+        if let Some((invariant, result)) = invariant_failure {
+            failure = Some(InvariantFailure {
+                invariant,
+                result,
+                after_calls: results.len(),
+            });
+            break;
+        }
+    }
+    let _ = fs::remove_dir_all(&fuzz_db);
+    (results, failure)
+}
+
 /// Returns (process-exit-code, Option<json-output>)
 pub fn invoke_command(invoked_by: &str, args: &[String]) -> (i32, Option<serde_json::Value>) {
+    invoke_command_with_debugger(invoked_by, args, None)
+}
+
+/// Like `invoke_command`, but `eval`, `eval_at_chaintip` and `execute` are run under `debugger`
+fn invoke_command_with_debugger(
+    invoked_by: &str,
+    args: &[String],
+    debugger: Option<&mut dyn EvalHook>,
+) -> (i32, Option<serde_json::Value>) {
     if args.len() < 1 {
         print_usage(invoked_by);
         return (1, None);
@@ -1025,23 +1547,7 @@ pub fn invoke_command(invoked_by: &str, args: &[String]) -> (i32, Option<serde_j
                         &format!("Error reading file: {}", filename),
                     )
                 };
-                let allocations: Vec<InitialAllocation> =
-                    friendly_expect(serde_json::from_str(&json_in), "Failure parsing JSON");
-
-                let allocations: Vec<_> = allocations
-                    .into_iter()
-                    .map(|a| {
-                        (
-                            friendly_expect(
-                                PrincipalData::parse(&a.principal),
-                                "Failed to parse principal in JSON",
-                            ),
-                            a.amount,
-                        )
-                    })
-                    .collect();
-
-                (&argv[2], allocations)
+                (&argv[2], parse_allocations(&json_in))
             } else if argv.len() == 2 {
                 (&argv[1], Vec::new())
             } else {
@@ -1056,40 +1562,10 @@ pub fn invoke_command(invoked_by: &str, args: &[String]) -> (i32, Option<serde_j
             };
 
             debug!("Initialize {}", &db_name);
-            let mut header_db = CLIHeadersDB::new(&db_name, mainnet);
-            let mut marf_kv = friendly_expect(
-                MarfedKV::open(db_name, None, None),
-                "Failed to open VM database.",
-            );
-
-            // install bootcode
-            let state = in_block(header_db, marf_kv, |header_db, mut marf| {
-                install_boot_code(&header_db, &mut marf);
-                (header_db, marf, ())
-            });
-
-            header_db = state.0;
-            marf_kv = state.1;
-
-            // set initial balances
-            in_block(header_db, marf_kv, |header_db, mut kv| {
-                {
-                    let mut db = kv.as_clarity_db(&header_db, &NULL_BURN_STATE_DB);
-                    db.begin();
-                    for (principal, amount) in allocations.iter() {
-                        let balance = STXBalance::initial(*amount as u128);
-                        let total_balance = balance.get_total_balance().unwrap();
-
-                        let mut snapshot = db.get_stx_balance_snapshot_genesis(principal).unwrap();
-                        snapshot.set_balance(balance);
-                        snapshot.save().unwrap();
-
-                        println!("{} credited: {} uSTX", principal, total_balance);
-                    }
-                    db.commit().unwrap();
-                };
-                (header_db, kv, ())
-            });
+            initialize_db(db_name, mainnet, &allocations);
+            for (principal, amount) in allocations.iter() {
+                println!("{} credited: {} uSTX", principal, amount);
+            }
 
             if mainnet {
                 (
@@ -1409,7 +1885,7 @@ pub fn invoke_command(invoked_by: &str, args: &[String]) -> (i32, Option<serde_j
 
             let (_, _, result_and_cost) = in_block(header_db, marf_kv, |header_db, mut marf| {
                 let result_and_cost =
-                    with_env_costs(mainnet, &header_db, &mut marf, None, |vm_env| {
+                    with_env_costs(mainnet, &header_db, &mut marf, None, debugger, |vm_env| {
                         vm_env
                             .get_exec_environment(None, None, &mut placeholder_context)
                             .eval_read_only_with_rules(
@@ -1486,6 +1962,7 @@ pub fn invoke_command(invoked_by: &str, args: &[String]) -> (i32, Option<serde_j
                     &header_db,
                     &mut marf,
                     coverage.as_mut(),
+                    debugger,
                     |vm_env| {
                         vm_env
                             .get_exec_environment(None, None, &mut placeholder_context)
@@ -1573,7 +2050,7 @@ pub fn invoke_command(invoked_by: &str, args: &[String]) -> (i32, Option<serde_j
             );
             let result_and_cost = at_block(chain_tip, marf_kv, |mut marf| {
                 let result_and_cost =
-                    with_env_costs(mainnet, &header_db, &mut marf, None, |vm_env| {
+                    with_env_costs(mainnet, &header_db, &mut marf, None, None, |vm_env| {
                         vm_env
                             .get_exec_environment(None, None, &mut placeholder_context)
                             .eval_read_only_with_rules(
@@ -1704,6 +2181,7 @@ pub fn invoke_command(invoked_by: &str, args: &[String]) -> (i32, Option<serde_j
                                 &header_db,
                                 &mut marf,
                                 coverage.as_mut(),
+                                None,
                                 |vm_env| {
                                     vm_env.initialize_versioned_contract(
                                         contract_identifier,
@@ -1842,6 +2320,7 @@ pub fn invoke_command(invoked_by: &str, args: &[String]) -> (i32, Option<serde_j
                     &header_db,
                     &mut marf,
                     coverage.as_mut(),
+                    debugger,
                     |vm_env| {
                         vm_env.execute_transaction(
                             sender,
@@ -1917,78 +2396,586 @@ pub fn invoke_command(invoked_by: &str, args: &[String]) -> (i32, Option<serde_j
             }
         }
         "make_lcov" => {
-            let mut register_files = vec![];
-            let mut coverage_files = vec![];
-            let coverage_folder = &args[1];
-            let lcov_output_file = &args[2];
-            for folder_entry in
-                fs::read_dir(coverage_folder).expect("Failed to read the coverage folder")
-            {
-                let folder_entry =
-                    folder_entry.expect("Failed to read entry in the coverage folder");
-                let entry_path = folder_entry.path();
-                if entry_path.is_file() {
-                    if entry_path.extension() == Some(OsStr::new("clarcovref")) {
-                        register_files.push(entry_path)
-                    } else if entry_path.extension() == Some(OsStr::new("clarcov")) {
-                        coverage_files.push(entry_path)
-                    }
-                }
-            }
-            CoverageReporter::produce_lcov(lcov_output_file, &register_files, &coverage_files)
-                .expect("Failed to produce an lcov output");
+            make_lcov(&args[1], &args[2]);
             (0, None)
         }
-        _ => {
-            print_usage(invoked_by);
-            (1, None)
-        }
-    }
-}
-
-#[cfg(test)]
-mod test {
-    use super::*;
+        "format" => {
+            let mut argv: Vec<String> = args.into_iter().map(|x| x.clone()).collect();
+            let check = if let Ok(Some(_)) = consume_arg(&mut argv, &["--check"], false) {
+                true
+            } else {
+                false
+            };
 
-    #[test]
-    fn test_initial_alloc() {
-        let db_name = format!("/tmp/db_{}", rand::thread_rng().gen::<i32>());
-        let json_name = format!("/tmp/test-alloc_{}.json", rand::thread_rng().gen::<i32>());
-        let clar_name = format!("/tmp/test-alloc_{}.clar", rand::thread_rng().gen::<i32>());
+            if argv.len() != 2 {
+                eprintln!(
+                    "Usage: {} {} [--check] [program-file.clar]",
+                    invoked_by, argv[0]
+                );
+                panic_test!();
+            }
 
-        fs::write(
-            &json_name,
-            r#"
-[ { "principal": "S1G2081040G2081040G2081040G208105NK8PE5",
-    "amount": 1000 },
-  { "principal": "S1G2081040G2081040G2081040G208105NK8PE5.names",
-    "amount": 2000 } ]
-"#,
-        )
-        .unwrap();
+            let content: String = if &argv[1] == "-" {
+                let mut buffer = String::new();
+                friendly_expect(
+                    io::stdin().read_to_string(&mut buffer),
+                    "Error reading from stdin.",
+                );
+                buffer
+            } else {
+                friendly_expect(
+                    fs::read_to_string(&argv[1]),
+                    &format!("Error reading file: {}", argv[1]),
+                )
+            };
 
-        fs::write(&clar_name, r#"
-(unwrap-panic (if (is-eq (stx-get-balance 'S1G2081040G2081040G2081040G208105NK8PE5) u1000) (ok 1) (err 2)))
-(unwrap-panic (if (is-eq (stx-get-balance 'S1G2081040G2081040G2081040G208105NK8PE5.names) u2000) (ok 1) (err 2)))
-"#).unwrap();
+            let formatted = match format_source(&content) {
+                Ok(formatted) => formatted,
+                Err(e) => {
+                    return (
+                        1,
+                        Some(json!({
+                            "message": "Failed to parse program.",
+                            "error": {
+                                "parse": serde_json::to_value(&e.diagnostic).unwrap()
+                            }
+                        })),
+                    );
+                }
+            };
 
-        let invoked = invoke_command(
-            "test",
-            &["initialize".to_string(), json_name.clone(), db_name.clone()],
-        );
-        let exit = invoked.0;
-        let result = invoked.1.unwrap();
+            if !check {
+                print!("{}", formatted);
+                (0, None)
+            } else if formatted == content {
+                (0, Some(json!({ "message": "Program is formatted." })))
+            } else {
+                (1, Some(json!({ "message": "Program is not formatted." })))
+            }
+        }
+        "lint" => {
+            let mut argv: Vec<String> = args.into_iter().map(|x| x.clone()).collect();
+            let config = if let Ok(optarg) = consume_arg(&mut argv, &["--config"], true) {
+                optarg
+                    .map(|config_file| {
+                        let config_json = friendly_expect(
+                            fs::read_to_string(&config_file),
+                            &format!("Error reading file: {}", config_file),
+                        );
+                        friendly_expect(
+                            LintConfig::from_json(&config_json),
+                            &format!("Error parsing lint config '{}'", config_file),
+                        )
+                    })
+                    .unwrap_or_default()
+            } else {
+                eprintln!("Expected argument for --config");
+                panic_test!();
+            };
 
-        assert_eq!(exit, 0);
-        assert_eq!(result["network"], "mainnet");
+            if argv.len() != 2 {
+                eprintln!(
+                    "Usage: {} {} [--config lint-config.json] [program-file.clar]",
+                    invoked_by, argv[0]
+                );
+                panic_test!();
+            }
 
-        let invoked = invoke_command(
-            "test",
-            &[
-                "launch".to_string(),
-                "S1G2081040G2081040G2081040G208105NK8PE5.tokens".to_string(),
-                clar_name,
-                db_name,
+            let content: String = if &argv[1] == "-" {
+                let mut buffer = String::new();
+                friendly_expect(
+                    io::stdin().read_to_string(&mut buffer),
+                    "Error reading from stdin.",
+                );
+                buffer
+            } else {
+                friendly_expect(
+                    fs::read_to_string(&argv[1]),
+                    &format!("Error reading file: {}", argv[1]),
+                )
+            };
+
+            let contract_id = QualifiedContractIdentifier::transient();
+            let clarity_version = ClarityVersion::default_for_epoch(DEFAULT_CLI_EPOCH);
+            let ast = friendly_expect(
+                parse(&contract_id, &content, clarity_version),
+                "Failed to parse program",
+            );
+            let contract_analysis = ContractAnalysis::new(
+                contract_id,
+                ast,
+                LimitedCostTracker::new_free(),
+                DEFAULT_CLI_EPOCH,
+                clarity_version,
+            );
+
+            let diagnostics = Linter::run(&contract_analysis, &config);
+            let has_errors = diagnostics
+                .iter()
+                .any(|diagnostic| diagnostic.level == Level::Error);
+            let result = json!({
+                "message": if has_errors { "Lints failed." } else { "Lints passed." },
+                "diagnostics": serde_json::to_value(&diagnostics).unwrap(),
+            });
+            (if has_errors { 1 } else { 0 }, Some(result))
+        }
+        "debug" => {
+            let mut argv: Vec<String> = args.into_iter().map(|x| x.clone()).collect();
+            let mut breakpoints = vec![];
+            while let Ok(Some(breakpoint)) = consume_arg(&mut argv, &["--break"], true) {
+                breakpoints.push(friendly_expect(
+                    breakpoint.parse::<Breakpoint>(),
+                    &format!("Invalid breakpoint '{}'", breakpoint),
+                ));
+            }
+            let mut watches = vec![];
+            while let Ok(Some(watch)) = consume_arg(&mut argv, &["--watch"], true) {
+                watches.push(watch);
+            }
+            let commands_file = if let Ok(optarg) = consume_arg(&mut argv, &["--commands"], true) {
+                optarg
+            } else {
+                eprintln!("Expected argument for --commands");
+                panic_test!();
+            };
+
+            if argv.len() < 2 || !["eval", "eval_at_chaintip", "execute"].contains(&&*argv[1]) {
+                eprintln!(
+                    "Usage: {} {} [--break line|function]... [--watch expression]... [--commands debugger-commands-file] (eval|eval_at_chaintip|execute) [args...]",
+                    invoked_by, argv[0]
+                );
+                panic_test!();
+            }
+
+            // debugger commands are read from stdin unless given in a file, so programs to
+            //  evaluate must be given in a file
+            let input: Box<dyn BufRead> = match commands_file {
+                Some(commands_file) => Box::new(BufReader::new(friendly_expect(
+                    fs::File::open(&commands_file),
+                    &format!("Error reading file: {}", commands_file),
+                ))),
+                None => Box::new(BufReader::new(io::stdin())),
+            };
+            let mut debugger = Debugger::new(input, io::stderr());
+            for breakpoint in breakpoints {
+                debugger.add_breakpoint(breakpoint);
+            }
+            for watch in watches {
+                debugger.add_watch(&watch);
+            }
+
+            invoke_command_with_debugger(invoked_by, &argv[1..], Some(&mut debugger))
+        }
+        "test" => {
+            let mut argv: Vec<String> = args.into_iter().map(|x| x.clone()).collect();
+            let mainnet = if let Ok(Some(_)) = consume_arg(&mut argv, &["--testnet"], false) {
+                false
+            } else {
+                true
+            };
+            let sender = if let Ok(optarg) = consume_arg(&mut argv, &["--sender"], true) {
+                let sender = optarg.unwrap_or_else(|| DEFAULT_TEST_SENDER.to_string());
+                friendly_expect(
+                    PrincipalData::parse_standard_principal(&sender),
+                    &format!("Failed to parse sender '{}'", sender),
+                )
+            } else {
+                eprintln!("Expected argument for --sender");
+                panic_test!();
+            };
+            let allocations = if let Ok(optarg) = consume_arg(&mut argv, &["--balances"], true) {
+                optarg
+                    .map(|balances_file| {
+                        parse_allocations(&friendly_expect(
+                            fs::read_to_string(&balances_file),
+                            &format!("Error reading file: {}", balances_file),
+                        ))
+                    })
+                    .unwrap_or_default()
+            } else {
+                eprintln!("Expected argument for --balances");
+                panic_test!();
+            };
+            let advance_blocks =
+                if let Ok(optarg) = consume_arg(&mut argv, &["--advance-blocks"], true) {
+                    optarg
+                        .map(|count| {
+                            friendly_expect(
+                                count.parse::<u64>(),
+                                &format!("Failed to parse block count '{}'", count),
+                            )
+                        })
+                        .unwrap_or(0)
+                } else {
+                    eprintln!("Expected argument for --advance-blocks");
+                    panic_test!();
+                };
+            let coverage_folder = if let Ok(covarg) = consume_arg(&mut argv, &["--c"], true) {
+                covarg
+            } else {
+                None
+            };
+            let lcov_output_file = if let Ok(optarg) = consume_arg(&mut argv, &["--lcov"], true) {
+                optarg
+            } else {
+                eprintln!("Expected argument for --lcov");
+                panic_test!();
+            };
+
+            if argv.len() < 2 {
+                eprintln!(
+                    "Usage: {} {} [--testnet] [--sender principal] [--balances initial-allocations.json] [--advance-blocks count] [--c coverage-folder] [--lcov output.lcov] [contract-definition.clar...]",
+                    invoked_by, argv[0]
+                );
+                eprintln!("   Contracts are deployed by the sender, in the order given, and named after their files.");
+                eprintln!("   Every public function whose name starts with `test-` is run against a fresh copy of the deployed contracts.");
+                eprintln!("   `;; @sender <principal>` and `;; @advance-blocks <count>` comments above a test override the sender and the number of blocks mined before it.");
+                panic_test!();
+            }
+
+            // the template database, its per-test copies and any coverage files which are
+            // only needed for the lcov report are all removed when `scratch` is dropped
+            let scratch = ScratchDir::new("clarity-test");
+            let coverage_folder = coverage_folder
+                .or_else(|| lcov_output_file.as_ref().map(|_| scratch.path("coverage")));
+            if let Some(ref coverage_folder) = coverage_folder {
+                friendly_expect(
+                    fs::create_dir_all(coverage_folder),
+                    "Failed to create the coverage folder",
+                );
+            }
+
+            let template_db = scratch.path("db");
+            let (header_db, marf_kv) = initialize_db(&template_db, mainnet, &allocations);
+            let contracts = match deploy_contract_files(
+                header_db,
+                marf_kv,
+                &sender,
+                &argv[1..],
+                coverage_folder.as_deref(),
+            ) {
+                Ok(contracts) => contracts,
+                Err(output) => return (1, Some(output)),
+            };
+            let tests: Vec<_> = contracts
+                .iter()
+                .flat_map(|contract| {
+                    find_contract_tests(
+                        &contract.contract_identifier,
+                        &contract.content,
+                        &contract.ast,
+                    )
+                })
+                .collect();
+
+            let mut coverage = coverage_folder.as_ref().map(|_| CoverageReporter::new());
+            let results: Vec<_> = tests
+                .iter()
+                .map(|test| {
+                    run_contract_test(
+                        &template_db,
+                        test,
+                        &PrincipalData::Standard(sender.clone()),
+                        advance_blocks,
+                        coverage.as_mut(),
+                    )
+                })
+                .collect();
+
+            if let Some(coverage_folder) = coverage_folder {
+                save_coverage(Some(coverage_folder.clone()), coverage, "test");
+                if let Some(lcov_output_file) = lcov_output_file {
+                    make_lcov(&coverage_folder, &lcov_output_file);
+                }
+            }
+
+            let failures = results
+                .iter()
+                .filter(|result| result["success"] != json!(true))
+                .count();
+            let message = if results.is_empty() {
+                "No tests found.".to_string()
+            } else if failures == 0 {
+                "All tests passed.".to_string()
+            } else {
+                format!("{} of {} tests failed.", failures, results.len())
+            };
+            (
+                if failures == 0 && !results.is_empty() {
+                    0
+                } else {
+                    1
+                },
+                Some(json!({
+                    "message": message,
+                    "tests": results,
+                })),
+            )
+        }
+        "fuzz" => {
+            let mut argv: Vec<String> = args.into_iter().map(|x| x.clone()).collect();
+            let mainnet = if let Ok(Some(_)) = consume_arg(&mut argv, &["--testnet"], false) {
+                false
+            } else {
+                true
+            };
+            let runs: u64 = consume_number_arg(&mut argv, "--runs", 100);
+            let depth: usize = consume_number_arg(&mut argv, "--depth", 10);
+            let seed: u64 = consume_number_arg(&mut argv, "--seed", rand::thread_rng().gen());
+            let principal_count: usize = consume_number_arg(&mut argv, "--principals", 4);
+            let balance: u64 = consume_number_arg(&mut argv, "--balance", 1_000_000_000_000);
+
+            if argv.len() < 2 || principal_count == 0 {
+                eprintln!(
+                    "Usage: {} {} [--testnet] [--runs count] [--depth calls] [--seed number] [--principals count] [--balance microstacks] [dependency.clar...] contract-definition.clar",
+                    invoked_by, argv[0]
+                );
+                eprintln!("   Contracts are deployed in the order given, and named after their files. The last one is fuzzed.");
+                eprintln!("   Each run makes `depth` random calls to its public functions, from random principals which each start with `balance`.");
+                eprintln!("   After every call, each read-only function whose name starts with `invariant-` and takes no arguments must return true or (ok true).");
+                eprintln!("   A failing run is shrunk to a minimal sequence of calls, and can be repeated by passing the same --seed.");
+                panic_test!();
+            }
+
+            let epoch = DEFAULT_CLI_EPOCH;
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            let deployer = friendly_expect(
+                PrincipalData::parse_standard_principal(DEFAULT_TEST_SENDER),
+                "Failed to parse the deployer principal",
+            );
+            let address_version = if mainnet {
+                C32_ADDRESS_VERSION_MAINNET_SINGLESIG
+            } else {
+                C32_ADDRESS_VERSION_TESTNET_SINGLESIG
+            };
+            let principals: Vec<_> = (0..principal_count)
+                .map(|_| PrincipalData::Standard(StandardPrincipalData(address_version, rng.gen())))
+                .collect();
+            let allocations: Vec<_> = principals
+                .iter()
+                .map(|principal| (principal.clone(), balance))
+                .collect();
+
+            // the template database and its per-run copies are removed when `scratch` is dropped
+            let scratch = ScratchDir::new("clarity-fuzz");
+            let template_db = scratch.path("db");
+            let (header_db, marf_kv) = initialize_db(&template_db, mainnet, &allocations);
+            let contracts =
+                match deploy_contract_files(header_db, marf_kv, &deployer, &argv[1..], None) {
+                    Ok(contracts) => contracts,
+                    Err(output) => return (1, Some(output)),
+                };
+            let target = friendly_expect_opt(contracts.last(), "No contract to fuzz");
+            let interface = friendly_expect(
+                build_contract_interface(&target.analysis),
+                "Failed to build the contract interface",
+            );
+
+            let mut functions = vec![];
+            let mut argument_types = HashMap::new();
+            let mut invariants = vec![];
+            let mut skipped = vec![];
+            for function in interface.functions.iter() {
+                match function.access {
+                    ContractInterfaceFunctionAccess::public => {
+                        let Some(FunctionType::Fixed(function_type)) =
+                            target.analysis.get_public_function_type(&function.name)
+                        else {
+                            continue;
+                        };
+                        let types: Vec<_> = function_type
+                            .args
+                            .iter()
+                            .map(|arg| arg.signature.clone())
+                            .collect();
+                        match types.iter().try_for_each(|type_sig| {
+                            random_value(&mut rng, &epoch, type_sig, &principals).map(|_| ())
+                        }) {
+                            Ok(()) => {
+                                functions.push(function.name.clone());
+                                argument_types.insert(function.name.clone(), types);
+                            }
+                            Err(error) => skipped.push(json!({
+                                "function": function.name,
+                                "reason": error,
+                            })),
+                        }
+                    }
+                    ContractInterfaceFunctionAccess::read_only
+                        if function.name.starts_with("invariant-") && function.args.is_empty() =>
+                    {
+                        invariants.push(function.name.clone());
+                    }
+                    _ => {}
+                }
+            }
+
+            if functions.is_empty() || invariants.is_empty() {
+                let message = if functions.is_empty() {
+                    "No public functions to call."
+                } else {
+                    "No invariants found."
+                };
+                return (
+                    1,
+                    Some(json!({
+                        "message": message,
+                        "skipped": skipped,
+                    })),
+                );
+            }
+
+            let mut failing_calls = None;
+            for _ in 0..runs {
+                let calls: Vec<_> = (0..depth)
+                    .map(|_| {
+                        let function = friendly_expect_opt(
+                            functions.choose(&mut rng),
+                            "No public functions to call",
+                        );
+                        let sender =
+                            friendly_expect_opt(principals.choose(&mut rng), "No principals")
+                                .clone();
+                        let arguments = argument_types[function]
+                            .iter()
+                            .map(|type_sig| {
+                                friendly_expect(
+                                    random_value(&mut rng, &epoch, type_sig, &principals),
+                                    "Failed to generate an argument",
+                                )
+                            })
+                            .collect();
+                        FuzzCall {
+                            sender,
+                            function: function.clone(),
+                            arguments,
+                        }
+                    })
+                    .collect();
+                let (_, failure) = run_fuzz_calls(
+                    &template_db,
+                    &target.contract_identifier,
+                    &invariants,
+                    &calls,
+                );
+                if let Some(failure) = failure {
+                    failing_calls = Some(calls[..failure.after_calls].to_vec());
+                    break;
+                }
+            }
+
+            let Some(failing_calls) = failing_calls else {
+                return (
+                    0,
+                    Some(json!({
+                        "message": format!("All invariants held over {} runs.", runs),
+                        "seed": seed,
+                        "invariants": invariants,
+                        "skipped": skipped,
+                    })),
+                );
+            };
+
+            let run = |calls: &[FuzzCall]| {
+                run_fuzz_calls(
+                    &template_db,
+                    &target.contract_identifier,
+                    &invariants,
+                    calls,
+                )
+            };
+            let calls = shrink_calls(
+                &epoch,
+                failing_calls,
+                &argument_types,
+                MAX_FUZZ_SHRINK_ATTEMPTS,
+                |calls| run(calls).1.is_some(),
+            );
+            let (results, failure) = run(&calls);
+            let failure = friendly_expect_opt(failure, "Failed to reproduce the invariant failure");
+
+            let calls: Vec<_> = calls
+                .iter()
+                .zip(results.iter())
+                .map(|(call, result)| {
+                    let arguments: Vec<_> = call
+                        .arguments
+                        .iter()
+                        .map(|argument| argument.to_string())
+                        .collect();
+                    json!({
+                        "sender": call.sender.to_string(),
+                        "function": call.function,
+                        "arguments": arguments,
+                        "result": result,
+                    })
+                })
+                .collect();
+            (
+                1,
+                Some(json!({
+                    "message": format!(
+                        "Invariant {} failed after {} calls.",
+                        failure.invariant, failure.after_calls
+                    ),
+                    "seed": seed,
+                    "invariant": failure.invariant,
+                    "result": failure.result,
+                    "calls": calls,
+                    "skipped": skipped,
+                })),
+            )
+        }
+        _ => {
+            print_usage(invoked_by);
+            (1, None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_initial_alloc() {
+        let db_name = format!("/tmp/db_{}", rand::thread_rng().gen::<i32>());
+        let json_name = format!("/tmp/test-alloc_{}.json", rand::thread_rng().gen::<i32>());
+        let clar_name = format!("/tmp/test-alloc_{}.clar", rand::thread_rng().gen::<i32>());
+
+        fs::write(
+            &json_name,
+            r#"
+[ { "principal": "S1G2081040G2081040G2081040G208105NK8PE5",
+    "amount": 1000 },
+  { "principal": "S1G2081040G2081040G2081040G208105NK8PE5.names",
+    "amount": 2000 } ]
+"#,
+        )
+        .unwrap();
+
+        fs::write(&clar_name, r#"
+(unwrap-panic (if (is-eq (stx-get-balance 'S1G2081040G2081040G2081040G208105NK8PE5) u1000) (ok 1) (err 2)))
+(unwrap-panic (if (is-eq (stx-get-balance 'S1G2081040G2081040G2081040G208105NK8PE5.names) u2000) (ok 1) (err 2)))
+"#).unwrap();
+
+        let invoked = invoke_command(
+            "test",
+            &["initialize".to_string(), json_name.clone(), db_name.clone()],
+        );
+        let exit = invoked.0;
+        let result = invoked.1.unwrap();
+
+        assert_eq!(exit, 0);
+        assert_eq!(result["network"], "mainnet");
+
+        let invoked = invoke_command(
+            "test",
+            &[
+                "launch".to_string(),
+                "S1G2081040G2081040G2081040G208105NK8PE5.tokens".to_string(),
+                clar_name,
+                db_name,
             ],
         );
         let exit = invoked.0;
@@ -2407,4 +3394,319 @@ mod test {
                 })
         );
     }
+
+    #[test]
+    fn test_format() {
+        let formatted_name = format!("/tmp/test-format_{}.clar", rand::thread_rng().gen::<i32>());
+        let unformatted_name = format!("/tmp/test-format_{}.clar", rand::thread_rng().gen::<i32>());
+        let invalid_name = format!("/tmp/test-format_{}.clar", rand::thread_rng().gen::<i32>());
+
+        fs::write(
+            &formatted_name,
+            ";; counter\n(define-data-var counter uint u0)\n",
+        )
+        .unwrap();
+        fs::write(
+            &unformatted_name,
+            ";; counter\n(define-data-var  counter\n  uint u0)",
+        )
+        .unwrap();
+        fs::write(&invalid_name, "(define-data-var counter uint u0").unwrap();
+
+        let invoked = invoke_command(
+            "test",
+            &[
+                "format".to_string(),
+                "--check".to_string(),
+                formatted_name.clone(),
+            ],
+        );
+        assert_eq!(invoked.0, 0);
+        assert_eq!(invoked.1.unwrap()["message"], "Program is formatted.");
+
+        let invoked = invoke_command(
+            "test",
+            &[
+                "format".to_string(),
+                "--check".to_string(),
+                unformatted_name.clone(),
+            ],
+        );
+        assert_eq!(invoked.0, 1);
+        assert_eq!(invoked.1.unwrap()["message"], "Program is not formatted.");
+
+        let invoked = invoke_command("test", &["format".to_string(), unformatted_name]);
+        assert_eq!(invoked, (0, None));
+
+        let invoked = invoke_command(
+            "test",
+            &["format".to_string(), "--check".to_string(), invalid_name],
+        );
+        assert_eq!(invoked.0, 1);
+        assert!(invoked.1.unwrap()["error"]["parse"] != json!(null));
+    }
+
+    #[test]
+    fn test_lint() {
+        let clar_name = format!("/tmp/test-lint_{}.clar", rand::thread_rng().gen::<i32>());
+        let config_name = format!("/tmp/test-lint_{}.json", rand::thread_rng().gen::<i32>());
+
+        fs::write(
+            &clar_name,
+            "(define-constant UNUSED u1)\n(define-read-only (get-value (x (optional uint))) (unwrap-panic x))\n",
+        )
+        .unwrap();
+        fs::write(&config_name, r#"{ "unchecked-unwrap-panic": "deny" }"#).unwrap();
+
+        let invoked = invoke_command("test", &["lint".to_string(), clar_name.clone()]);
+        let exit = invoked.0;
+        let result = invoked.1.unwrap();
+
+        assert_eq!(exit, 0);
+        assert_eq!(result["message"], "Lints passed.");
+        assert_eq!(result["diagnostics"].as_array().unwrap().len(), 2);
+
+        let invoked = invoke_command(
+            "test",
+            &[
+                "lint".to_string(),
+                "--config".to_string(),
+                config_name,
+                clar_name,
+            ],
+        );
+        let exit = invoked.0;
+        let result = invoked.1.unwrap();
+
+        assert_eq!(exit, 1);
+        assert_eq!(result["message"], "Lints failed.");
+        let levels: Vec<_> = result["diagnostics"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|diagnostic| diagnostic["level"].clone())
+            .collect();
+        assert!(levels.contains(&json!("Error")));
+        assert!(levels.contains(&json!("Warning")));
+    }
+
+    #[test]
+    fn test_debug() {
+        let db_name = format!("/tmp/db_{}", rand::thread_rng().gen::<i32>());
+        let clar_name = format!("/tmp/test-debug_{}.clar", rand::thread_rng().gen::<i32>());
+        let commands_name = format!("/tmp/test-debug_{}.txt", rand::thread_rng().gen::<i32>());
+
+        fs::write(
+            &clar_name,
+            "(define-data-var counter uint u0)\n(define-private (double (x uint)) (* x u2))\n(define-public (increment (by uint))\n  (begin\n    (var-set counter (+ (var-get counter) (double by)))\n    (ok (var-get counter))))\n",
+        )
+        .unwrap();
+        fs::write(&commands_name, "continue\nlocals\nbacktrace\ncontinue\n").unwrap();
+
+        let invoked = invoke_command("test", &["initialize".to_string(), db_name.clone()]);
+        assert_eq!(invoked.0, 0);
+
+        let invoked = invoke_command(
+            "test",
+            &[
+                "launch".to_string(),
+                "S1G2081040G2081040G2081040G208105NK8PE5.counter".to_string(),
+                clar_name,
+                db_name.clone(),
+            ],
+        );
+        assert_eq!(invoked.0, 0);
+
+        let invoked = invoke_command(
+            "test",
+            &[
+                "debug".to_string(),
+                "--break".to_string(),
+                "double".to_string(),
+                "--watch".to_string(),
+                "(var-get counter)".to_string(),
+                "--commands".to_string(),
+                commands_name,
+                "execute".to_string(),
+                db_name,
+                "S1G2081040G2081040G2081040G208105NK8PE5.counter".to_string(),
+                "increment".to_string(),
+                "SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR".to_string(),
+                "u3".to_string(),
+            ],
+        );
+
+        let exit = invoked.0;
+        let result = invoked.1.unwrap();
+
+        assert_eq!(exit, 0);
+        assert_eq!(result["output"], json!({"UInt": 6}));
+    }
+
+    #[test]
+    fn test_contract_tests() {
+        let dir_name = format!(
+            "/tmp/test-contract-tests_{}",
+            rand::thread_rng().gen::<u32>()
+        );
+        fs::create_dir_all(&dir_name).unwrap();
+        let counter_name = format!("{}/counter.clar", dir_name);
+        let tests_name = format!("{}/counter-tests.clar", dir_name);
+        let lcov_name = format!("{}/counter.lcov", dir_name);
+
+        fs::write(
+            &counter_name,
+            "(define-data-var counter uint u0)
+(define-public (increment)
+  (begin
+    (var-set counter (+ (var-get counter) u1))
+    (ok (var-get counter))))
+(define-read-only (get-counter) (var-get counter))
+",
+        )
+        .unwrap();
+        fs::write(
+            &tests_name,
+            "(define-public (test-increment)
+  (begin
+    (try! (contract-call? .counter increment))
+    (asserts! (is-eq (contract-call? .counter get-counter) u1) (err u1))
+    (ok true)))
+
+;; each test starts from a fresh deployment
+(define-public (test-increment-again)
+  (test-increment))
+
+;; @advance-blocks 5
+(define-public (test-block-height)
+  (begin
+    (asserts! (> block-height u7) (err block-height))
+    (ok true)))
+
+;; @sender 'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR
+(define-public (test-sender)
+  (begin
+    (asserts! (is-eq tx-sender 'S1G2081040G2081040G2081040G208105NK8PE5) (err u2))
+    (ok true)))
+",
+        )
+        .unwrap();
+
+        let invoked = invoke_command(
+            "test",
+            &[
+                "test".to_string(),
+                "--lcov".to_string(),
+                lcov_name.clone(),
+                counter_name,
+                tests_name,
+            ],
+        );
+
+        let exit = invoked.0;
+        let result = invoked.1.unwrap();
+
+        assert_eq!(exit, 1);
+        assert_eq!(result["message"], "1 of 4 tests failed.");
+        let tests: Vec<_> = result["tests"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|test| {
+                (
+                    test["test"].clone(),
+                    test["success"].clone(),
+                    test["result"].clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            tests,
+            vec![
+                (json!("test-increment"), json!(true), json!("(ok true)")),
+                (
+                    json!("test-increment-again"),
+                    json!(true),
+                    json!("(ok true)")
+                ),
+                (json!("test-block-height"), json!(true), json!("(ok true)")),
+                (json!("test-sender"), json!(false), json!("(err u2)")),
+            ]
+        );
+        assert!(fs::metadata(&lcov_name).is_ok());
+
+        fs::remove_dir_all(&dir_name).unwrap();
+    }
+
+    #[test]
+    fn test_fuzz() {
+        let dir_name = format!("/tmp/test-fuzz_{}", rand::thread_rng().gen::<u32>());
+        fs::create_dir_all(&dir_name).unwrap();
+        let vault_name = format!("{}/vault.clar", dir_name);
+        let bounded_vault_name = format!("{}/bounded-vault.clar", dir_name);
+
+        let vault = "(define-data-var total uint u0)
+(define-map deposits principal uint)
+(define-public (deposit (amount uint))
+  (begin
+    (asserts! (<= amount u1000) (err u1))
+    (map-set deposits tx-sender (+ (default-to u0 (map-get? deposits tx-sender)) amount))
+    (var-set total (+ (var-get total) amount))
+    (ok (var-get total))))
+(define-read-only (invariant-bounded)
+  (ok (<= (var-get total) u100000)))
+";
+        fs::write(&bounded_vault_name, vault).unwrap();
+        fs::write(
+            &vault_name,
+            format!(
+                "{}(define-read-only (invariant-small) (<= (var-get total) u100))\n",
+                vault
+            ),
+        )
+        .unwrap();
+
+        let fuzz = |contract: &str| {
+            invoke_command(
+                "test",
+                &[
+                    "fuzz".to_string(),
+                    "--runs".to_string(),
+                    "50".to_string(),
+                    "--seed".to_string(),
+                    "42".to_string(),
+                    contract.to_string(),
+                ],
+            )
+        };
+
+        let (exit, result) = fuzz(&bounded_vault_name);
+        let result = result.unwrap();
+        assert_eq!(exit, 0);
+        assert_eq!(result["message"], "All invariants held over 50 runs.");
+        assert_eq!(result["invariants"], json!(["invariant-bounded"]));
+
+        let (exit, result) = fuzz(&vault_name);
+        let result = result.unwrap();
+        assert_eq!(exit, 1);
+        assert_eq!(result["seed"], 42);
+        assert_eq!(result["invariant"], "invariant-small");
+        assert_eq!(result["result"], "false");
+        let calls = result["calls"].as_array().unwrap();
+        assert!(calls.iter().all(|call| call["function"] == "deposit"));
+        // the last call is the one which took the total over the limit
+        let total: u128 = calls.last().unwrap()["result"]
+            .as_str()
+            .unwrap()
+            .trim_start_matches("(ok u")
+            .trim_end_matches(')')
+            .parse()
+            .unwrap();
+        assert!(total > 100);
+
+        // the same seed reproduces the same failure
+        assert_eq!(fuzz(&vault_name), (exit, Some(result)));
+
+        fs::remove_dir_all(&dir_name).unwrap();
+    }
 }
@@ -25,8 +25,8 @@ pub use clarity::vm::clarity::{ClarityConnection, Error};
 use clarity::vm::contexts::{AssetMap, Environment, OwnedEnvironment};
 use clarity::vm::costs::{CostTracker, ExecutionCost, LimitedCostTracker};
 use clarity::vm::database::{
-    BurnStateDB, ClarityDatabase, HeadersDB, RollbackWrapper, RollbackWrapperPersistedLog,
-    STXBalance, SqliteConnection, NULL_BURN_STATE_DB, NULL_HEADER_DB,
+    BurnStateDB, ClarityBackingStore, ClarityDatabase, HeadersDB, RollbackWrapper,
+    RollbackWrapperPersistedLog, STXBalance, SqliteConnection, NULL_BURN_STATE_DB, NULL_HEADER_DB,
 };
 use clarity::vm::errors::Error as InterpreterError;
 use clarity::vm::representations::SymbolicExpression;
@@ -132,7 +132,7 @@ pub struct ClarityBlockConnection<'a, 'b> {
 ///   rollback the transaction by dropping this struct.
 pub struct ClarityTransactionConnection<'a, 'b> {
     log: Option<RollbackWrapperPersistedLog>,
-    store: &'a mut WritableMarfStore<'b>,
+    store: &'a mut (dyn ClarityBackingStore + 'b),
     header_db: &'a dyn HeadersDB,
     burn_state_db: &'a dyn BurnStateDB,
     cost_track: &'a mut Option<LimitedCostTracker>,
@@ -678,6 +678,36 @@ impl<'a, 'b> ClarityConnection for ClarityBlockConnection<'a, 'b> {
     }
 }
 
+impl<'a> ClarityReadOnlyConnection<'a> {
+    /// Execute `todo` as a transaction on top of this connection's block.
+    /// The transaction's edit log is never committed, so all of its writes are discarded once
+    /// `todo` returns.  Costs are charged to `cost_track`.
+    pub fn as_ephemeral_transaction<F, R>(
+        &mut self,
+        mainnet: bool,
+        chain_id: u32,
+        cost_track: &mut Option<LimitedCostTracker>,
+        todo: F,
+    ) -> R
+    where
+        F: FnOnce(&mut ClarityTransactionConnection) -> R,
+    {
+        let mut log = RollbackWrapperPersistedLog::new();
+        log.nest();
+        let mut tx = ClarityTransactionConnection {
+            store: &mut self.datastore,
+            cost_track,
+            header_db: self.header_db,
+            burn_state_db: self.burn_state_db,
+            log: Some(log),
+            mainnet,
+            chain_id,
+            epoch: self.epoch,
+        };
+        todo(&mut tx)
+    }
+}
+
 impl ClarityConnection for ClarityReadOnlyConnection<'_> {
     /// Do something with ownership of the underlying DB that involves only reading.
     fn with_clarity_db_readonly_owned<F, R>(&mut self, to_do: F) -> R
@@ -17,7 +17,7 @@
 //! Subcommands used by `stacks-inspect` binary
 
 use std::cell::LazyCell;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 use std::{env, fs, io, process, thread};
 
@@ -30,19 +30,32 @@ use stacks_common::types::chainstate::{BlockHeaderHash, BurnchainHeaderHash, Sta
 use stacks_common::types::sqlite::NO_PARAMS;
 
 use crate::burnchains::db::BurnchainDB;
-use crate::burnchains::PoxConstants;
+use crate::burnchains::{Burnchain, PoxConstants};
 use crate::chainstate::burn::db::sortdb::{
     get_ancestor_sort_id, SortitionDB, SortitionHandle, SortitionHandleContext,
 };
 use crate::chainstate::burn::{BlockSnapshot, ConsensusHash};
-use crate::chainstate::coordinator::OnChainRewardSetProvider;
+use crate::chainstate::coordinator::{
+    calculate_paid_rewards, dispatcher_announce_burn_ops, BlockEventDispatcher,
+    Error as CoordinatorError, OnChainRewardSetProvider,
+};
 use crate::chainstate::nakamoto::{NakamotoBlock, NakamotoChainState};
+use crate::chainstate::stacks::boot::RewardSetData;
 use crate::chainstate::stacks::db::blocks::StagingBlock;
-use crate::chainstate::stacks::db::{StacksBlockHeaderTypes, StacksChainState, StacksHeaderInfo};
+use crate::chainstate::stacks::db::{
+    StacksBlockHeaderTypes, StacksChainState, StacksEpochReceipt, StacksHeaderInfo,
+};
 use crate::chainstate::stacks::miner::*;
 use crate::chainstate::stacks::{Error as ChainstateError, *};
 use crate::clarity_vm::clarity::ClarityInstance;
 use crate::core::*;
+use crate::cost_estimates::fee_inclusion_time::{self, TimeToInclusionFeeRateEstimator};
+use crate::cost_estimates::fee_medians::{self, WeightedMedianFeeRateEstimator};
+use crate::cost_estimates::fee_scalar::{self, ScalarFeeRateEstimator};
+use crate::cost_estimates::metrics::{CostMetric, ProportionalDotProduct};
+use crate::cost_estimates::{
+    pessimistic, CostEstimator, EstimatorState, FeeEstimator, PessimisticEstimator,
+};
 use crate::util_lib::db::IndexDBTx;
 
 /// Can be used with CLI commands to support non-mainnet chainstate
@@ -369,6 +382,331 @@ pub fn command_replay_mock_mining(argv: &[String], conf: Option<&StacksChainConf
     }
 }
 
+/// Export the node's cost and fee estimators to a versioned `EstimatorState` file, which can be
+/// loaded into another node's estimators with `command_import_estimators()`
+/// Terminates on error using `process::exit()`
+///
+/// Arguments:
+///  - `argv`: Args in CLI format: `<command-name> [args...]`
+pub fn command_export_estimators(argv: &[String]) {
+    let mut argv = argv.to_vec();
+    let settings = FeeEstimationSettings::drain_args(&mut argv);
+    let print_help_and_exit = || -> ! {
+        let n = &argv[0];
+        eprintln!("Usage:");
+        eprintln!("  {n} <database-path> <fee-estimator> <output-path> [fee-estimation-options]");
+        eprintln!();
+        eprintln!("<fee-estimator> is the node's `fee_estimation.fee_estimator`:");
+        eprintln!("  {}", FEE_ESTIMATOR_NAMES.join(", "));
+        eprintln!();
+        FeeEstimationSettings::print_usage();
+        process::exit(1);
+    };
+    let settings = settings.unwrap_or_else(|| print_help_and_exit());
+    let db_path = argv.get(1).unwrap_or_else(|| print_help_and_exit());
+    let fee_estimator_name = argv.get(2).unwrap_or_else(|| print_help_and_exit());
+    let output_path = argv.get(3).unwrap_or_else(|| print_help_and_exit());
+
+    let estimates_path = PathBuf::from(format!("{db_path}/chainstate/estimates"));
+    if !estimates_path.is_dir() {
+        eprintln!("No estimator DBs in {}", estimates_path.display());
+        process::exit(1);
+    }
+    let cost_estimator = open_cost_estimator(&estimates_path);
+    let fee_estimator = open_fee_estimator(db_path, fee_estimator_name, &settings)
+        .unwrap_or_else(|| print_help_and_exit());
+
+    let state = EstimatorState::export(Some(&cost_estimator), Some(fee_estimator.as_ref()))
+        .unwrap_or_else(|e| panic!("Failed to export estimators: {e}"));
+    let output = fs::File::create(output_path)
+        .unwrap_or_else(|e| panic!("Failed to create {output_path}: {e:?}"));
+    serde_json::to_writer_pretty(output, &state)
+        .unwrap_or_else(|e| panic!("Failed to write {output_path}: {e:?}"));
+    println!("Exported estimators to {output_path}");
+}
+
+/// Replace the node's cost and fee estimators with the state exported by
+/// `command_export_estimators()`. The node must not be running.
+/// Terminates on error using `process::exit()`
+///
+/// Arguments:
+///  - `argv`: Args in CLI format: `<command-name> [args...]`
+pub fn command_import_estimators(argv: &[String]) {
+    let mut argv = argv.to_vec();
+    let settings = FeeEstimationSettings::drain_args(&mut argv);
+    let print_help_and_exit = || -> ! {
+        let n = &argv[0];
+        eprintln!("Usage:");
+        eprintln!("  {n} <database-path> <input-path> [fee-estimation-options]");
+        eprintln!();
+        FeeEstimationSettings::print_usage();
+        process::exit(1);
+    };
+    let settings = settings.unwrap_or_else(|| print_help_and_exit());
+    let db_path = argv.get(1).unwrap_or_else(|| print_help_and_exit());
+    let input_path = argv.get(2).unwrap_or_else(|| print_help_and_exit());
+
+    let input =
+        fs::File::open(input_path).unwrap_or_else(|e| panic!("Failed to open {input_path}: {e:?}"));
+    let state: EstimatorState = serde_json::from_reader(io::BufReader::new(input))
+        .unwrap_or_else(|e| panic!("Failed to read {input_path}: {e:?}"));
+
+    let estimates_path = PathBuf::from(format!("{db_path}/chainstate/estimates"));
+    fs::create_dir_all(&estimates_path)
+        .unwrap_or_else(|e| panic!("Failed to create {}: {e:?}", estimates_path.display()));
+    let mut cost_estimator = state
+        .cost_estimator
+        .as_ref()
+        .map(|_| open_cost_estimator(&estimates_path));
+    let mut fee_estimator = state.fee_estimator.as_ref().map(|exported| {
+        open_fee_estimator(db_path, &exported.estimator, &settings).unwrap_or_else(|| {
+            eprintln!("Unknown fee estimator `{}`", &exported.estimator);
+            process::exit(1);
+        })
+    });
+
+    state
+        .import(
+            cost_estimator
+                .as_mut()
+                .map(|estimator| estimator as &mut dyn CostEstimator),
+            fee_estimator
+                .as_mut()
+                .map(|estimator| estimator.as_mut() as &mut dyn FeeEstimator),
+        )
+        .unwrap_or_else(|e| panic!("Failed to import estimators: {e}"));
+    println!("Imported estimators from {input_path}");
+}
+
+/// Rebuild the node's cost and fee estimators from scratch, by re-evaluating the canonical
+/// Stacks blocks and notifying the estimators of their receipts.  The node must not be running.
+/// Terminates on error using `process::exit()`
+///
+/// Arguments:
+///  - `argv`: Args in CLI format: `<command-name> [args...]`
+///  - `conf`: Optional config for running on non-mainnet chainstate
+pub fn command_rebuild_estimators(argv: &[String], conf: Option<&StacksChainConfig>) {
+    let mut argv = argv.to_vec();
+    let settings = FeeEstimationSettings::drain_args(&mut argv);
+    let print_help_and_exit = || -> ! {
+        let n = &argv[0];
+        eprintln!("Usage:");
+        eprintln!("  {n} <database-path> <fee-estimator> [fee-estimation-options]");
+        eprintln!(
+            "  {n} <database-path> <fee-estimator> <first-burn-height> <last-burn-height> [fee-estimation-options]"
+        );
+        eprintln!();
+        eprintln!("<fee-estimator> is the node's `fee_estimation.fee_estimator`:");
+        eprintln!("  {}", FEE_ESTIMATOR_NAMES.join(", "));
+        eprintln!();
+        FeeEstimationSettings::print_usage();
+        process::exit(1);
+    };
+    let settings = settings.unwrap_or_else(|| print_help_and_exit());
+    let start = Instant::now();
+    let db_path = argv.get(1).unwrap_or_else(|| print_help_and_exit());
+    let fee_estimator_name = argv.get(2).unwrap_or_else(|| print_help_and_exit());
+    let (first_burn_height, last_burn_height) = match (argv.get(3), argv.get(4)) {
+        (Some(first), Some(last)) => (
+            first
+                .parse::<u64>()
+                .expect("<first-burn-height> not a valid u64"),
+            last.parse::<u64>()
+                .expect("<last-burn-height> not a valid u64"),
+        ),
+        (None, None) => (0, u64::MAX),
+        _ => print_help_and_exit(),
+    };
+    if !FEE_ESTIMATOR_NAMES.contains(&fee_estimator_name.as_str()) {
+        print_help_and_exit();
+    }
+
+    // start from empty estimator DBs
+    let estimates_path = PathBuf::from(format!("{db_path}/chainstate/estimates"));
+    fs::create_dir_all(&estimates_path)
+        .unwrap_or_else(|e| panic!("Failed to create {}: {e:?}", estimates_path.display()));
+    for filename in [
+        pessimistic::DB_FILENAME,
+        fee_estimator_db_filename(fee_estimator_name).unwrap_or_else(|| print_help_and_exit()),
+    ] {
+        let path = estimates_path.join(filename);
+        if path.exists() {
+            println!("Removing {}", path.display());
+            fs::remove_file(&path)
+                .unwrap_or_else(|e| panic!("Failed to remove {}: {e:?}", path.display()));
+        }
+    }
+    let mut cost_estimator = open_cost_estimator(&estimates_path);
+    let mut fee_estimator = open_fee_estimator(db_path, fee_estimator_name, &settings)
+        .unwrap_or_else(|| print_help_and_exit());
+
+    let chain_state_path = format!("{db_path}/chainstate/");
+    let sort_db_path = format!("{db_path}/burnchain/sortition");
+    let burn_db_path = format!("{db_path}/burnchain/burnchain.sqlite");
+    let burnchain_blocks_db = BurnchainDB::open(&burn_db_path, false).unwrap();
+
+    let default_conf = STACKS_CHAIN_CONFIG_DEFAULT_MAINNET;
+    let conf = conf.unwrap_or(&default_conf);
+
+    let mainnet = conf.chain_id == CHAIN_ID_MAINNET;
+    let (mut chainstate, _) =
+        StacksChainState::open(mainnet, conf.chain_id, &chain_state_path, None).unwrap();
+
+    let mut sortdb = SortitionDB::connect(
+        &sort_db_path,
+        conf.first_block_height,
+        &conf.first_burn_header_hash,
+        conf.first_burn_header_timestamp,
+        &conf.epochs,
+        conf.pox_constants.clone(),
+        None,
+        true,
+    )
+    .unwrap();
+
+    let num_blocks = replay_estimators(
+        &mut sortdb,
+        &mut chainstate,
+        &burnchain_blocks_db,
+        first_burn_height,
+        last_burn_height,
+        &mut cost_estimator,
+        fee_estimator.as_mut(),
+    )
+    .unwrap_or_else(|e| panic!("Failed to rebuild estimators: {e:?}"));
+    println!(
+        "Rebuilt estimators from {num_blocks} blocks. run_time_seconds = {}",
+        start.elapsed().as_secs()
+    );
+}
+
+/// Names of the fee estimators which can be exported, imported and rebuilt, as given in the
+/// node's `[fee_estimation]` config
+const FEE_ESTIMATOR_NAMES: [&str; 3] = [
+    fee_scalar::ESTIMATOR_NAME,
+    "fuzzed_weighted_median_fee_rate",
+    fee_inclusion_time::ESTIMATOR_NAME,
+];
+
+/// Name of the node's DB for the fee estimator called `name`
+fn fee_estimator_db_filename(name: &str) -> Option<&'static str> {
+    match name {
+        fee_scalar::ESTIMATOR_NAME => Some(fee_scalar::DB_FILENAME),
+        // the node only runs a fuzzed weighted median estimator, whose exported state is the
+        // underlying estimator's
+        "fuzzed_weighted_median_fee_rate" | fee_medians::ESTIMATOR_NAME => {
+            Some(fee_medians::DB_FILENAME)
+        }
+        fee_inclusion_time::ESTIMATOR_NAME => Some(fee_inclusion_time::DB_FILENAME),
+        _ => None,
+    }
+}
+
+/// Open the node's cost estimator, as configured by default
+fn open_cost_estimator(estimates_path: &Path) -> PessimisticEstimator {
+    PessimisticEstimator::open(&estimates_path.join(pessimistic::DB_FILENAME), false)
+        .expect("Error opening cost estimator")
+}
+
+/// The node's `[fee_estimation]` settings which determine how its fee estimator weighs and keeps
+/// samples. These must match the node's config, or the estimator DBs will be read and written
+/// differently from how the node uses them.
+struct FeeEstimationSettings {
+    /// `fee_estimation.cost_metric`
+    cost_metric: String,
+    /// `fee_estimation.fee_rate_window_size`
+    fee_rate_window_size: u32,
+    /// `fee_estimation.fee_rate_max_samples`
+    fee_rate_max_samples: u32,
+}
+
+impl Default for FeeEstimationSettings {
+    fn default() -> Self {
+        Self {
+            cost_metric: "proportion_dot_product".into(),
+            fee_rate_window_size: fee_medians::DEFAULT_WINDOW_SIZE,
+            fee_rate_max_samples: fee_inclusion_time::DEFAULT_MAX_SAMPLES,
+        }
+    }
+}
+
+impl FeeEstimationSettings {
+    fn print_usage() {
+        eprintln!("[fee-estimation-options] must match the node's `[fee_estimation]` config:");
+        eprintln!("  --cost-metric <name>             (default: proportion_dot_product)");
+        eprintln!(
+            "  --fee-rate-window-size <blocks>  (default: {})",
+            fee_medians::DEFAULT_WINDOW_SIZE
+        );
+        eprintln!(
+            "  --fee-rate-max-samples <txs>     (default: {})",
+            fee_inclusion_time::DEFAULT_MAX_SAMPLES
+        );
+    }
+
+    /// Remove the fee estimation options and their values from `argv`.
+    /// Returns `None` if an option is missing its value or the value is invalid.
+    fn drain_args(argv: &mut Vec<String>) -> Option<Self> {
+        let mut settings = Self::default();
+        while let Some(ix) = argv.iter().position(|arg| arg.starts_with("--")) {
+            let value = argv.get(ix + 1)?.clone();
+            match argv[ix].as_str() {
+                "--cost-metric" => settings.cost_metric = value,
+                "--fee-rate-window-size" => settings.fee_rate_window_size = value.parse().ok()?,
+                "--fee-rate-max-samples" => settings.fee_rate_max_samples = value.parse().ok()?,
+                _ => return None,
+            }
+            argv.drain(ix..=ix + 1);
+        }
+        settings.make_cost_metric()?;
+        Some(settings)
+    }
+
+    /// Make the cost metric named by `fee_estimation.cost_metric`, as the node does
+    fn make_cost_metric(&self) -> Option<Box<dyn CostMetric>> {
+        match self.cost_metric.to_lowercase().as_str() {
+            "proportion_dot_product" => {
+                Some(Box::new(ProportionalDotProduct::new(MAX_BLOCK_LEN.into())))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Open the node's fee estimator called `name`, as configured by `settings`
+fn open_fee_estimator(
+    db_path: &str,
+    name: &str,
+    settings: &FeeEstimationSettings,
+) -> Option<Box<dyn FeeEstimator>> {
+    let estimates_path = PathBuf::from(format!("{db_path}/chainstate/estimates"));
+    let path = estimates_path.join(fee_estimator_db_filename(name)?);
+    let metric = settings.make_cost_metric()?;
+    let fee_estimator: Box<dyn FeeEstimator> = match name {
+        fee_scalar::ESTIMATOR_NAME => Box::new(
+            ScalarFeeRateEstimator::open(&path, metric).expect("Error opening fee estimator"),
+        ),
+        fee_inclusion_time::ESTIMATOR_NAME => {
+            let mempool_path = MemPoolDB::db_path(&format!("{db_path}/chainstate"))
+                .expect("Failed to get mempool DB path");
+            Box::new(
+                TimeToInclusionFeeRateEstimator::open(
+                    &path,
+                    Path::new(&mempool_path),
+                    metric,
+                    settings.fee_rate_max_samples,
+                )
+                .expect("Error opening fee estimator"),
+            )
+        }
+        _ => Box::new(
+            WeightedMedianFeeRateEstimator::open(&path, metric, settings.fee_rate_window_size)
+                .expect("Error opening fee estimator"),
+        ),
+    };
+    Some(fee_estimator)
+}
+
 /// Fetch and process a `StagingBlock` from database and call `replay_block()` to validate
 fn replay_staging_block(
     db_path: &str,
@@ -399,6 +737,28 @@ fn replay_staging_block(
         true,
     )
     .unwrap();
+    replay_staging_block_at(
+        &mut sortdb,
+        &mut chainstate,
+        &burnchain_blocks_db,
+        &block_id,
+    );
+}
+
+/// Load a `StagingBlock` and call `replay_block()` to re-evaluate it on top of its parent.
+/// Returns the staging block, the block, and what evaluating it produced, or None if it could
+/// not be evaluated.
+fn replay_staging_block_at(
+    sortdb: &mut SortitionDB,
+    chainstate: &mut StacksChainState,
+    burnchain_blocks_db: &BurnchainDB,
+    block_id: &StacksBlockId,
+) -> Option<(
+    StagingBlock,
+    StacksBlock,
+    StacksEpochReceipt,
+    Option<RewardSetData>,
+)> {
     let sort_tx = sortdb.tx_begin_at_tip();
 
     let blocks_path = chainstate.blocks_path.clone();
@@ -406,7 +766,7 @@ fn replay_staging_block(
         .chainstate_tx_begin()
         .expect("Failed to start chainstate tx");
     let mut next_staging_block =
-        StacksChainState::load_staging_block_info(&chainstate_tx.tx, &block_id)
+        StacksChainState::load_staging_block_info(&chainstate_tx.tx, block_id)
             .expect("Failed to load staging block data")
             .expect("No such index block hash in block database");
 
@@ -421,30 +781,31 @@ fn replay_staging_block(
     let Some(parent_header_info) =
         StacksChainState::get_parent_header_info(&mut chainstate_tx, &next_staging_block).unwrap()
     else {
-        println!("Failed to load parent head info for block: {index_block_hash_hex}");
-        return;
+        println!("Failed to load parent head info for block: {block_id}");
+        return None;
     };
 
     let block =
         StacksChainState::extract_stacks_block(&next_staging_block).expect("Failed to get block");
     let block_size = next_staging_block.block_data.len() as u64;
 
-    replay_block(
+    let (receipt, reward_set_data) = replay_block(
         sort_tx,
         chainstate_tx,
         clarity_instance,
-        &burnchain_blocks_db,
+        burnchain_blocks_db,
         &parent_header_info,
         &next_staging_block.parent_microblock_hash,
         next_staging_block.parent_microblock_seq,
-        &block_id,
+        block_id,
         &block,
         block_size,
         &next_staging_block.consensus_hash,
         &next_staging_block.anchored_block_hash,
         next_staging_block.commit_burn,
         next_staging_block.sortition_burn,
-    );
+    )?;
+    Some((next_staging_block, block, receipt, reward_set_data))
 }
 
 /// Process a mock mined block and call `replay_block()` to validate
@@ -521,7 +882,8 @@ fn replay_mock_mined_block(
     );
 }
 
-/// Validate a block against chainstate
+/// Validate a block against chainstate.
+/// Returns the block's receipt and reward set data, or None if it could not be evaluated.
 fn replay_block(
     mut sort_tx: IndexDBTx<SortitionHandleContext, SortitionId>,
     mut chainstate_tx: ChainstateTx,
@@ -537,7 +899,7 @@ fn replay_block(
     block_hash: &BlockHeaderHash,
     block_commit_burn: u64,
     block_sortition_burn: u64,
-) {
+) -> Option<(StacksEpochReceipt, Option<RewardSetData>)> {
     let parent_block_header = match &parent_header_info.anchored_header {
         StacksBlockHeaderTypes::Epoch2(bh) => bh,
         StacksBlockHeaderTypes::Nakamoto(_) => panic!("Nakamoto blocks not supported yet"),
@@ -554,7 +916,7 @@ fn replay_block(
     )
     .unwrap() else {
         println!("No microblock stream found for {block_id}");
-        return;
+        return None;
     };
 
     let (burn_header_hash, burn_header_height, burn_header_timestamp, _winning_block_txid) =
@@ -585,7 +947,7 @@ fn replay_block(
             &parent_header_info.consensus_hash
         );
         println!("{msg}");
-        return;
+        return None;
     }
 
     // validation check -- validate parent microblocks and find the ones that connect the
@@ -639,14 +1001,15 @@ fn replay_block(
         block_am.weight(),
         true,
     ) {
-        Ok((_receipt, _, _)) => {
+        Ok((receipt, _, reward_set_data)) => {
             info!("Block processed successfully! block = {block_id}");
+            Some((receipt, reward_set_data))
         }
         Err(e) => {
             println!("Failed processing block! block = {block_id}, error = {e:?}");
             process::exit(1);
         }
-    };
+    }
 }
 
 /// Fetch and process a NakamotoBlock from database and call `replay_block_nakamoto()` to validate
@@ -679,12 +1042,14 @@ fn replay_naka_staging_block(db_path: &str, index_block_hash_hex: &str, conf: &S
     replay_block_nakamoto(&mut sortdb, &mut chainstate, &block, block_size).unwrap();
 }
 
+/// Validate a Nakamoto block against chainstate.
+/// Returns the block's receipt and reward set data, or None if it could not be evaluated yet.
 fn replay_block_nakamoto(
     sort_db: &mut SortitionDB,
     stacks_chain_state: &mut StacksChainState,
     block: &NakamotoBlock,
     block_size: u64,
-) -> Result<(), ChainstateError> {
+) -> Result<Option<(StacksEpochReceipt, Option<RewardSetData>)>, ChainstateError> {
     // find corresponding snapshot
     let next_ready_block_snapshot =
         SortitionDB::get_block_snapshot_consensus(sort_db.conn(), &block.header.consensus_hash)?
@@ -746,7 +1111,7 @@ fn replay_block_nakamoto(
                "stacks_block_id" => %block.header.block_id(),
                "parent_block_id" => %block.header.parent_block_id
         );
-        return Ok(());
+        return Ok(None);
     };
 
     // sanity check -- must attach to parent
@@ -852,7 +1217,7 @@ fn replay_block_nakamoto(
             "stacks_block_id" => %block.header.block_id(),
             "burn_view_consensus_hash" => %burnchain_view,
         );
-        return Ok(());
+        return Ok(None);
     };
 
     // find commit and sortition burns if this is a tenure-start block
@@ -938,5 +1303,283 @@ fn replay_block_nakamoto(
         return Err(e);
     };
 
+    let (receipt, _, reward_set_data) = ok_opt.expect("FATAL: unreachable");
+    Ok(Some((receipt, reward_set_data)))
+}
+
+/// Re-derive the events for the canonical burnchain blocks at heights
+/// `first_burn_height..=last_burn_height`, and for the canonical Stacks blocks elected in them,
+/// and announce them to `dispatcher` in the order the node would have processed them.
+///
+/// Each Stacks block is re-evaluated on top of its parent in a transaction that is then rolled
+/// back, so the chainstate is not modified.
+pub fn replay_events<T: BlockEventDispatcher>(
+    sortdb: &mut SortitionDB,
+    chainstate: &mut StacksChainState,
+    burnchain: &Burnchain,
+    first_burn_height: u64,
+    last_burn_height: u64,
+    dispatcher: &T,
+) -> Result<(), CoordinatorError> {
+    let burnchain_blocks_db = BurnchainDB::open(&burnchain.get_burnchaindb_path(), false)?;
+    let sortition_tip = SortitionDB::get_canonical_burn_chain_tip(sortdb.conn())?;
+    let last_burn_height = last_burn_height.min(sortition_tip.block_height);
+
+    let stacks_headers =
+        get_canonical_stacks_headers(sortdb, chainstate, first_burn_height, last_burn_height)?;
+    let mut stacks_headers = stacks_headers.into_iter().peekable();
+
+    info!(
+        "Replaying events for burn blocks {first_burn_height}..={last_burn_height}, containing {} Stacks blocks",
+        stacks_headers.len()
+    );
+
+    for burn_height in first_burn_height..=last_burn_height {
+        let Some(snapshot) = SortitionDB::get_ancestor_snapshot(
+            &sortdb.index_conn(),
+            burn_height,
+            &sortition_tip.sortition_id,
+        )?
+        else {
+            continue;
+        };
+        if snapshot.block_height > 0 {
+            let parent_snapshot =
+                SortitionDB::get_block_snapshot(sortdb.conn(), &snapshot.parent_sortition_id)?
+                    .ok_or(CoordinatorError::NoSortitions)?;
+
+            // the reward set is re-read from the sortition DB, as it was when this block was
+            // evaluated for sortition
+            let next_pox_info = if burnchain.is_reward_cycle_start(burn_height) {
+                burnchain
+                    .block_height_to_reward_cycle(burn_height)
+                    .and_then(|reward_cycle| {
+                        sortdb
+                            .get_preprocessed_reward_set_for_reward_cycle(
+                                &parent_snapshot.sortition_id,
+                                reward_cycle,
+                            )
+                            .ok()
+                    })
+                    .map(|(reward_cycle_info, _)| reward_cycle_info)
+            } else {
+                None
+            };
+            let reward_set_info = sortdb.get_next_block_recipients(
+                burnchain,
+                &parent_snapshot,
+                next_pox_info.as_ref(),
+            )?;
+
+            let burnchain_block = BurnchainDB::get_burnchain_block(
+                burnchain_blocks_db.conn(),
+                &snapshot.burn_header_hash,
+            )?;
+            dispatcher_announce_burn_ops(
+                dispatcher,
+                &burnchain_block.header,
+                calculate_paid_rewards(&burnchain_block.ops),
+                reward_set_info,
+            );
+        }
+
+        while let Some(header) =
+            stacks_headers.next_if(|header| u64::from(header.burn_header_height) == burn_height)
+        {
+            replay_block_events(
+                sortdb,
+                chainstate,
+                &burnchain_blocks_db,
+                &header,
+                dispatcher,
+            )?;
+        }
+    }
     Ok(())
 }
+
+/// Get the headers of the canonical Stacks blocks elected in burnchain blocks
+/// `first_burn_height..=last_burn_height`, in the order they were processed
+fn get_canonical_stacks_headers(
+    sortdb: &SortitionDB,
+    chainstate: &StacksChainState,
+    first_burn_height: u64,
+    last_burn_height: u64,
+) -> Result<Vec<StacksHeaderInfo>, CoordinatorError> {
+    // Walk back from the canonical Stacks tip to find the blocks elected in this range
+    let mut stacks_headers = vec![];
+    let mut cursor = NakamotoChainState::get_canonical_block_header(chainstate.db(), sortdb)?;
+    while let Some(header) = cursor {
+        let burn_height = u64::from(header.burn_header_height);
+        if burn_height < first_burn_height || header.stacks_block_height == 0 {
+            break;
+        }
+        let parent_block_id =
+            StacksChainState::get_parent_block_id(chainstate.db(), &header.index_block_hash())?;
+        if burn_height <= last_burn_height {
+            stacks_headers.push(header);
+        }
+        cursor = match parent_block_id {
+            Some(parent_block_id) => {
+                NakamotoChainState::get_block_header(chainstate.db(), &parent_block_id)?
+            }
+            None => None,
+        };
+    }
+    stacks_headers.reverse();
+    Ok(stacks_headers)
+}
+
+/// Re-evaluate a processed Stacks block, and announce it to `dispatcher`
+fn replay_block_events<T: BlockEventDispatcher>(
+    sortdb: &mut SortitionDB,
+    chainstate: &mut StacksChainState,
+    burnchain_blocks_db: &BurnchainDB,
+    header: &StacksHeaderInfo,
+    dispatcher: &T,
+) -> Result<(), CoordinatorError> {
+    let block_id = header.index_block_hash();
+    let winning_block_txid =
+        SortitionDB::get_block_snapshot_consensus(sortdb.conn(), &header.consensus_hash)?
+            .ok_or(ChainstateError::NoSuchBlockError)?
+            .winning_block_txid;
+    let pox_constants = sortdb.pox_constants.clone();
+    let failed_to_replay =
+        || ChainstateError::InvalidStacksBlock(format!("Failed to replay block {block_id}"));
+
+    debug!("Replaying events for Stacks block {block_id}");
+    match header.anchored_header {
+        StacksBlockHeaderTypes::Epoch2(_) => {
+            let (staging_block, block, receipt, reward_set_data) =
+                replay_staging_block_at(sortdb, chainstate, burnchain_blocks_db, &block_id)
+                    .ok_or_else(failed_to_replay)?;
+            let parent_block_id = StacksBlockId::new(
+                &staging_block.parent_consensus_hash,
+                &staging_block.parent_anchored_block_hash,
+            );
+            dispatcher.announce_block(
+                &block.into(),
+                &receipt.header,
+                &receipt.tx_receipts,
+                &parent_block_id,
+                winning_block_txid,
+                &receipt.matured_rewards,
+                receipt.matured_rewards_info.as_ref(),
+                receipt.parent_burn_block_hash,
+                receipt.parent_burn_block_height,
+                receipt.parent_burn_block_timestamp,
+                &receipt.anchored_block_cost,
+                &receipt.parent_microblocks_cost,
+                &pox_constants,
+                &reward_set_data,
+                &None,
+                None,
+                staging_block.height,
+            );
+        }
+        StacksBlockHeaderTypes::Nakamoto(_) => {
+            let (block, block_size) = chainstate
+                .nakamoto_blocks_db()
+                .get_nakamoto_block(&block_id)?
+                .ok_or(ChainstateError::NoSuchBlockError)?;
+            let parent_header = NakamotoChainState::get_block_header(
+                chainstate.db(),
+                &block.header.parent_block_id,
+            )?
+            .ok_or(ChainstateError::NoSuchBlockError)?;
+            let (receipt, reward_set_data) =
+                replay_block_nakamoto(sortdb, chainstate, &block, block_size)?
+                    .ok_or_else(failed_to_replay)?;
+
+            let parent_block_id = block.header.parent_block_id;
+            let signer_bitvec = block.header.pox_treatment.clone();
+            let block_timestamp = block.header.timestamp;
+            let block_event = (block, parent_header.anchored_header.block_hash()).into();
+            dispatcher.announce_block(
+                &block_event,
+                &receipt.header,
+                &receipt.tx_receipts,
+                &parent_block_id,
+                winning_block_txid,
+                &receipt.matured_rewards,
+                receipt.matured_rewards_info.as_ref(),
+                receipt.parent_burn_block_hash,
+                receipt.parent_burn_block_height,
+                receipt.parent_burn_block_timestamp,
+                &receipt.anchored_block_cost,
+                &receipt.parent_microblocks_cost,
+                &pox_constants,
+                &reward_set_data,
+                &Some(signer_bitvec),
+                Some(block_timestamp),
+                receipt.coinbase_height,
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Re-evaluate the canonical Stacks blocks elected in burnchain blocks
+/// `first_burn_height..=last_burn_height`, and notify the estimators of their receipts as the
+/// coordinator does when it processes them.  Returns the number of blocks replayed.
+///
+/// Each Stacks block is re-evaluated on top of its parent in a transaction that is then rolled
+/// back, so the chainstate is not modified.
+pub fn replay_estimators(
+    sortdb: &mut SortitionDB,
+    chainstate: &mut StacksChainState,
+    burnchain_blocks_db: &BurnchainDB,
+    first_burn_height: u64,
+    last_burn_height: u64,
+    cost_estimator: &mut dyn CostEstimator,
+    fee_estimator: &mut dyn FeeEstimator,
+) -> Result<u64, CoordinatorError> {
+    let stacks_headers =
+        get_canonical_stacks_headers(sortdb, chainstate, first_burn_height, last_burn_height)?;
+    info!(
+        "Replaying {} Stacks blocks into the cost and fee estimators",
+        stacks_headers.len()
+    );
+
+    let mut num_blocks = 0;
+    for (i, header) in stacks_headers.iter().enumerate() {
+        if i % 100 == 0 {
+            println!("Replayed {i}...");
+        }
+        let block_id = header.index_block_hash();
+        let receipt = match header.anchored_header {
+            StacksBlockHeaderTypes::Epoch2(_) => {
+                replay_staging_block_at(sortdb, chainstate, burnchain_blocks_db, &block_id)
+                    .map(|(_, _, receipt, _)| receipt)
+            }
+            StacksBlockHeaderTypes::Nakamoto(_) => {
+                let (block, block_size) = chainstate
+                    .nakamoto_blocks_db()
+                    .get_nakamoto_block(&block_id)?
+                    .ok_or(ChainstateError::NoSuchBlockError)?;
+                replay_block_nakamoto(sortdb, chainstate, &block, block_size)?
+                    .map(|(receipt, _)| receipt)
+            }
+        };
+        let Some(receipt) = receipt else {
+            warn!("Failed to replay block {block_id}, skipping it");
+            continue;
+        };
+
+        let stacks_epoch =
+            SortitionDB::get_stacks_epoch_by_epoch_id(sortdb.conn(), &receipt.evaluated_epoch)?
+                .expect("Could not find a stacks epoch.");
+        cost_estimator.notify_block(
+            &receipt.tx_receipts,
+            &stacks_epoch.block_limit,
+            &stacks_epoch.epoch_id,
+        );
+        if let Err(e) = fee_estimator.notify_block(&receipt, &stacks_epoch.block_limit) {
+            warn!("FeeEstimator failed to process block receipt";
+                  "stacks_block" => %block_id,
+                  "error" => %e);
+        }
+        num_blocks += 1;
+    }
+    Ok(num_blocks)
+}
@@ -15,7 +15,7 @@
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::cmp::{self, Ordering};
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
 use std::hash::Hasher;
 use std::io::{Read, Write};
 use std::ops::{Deref, DerefMut};
@@ -24,7 +24,8 @@ use std::str::FromStr;
 use std::time::{Duration, Instant, SystemTime};
 use std::{fs, io};
 
-use clarity::vm::types::PrincipalData;
+use clarity::vm::types::{PrincipalData, QualifiedContractIdentifier, StacksAddressExtensions};
+use clarity::vm::ClarityName;
 use rand::distributions::Uniform;
 use rand::prelude::Distribution;
 use rusqlite::types::ToSql;
@@ -34,6 +35,7 @@ use rusqlite::{
 use siphasher::sip::SipHasher; // this is SipHash-2-4
 use stacks_common::codec::{
     read_next, write_next, Error as codec_error, StacksMessageCodec, MAX_MESSAGE_LEN,
+    MAX_PAYLOAD_LEN,
 };
 use stacks_common::types::chainstate::{BlockHeaderHash, StacksAddress, StacksBlockId};
 use stacks_common::types::sqlite::NO_PARAMS;
@@ -59,7 +61,7 @@ use crate::core::{
     ExecutionCost, StacksEpochId, FIRST_BURNCHAIN_CONSENSUS_HASH, FIRST_STACKS_BLOCK_HASH,
 };
 use crate::cost_estimates::metrics::{CostMetric, UnitMetric};
-use crate::cost_estimates::{CostEstimator, EstimatorError, UnitEstimator};
+use crate::cost_estimates::{CostEstimator, EstimatorError, FeeRateHistogram, UnitEstimator};
 use crate::monitoring::increment_stx_mempool_gc;
 use crate::net::api::postblock_proposal::{BlockValidateOk, BlockValidateReject};
 use crate::net::Error as net_error;
@@ -74,6 +76,8 @@ use crate::{cost_estimates, monitoring};
 // maximum number of confirmations a transaction can have before it's garbage-collected
 pub static MEMPOOL_MAX_TRANSACTION_AGE: u64 = 256;
 pub static MAXIMUM_MEMPOOL_TX_CHAINING: u64 = 25;
+// maximum number of stuck transactions to report to event observers at once
+pub static MAXIMUM_MEMPOOL_STUCK_TXS_REPORTED: usize = 1000;
 pub static MEMPOOL_NAKAMOTO_MAX_TRANSACTION_AGE: Duration =
     Duration::from_secs(MEMPOOL_MAX_TRANSACTION_AGE * 10 * 60);
 
@@ -94,6 +98,186 @@ pub const BLOOM_COUNTER_DEPTH: usize = 2;
 pub const DEFAULT_BLACKLIST_TIMEOUT: u64 = 24 * 60 * 60 * 2;
 pub const DEFAULT_BLACKLIST_MAX_SIZE: u64 = 134217728; // 2**27 -- the blacklist table can reach at most 4GB at 128 bytes per record
 
+/// Bounds on the size of the mempool.  When a new transaction would push the mempool past
+/// `max_txs` or `max_bytes`, the transactions with the lowest fee rate (fee per byte) which no
+/// other transaction depends on are evicted to make room.  `None` means unbounded.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MemPoolLimits {
+    /// Maximum number of transactions to store
+    pub max_txs: Option<u64>,
+    /// Maximum total length of the stored transactions, in bytes
+    pub max_bytes: Option<u64>,
+    /// Maximum number of transactions with the same origin address
+    pub max_txs_per_origin: Option<u64>,
+}
+
+impl MemPoolLimits {
+    /// Are any limits set?
+    pub fn is_bounded(&self) -> bool {
+        self.max_txs.is_some() || self.max_bytes.is_some() || self.max_txs_per_origin.is_some()
+    }
+}
+
+/// What to do with a transaction which matches a `MemPoolAdmissionRule`
+#[derive(Debug, Clone, PartialEq)]
+pub enum MemPoolAdmissionAction {
+    /// Admit the transaction, subject to the usual admission checks
+    Allow,
+    /// Reject the transaction
+    Deny,
+    /// Admit at most `max_txs` matching transactions in any `window_secs` seconds, and reject
+    /// the rest
+    RateLimit { max_txs: u64, window_secs: u64 },
+}
+
+/// A rule of a `MemPoolAdmissionPolicy`.  A transaction matches the rule if it matches every
+/// criterion which is set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemPoolAdmissionRule {
+    /// Name of the rule, reported to submitters whose transactions it rejects
+    pub name: String,
+    pub origin: Option<StacksAddress>,
+    pub sponsor: Option<StacksAddress>,
+    /// The contract which the transaction calls
+    pub contract: Option<QualifiedContractIdentifier>,
+    /// The public function which the transaction calls
+    pub function: Option<ClarityName>,
+    pub payload_type: Option<MemPoolWalkTxTypes>,
+    pub action: MemPoolAdmissionAction,
+}
+
+impl MemPoolAdmissionRule {
+    /// Does this rule apply to `tx`?
+    pub fn matches(&self, tx: &StacksTransaction) -> bool {
+        if let Some(origin) = self.origin.as_ref() {
+            if *origin != tx.origin_address() {
+                return false;
+            }
+        }
+        if let Some(sponsor) = self.sponsor.as_ref() {
+            if Some(sponsor) != tx.sponsor_address().as_ref() {
+                return false;
+            }
+        }
+        let contract_call = match &tx.payload {
+            TransactionPayload::ContractCall(contract_call) => Some(contract_call),
+            _ => None,
+        };
+        if let Some(contract) = self.contract.as_ref() {
+            if contract_call.map(|cc| cc.to_clarity_contract_id()).as_ref() != Some(contract) {
+                return false;
+            }
+        }
+        if let Some(function) = self.function.as_ref() {
+            if contract_call.map(|cc| &cc.function_name) != Some(function) {
+                return false;
+            }
+        }
+        if let Some(payload_type) = self.payload_type.as_ref() {
+            let tx_payload_type = match &tx.payload {
+                TransactionPayload::TokenTransfer(..) => Some(MemPoolWalkTxTypes::TokenTransfer),
+                TransactionPayload::SmartContract(..) => Some(MemPoolWalkTxTypes::SmartContract),
+                TransactionPayload::ContractCall(..) => Some(MemPoolWalkTxTypes::ContractCall),
+                _ => None,
+            };
+            if tx_payload_type.as_ref() != Some(payload_type) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Node-local rules for which transactions to admit into the mempool, applied on top of the
+/// consensus admission checks to transactions received from clients and peers.  The first rule
+/// which matches a transaction decides what happens to it.  If no rule matches, the
+/// transaction is admitted only if `default_allow` is set.
+#[derive(Debug, Clone)]
+pub struct MemPoolAdmissionPolicy {
+    pub rules: Vec<MemPoolAdmissionRule>,
+    pub default_allow: bool,
+    /// When each rate-limited rule admitted its recent transactions, oldest first
+    admit_times: HashMap<usize, VecDeque<u64>>,
+}
+
+impl Default for MemPoolAdmissionPolicy {
+    fn default() -> Self {
+        Self::new(vec![], true)
+    }
+}
+
+impl MemPoolAdmissionPolicy {
+    pub fn new(rules: Vec<MemPoolAdmissionRule>, default_allow: bool) -> Self {
+        Self {
+            rules,
+            default_allow,
+            admit_times: HashMap::new(),
+        }
+    }
+
+    /// Decide whether to admit `tx` at `now` (in seconds since the epoch).  If the transaction
+    /// is admitted by a rate-limited rule, then the index of that rule is returned, and the
+    /// caller must pass it to `note_admitted` once the transaction is admitted.
+    pub fn check_tx(
+        &self,
+        tx: &StacksTransaction,
+        now: u64,
+    ) -> Result<Option<usize>, MemPoolRejection> {
+        let Some((rule_index, rule)) = self
+            .rules
+            .iter()
+            .enumerate()
+            .find(|(_, rule)| rule.matches(tx))
+        else {
+            if self.default_allow {
+                return Ok(None);
+            }
+            return Err(MemPoolRejection::DeniedByPolicy("default".into()));
+        };
+        match rule.action {
+            MemPoolAdmissionAction::Allow => Ok(None),
+            MemPoolAdmissionAction::Deny => {
+                Err(MemPoolRejection::DeniedByPolicy(rule.name.clone()))
+            }
+            MemPoolAdmissionAction::RateLimit {
+                max_txs,
+                window_secs,
+            } => {
+                let window_start = now.saturating_sub(window_secs);
+                let num_admitted = self
+                    .admit_times
+                    .get(&rule_index)
+                    .map(|times| times.iter().filter(|time| **time > window_start).count())
+                    .unwrap_or(0);
+                if num_admitted as u64 >= max_txs {
+                    return Err(MemPoolRejection::RateLimitedByPolicy {
+                        rule: rule.name.clone(),
+                        max_txs,
+                        window_secs,
+                    });
+                }
+                Ok(Some(rule_index))
+            }
+        }
+    }
+
+    /// Count a transaction admitted at `now` against the limit of the rate-limited rule
+    /// `rule_index`, as returned by `check_tx`
+    pub fn note_admitted(&mut self, rule_index: usize, now: u64) {
+        let Some(MemPoolAdmissionAction::RateLimit { window_secs, .. }) =
+            self.rules.get(rule_index).map(|rule| &rule.action)
+        else {
+            return;
+        };
+        let window_start = now.saturating_sub(*window_secs);
+        let times = self.admit_times.entry(rule_index).or_default();
+        while times.front().is_some_and(|time| *time <= window_start) {
+            times.pop_front();
+        }
+        times.push_back(now);
+    }
+}
+
 // maximum many tx tags we'll send before sending a bloom filter instead.
 // The parameter choice here is due to performance -- calculating a tag set can be slower than just
 // loading the bloom filter, even though the bloom filter is larger.
@@ -302,9 +486,247 @@ pub fn decode_tx_stream<R: Read>(
     Ok((txs, page_id))
 }
 
+/// The version of the metadata trailer written by `export_txs`.  Other versions are rejected on
+/// import.
+pub const MEMPOOL_EXPORT_TRAILER_VERSION: u32 = 1;
+
+/// The metadata that the exporting node kept for a transaction in a mempool export
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportedTxMetadata {
+    pub txid: Txid,
+    /// When the exporting node accepted the transaction, in seconds since the epoch
+    pub accept_time: u64,
+    /// The coinbase height of the exporting node's chain tip when it accepted the transaction
+    pub coinbase_height: u64,
+    /// The exporting node's estimate of the transaction's fee rate, if it had one
+    pub fee_rate: Option<f64>,
+    /// The exporting node's estimate of the time it takes to evaluate the transaction
+    pub time_estimate_ms: Option<u64>,
+}
+
+impl StacksMessageCodec for ExportedTxMetadata {
+    fn consensus_serialize<W: Write>(&self, fd: &mut W) -> Result<(), codec_error> {
+        write_next(fd, &self.txid)?;
+        write_next(fd, &self.accept_time)?;
+        write_next(fd, &self.coinbase_height)?;
+        // optional fields are a 1-byte presence flag, followed by the value if it is present
+        match self.fee_rate {
+            Some(fee_rate) => {
+                write_next(fd, &1u8)?;
+                write_next(fd, &fee_rate.to_bits())?;
+            }
+            None => write_next(fd, &0u8)?,
+        }
+        match self.time_estimate_ms {
+            Some(time_estimate_ms) => {
+                write_next(fd, &1u8)?;
+                write_next(fd, &time_estimate_ms)?;
+            }
+            None => write_next(fd, &0u8)?,
+        }
+        Ok(())
+    }
+
+    fn consensus_deserialize<R: Read>(fd: &mut R) -> Result<ExportedTxMetadata, codec_error> {
+        fn read_optional_u64<R: Read>(fd: &mut R) -> Result<Option<u64>, codec_error> {
+            let flag: u8 = read_next(fd)?;
+            match flag {
+                0 => Ok(None),
+                1 => Ok(Some(read_next(fd)?)),
+                _ => Err(codec_error::DeserializeError(format!(
+                    "Invalid presence flag {}",
+                    flag
+                ))),
+            }
+        }
+
+        let txid: Txid = read_next(fd)?;
+        let accept_time: u64 = read_next(fd)?;
+        let coinbase_height: u64 = read_next(fd)?;
+        let fee_rate = read_optional_u64(fd)?.map(f64::from_bits);
+        let time_estimate_ms = read_optional_u64(fd)?;
+        Ok(ExportedTxMetadata {
+            txid,
+            accept_time,
+            coinbase_height,
+            fee_rate,
+            time_estimate_ms,
+        })
+    }
+}
+
+/// Write the mempool's transactions to `fd` so that another node can import them with
+/// `MemPoolExportReader`.  Transactions are written oldest first, so that each origin's
+/// transactions are re-admitted in the order that this node admitted them.  Rows are streamed
+/// from the database, so the mempool's transactions are never loaded into memory all at once.
+///
+/// The transactions are a sequence of pages.  Each page is a 4-byte big-endian length, followed
+/// by that many bytes of transactions in the framing read by `decode_tx_stream` (without a page
+/// ID).  Pages are at most `MAX_PAYLOAD_LEN` bytes, which keeps each within the bound of
+/// `decode_tx_stream`.  A zero length ends the pages.
+///
+/// The pages are followed by a trailer with each transaction's metadata: a 4-byte big-endian
+/// `MEMPOOL_EXPORT_TRAILER_VERSION`, an 8-byte big-endian count, and then that many serialized
+/// `ExportedTxMetadata`s.
+///
+/// Returns the number of transactions written.
+pub fn export_txs<W: Write>(conn: &DBConn, fd: &mut W) -> Result<u64, net_error> {
+    let sql = "SELECT * FROM mempool ORDER BY accept_time ASC, origin_nonce ASC";
+    let mut stmt = conn.prepare(sql).map_err(db_error::SqliteError)?;
+    let mut rows = stmt.query(NO_PARAMS).map_err(db_error::SqliteError)?;
+
+    let mut num_txs = 0;
+    let mut page = vec![];
+    let mut trailer = vec![];
+    while let Some(row) = rows.next().map_err(db_error::SqliteError)? {
+        let tx_info = MemPoolTxInfo::from_row(row)?;
+        let fee_rate: Option<f64> = row.get("fee_rate").map_err(db_error::SqliteError)?;
+        ExportedTxMetadata {
+            txid: tx_info.tx.txid(),
+            accept_time: tx_info.metadata.accept_time,
+            coinbase_height: tx_info.metadata.coinbase_height,
+            fee_rate,
+            time_estimate_ms: tx_info.metadata.time_estimate_ms,
+        }
+        .consensus_serialize(&mut trailer)?;
+
+        let tx_bytes = tx_info.tx.serialize_to_vec();
+        if !page.is_empty() && page.len() + tx_bytes.len() > MAX_PAYLOAD_LEN as usize {
+            write_export_page(fd, &page)?;
+            page.clear();
+        }
+        page.extend_from_slice(&tx_bytes);
+        num_txs += 1;
+    }
+    if !page.is_empty() {
+        write_export_page(fd, &page)?;
+    }
+    write_next(fd, &0u32)?;
+
+    write_next(fd, &MEMPOOL_EXPORT_TRAILER_VERSION)?;
+    write_next(fd, &num_txs)?;
+    fd.write_all(&trailer).map_err(net_error::WriteError)?;
+    Ok(num_txs)
+}
+
+fn write_export_page<W: Write>(fd: &mut W, page: &[u8]) -> Result<(), net_error> {
+    let page_len = u32::try_from(page.len())
+        .map_err(|_| net_error::OverflowError("Mempool export page is too big".into()))?;
+    write_next(fd, &page_len)?;
+    fd.write_all(page).map_err(net_error::WriteError)
+}
+
+/// Reads back an export written by `export_txs`: first its transactions one page at a time, in
+/// the order they were written, and then their metadata
+pub struct MemPoolExportReader<R: Read> {
+    fd: R,
+    /// Transactions of the current page which have not been read yet
+    page: VecDeque<StacksTransaction>,
+    /// Whether or not the end of the pages has been read
+    pages_done: bool,
+    /// Number of metadata records left in the trailer, once its header has been read
+    trailer_remaining: Option<u64>,
+}
+
+impl<R: Read> MemPoolExportReader<R> {
+    pub fn new(fd: R) -> Self {
+        Self {
+            fd,
+            page: VecDeque::new(),
+            pages_done: false,
+            trailer_remaining: None,
+        }
+    }
+
+    /// Read the next transaction, or None once all of the transactions have been read
+    pub fn next_tx(&mut self) -> Result<Option<StacksTransaction>, net_error> {
+        while self.page.is_empty() {
+            if self.pages_done {
+                return Ok(None);
+            }
+            let page_len: u32 = read_next(&mut self.fd)?;
+            if page_len == 0 {
+                self.pages_done = true;
+                return Ok(None);
+            }
+            if page_len > MAX_PAYLOAD_LEN {
+                return Err(net_error::OverflowError(format!(
+                    "Mempool export page of {} bytes is too big",
+                    page_len
+                )));
+            }
+            let mut page = vec![0u8; page_len as usize];
+            self.fd
+                .read_exact(&mut page)
+                .map_err(net_error::ReadError)?;
+
+            let (page_txs, page_id) = decode_tx_stream(&mut &page[..])?;
+            if page_id.is_some() {
+                return Err(net_error::DeserializeError(
+                    "Unexpected page ID in mempool export".into(),
+                ));
+            }
+            self.page.extend(page_txs);
+        }
+        Ok(self.page.pop_front())
+    }
+
+    /// Read the next transaction's metadata from the trailer, or None at the end of the export.
+    /// Any transactions which have not been read yet are skipped.
+    pub fn next_metadata(&mut self) -> Result<Option<ExportedTxMetadata>, net_error> {
+        while self.next_tx()?.is_some() {}
+
+        let remaining = match self.trailer_remaining {
+            Some(remaining) => remaining,
+            None => {
+                let version: u32 = read_next(&mut self.fd)?;
+                if version != MEMPOOL_EXPORT_TRAILER_VERSION {
+                    return Err(net_error::DeserializeError(format!(
+                        "Unsupported mempool export trailer version {} (expected {})",
+                        version, MEMPOOL_EXPORT_TRAILER_VERSION
+                    )));
+                }
+                read_next(&mut self.fd)?
+            }
+        };
+        if remaining == 0 {
+            self.trailer_remaining = Some(0);
+            // nothing may follow the trailer
+            let mut extra = [0u8; 1];
+            if self.fd.read(&mut extra).map_err(net_error::ReadError)? != 0 {
+                return Err(net_error::DeserializeError(
+                    "Trailing bytes after mempool export".into(),
+                ));
+            }
+            return Ok(None);
+        }
+        let metadata = ExportedTxMetadata::consensus_deserialize(&mut self.fd)?;
+        self.trailer_remaining = Some(remaining - 1);
+        Ok(Some(metadata))
+    }
+}
+
+/// Read back all of the transactions written by `export_txs` in the order they were written,
+/// along with their metadata
+pub fn read_exported_txs<R: Read>(
+    fd: &mut R,
+) -> Result<(Vec<StacksTransaction>, Vec<ExportedTxMetadata>), net_error> {
+    let mut reader = MemPoolExportReader::new(fd);
+    let mut txs = vec![];
+    while let Some(tx) = reader.next_tx()? {
+        txs.push(tx);
+    }
+    let mut metadata = vec![];
+    while let Some(tx_metadata) = reader.next_metadata()? {
+        metadata.push(tx_metadata);
+    }
+    Ok((txs, metadata))
+}
+
 pub struct MemPoolAdmitter {
     cur_block: BlockHeaderHash,
     cur_consensus_hash: ConsensusHash,
+    policy: MemPoolAdmissionPolicy,
 }
 
 enum MemPoolWalkResult {
@@ -318,20 +740,30 @@ impl MemPoolAdmitter {
         MemPoolAdmitter {
             cur_block,
             cur_consensus_hash,
+            policy: MemPoolAdmissionPolicy::default(),
         }
     }
 
+    pub fn set_policy(&mut self, policy: MemPoolAdmissionPolicy) {
+        self.policy = policy;
+    }
+
     pub fn set_block(&mut self, cur_block: &BlockHeaderHash, cur_consensus_hash: ConsensusHash) {
         self.cur_consensus_hash = cur_consensus_hash.clone();
         self.cur_block = cur_block.clone();
     }
+    /// Check whether `tx` may be admitted.  If it is admitted by a rate-limited admission rule,
+    /// then the index of that rule is returned, and the caller must pass it to `note_admitted`
+    /// once the transaction has been stored.
     pub fn will_admit_tx(
         &mut self,
         chainstate: &mut StacksChainState,
         sortdb: &SortitionDB,
         tx: &StacksTransaction,
         tx_size: u64,
-    ) -> Result<(), MemPoolRejection> {
+    ) -> Result<Option<usize>, MemPoolRejection> {
+        let rate_limited_rule = self.policy.check_tx(tx, get_epoch_time_secs())?;
+
         let sortition_id = match SortitionDB::get_sortition_id_by_consensus(
             &sortdb.conn(),
             &self.cur_consensus_hash,
@@ -345,7 +777,14 @@ impl MemPoolAdmitter {
             &self.cur_block,
             tx,
             tx_size,
-        )
+        )?;
+        Ok(rate_limited_rule)
+    }
+
+    /// Count a transaction admitted at `now` against the limit of the rate-limited admission rule
+    /// `rule_index`, as returned by `will_admit_tx`
+    pub fn note_admitted(&mut self, rule_index: usize, now: u64) {
+        self.policy.note_admitted(rule_index, now);
     }
 }
 
@@ -355,6 +794,7 @@ pub enum MemPoolDropReason {
     STALE_COLLECT,
     TOO_EXPENSIVE,
     PROBLEMATIC,
+    CAPACITY_EVICTION,
 }
 
 pub struct ConsiderTransaction {
@@ -380,6 +820,7 @@ impl std::fmt::Display for MemPoolDropReason {
             MemPoolDropReason::REPLACE_ACROSS_FORK => write!(f, "ReplaceAcrossFork"),
             MemPoolDropReason::REPLACE_BY_FEE => write!(f, "ReplaceByFee"),
             MemPoolDropReason::PROBLEMATIC => write!(f, "Problematic"),
+            MemPoolDropReason::CAPACITY_EVICTION => write!(f, "CapacityEviction"),
         }
     }
 }
@@ -391,6 +832,7 @@ pub trait ProposalCallbackReceiver: Send {
 pub trait MemPoolEventDispatcher {
     fn get_proposal_callback_receiver(&self) -> Option<Box<dyn ProposalCallbackReceiver>>;
     fn mempool_txs_dropped(&self, txids: Vec<Txid>, reason: MemPoolDropReason);
+    fn mempool_txs_stuck(&self, stuck_txs: Vec<MemPoolStuckTx>);
     fn mined_block_event(
         &self,
         target_burn_height: u64,
@@ -519,6 +961,86 @@ impl MemPoolWalkTxTypes {
     }
 }
 
+/// Maximum number of sequential transactions from one origin which are scored together as a
+/// package by `MemPoolWalkStrategy::NonceChainPackage`
+pub const MAX_MEMPOOL_PACKAGE_TXS: usize = 25;
+
+/// Order in which a miner's mempool walk considers transactions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MemPoolWalkStrategy {
+    /// Highest estimated fee rate first, with a chance of considering a transaction without a
+    /// fee rate estimate instead (see `MemPoolWalkSettings::consider_no_estimate_tx_prob`)
+    GlobalFeeRate,
+    /// Highest estimated fee rate first.  Transactions without a fee rate estimate are only
+    /// considered once every transaction with one has been.
+    StrictFeeRate,
+    /// Highest package fee rate first, where a transaction's package is the most profitable
+    /// run of it and the origin's next sequential nonces (see `MemPoolDB::package_fee_rates`),
+    /// scored by their estimated fee rates.  A low-fee transaction is considered early if later
+    /// transactions from the same origin pay for it.  Transactions without a fee rate estimate
+    /// are only considered once every transaction with one has been.
+    NonceChainPackage,
+    /// Oldest transaction first, by the time this node accepted it
+    AcceptTimeFifo,
+}
+
+impl FromStr for MemPoolWalkStrategy {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "GlobalFeeRate" => Ok(Self::GlobalFeeRate),
+            "StrictFeeRate" => Ok(Self::StrictFeeRate),
+            "NonceChainPackage" => Ok(Self::NonceChainPackage),
+            "AcceptTimeFifo" => Ok(Self::AcceptTimeFifo),
+            _ => Err("Unknown mempool walk strategy"),
+        }
+    }
+}
+
+/// Which pending transactions to list from the mempool
+#[derive(Debug, Clone, PartialEq)]
+pub enum MemPoolTxFilter {
+    /// All transactions, highest fee rate first
+    All,
+    /// Transactions originated by this address, lowest nonce first
+    Origin(StacksAddress),
+    /// Transactions sponsored by this address, lowest nonce first
+    Sponsor(StacksAddress),
+    /// Contract-calls to this contract, highest fee rate first
+    ContractCall(QualifiedContractIdentifier),
+}
+
+/// A pending transaction which cannot be mined until a missing nonce of its origin or sponsor
+/// account is filled
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemPoolStuckTx {
+    pub txid: Txid,
+    /// The origin or sponsor account which is missing a nonce
+    pub address: StacksAddress,
+    /// The lowest nonce of `address` which is neither confirmed nor pending in the mempool
+    pub missing_nonce: u64,
+}
+
+/// Whether or not a pending transaction's nonces allow it to be mined
+#[derive(Debug, Clone, PartialEq)]
+pub enum MemPoolTxNonceStatus {
+    /// The transaction's nonces are the accounts' confirmed nonces, so it can be mined next
+    Ready,
+    /// The transaction can be mined once the pending transactions with lower nonces are mined
+    Queued,
+    /// The transaction cannot be mined until `missing_nonce` of `address` is filled
+    Blocked {
+        address: StacksAddress,
+        missing_nonce: u64,
+    },
+    /// The transaction's nonce for `address` is below its confirmed nonce, so it can never be
+    /// mined
+    Stale {
+        address: StacksAddress,
+        confirmed_nonce: u64,
+    },
+}
+
 #[derive(Debug, Clone)]
 pub struct MemPoolWalkSettings {
     /// Maximum amount of time a miner will spend walking through mempool transactions, in
@@ -540,6 +1062,8 @@ pub struct MemPoolWalkSettings {
     /// What percentage of the remaining cost limit should we consume before stopping the walk
     /// None means we consume the entire cost limit ASAP
     pub tenure_cost_limit_per_block_percentage: Option<u8>,
+    /// Order in which to consider transactions
+    pub strategy: MemPoolWalkStrategy,
 }
 
 impl Default for MemPoolWalkSettings {
@@ -558,6 +1082,7 @@ impl Default for MemPoolWalkSettings {
             .collect(),
             filter_origins: HashSet::new(),
             tenure_cost_limit_per_block_percentage: None,
+            strategy: MemPoolWalkStrategy::GlobalFeeRate,
         }
     }
 }
@@ -577,6 +1102,7 @@ impl MemPoolWalkSettings {
             .collect(),
             filter_origins: HashSet::new(),
             tenure_cost_limit_per_block_percentage: None,
+            strategy: MemPoolWalkStrategy::GlobalFeeRate,
         }
     }
 }
@@ -820,6 +1346,28 @@ const MEMPOOL_SCHEMA_7_TIME_ESTIMATES: &'static [&'static str] = &[
     "#,
 ];
 
+const MEMPOOL_SCHEMA_8_CONTRACT_CALLS: &'static [&'static str] = &[
+    r#"
+    -- The contract called by a contract-call transaction, or NULL for other transactions.
+    -- ALLOW NULL
+    ALTER TABLE mempool ADD COLUMN contract_call TEXT;
+    "#,
+    r#"
+    CREATE INDEX IF NOT EXISTS by_contract_call ON mempool(contract_call);
+    "#,
+];
+
+const MEMPOOL_SCHEMA_9_EVICTION: &'static [&'static str] = &[
+    r#"
+    -- Order in which transactions are evicted when the mempool is full (see `enforce_limits`)
+    CREATE INDEX IF NOT EXISTS by_fee_per_byte
+        ON mempool(CAST(tx_fee AS REAL) / length ASC, accept_time DESC);
+    "#,
+    r#"
+    INSERT INTO schema_version (version) VALUES (9)
+    "#,
+];
+
 const MEMPOOL_INDEXES: &'static [&'static str] = &[
     "CREATE INDEX IF NOT EXISTS by_txid ON mempool(txid);",
     "CREATE INDEX IF NOT EXISTS by_height ON mempool(height);",
@@ -844,6 +1392,7 @@ pub struct MemPoolDB {
     metric: Box<dyn CostMetric>,
     pub blacklist_timeout: u64,
     pub blacklist_max_size: u64,
+    pub limits: MemPoolLimits,
 }
 
 pub struct MemPoolTx<'a> {
@@ -1216,6 +1765,267 @@ impl CandidateCache {
     }
 }
 
+/// Number of mempool rows read at a time by a mempool walk whose strategy orders its candidates
+/// up front (every strategy but `MemPoolWalkStrategy::GlobalFeeRate`)
+pub const MEMPOOL_WALK_PAGE_SIZE: u32 = 1024;
+
+/// How far an ordered mempool walk has read through the mempool
+enum WalkCursor {
+    /// Transactions with a fee rate estimate, highest first, after the given (fee rate, txid)
+    FeeRate(Option<(f64, Txid)>),
+    /// Transactions without a fee rate estimate, after the given txid
+    NoEstimate(Option<Txid>),
+    /// All transactions, oldest first, after the given (accept time, txid)
+    AcceptTime(Option<(u64, Txid)>),
+    /// Every transaction has been read
+    Done,
+}
+
+/// A mempool walk candidate, ordered by the package fee rate of the run of transactions which it
+/// starts (see `MemPoolDB::package_fee_rates`).  Among equal fee rates, the lower nonce comes
+/// first.
+struct PackageCandidate {
+    fee_rate: f64,
+    candidate: MemPoolTxInfoPartial,
+}
+
+impl PartialEq for PackageCandidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for PackageCandidate {}
+
+impl PartialOrd for PackageCandidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PackageCandidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.fee_rate
+            .partial_cmp(&other.fee_rate)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| {
+                other
+                    .candidate
+                    .origin_nonce
+                    .cmp(&self.candidate.origin_nonce)
+            })
+            .then_with(|| other.candidate.txid.cmp(&self.candidate.txid))
+    }
+}
+
+/// The candidates of a mempool walk whose strategy orders them up front, read from the mempool
+/// `MEMPOOL_WALK_PAGE_SIZE` rows at a time.
+///
+/// `NonceChainPackage` reads transactions by estimated fee rate, highest first, and scores each
+/// one's origin's nearby transactions as packages.  A package's fee rate is never higher than the
+/// highest fee rate of its members, so a scored package can be handed out once its fee rate is at
+/// least the fee rate of the next transaction to be read.
+struct OrderedCandidates {
+    strategy: MemPoolWalkStrategy,
+    cursor: WalkCursor,
+    /// Rows read from the mempool but not yet handed out or scored, in order
+    page: VecDeque<MemPoolTxInfoPartial>,
+    /// `NonceChainPackage` only: scored candidates which have not been handed out
+    packages: BinaryHeap<PackageCandidate>,
+    /// `NonceChainPackage` only: the transactions which have been scored
+    scored: HashSet<Txid>,
+}
+
+impl OrderedCandidates {
+    /// Returns None for `MemPoolWalkStrategy::GlobalFeeRate`, whose candidates are streamed from
+    /// the database as the walk proceeds.
+    fn new(strategy: MemPoolWalkStrategy) -> Option<Self> {
+        let cursor = match strategy {
+            MemPoolWalkStrategy::GlobalFeeRate => return None,
+            MemPoolWalkStrategy::StrictFeeRate | MemPoolWalkStrategy::NonceChainPackage => {
+                WalkCursor::FeeRate(None)
+            }
+            MemPoolWalkStrategy::AcceptTimeFifo => WalkCursor::AcceptTime(None),
+        };
+        Some(Self {
+            strategy,
+            cursor,
+            page: VecDeque::new(),
+            packages: BinaryHeap::new(),
+            scored: HashSet::new(),
+        })
+    }
+
+    /// Read the next page of rows, if the current one has been used up.  Moves on to the
+    /// transactions without a fee rate estimate once those with one have all been read.
+    fn fill_page(&mut self, conn: &DBConn) -> Result<(), db_error> {
+        let cols = "txid, origin_nonce, origin_address, sponsor_nonce, sponsor_address, fee_rate, accept_time";
+        let limit = MEMPOOL_WALK_PAGE_SIZE;
+        while self.page.is_empty() {
+            let mut rows: Vec<(MemPoolTxInfoPartial, u64)> = vec![];
+            let mut stmt;
+            let mut query = match &self.cursor {
+                WalkCursor::Done => return Ok(()),
+                WalkCursor::FeeRate(None) => {
+                    let sql = format!("SELECT {cols} FROM mempool WHERE fee_rate IS NOT NULL ORDER BY fee_rate DESC, txid ASC LIMIT ?1");
+                    stmt = conn.prepare(&sql)?;
+                    stmt.query(params![limit])?
+                }
+                WalkCursor::FeeRate(Some((fee_rate, txid))) => {
+                    let sql = format!("SELECT {cols} FROM mempool WHERE fee_rate <= ?1 AND (fee_rate < ?1 OR txid > ?2) ORDER BY fee_rate DESC, txid ASC LIMIT ?3");
+                    stmt = conn.prepare(&sql)?;
+                    stmt.query(params![fee_rate, txid, limit])?
+                }
+                WalkCursor::NoEstimate(None) => {
+                    let sql = format!("SELECT {cols} FROM mempool WHERE fee_rate IS NULL ORDER BY txid ASC LIMIT ?1");
+                    stmt = conn.prepare(&sql)?;
+                    stmt.query(params![limit])?
+                }
+                WalkCursor::NoEstimate(Some(txid)) => {
+                    let sql = format!("SELECT {cols} FROM mempool WHERE fee_rate IS NULL AND txid > ?1 ORDER BY txid ASC LIMIT ?2");
+                    stmt = conn.prepare(&sql)?;
+                    stmt.query(params![txid, limit])?
+                }
+                WalkCursor::AcceptTime(None) => {
+                    let sql = format!(
+                        "SELECT {cols} FROM mempool ORDER BY accept_time ASC, txid ASC LIMIT ?1"
+                    );
+                    stmt = conn.prepare(&sql)?;
+                    stmt.query(params![limit])?
+                }
+                WalkCursor::AcceptTime(Some((accept_time, txid))) => {
+                    let sql = format!("SELECT {cols} FROM mempool WHERE accept_time >= ?1 AND (accept_time > ?1 OR txid > ?2) ORDER BY accept_time ASC, txid ASC LIMIT ?3");
+                    stmt = conn.prepare(&sql)?;
+                    stmt.query(params![u64_to_sql(*accept_time)?, txid, limit])?
+                }
+            };
+            while let Some(row) = query.next()? {
+                let candidate = MemPoolTxInfoPartial::from_row(row)?;
+                let accept_time = u64::from_column(row, "accept_time")?;
+                rows.push((candidate, accept_time));
+            }
+
+            let last = rows
+                .last()
+                .map(|(candidate, accept_time)| (candidate.clone(), *accept_time));
+            self.cursor = match (&self.cursor, last) {
+                (WalkCursor::FeeRate(_), Some((candidate, _))) => match candidate.fee_rate {
+                    Some(fee_rate) => WalkCursor::FeeRate(Some((fee_rate, candidate.txid))),
+                    None => WalkCursor::NoEstimate(None),
+                },
+                (WalkCursor::FeeRate(_), None) => WalkCursor::NoEstimate(None),
+                (WalkCursor::NoEstimate(_), Some((candidate, _))) => {
+                    WalkCursor::NoEstimate(Some(candidate.txid))
+                }
+                (WalkCursor::AcceptTime(_), Some((candidate, accept_time))) => {
+                    WalkCursor::AcceptTime(Some((accept_time, candidate.txid)))
+                }
+                (_, None) | (WalkCursor::Done, _) => WalkCursor::Done,
+            };
+            self.page
+                .extend(rows.into_iter().map(|(candidate, _)| candidate));
+        }
+        Ok(())
+    }
+
+    /// Score the packages which start at `candidate` or at one of the origin's transactions with
+    /// a lower nonce that could include it, and which have not been scored yet.  Only
+    /// transactions with a fee rate estimate are scored; a transaction without one ends a
+    /// package.
+    fn score_packages(
+        &mut self,
+        conn: &DBConn,
+        candidate: &MemPoolTxInfoPartial,
+    ) -> Result<(), db_error> {
+        let window = MAX_MEMPOOL_PACKAGE_TXS as u64 - 1;
+        let sql = "SELECT txid, origin_nonce, origin_address, sponsor_nonce, sponsor_address, fee_rate, tx_fee
+            FROM mempool WHERE origin_address = ?1 AND origin_nonce >= ?2 AND origin_nonce <= ?3
+            ORDER BY origin_nonce ASC";
+        let args = params![
+            candidate.origin_address.to_string(),
+            u64_to_sql(candidate.origin_nonce.saturating_sub(window))?,
+            u64_to_sql(cmp::min(
+                candidate.origin_nonce.saturating_add(window),
+                i64::MAX as u64
+            ))?,
+        ];
+        let mut stmt = conn.prepare(sql)?;
+        let mut rows = stmt.query(args)?;
+
+        // split the origin's transactions into runs of estimated transactions
+        let mut runs: Vec<Vec<(MemPoolTxInfoPartial, u64)>> = vec![vec![]];
+        while let Some(row) = rows.next()? {
+            let member = MemPoolTxInfoPartial::from_row(row)?;
+            let tx_fee = u64::from_column(row, "tx_fee")?;
+            if member.fee_rate.is_some() {
+                if let Some(run) = runs.last_mut() {
+                    run.push((member, tx_fee));
+                }
+            } else {
+                runs.push(vec![]);
+            }
+        }
+
+        for run in runs.into_iter() {
+            // a transaction's estimated cost is its fee divided by its fee rate
+            let chain: Vec<_> = run
+                .iter()
+                .map(|(member, tx_fee)| {
+                    let fee_rate = member.fee_rate.unwrap_or(0.0);
+                    let cost = if fee_rate > 0.0 {
+                        *tx_fee as f64 / fee_rate
+                    } else {
+                        f64::INFINITY
+                    };
+                    (member.origin_nonce, *tx_fee, cost)
+                })
+                .collect();
+            let fee_rates = MemPoolDB::package_fee_rates(&chain);
+            for ((member, _), fee_rate) in run.into_iter().zip(fee_rates) {
+                if member.origin_nonce <= candidate.origin_nonce
+                    && self.scored.insert(member.txid.clone())
+                {
+                    self.packages.push(PackageCandidate {
+                        fee_rate,
+                        candidate: member,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the next candidate to consider, or None if every transaction has been handed out
+    fn next(&mut self, conn: &DBConn) -> Result<Option<MemPoolTxInfoPartial>, db_error> {
+        loop {
+            self.fill_page(conn)?;
+            if self.strategy != MemPoolWalkStrategy::NonceChainPackage {
+                return Ok(self.page.pop_front());
+            }
+
+            // hand out the best scored package, unless an unread transaction could start a
+            // better one
+            let next_fee_rate = self.page.front().and_then(|candidate| candidate.fee_rate);
+            if let Some(best) = self.packages.peek() {
+                if next_fee_rate.map_or(true, |fee_rate| best.fee_rate >= fee_rate) {
+                    return Ok(self.packages.pop().map(|package| package.candidate));
+                }
+            }
+
+            let Some(candidate) = self.page.pop_front() else {
+                return Ok(None);
+            };
+            if candidate.fee_rate.is_none() {
+                // unestimated transactions are considered last, and are not scored
+                return Ok(Some(candidate));
+            }
+            if !self.scored.contains(&candidate.txid) {
+                self.score_packages(conn, &candidate)?;
+            }
+        }
+    }
+}
+
 /// Evaluates the pair of nonces, to determine an order
 ///
 /// Returns:
@@ -1307,6 +2117,12 @@ impl MemPoolDB {
                     MemPoolDB::instantiate_schema_7(tx)?;
                 }
                 7 => {
+                    MemPoolDB::instantiate_schema_8(tx)?;
+                }
+                8 => {
+                    MemPoolDB::instantiate_schema_9(tx)?;
+                }
+                9 => {
                     break;
                 }
                 _ => {
@@ -1393,6 +2209,52 @@ impl MemPoolDB {
         Ok(())
     }
 
+    /// Add the contract-call column, and fill it in for the transactions already stored
+    #[cfg_attr(test, mutants::skip)]
+    fn instantiate_schema_8(tx: &DBTx) -> Result<(), db_error> {
+        for sql_exec in MEMPOOL_SCHEMA_8_CONTRACT_CALLS {
+            tx.execute_batch(sql_exec)?;
+        }
+
+        let rows: Vec<(Txid, Vec<u8>)> = {
+            let mut stmt = tx.prepare("SELECT txid, tx FROM mempool")?;
+            let rows = stmt.query_map(NO_PARAMS, |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect::<Result<_, _>>()?
+        };
+        for (txid, tx_bytes) in rows.into_iter() {
+            let Some(contract_call) = MemPoolDB::get_contract_call(&tx_bytes) else {
+                continue;
+            };
+            tx.execute(
+                "UPDATE mempool SET contract_call = ?1 WHERE txid = ?2",
+                params![contract_call, txid],
+            )?;
+        }
+
+        tx.execute_batch("INSERT INTO schema_version (version) VALUES (8)")?;
+        Ok(())
+    }
+
+    /// Add the index used to choose which transactions to evict
+    #[cfg_attr(test, mutants::skip)]
+    fn instantiate_schema_9(tx: &DBTx) -> Result<(), db_error> {
+        for sql_exec in MEMPOOL_SCHEMA_9_EVICTION {
+            tx.execute_batch(sql_exec)?;
+        }
+
+        Ok(())
+    }
+
+    /// The contract called by a serialized transaction, if it is a contract-call which can be
+    /// decoded.  This is what the `contract_call` column stores.
+    fn get_contract_call(tx_bytes: &[u8]) -> Option<String> {
+        let tx = StacksTransaction::consensus_deserialize(&mut &tx_bytes[..]).ok()?;
+        match tx.payload {
+            TransactionPayload::ContractCall(ref cc) => Some(cc.contract_identifier().to_string()),
+            _ => None,
+        }
+    }
+
     #[cfg_attr(test, mutants::skip)]
     pub fn db_path(chainstate_root_path: &str) -> Result<String, db_error> {
         let mut path = PathBuf::from(chainstate_root_path);
@@ -1455,6 +2317,7 @@ impl MemPoolDB {
             metric,
             blacklist_timeout: DEFAULT_BLACKLIST_TIMEOUT,
             blacklist_max_size: DEFAULT_BLACKLIST_MAX_SIZE,
+            limits: MemPoolLimits::default(),
         })
     }
 
@@ -1580,6 +2443,31 @@ impl MemPoolDB {
         Ok(updated)
     }
 
+    /// Summarize the fee rates of the transactions in the mempool which `estimate_tx_rates` has
+    /// estimated.  Returns the histogram, and the number of transactions without an estimate.
+    pub fn get_fee_rate_histogram(conn: &DBConn) -> Result<(FeeRateHistogram, u64), db_error> {
+        let mut histogram = FeeRateHistogram::default();
+        let mut num_unestimated = 0;
+        let mut stmt = conn.prepare("SELECT tx_fee, fee_rate FROM mempool")?;
+        let mut rows = stmt.query(NO_PARAMS)?;
+        while let Some(row) = rows.next()? {
+            let tx_fee: u64 = u64::from_column(row, "tx_fee")?;
+            let fee_rate: Option<f64> = row.get("fee_rate")?;
+            let Some(fee_rate) = fee_rate else {
+                num_unestimated += 1;
+                continue;
+            };
+            // the fee rate is the fee per unit of scalar cost, so recover the scalar cost
+            let weight = if fee_rate > 0.0 {
+                (tx_fee as f64 / fee_rate) as u64
+            } else {
+                0
+            };
+            histogram.add(fee_rate, 1, weight);
+        }
+        Ok((histogram, num_unestimated))
+    }
+
     /// Helper method to record nonces to a retry-buffer.
     /// This is needed for when we try to write-through a new (address, nonce) pair to the on-disk
     /// `nonces` cache, but the write fails due to lock contention from another thread.  The
@@ -1643,7 +2531,8 @@ impl MemPoolDB {
         let start_time = Instant::now();
         let mut total_considered = 0;
 
-        debug!("Mempool walk for {}ms", settings.max_walk_time_ms,);
+        debug!("Mempool walk for {}ms", settings.max_walk_time_ms;
+               "strategy" => ?settings.strategy);
 
         let tx_consideration_sampler = Uniform::new(0, 100);
         let mut rng = rand::thread_rng();
@@ -1681,6 +2570,9 @@ impl MemPoolDB {
             .query(NO_PARAMS)
             .map_err(|err| Error::SqliteError(err))?;
 
+        // strategies other than `GlobalFeeRate` order the candidates up front
+        let mut ordered_candidates = OrderedCandidates::new(settings.strategy);
+
         let stop_reason = loop {
             if start_time.elapsed().as_millis() > settings.max_walk_time_ms as u128 {
                 debug!("Mempool iteration deadline exceeded";
@@ -1697,6 +2589,22 @@ impl MemPoolDB {
                     let update_estimate = tx.fee_rate.is_none();
                     (tx, update_estimate)
                 }
+                None if ordered_candidates.is_some() => {
+                    let next = match ordered_candidates.as_mut() {
+                        Some(candidates) => candidates.next(self.conn())?,
+                        None => None,
+                    };
+                    match next {
+                        Some(tx) => {
+                            let update_estimate = tx.fee_rate.is_none();
+                            (tx, update_estimate)
+                        }
+                        None => {
+                            debug!("No more transactions to consider in mempool");
+                            break MempoolIterationStopReason::NoMoreCandidates;
+                        }
+                    }
+                }
                 None => {
                     // When the retry list is empty, read from the mempool db,
                     // randomly selecting from either the null fee-rate transactions
@@ -1971,6 +2879,24 @@ impl MemPoolDB {
         query_row(conn, "SELECT * FROM mempool WHERE txid = ?1", params![txid])
     }
 
+    /// Give a transaction imported with `submit` the accept time and estimates that it had on
+    /// the node which exported it, so that it ages out of the mempool and is ranked as it would
+    /// have been there.  Estimates which the exporting node did not have are left alone.
+    pub fn restore_exported_tx_metadata(
+        &mut self,
+        metadata: &ExportedTxMetadata,
+    ) -> Result<(), db_error> {
+        let sql = "UPDATE mempool SET accept_time = ?1, fee_rate = COALESCE(?2, fee_rate), time_estimate_ms = COALESCE(?3, time_estimate_ms) WHERE txid = ?4";
+        let args = params![
+            u64_to_sql(metadata.accept_time)?,
+            metadata.fee_rate,
+            metadata.time_estimate_ms.map(u64_to_sql).transpose()?,
+            metadata.txid,
+        ];
+        self.db.execute(sql, args)?;
+        Ok(())
+    }
+
     /// Get all transactions across all tips
     #[cfg(test)]
     pub fn get_all_txs(conn: &DBConn) -> Result<Vec<MemPoolTxInfo>, db_error> {
@@ -2029,6 +2955,238 @@ impl MemPoolDB {
         query_row(conn, &sql, args)
     }
 
+    /// Get a page of up to `limit` pending transactions which match `filter`, skipping the first
+    /// `offset` matches.
+    pub fn get_txs_page(
+        conn: &DBConn,
+        filter: &MemPoolTxFilter,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Vec<MemPoolTxInfo>, db_error> {
+        let by_fee_rate_sql = "SELECT * FROM mempool ORDER BY CAST(tx_fee AS REAL) / length DESC, txid ASC LIMIT ?1 OFFSET ?2";
+        match filter {
+            MemPoolTxFilter::All => {
+                let args = params![u64_to_sql(limit)?, u64_to_sql(offset)?];
+                query_rows(conn, by_fee_rate_sql, args)
+            }
+            MemPoolTxFilter::Origin(addr) | MemPoolTxFilter::Sponsor(addr) => {
+                let sql = format!(
+                    "SELECT * FROM mempool WHERE {0}_address = ?1 ORDER BY {0}_nonce ASC LIMIT ?2 OFFSET ?3",
+                    if matches!(filter, MemPoolTxFilter::Origin(..)) {
+                        "origin"
+                    } else {
+                        "sponsor"
+                    }
+                );
+                let args = params![addr.to_string(), u64_to_sql(limit)?, u64_to_sql(offset)?];
+                query_rows(conn, &sql, args)
+            }
+            MemPoolTxFilter::ContractCall(contract_id) => {
+                let sql = "SELECT * FROM mempool WHERE contract_call = ?1 ORDER BY CAST(tx_fee AS REAL) / length DESC, txid ASC LIMIT ?2 OFFSET ?3";
+                let args = params![
+                    contract_id.to_string(),
+                    u64_to_sql(limit)?,
+                    u64_to_sql(offset)?
+                ];
+                query_rows(conn, sql, args)
+            }
+        }
+    }
+
+    /// Get the nonces of all pending transactions which `addr` originates or sponsors, lowest
+    /// first.
+    pub fn get_pending_nonces(conn: &DBConn, addr: &StacksAddress) -> Result<Vec<u64>, db_error> {
+        let sql = "SELECT origin_nonce FROM mempool WHERE origin_address = ?1
+            UNION SELECT sponsor_nonce FROM mempool WHERE sponsor_address = ?1
+            ORDER BY 1 ASC";
+        query_rows(conn, sql, params![addr.to_string()])
+    }
+
+    /// Compute the package fee rate of each of one origin's pending transactions, given as
+    /// `(origin_nonce, tx_fee, cost)` and sorted by nonce, where `cost` is the transaction's size
+    /// in the unit which fee rates are measured in.  A transaction's package fee rate is the
+    /// highest fee per unit of cost of any run of sequential nonces which starts at it, of at
+    /// most `MAX_MEMPOOL_PACKAGE_TXS` transactions.  It is never lower than the transaction's own
+    /// fee rate.
+    pub fn package_fee_rates(chain: &[(u64, u64, f64)]) -> Vec<f64> {
+        (0..chain.len())
+            .map(|start| {
+                let mut package_fee = 0u64;
+                let mut package_cost = 0.0f64;
+                let mut best_fee_rate = 0.0f64;
+                let end = cmp::min(chain.len(), start + MAX_MEMPOOL_PACKAGE_TXS);
+                for i in start..end {
+                    if i > start && chain[i].0 != chain[i - 1].0 + 1 {
+                        break;
+                    }
+                    package_fee = package_fee.saturating_add(chain[i].1);
+                    package_cost += chain[i].2;
+                    let fee_rate = package_fee as f64 / package_cost.max(1.0);
+                    if fee_rate > best_fee_rate {
+                        best_fee_rate = fee_rate;
+                    }
+                }
+                best_fee_rate
+            })
+            .collect()
+    }
+
+    /// Get the package fee rate (see `package_fee_rates`) of a transaction from `origin_address`
+    /// at `origin_nonce` which pays `tx_fee` for `length` bytes, together with the origin's
+    /// pending transactions at the next sequential nonces.  Any pending transaction at
+    /// `origin_nonce` itself is disregarded, so this also scores a would-be replacement.  Since
+    /// a would-be replacement has no fee rate estimate yet, this rate is in fee per byte.
+    pub fn get_package_fee_rate(
+        conn: &DBConn,
+        origin_address: &StacksAddress,
+        origin_nonce: u64,
+        tx_fee: u64,
+        length: u64,
+    ) -> Result<f64, db_error> {
+        let sql = "SELECT * FROM mempool WHERE origin_address = ?1 AND origin_nonce > ?2
+            ORDER BY origin_nonce ASC LIMIT ?3";
+        let args = params![
+            origin_address.to_string(),
+            u64_to_sql(origin_nonce)?,
+            u64_to_sql(MAX_MEMPOOL_PACKAGE_TXS as u64 - 1)?,
+        ];
+        let successors: Vec<MemPoolTxMetadata> = query_rows(conn, sql, args)?;
+
+        let mut chain = vec![(origin_nonce, tx_fee, length as f64)];
+        chain.extend(
+            successors
+                .into_iter()
+                .map(|metadata| (metadata.origin_nonce, metadata.tx_fee, metadata.len as f64)),
+        );
+        Ok(Self::package_fee_rates(&chain)[0])
+    }
+
+    /// Get the lowest nonce of `addr`, at or above `confirmed_nonce`, for which the mempool has
+    /// no transaction.  This is one past the end of the unbroken chain of pending nonces which
+    /// starts at the confirmed nonce.
+    pub fn get_first_missing_nonce(
+        conn: &DBConn,
+        addr: &StacksAddress,
+        confirmed_nonce: u64,
+    ) -> Result<u64, db_error> {
+        let mut next_nonce = confirmed_nonce;
+        for nonce in Self::get_pending_nonces(conn, addr)?.into_iter() {
+            if nonce > next_nonce {
+                break;
+            }
+            if nonce == next_nonce {
+                next_nonce += 1;
+            }
+        }
+        Ok(next_nonce)
+    }
+
+    /// Determine whether or not a pending transaction can be mined, given the confirmed nonces of
+    /// its origin and sponsor accounts.
+    pub fn get_tx_nonce_status(
+        conn: &DBConn,
+        metadata: &MemPoolTxMetadata,
+        confirmed_origin_nonce: u64,
+        confirmed_sponsor_nonce: u64,
+    ) -> Result<MemPoolTxNonceStatus, db_error> {
+        let accounts = [
+            (
+                &metadata.origin_address,
+                metadata.origin_nonce,
+                confirmed_origin_nonce,
+            ),
+            (
+                &metadata.sponsor_address,
+                metadata.sponsor_nonce,
+                confirmed_sponsor_nonce,
+            ),
+        ];
+        for (address, nonce, confirmed_nonce) in accounts.iter() {
+            if nonce < confirmed_nonce {
+                return Ok(MemPoolTxNonceStatus::Stale {
+                    address: (*address).clone(),
+                    confirmed_nonce: *confirmed_nonce,
+                });
+            }
+        }
+        for (address, nonce, confirmed_nonce) in accounts.iter() {
+            let missing_nonce = Self::get_first_missing_nonce(conn, address, *confirmed_nonce)?;
+            if *nonce > missing_nonce {
+                return Ok(MemPoolTxNonceStatus::Blocked {
+                    address: (*address).clone(),
+                    missing_nonce,
+                });
+            }
+        }
+        if metadata.origin_nonce == confirmed_origin_nonce
+            && metadata.sponsor_nonce == confirmed_sponsor_nonce
+        {
+            Ok(MemPoolTxNonceStatus::Ready)
+        } else {
+            Ok(MemPoolTxNonceStatus::Queued)
+        }
+    }
+
+    /// Find up to `max_txs` pending transactions which are stuck behind a missing nonce, reading
+    /// the accounts' confirmed nonces through `clarity_conn`.  `iterate_candidates` skips these
+    /// transactions without reporting them, so this lets the node tell its event observers which
+    /// accounts need to fill or replace a nonce.
+    ///
+    /// Accounts are checked in address order, starting after `start_after`, until `deadline`
+    /// passes (at least one account is always checked).  Returns the stuck transactions found,
+    /// and the address to pass as `start_after` to carry on from where this call stopped, or None
+    /// if every account was checked.
+    pub fn find_stuck_txs<C: ClarityConnection>(
+        &self,
+        clarity_conn: &mut C,
+        start_after: Option<&StacksAddress>,
+        max_txs: usize,
+        deadline: Instant,
+    ) -> Result<(Vec<MemPoolStuckTx>, Option<StacksAddress>), db_error> {
+        let sql = "SELECT address FROM (
+                SELECT origin_address AS address FROM mempool
+                UNION SELECT sponsor_address AS address FROM mempool
+            ) WHERE address > ?1 ORDER BY address ASC";
+        let start_after = start_after.map(|addr| addr.to_string()).unwrap_or_default();
+        let addresses: Vec<StacksAddress> = query_rows(self.conn(), sql, params![start_after])?;
+
+        let mut stuck_txs = vec![];
+        let mut stuck_txids = HashSet::new();
+        let mut last_checked: Option<StacksAddress> = None;
+        for address in addresses.into_iter() {
+            if last_checked.is_some() && Instant::now() >= deadline {
+                return Ok((stuck_txs, last_checked));
+            }
+            let confirmed_nonce =
+                StacksChainState::get_nonce(clarity_conn, &address.clone().into());
+            let missing_nonce =
+                Self::get_first_missing_nonce(self.conn(), &address, confirmed_nonce)?;
+            let sql = "SELECT txid FROM mempool
+                WHERE (origin_address = ?1 AND origin_nonce > ?2)
+                OR (sponsor_address = ?1 AND sponsor_nonce > ?2)";
+            let args = params![address.to_string(), u64_to_sql(missing_nonce)?];
+            let txids: Vec<Txid> = query_rows(self.conn(), sql, args)?;
+            for txid in txids.into_iter() {
+                if stuck_txs.len() >= max_txs {
+                    // this account was only partially reported, so check it again next time,
+                    // unless it is the first one checked (in which case skip the rest of its
+                    // transactions, so that the next call makes progress)
+                    return Ok((stuck_txs, last_checked.or(Some(address))));
+                }
+                if !stuck_txids.insert(txid.clone()) {
+                    continue;
+                }
+                stuck_txs.push(MemPoolStuckTx {
+                    txid,
+                    address: address.clone(),
+                    missing_nonce,
+                });
+            }
+            last_checked = Some(address);
+        }
+        Ok((stuck_txs, None))
+    }
+
     /// Are the given fully-qualified blocks, identified by their (consensus-hash, block-header-hash) pairs, in the same fork?
     /// That is, is one block an ancestor of another?
     /// TODO: Nakamoto-ize
@@ -2164,6 +3322,33 @@ impl MemPoolDB {
                 );
                 replace_reason = MemPoolDropReason::REPLACE_ACROSS_FORK;
                 true
+            } else if prior_tx.origin_address == *origin_address
+                && prior_tx.origin_nonce == origin_nonce
+                && tx_fee >= prior_tx.tx_fee
+                && MemPoolDB::get_package_fee_rate(
+                    tx,
+                    origin_address,
+                    origin_nonce,
+                    tx_fee,
+                    length,
+                )? > MemPoolDB::get_package_fee_rate(
+                    tx,
+                    origin_address,
+                    origin_nonce,
+                    prior_tx.tx_fee,
+                    prior_tx.len,
+                )?
+            {
+                // is this a replace-by-fee of the package which the transaction starts?  A
+                // smaller transaction with the same fee can raise the package's fee rate.  The
+                // fee may never go down, so that a package cannot be replaced over and over
+                // again while paying less each time.
+                debug!(
+                    "Can replace {} with {} for {},{} by package fee rate",
+                    &prior_tx.txid, &txid, origin_address, origin_nonce
+                );
+                replace_reason = MemPoolDropReason::REPLACE_BY_FEE;
+                true
             } else {
                 // there's a >= fee tx in this fork, cannot add
                 info!("TX conflicts with sponsor/origin nonce in same fork with >= fee";
@@ -2192,6 +3377,8 @@ impl MemPoolDB {
             prior_tx.as_ref().map(|tx| tx.txid.clone()),
         )?;
 
+        let contract_call = MemPoolDB::get_contract_call(&tx_bytes);
+
         let sql = "INSERT OR REPLACE INTO mempool (
             txid,
             origin_address,
@@ -2204,8 +3391,9 @@ impl MemPoolDB {
             block_header_hash,
             height,
             accept_time,
-            tx)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)";
+            tx,
+            contract_call)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)";
 
         let args = params![
             txid,
@@ -2220,6 +3408,7 @@ impl MemPoolDB {
             u64_to_sql(coinbase_height)?,
             u64_to_sql(get_epoch_time_secs())?,
             tx_bytes,
+            contract_call,
         ];
 
         tx.execute(sql, args)
@@ -2307,6 +3496,86 @@ impl MemPoolDB {
         Ok(())
     }
 
+    /// Enforce `limits` after `new_txid` has been added to the mempool, evicting the
+    /// lowest-fee-rate transactions if the mempool has grown too big.  Only transactions which no
+    /// other mempool transaction depends on (i.e. the last nonce of each origin and sponsor) are
+    /// eligible for eviction, so eviction never leaves a nonce gap.  Among transactions with the
+    /// same fee rate, the most recently accepted is evicted first.
+    ///
+    /// Returns an error if `new_txid` itself would be evicted, or if its origin has too many
+    /// transactions in the mempool.  In either case, the caller must roll back `tx`.
+    pub fn enforce_limits(
+        tx: &MemPoolTx,
+        limits: &MemPoolLimits,
+        new_txid: &Txid,
+        origin_address: &StacksAddress,
+        event_observer: Option<&dyn MemPoolEventDispatcher>,
+    ) -> Result<(), MemPoolRejection> {
+        if let Some(max_txs_per_origin) = limits.max_txs_per_origin {
+            // `new_txid` is already stored, so this allows `max_txs_per_origin` transactions
+            let sql = "SELECT COUNT(txid) FROM mempool WHERE origin_address = ?1";
+            let args = params![origin_address.to_string()];
+            let num_origin_txs = query_int(tx, sql, args)? as u64;
+            if num_origin_txs > max_txs_per_origin {
+                return Err(MemPoolRejection::TooManyPendingTransactions {
+                    principal: origin_address.to_account_principal(),
+                    max_txs: max_txs_per_origin,
+                });
+            }
+        }
+
+        if limits.max_txs.is_none() && limits.max_bytes.is_none() {
+            return Ok(());
+        }
+
+        let max_txs = limits.max_txs.unwrap_or(u64::MAX);
+        let max_bytes = limits.max_bytes.unwrap_or(u64::MAX);
+        let (mut num_txs, mut num_bytes): (u64, u64) = query_row(
+            tx,
+            "SELECT COUNT(txid), IFNULL(SUM(length), 0) FROM mempool",
+            NO_PARAMS,
+        )?
+        .unwrap_or((0, 0));
+
+        // the `by_fee_per_byte` index yields the candidates in eviction order, and the nonce
+        // indexes check that nothing depends on them, so each eviction only looks at the
+        // lowest-fee transactions rather than the whole mempool
+        let sql = "SELECT a.txid, a.length FROM mempool AS a
+            WHERE NOT EXISTS (SELECT 1 FROM mempool AS b WHERE b.origin_address = a.origin_address AND b.origin_nonce = a.origin_nonce + 1)
+            AND NOT EXISTS (SELECT 1 FROM mempool AS b WHERE b.sponsor_address = a.sponsor_address AND b.sponsor_nonce = a.sponsor_nonce + 1)
+            ORDER BY CAST(a.tx_fee AS REAL) / a.length ASC, a.accept_time DESC LIMIT 1";
+        let mut evicted = vec![];
+        while num_txs > max_txs || num_bytes > max_bytes {
+            let victim: Option<(Txid, u64)> = tx
+                .query_row(sql, NO_PARAMS, |row| Ok((row.get(0)?, row.get(1)?)))
+                .optional()
+                .map_err(db_error::SqliteError)?;
+            let Some((victim_txid, victim_length)) = victim else {
+                break;
+            };
+            if victim_txid == *new_txid {
+                return Err(MemPoolRejection::MemPoolFull);
+            }
+
+            MemPoolDB::inner_drop_txs(tx, &[victim_txid.clone()])?;
+            num_txs = num_txs.saturating_sub(1);
+            num_bytes = num_bytes.saturating_sub(victim_length);
+            evicted.push(victim_txid);
+        }
+
+        if !evicted.is_empty() {
+            debug!(
+                "Evicted {} transactions to make room for {}",
+                evicted.len(),
+                new_txid
+            );
+            if let Some(event_observer) = event_observer {
+                event_observer.mempool_txs_dropped(evicted, MemPoolDropReason::CAPACITY_EVICTION);
+            }
+        }
+        Ok(())
+    }
+
     #[cfg(test)]
     pub fn clear_before_coinbase_height(
         &mut self,
@@ -2318,6 +3587,9 @@ impl MemPoolDB {
     }
 
     /// Submit a transaction to the mempool at a particular chain tip.
+    /// Returns the index of the rate-limited admission rule which admitted the transaction, if
+    /// any.  The caller must pass it to `MemPoolAdmitter::note_admitted` once `mempool_tx` is
+    /// committed.
     fn tx_submit(
         mempool_tx: &mut MemPoolTx,
         chainstate: &mut StacksChainState,
@@ -2328,7 +3600,7 @@ impl MemPoolDB {
         do_admission_checks: bool,
         event_observer: Option<&dyn MemPoolEventDispatcher>,
         fee_rate_estimate: Option<f64>,
-    ) -> Result<(), MemPoolRejection> {
+    ) -> Result<Option<usize>, MemPoolRejection> {
         test_debug!(
             "Mempool submit {} at {}/{}",
             tx.txid(),
@@ -2374,14 +3646,16 @@ impl MemPoolDB {
                 (origin_address.clone(), origin_nonce)
             };
 
-        if do_admission_checks {
+        let rate_limited_rule = if do_admission_checks {
             mempool_tx
                 .admitter
                 .set_block(&block_hash, (*consensus_hash).clone());
             mempool_tx
                 .admitter
-                .will_admit_tx(chainstate, sortdb, tx, len)?;
-        }
+                .will_admit_tx(chainstate, sortdb, tx, len)?
+        } else {
+            None
+        };
 
         MemPoolDB::try_add_tx(
             mempool_tx,
@@ -2411,7 +3685,12 @@ impl MemPoolDB {
             warn!("Failed to monitor TX receive: {:?}", e; "txid" => %txid);
         }
 
-        Ok(())
+        Ok(rate_limited_rule)
+    }
+
+    /// Set the node-local rules for which transactions `submit` admits
+    pub fn set_admission_policy(&mut self, policy: MemPoolAdmissionPolicy) {
+        self.admitter.set_policy(policy);
     }
 
     /// One-shot transaction submit.
@@ -2453,6 +3732,7 @@ impl MemPoolDB {
             stacks_epoch_id,
         );
 
+        let limits = self.limits.clone();
         let mut mempool_tx = self.tx_begin().map_err(MemPoolRejection::DBError)?;
 
         let fee_rate = match estimator_result {
@@ -2466,7 +3746,7 @@ impl MemPoolDB {
             }
         };
 
-        MemPoolDB::tx_submit(
+        let rate_limited_rule = MemPoolDB::tx_submit(
             &mut mempool_tx,
             chainstate,
             sortdb,
@@ -2477,7 +3757,22 @@ impl MemPoolDB {
             event_observer,
             fee_rate,
         )?;
+        if limits.is_bounded() {
+            MemPoolDB::enforce_limits(
+                &mempool_tx,
+                &limits,
+                &tx.txid(),
+                &tx.origin_address(),
+                event_observer,
+            )?;
+        }
         mempool_tx.commit().map_err(MemPoolRejection::DBError)?;
+
+        // only count the transaction against its rate limit once it is actually stored
+        if let Some(rule_index) = rate_limited_rule {
+            self.admitter
+                .note_admitted(rule_index, get_epoch_time_secs());
+        }
         Ok(())
     }
 
@@ -15,7 +15,7 @@
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::collections::{HashMap, HashSet};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use std::{cmp, io};
 
 use clarity::vm::costs::ExecutionCost;
@@ -61,14 +61,17 @@ use crate::chainstate::stacks::{
     C32_ADDRESS_VERSION_TESTNET_SINGLESIG,
 };
 use crate::core::mempool::{
-    db_get_all_nonces, MemPoolSyncData, MemPoolWalkSettings, MemPoolWalkTxTypes, TxTag,
-    BLOOM_COUNTER_DEPTH, BLOOM_COUNTER_ERROR_RATE, MAX_BLOOM_COUNTER_TXS,
+    db_get_all_nonces, decode_tx_stream, export_txs, read_exported_txs, ExportedTxMetadata,
+    MemPoolAdmissionAction, MemPoolAdmissionPolicy, MemPoolAdmissionRule, MemPoolLimits,
+    MemPoolSyncData, MemPoolTx, MemPoolTxFilter, MemPoolTxNonceStatus, MemPoolWalkSettings,
+    MemPoolWalkStrategy, MemPoolWalkTxTypes, TxTag, BLOOM_COUNTER_DEPTH, BLOOM_COUNTER_ERROR_RATE,
+    MAX_BLOOM_COUNTER_TXS, MEMPOOL_EXPORT_TRAILER_VERSION,
 };
 use crate::core::{FIRST_BURNCHAIN_CONSENSUS_HASH, FIRST_STACKS_BLOCK_HASH};
 use crate::net::Error as NetError;
 use crate::util_lib::bloom::test::setup_bloom_counter;
 use crate::util_lib::bloom::*;
-use crate::util_lib::db::{tx_begin_immediate, DBConn, FromRow};
+use crate::util_lib::db::{query_int, tx_begin_immediate, DBConn, FromRow};
 use crate::util_lib::strings::StacksString;
 
 const FOO_CONTRACT: &'static str = "(define-public (foo) (ok 1))
@@ -2683,6 +2686,358 @@ fn test_drop_and_blacklist_txs_by_size() {
     assert_eq!(num_blacklisted, 5);
 }
 
+fn add_token_transfer_to_mempool(
+    mempool_tx: &mut MemPoolTx,
+    chainstate: &mut StacksChainState,
+    pk: &StacksPrivateKey,
+    nonce: u64,
+    fee: u64,
+) -> Txid {
+    let addr = StacksAddress {
+        version: 1,
+        bytes: Hash160([0xff; 20]),
+    };
+    let block_height = 10;
+    let mut tx = StacksTransaction {
+        version: TransactionVersion::Testnet,
+        chain_id: 0x80000000,
+        auth: TransactionAuth::from_p2pkh(pk).unwrap(),
+        anchor_mode: TransactionAnchorMode::Any,
+        post_condition_mode: TransactionPostConditionMode::Allow,
+        post_conditions: vec![],
+        payload: TransactionPayload::TokenTransfer(
+            addr.to_account_principal(),
+            123,
+            TokenTransferMemo([0u8; 34]),
+        ),
+    };
+    tx.set_tx_fee(fee);
+    tx.set_origin_nonce(nonce);
+
+    let txid = tx.txid();
+    let origin_addr = tx.origin_address();
+    MemPoolDB::try_add_tx(
+        mempool_tx,
+        chainstate,
+        &ConsensusHash([0x1 + (block_height as u8); 20]),
+        &BlockHeaderHash([0x2 + (block_height as u8); 32]),
+        false, // don't resolve the above chain tip since it doesn't exist
+        txid.clone(),
+        tx.serialize_to_vec(),
+        fee,
+        block_height,
+        &origin_addr,
+        nonce,
+        &origin_addr,
+        nonce,
+        None,
+    )
+    .unwrap();
+    txid
+}
+
+#[test]
+fn test_enforce_mempool_limits() {
+    let mut chainstate = instantiate_chainstate(false, 0x80000000, function_name!());
+    let chainstate_path = chainstate_path(function_name!());
+    let mut mempool = MemPoolDB::open_test(false, 0x80000000, &chainstate_path).unwrap();
+
+    let limits = MemPoolLimits {
+        max_txs: Some(3),
+        max_bytes: None,
+        max_txs_per_origin: Some(2),
+    };
+    assert!(limits.is_bounded());
+    assert!(!MemPoolLimits::default().is_bounded());
+
+    // five txs of the same size from different origins, with increasing fees
+    let mut mempool_tx = mempool.tx_begin().unwrap();
+    let mut txids = vec![];
+    for i in 1..=5 {
+        let pk = StacksPrivateKey::new();
+        let txid =
+            add_token_transfer_to_mempool(&mut mempool_tx, &mut chainstate, &pk, 0, i * 1000);
+        txids.push(txid);
+    }
+
+    // a low-fee tx whose successor is in the mempool must not be evicted
+    let chained_pk = StacksPrivateKey::new();
+    let chained_parent_txid =
+        add_token_transfer_to_mempool(&mut mempool_tx, &mut chainstate, &chained_pk, 0, 100);
+    let chained_child_txid =
+        add_token_transfer_to_mempool(&mut mempool_tx, &mut chainstate, &chained_pk, 1, 10000);
+    MemPoolDB::enforce_limits(
+        &mempool_tx,
+        &limits,
+        &chained_child_txid,
+        &StacksAddress::p2pkh(false, &StacksPublicKey::from_private(&chained_pk)),
+        None,
+    )
+    .unwrap();
+    mempool_tx.commit().unwrap();
+
+    // the four lowest-fee txs without successors were evicted
+    assert!(!mempool.has_tx(&txids[0]));
+    assert!(!mempool.has_tx(&txids[1]));
+    assert!(!mempool.has_tx(&txids[2]));
+    assert!(!mempool.has_tx(&txids[3]));
+    assert!(mempool.has_tx(&txids[4]));
+    assert!(mempool.has_tx(&chained_parent_txid));
+    assert!(mempool.has_tx(&chained_child_txid));
+
+    // a new tx with too low a fee rate is rejected, and nothing else is evicted
+    let mut mempool_tx = mempool.tx_begin().unwrap();
+    let pk = StacksPrivateKey::new();
+    let cheap_txid = add_token_transfer_to_mempool(&mut mempool_tx, &mut chainstate, &pk, 0, 1);
+    let res = MemPoolDB::enforce_limits(
+        &mempool_tx,
+        &limits,
+        &cheap_txid,
+        &StacksAddress::p2pkh(false, &StacksPublicKey::from_private(&pk)),
+        None,
+    );
+    assert!(matches!(res, Err(MemPoolRejection::MemPoolFull)));
+    drop(mempool_tx);
+    assert!(!mempool.has_tx(&cheap_txid));
+    assert!(mempool.has_tx(&txids[4]));
+
+    // an origin may not have more than two pending txs
+    let mut mempool_tx = mempool.tx_begin().unwrap();
+    let third_txid =
+        add_token_transfer_to_mempool(&mut mempool_tx, &mut chainstate, &chained_pk, 2, 20000);
+    let chained_addr = StacksAddress::p2pkh(false, &StacksPublicKey::from_private(&chained_pk));
+    let res = MemPoolDB::enforce_limits(&mempool_tx, &limits, &third_txid, &chained_addr, None);
+    match res {
+        Err(MemPoolRejection::TooManyPendingTransactions { principal, max_txs }) => {
+            assert_eq!(principal, chained_addr.to_account_principal());
+            assert_eq!(max_txs, 2);
+        }
+        _ => panic!("Expected TooManyPendingTransactions, got {res:?}"),
+    }
+}
+
+#[test]
+fn test_enforce_mempool_limits_at_scale() {
+    let mut chainstate = instantiate_chainstate(false, 0x80000000, function_name!());
+    let chainstate_path = chainstate_path(function_name!());
+    let mut mempool = MemPoolDB::open_test(false, 0x80000000, &chainstate_path).unwrap();
+
+    let num_txs = 10_000;
+    let num_spam_txs = 1_000;
+    let limits = MemPoolLimits {
+        max_txs: Some(num_txs),
+        max_bytes: None,
+        max_txs_per_origin: None,
+    };
+
+    // fill the mempool with txs from different origins, with increasing fees
+    let mut mempool_tx = mempool.tx_begin().unwrap();
+    let mut txids = vec![];
+    for i in 0..num_txs {
+        let pk = StacksPrivateKey::new();
+        let txid =
+            add_token_transfer_to_mempool(&mut mempool_tx, &mut chainstate, &pk, 0, 1000 + i);
+        txids.push(txid);
+    }
+    mempool_tx.commit().unwrap();
+
+    // a wave of higher-fee txs each evicts the lowest-fee tx left
+    for i in 0..num_spam_txs {
+        let mut mempool_tx = mempool.tx_begin().unwrap();
+        let pk = StacksPrivateKey::new();
+        let txid =
+            add_token_transfer_to_mempool(&mut mempool_tx, &mut chainstate, &pk, 0, 100_000 + i);
+        MemPoolDB::enforce_limits(
+            &mempool_tx,
+            &limits,
+            &txid,
+            &StacksAddress::p2pkh(false, &StacksPublicKey::from_private(&pk)),
+            None,
+        )
+        .unwrap();
+        mempool_tx.commit().unwrap();
+    }
+
+    let num_stored =
+        query_int(mempool.conn(), "SELECT COUNT(txid) FROM mempool", params![]).unwrap() as u64;
+    assert_eq!(num_stored, num_txs);
+    for (i, txid) in txids.iter().enumerate() {
+        assert_eq!(mempool.has_tx(txid), i as u64 >= num_spam_txs);
+    }
+}
+
+#[test]
+fn test_mempool_stuck_txs() {
+    let mut chainstate =
+        instantiate_chainstate_with_balances(false, 0x80000000, function_name!(), vec![]);
+    let b_1 = make_block(
+        &mut chainstate,
+        ConsensusHash([0x1; 20]),
+        &(
+            FIRST_BURNCHAIN_CONSENSUS_HASH.clone(),
+            FIRST_STACKS_BLOCK_HASH.clone(),
+        ),
+        1,
+        1,
+    );
+    let chainstate_path = chainstate_path(function_name!());
+    let mut mempool = MemPoolDB::open_test(false, 0x80000000, &chainstate_path).unwrap();
+
+    let pk_ready = StacksPrivateKey::new();
+    let pk_gap = StacksPrivateKey::new();
+    let pk_no_first = StacksPrivateKey::new();
+    let addr_ready = StacksAddress::p2pkh(false, &StacksPublicKey::from_private(&pk_ready));
+    let addr_gap = StacksAddress::p2pkh(false, &StacksPublicKey::from_private(&pk_gap));
+    let addr_no_first = StacksAddress::p2pkh(false, &StacksPublicKey::from_private(&pk_no_first));
+
+    let mut mempool_tx = mempool.tx_begin().unwrap();
+    let ready_txids: Vec<_> = [0, 1, 2]
+        .into_iter()
+        .map(|nonce| {
+            add_token_transfer_to_mempool(&mut mempool_tx, &mut chainstate, &pk_ready, nonce, 1000)
+        })
+        .collect();
+    let gap_txids: Vec<_> = [0, 2, 3]
+        .into_iter()
+        .map(|nonce| {
+            add_token_transfer_to_mempool(&mut mempool_tx, &mut chainstate, &pk_gap, nonce, 1000)
+        })
+        .collect();
+    let no_first_txid =
+        add_token_transfer_to_mempool(&mut mempool_tx, &mut chainstate, &pk_no_first, 1, 1000);
+    mempool_tx.commit().unwrap();
+
+    // the unbroken chain of nonces from the confirmed nonce
+    assert_eq!(
+        MemPoolDB::get_first_missing_nonce(mempool.conn(), &addr_ready, 0).unwrap(),
+        3
+    );
+    assert_eq!(
+        MemPoolDB::get_first_missing_nonce(mempool.conn(), &addr_gap, 0).unwrap(),
+        1
+    );
+    assert_eq!(
+        MemPoolDB::get_first_missing_nonce(mempool.conn(), &addr_no_first, 0).unwrap(),
+        0
+    );
+
+    let nonce_status = |txid: &Txid, confirmed_nonce: u64| {
+        let metadata = MemPoolDB::get_tx(mempool.conn(), txid)
+            .unwrap()
+            .unwrap()
+            .metadata;
+        MemPoolDB::get_tx_nonce_status(mempool.conn(), &metadata, confirmed_nonce, confirmed_nonce)
+            .unwrap()
+    };
+    assert_eq!(
+        nonce_status(&ready_txids[0], 0),
+        MemPoolTxNonceStatus::Ready
+    );
+    assert_eq!(
+        nonce_status(&ready_txids[2], 0),
+        MemPoolTxNonceStatus::Queued
+    );
+    assert_eq!(
+        nonce_status(&ready_txids[0], 1),
+        MemPoolTxNonceStatus::Stale {
+            address: addr_ready.clone(),
+            confirmed_nonce: 1
+        }
+    );
+    assert_eq!(
+        nonce_status(&gap_txids[2], 0),
+        MemPoolTxNonceStatus::Blocked {
+            address: addr_gap.clone(),
+            missing_nonce: 1
+        }
+    );
+
+    // no account has a confirmed nonce at b_1
+    let (stuck_txs, resume_after) = chainstate
+        .with_read_only_clarity_tx(
+            &TEST_BURN_STATE_DB,
+            &StacksBlockHeader::make_index_block_hash(&b_1.0, &b_1.1),
+            |clarity_conn| {
+                mempool
+                    .find_stuck_txs(
+                        clarity_conn,
+                        None,
+                        100,
+                        Instant::now() + Duration::from_secs(60),
+                    )
+                    .unwrap()
+            },
+        )
+        .unwrap();
+    // the whole mempool was checked
+    assert!(resume_after.is_none());
+    let mut stuck_txids: Vec<_> = stuck_txs.iter().map(|tx| tx.txid.clone()).collect();
+    stuck_txids.sort();
+    let mut expected_txids = vec![
+        gap_txids[1].clone(),
+        gap_txids[2].clone(),
+        no_first_txid.clone(),
+    ];
+    expected_txids.sort();
+    assert_eq!(stuck_txids, expected_txids);
+    for stuck_tx in stuck_txs.iter() {
+        if stuck_tx.txid == no_first_txid {
+            assert_eq!(stuck_tx.address, addr_no_first);
+            assert_eq!(stuck_tx.missing_nonce, 0);
+        } else {
+            assert_eq!(stuck_tx.address, addr_gap);
+            assert_eq!(stuck_tx.missing_nonce, 1);
+        }
+    }
+
+    // the number of reported transactions is bounded, and the check can be resumed
+    let (stuck_txs, resume_after) = chainstate
+        .with_read_only_clarity_tx(
+            &TEST_BURN_STATE_DB,
+            &StacksBlockHeader::make_index_block_hash(&b_1.0, &b_1.1),
+            |clarity_conn| {
+                mempool
+                    .find_stuck_txs(
+                        clarity_conn,
+                        None,
+                        2,
+                        Instant::now() + Duration::from_secs(60),
+                    )
+                    .unwrap()
+            },
+        )
+        .unwrap();
+    assert_eq!(stuck_txs.len(), 2);
+    assert!(resume_after.is_some());
+
+    // once the deadline has passed, only one account is checked per call, but resuming from
+    // where each call stopped still finds every stuck transaction
+    let mut resume_after = None;
+    let mut stuck_txids = HashSet::new();
+    let mut num_calls = 0;
+    loop {
+        let (stuck_txs, next_resume_after) = chainstate
+            .with_read_only_clarity_tx(
+                &TEST_BURN_STATE_DB,
+                &StacksBlockHeader::make_index_block_hash(&b_1.0, &b_1.1),
+                |clarity_conn| {
+                    mempool
+                        .find_stuck_txs(clarity_conn, resume_after.as_ref(), 100, Instant::now())
+                        .unwrap()
+                },
+            )
+            .unwrap();
+        num_calls += 1;
+        stuck_txids.extend(stuck_txs.into_iter().map(|tx| tx.txid));
+        resume_after = next_resume_after;
+        if resume_after.is_none() {
+            break;
+        }
+    }
+    assert!(num_calls > 1);
+    assert_eq!(stuck_txids, expected_txids.into_iter().collect());
+}
+
 #[test]
 fn test_filter_txs_by_type() {
     let mut chainstate = instantiate_chainstate(false, 0x80000000, function_name!());
@@ -2839,3 +3194,578 @@ fn test_filter_txs_by_type() {
         },
     );
 }
+
+#[test]
+fn test_package_fee_rates() {
+    assert!(MemPoolDB::package_fee_rates(&[]).is_empty());
+
+    // a low-fee transaction is paid for by its successor, but not across a nonce gap
+    let fee_rates =
+        MemPoolDB::package_fee_rates(&[(0, 100, 100.0), (1, 10000, 100.0), (3, 5000, 100.0)]);
+    assert_eq!(fee_rates, vec![50.5, 100.0, 50.0]);
+
+    // a successor with a lower fee does not lower the package fee rate
+    let fee_rates = MemPoolDB::package_fee_rates(&[(4, 3000, 100.0), (5, 1000, 100.0)]);
+    assert_eq!(fee_rates, vec![30.0, 10.0]);
+
+    // a transaction which can't be estimated costs too much to pay for
+    let fee_rates = MemPoolDB::package_fee_rates(&[(0, 100, f64::INFINITY), (1, 10000, 100.0)]);
+    assert_eq!(fee_rates, vec![0.0, 100.0]);
+}
+
+#[test]
+fn test_iterate_candidates_walk_strategies() {
+    let mut chainstate =
+        instantiate_chainstate_with_balances(false, 0x80000000, function_name!(), vec![]);
+    let b_1 = make_block(
+        &mut chainstate,
+        ConsensusHash([0x1; 20]),
+        &(
+            FIRST_BURNCHAIN_CONSENSUS_HASH.clone(),
+            FIRST_STACKS_BLOCK_HASH.clone(),
+        ),
+        1,
+        1,
+    );
+    let chainstate_path = chainstate_path(function_name!());
+    let mut mempool = MemPoolDB::open_test(false, 0x80000000, &chainstate_path).unwrap();
+
+    let pk_a = StacksPrivateKey::new();
+    let pk_b = StacksPrivateKey::new();
+    let pk_c = StacksPrivateKey::new();
+
+    let pk_d = StacksPrivateKey::new();
+
+    // (txid, fee rate, accept time) of each transaction.  A's first transaction pays a low fee,
+    // but its second pays enough for both.  D's transaction has no fee rate estimate.
+    let mut mempool_tx = mempool.tx_begin().unwrap();
+    let a_0 = add_token_transfer_to_mempool(&mut mempool_tx, &mut chainstate, &pk_a, 0, 100);
+    let a_1 = add_token_transfer_to_mempool(&mut mempool_tx, &mut chainstate, &pk_a, 1, 10000);
+    let b_0 = add_token_transfer_to_mempool(&mut mempool_tx, &mut chainstate, &pk_b, 0, 2000);
+    let c_0 = add_token_transfer_to_mempool(&mut mempool_tx, &mut chainstate, &pk_c, 0, 3000);
+    let d_0 = add_token_transfer_to_mempool(&mut mempool_tx, &mut chainstate, &pk_d, 0, 5000);
+    for (txid, fee_rate, accept_time) in [
+        (&a_0, Some(1.0), 3),
+        (&a_1, Some(100.0), 1),
+        (&b_0, Some(20.0), 2),
+        (&c_0, Some(30.0), 4),
+        (&d_0, None, 0),
+    ] {
+        mempool_tx
+            .execute(
+                "UPDATE mempool SET fee_rate = ?1, accept_time = ?2 WHERE txid = ?3",
+                params![fee_rate, accept_time, txid],
+            )
+            .unwrap();
+    }
+    mempool_tx.commit().unwrap();
+
+    let mut walk_order = |mempool: &mut MemPoolDB, strategy, consider_no_estimate_tx_prob| {
+        let _ = mempool.reset_nonce_cache();
+        let mut mempool_settings = MemPoolWalkSettings::default();
+        mempool_settings.consider_no_estimate_tx_prob = consider_no_estimate_tx_prob;
+        mempool_settings.strategy = strategy;
+
+        let mut considered = vec![];
+        chainstate.with_read_only_clarity_tx(
+            &TEST_BURN_STATE_DB,
+            &StacksBlockHeader::make_index_block_hash(&b_1.0, &b_1.1),
+            |clarity_conn| {
+                mempool
+                    .iterate_candidates::<_, ChainstateError, _>(
+                        clarity_conn,
+                        &mut vec![],
+                        mempool_settings,
+                        |_, available_tx, _| {
+                            considered.push(available_tx.tx.metadata.txid.clone());
+                            Ok(Some(
+                                // Generate any success result
+                                TransactionResult::success(
+                                    &available_tx.tx.tx,
+                                    available_tx.tx.metadata.tx_fee,
+                                    StacksTransactionReceipt::from_stx_transfer(
+                                        available_tx.tx.tx.clone(),
+                                        vec![],
+                                        Value::okay(Value::Bool(true)).unwrap(),
+                                        ExecutionCost::ZERO,
+                                    ),
+                                )
+                                .convert_to_event(),
+                            ))
+                        },
+                    )
+                    .unwrap();
+            },
+        );
+        considered
+    };
+
+    // A's low-fee transaction is considered last, holding back its high-fee successor.  D's
+    // transaction is only considered once the estimated ones have been.
+    assert_eq!(
+        walk_order(&mut mempool, MemPoolWalkStrategy::GlobalFeeRate, 0),
+        vec![
+            c_0.clone(),
+            b_0.clone(),
+            a_0.clone(),
+            a_1.clone(),
+            d_0.clone()
+        ]
+    );
+    assert_eq!(
+        walk_order(&mut mempool, MemPoolWalkStrategy::StrictFeeRate, 0),
+        vec![
+            c_0.clone(),
+            b_0.clone(),
+            a_0.clone(),
+            a_1.clone(),
+            d_0.clone()
+        ]
+    );
+
+    // `GlobalFeeRate` may pick the unestimated transaction first, but `StrictFeeRate` never does
+    assert_eq!(
+        walk_order(&mut mempool, MemPoolWalkStrategy::GlobalFeeRate, 100)[0],
+        d_0
+    );
+    assert_eq!(
+        walk_order(&mut mempool, MemPoolWalkStrategy::StrictFeeRate, 100),
+        vec![
+            c_0.clone(),
+            b_0.clone(),
+            a_0.clone(),
+            a_1.clone(),
+            d_0.clone()
+        ]
+    );
+
+    // A's package is considered first
+    assert_eq!(
+        walk_order(&mut mempool, MemPoolWalkStrategy::NonceChainPackage, 0),
+        vec![
+            a_0.clone(),
+            a_1.clone(),
+            c_0.clone(),
+            b_0.clone(),
+            d_0.clone()
+        ]
+    );
+
+    // A's second transaction is oldest but one, but must wait for its first
+    assert_eq!(
+        walk_order(&mut mempool, MemPoolWalkStrategy::AcceptTimeFifo, 0),
+        vec![
+            d_0.clone(),
+            b_0.clone(),
+            a_0.clone(),
+            a_1.clone(),
+            c_0.clone()
+        ]
+    );
+}
+
+#[test]
+fn test_mempool_package_rbf() {
+    let mut chainstate =
+        instantiate_chainstate_with_balances(false, 0x80000000, function_name!(), vec![]);
+    let chainstate_path = chainstate_path(function_name!());
+    let mut mempool = MemPoolDB::open_test(false, 0x80000000, &chainstate_path).unwrap();
+
+    let origin = StacksAddress::p2pkh(
+        false,
+        &StacksPublicKey::from_private(&StacksPrivateKey::new()),
+    );
+
+    // only the lengths of these transactions matter
+    let mut add_tx = |mempool: &mut MemPoolDB, txid: Txid, nonce: u64, fee: u64, length: usize| {
+        let mut mempool_tx = mempool.tx_begin().unwrap();
+        let res = MemPoolDB::try_add_tx(
+            &mut mempool_tx,
+            &mut chainstate,
+            &ConsensusHash([0x1; 20]),
+            &BlockHeaderHash([0x2; 32]),
+            false,
+            txid,
+            vec![0u8; length],
+            fee,
+            10,
+            &origin,
+            nonce,
+            &origin,
+            nonce,
+            None,
+        );
+        mempool_tx.commit().unwrap();
+        res
+    };
+
+    // a low-fee transaction, followed by a high-fee one
+    add_tx(&mut mempool, Txid([0x01; 32]), 0, 200, 200).unwrap();
+    add_tx(&mut mempool, Txid([0x02; 32]), 1, 10000, 100).unwrap();
+    assert_eq!(
+        MemPoolDB::get_package_fee_rate(mempool.conn(), &origin, 0, 200, 200).unwrap(),
+        34.0
+    );
+
+    // a smaller replacement with the same fee raises the package fee rate
+    add_tx(&mut mempool, Txid([0x03; 32]), 0, 200, 100).unwrap();
+    assert!(MemPoolDB::get_tx(mempool.conn(), &Txid([0x01; 32]))
+        .unwrap()
+        .is_none());
+    assert!(MemPoolDB::get_tx(mempool.conn(), &Txid([0x03; 32]))
+        .unwrap()
+        .is_some());
+    assert_eq!(
+        MemPoolDB::get_package_fee_rate(mempool.conn(), &origin, 0, 200, 100).unwrap(),
+        51.0
+    );
+
+    // a replacement with a lower fee is rejected, even though it would raise the package fee
+    // rate
+    assert!(MemPoolDB::get_package_fee_rate(mempool.conn(), &origin, 0, 150, 50).unwrap() > 51.0);
+    let res = add_tx(&mut mempool, Txid([0x04; 32]), 0, 150, 50);
+    assert!(matches!(
+        res,
+        Err(MemPoolRejection::ConflictingNonceInMempool)
+    ));
+
+    // a replacement which lowers both the fee and the package fee rate is rejected
+    let res = add_tx(&mut mempool, Txid([0x05; 32]), 0, 100, 200);
+    assert!(matches!(
+        res,
+        Err(MemPoolRejection::ConflictingNonceInMempool)
+    ));
+    assert!(MemPoolDB::get_tx(mempool.conn(), &Txid([0x03; 32]))
+        .unwrap()
+        .is_some());
+}
+
+#[test]
+fn test_mempool_export_import_stream() {
+    let mut chainstate =
+        instantiate_chainstate_with_balances(false, 0x80000000, function_name!(), vec![]);
+    let chainstate_path = chainstate_path(function_name!());
+    let mut mempool = MemPoolDB::open_test(false, 0x80000000, &chainstate_path).unwrap();
+
+    // an empty mempool exports just the end of the pages and an empty trailer
+    let mut export = vec![];
+    assert_eq!(export_txs(mempool.conn(), &mut export).unwrap(), 0);
+    assert_eq!(export.len(), 16);
+    let (txs, metadata) = read_exported_txs(&mut &export[..]).unwrap();
+    assert!(txs.is_empty());
+    assert!(metadata.is_empty());
+
+    let pk_1 = StacksPrivateKey::new();
+    let pk_2 = StacksPrivateKey::new();
+    let mut mempool_tx = mempool.tx_begin().unwrap();
+    let txids = [
+        add_token_transfer_to_mempool(&mut mempool_tx, &mut chainstate, &pk_1, 0, 1000),
+        add_token_transfer_to_mempool(&mut mempool_tx, &mut chainstate, &pk_2, 0, 2000),
+        add_token_transfer_to_mempool(&mut mempool_tx, &mut chainstate, &pk_1, 1, 3000),
+    ];
+    for (i, txid) in txids.iter().enumerate() {
+        mempool_tx
+            .execute(
+                "UPDATE mempool SET accept_time = ?1 WHERE txid = ?2",
+                params![i as i64, txid],
+            )
+            .unwrap();
+    }
+    mempool_tx
+        .execute(
+            "UPDATE mempool SET fee_rate = 1.5 WHERE txid = ?1",
+            params![txids[1]],
+        )
+        .unwrap();
+    mempool_tx.commit().unwrap();
+
+    // transactions come back in the order they were accepted, followed by their metadata
+    let mut export = vec![];
+    assert_eq!(export_txs(mempool.conn(), &mut export).unwrap(), 3);
+    let (txs, metadata) = read_exported_txs(&mut &export[..]).unwrap();
+    assert_eq!(
+        txs.iter().map(|tx| tx.txid()).collect::<Vec<_>>(),
+        txids.to_vec()
+    );
+    assert_eq!(
+        metadata.iter().map(|md| md.txid).collect::<Vec<_>>(),
+        txids.to_vec()
+    );
+    for (i, md) in metadata.iter().enumerate() {
+        assert_eq!(md.accept_time, i as u64);
+        assert_eq!(md.coinbase_height, 10);
+    }
+    assert_eq!(metadata[0].fee_rate, None);
+    assert_eq!(metadata[1].fee_rate, Some(1.5));
+
+    // the transactions are a single page in the framing of `decode_tx_stream`
+    let page_len = u32::from_be_bytes(export[0..4].try_into().unwrap()) as usize;
+    let (page_txs, page_id) = decode_tx_stream(&mut &export[4..4 + page_len]).unwrap();
+    assert_eq!(page_txs, txs);
+    assert!(page_id.is_none());
+    assert_eq!(export[4 + page_len..8 + page_len], [0u8; 4]);
+
+    // the metadata can be restored after the transaction is re-admitted
+    mempool
+        .restore_exported_tx_metadata(&ExportedTxMetadata {
+            accept_time: 100,
+            fee_rate: Some(2.5),
+            ..metadata[0].clone()
+        })
+        .unwrap();
+    let mut export_again = vec![];
+    export_txs(mempool.conn(), &mut export_again).unwrap();
+    let (_, restored) = read_exported_txs(&mut &export_again[..]).unwrap();
+    assert_eq!(restored[2].txid, txids[0]);
+    assert_eq!(restored[2].accept_time, 100);
+    assert_eq!(restored[2].fee_rate, Some(2.5));
+
+    // a truncated export is rejected
+    let mut truncated = export.clone();
+    truncated.pop();
+    assert!(read_exported_txs(&mut &truncated[..]).is_err());
+
+    // so is one with a trailer in an unknown version
+    let mut future = export.clone();
+    let trailer_start = 8 + page_len;
+    future[trailer_start..trailer_start + 4]
+        .copy_from_slice(&(MEMPOOL_EXPORT_TRAILER_VERSION + 1).to_be_bytes());
+    assert!(read_exported_txs(&mut &future[..]).is_err());
+}
+
+#[test]
+fn test_get_txs_page_by_contract_call() {
+    let mut chainstate =
+        instantiate_chainstate_with_balances(false, 0x80000000, function_name!(), vec![]);
+    let chainstate_path = chainstate_path(function_name!());
+    let mut mempool = MemPoolDB::open_test(false, 0x80000000, &chainstate_path).unwrap();
+
+    let contract_id = QualifiedContractIdentifier::parse(
+        "ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.foo-contract",
+    )
+    .unwrap();
+    let other_contract_id = QualifiedContractIdentifier::parse(
+        "ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.bar-contract",
+    )
+    .unwrap();
+
+    let mut add_contract_call =
+        |mempool_tx: &mut MemPoolTx, contract_id: &QualifiedContractIdentifier, fee: u64| {
+            let pk = StacksPrivateKey::new();
+            let mut tx = StacksTransaction {
+                version: TransactionVersion::Testnet,
+                chain_id: 0x80000000,
+                auth: TransactionAuth::from_p2pkh(&pk).unwrap(),
+                anchor_mode: TransactionAnchorMode::Any,
+                post_condition_mode: TransactionPostConditionMode::Allow,
+                post_conditions: vec![],
+                payload: TransactionPayload::ContractCall(TransactionContractCall {
+                    address: StacksAddress::from(contract_id.issuer.clone()),
+                    contract_name: contract_id.name.clone(),
+                    function_name: ClarityName::from("foo"),
+                    function_args: vec![],
+                }),
+            };
+            tx.set_tx_fee(fee);
+            tx.set_origin_nonce(0);
+
+            let txid = tx.txid();
+            let origin_addr = tx.origin_address();
+            MemPoolDB::try_add_tx(
+                mempool_tx,
+                &mut chainstate,
+                &ConsensusHash([0x1; 20]),
+                &BlockHeaderHash([0x2; 32]),
+                false,
+                txid.clone(),
+                tx.serialize_to_vec(),
+                fee,
+                10,
+                &origin_addr,
+                0,
+                &origin_addr,
+                0,
+                None,
+            )
+            .unwrap();
+            txid
+        };
+
+    let mut mempool_tx = mempool.tx_begin().unwrap();
+    let low_fee = add_contract_call(&mut mempool_tx, &contract_id, 1000);
+    add_contract_call(&mut mempool_tx, &other_contract_id, 2000);
+    let high_fee = add_contract_call(&mut mempool_tx, &contract_id, 3000);
+    mempool_tx.commit().unwrap();
+
+    let mut mempool_tx = mempool.tx_begin().unwrap();
+    add_token_transfer_to_mempool(
+        &mut mempool_tx,
+        &mut chainstate,
+        &StacksPrivateKey::new(),
+        0,
+        4000,
+    );
+    mempool_tx.commit().unwrap();
+
+    // only calls to the contract are returned, highest fee rate first
+    let filter = MemPoolTxFilter::ContractCall(contract_id);
+    let txids: Vec<_> = MemPoolDB::get_txs_page(mempool.conn(), &filter, 0, 10)
+        .unwrap()
+        .into_iter()
+        .map(|tx_info| tx_info.tx.txid())
+        .collect();
+    assert_eq!(txids, vec![high_fee, low_fee.clone()]);
+
+    let txids: Vec<_> = MemPoolDB::get_txs_page(mempool.conn(), &filter, 1, 10)
+        .unwrap()
+        .into_iter()
+        .map(|tx_info| tx_info.tx.txid())
+        .collect();
+    assert_eq!(txids, vec![low_fee]);
+}
+
+#[test]
+fn test_mempool_admission_policy() {
+    let make_tx = |pk: &StacksPrivateKey, payload: TransactionPayload| StacksTransaction {
+        version: TransactionVersion::Testnet,
+        chain_id: 0x80000000,
+        auth: TransactionAuth::from_p2pkh(pk).unwrap(),
+        anchor_mode: TransactionAnchorMode::Any,
+        post_condition_mode: TransactionPostConditionMode::Allow,
+        post_conditions: vec![],
+        payload,
+    };
+    let contract_id = QualifiedContractIdentifier::parse(
+        "ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.foo-contract",
+    )
+    .unwrap();
+    let make_contract_call = |pk: &StacksPrivateKey, function: &str| {
+        make_tx(
+            pk,
+            TransactionPayload::ContractCall(TransactionContractCall {
+                address: StacksAddress::from(contract_id.issuer.clone()),
+                contract_name: contract_id.name.clone(),
+                function_name: ClarityName::from(function),
+                function_args: vec![],
+            }),
+        )
+    };
+    let make_transfer = |pk: &StacksPrivateKey| {
+        make_tx(
+            pk,
+            TransactionPayload::TokenTransfer(
+                PrincipalData::Standard(contract_id.issuer.clone()),
+                123,
+                TokenTransferMemo([0u8; 34]),
+            ),
+        )
+    };
+    let empty_rule = |name: &str, action| MemPoolAdmissionRule {
+        name: name.into(),
+        origin: None,
+        sponsor: None,
+        contract: None,
+        function: None,
+        payload_type: None,
+        action,
+    };
+
+    let pk_1 = StacksPrivateKey::new();
+    let pk_2 = StacksPrivateKey::new();
+    let addr_1 = make_transfer(&pk_1).origin_address();
+
+    // the default policy admits everything
+    let policy = MemPoolAdmissionPolicy::default();
+    assert_eq!(policy.check_tx(&make_transfer(&pk_1), 0).unwrap(), None);
+    assert_eq!(
+        policy
+            .check_tx(&make_contract_call(&pk_2, "foo"), 0)
+            .unwrap(),
+        None
+    );
+
+    // the first matching rule decides
+    let policy = MemPoolAdmissionPolicy::new(
+        vec![
+            MemPoolAdmissionRule {
+                function: Some("bar".into()),
+                ..empty_rule("allow-bar", MemPoolAdmissionAction::Allow)
+            },
+            MemPoolAdmissionRule {
+                origin: Some(addr_1.clone()),
+                ..empty_rule("deny-origin", MemPoolAdmissionAction::Deny)
+            },
+            MemPoolAdmissionRule {
+                contract: Some(contract_id.clone()),
+                ..empty_rule("deny-contract", MemPoolAdmissionAction::Deny)
+            },
+        ],
+        true,
+    );
+    assert_eq!(
+        policy
+            .check_tx(&make_contract_call(&pk_1, "bar"), 0)
+            .unwrap(),
+        None
+    );
+    assert!(matches!(
+        policy.check_tx(&make_transfer(&pk_1), 0),
+        Err(MemPoolRejection::DeniedByPolicy(rule)) if rule == "deny-origin"
+    ));
+    assert!(matches!(
+        policy.check_tx(&make_contract_call(&pk_2, "foo"), 0),
+        Err(MemPoolRejection::DeniedByPolicy(rule)) if rule == "deny-contract"
+    ));
+    assert_eq!(policy.check_tx(&make_transfer(&pk_2), 0).unwrap(), None);
+
+    // unmatched transactions are rejected if the default is to deny
+    let policy = MemPoolAdmissionPolicy::new(
+        vec![MemPoolAdmissionRule {
+            payload_type: Some(MemPoolWalkTxTypes::TokenTransfer),
+            ..empty_rule("allow-transfers", MemPoolAdmissionAction::Allow)
+        }],
+        false,
+    );
+    assert_eq!(policy.check_tx(&make_transfer(&pk_1), 0).unwrap(), None);
+    assert!(matches!(
+        policy.check_tx(&make_contract_call(&pk_1, "foo"), 0),
+        Err(MemPoolRejection::DeniedByPolicy(rule)) if rule == "default"
+    ));
+
+    // rate-limited rules admit at most max_txs transactions per window
+    let mut policy = MemPoolAdmissionPolicy::new(
+        vec![MemPoolAdmissionRule {
+            origin: Some(addr_1.clone()),
+            ..empty_rule(
+                "limit-origin",
+                MemPoolAdmissionAction::RateLimit {
+                    max_txs: 2,
+                    window_secs: 10,
+                },
+            )
+        }],
+        true,
+    );
+    for now in [100, 105] {
+        let rule_index = policy.check_tx(&make_transfer(&pk_1), now).unwrap();
+        assert_eq!(rule_index, Some(0));
+        policy.note_admitted(0, now);
+    }
+    assert!(matches!(
+        policy.check_tx(&make_transfer(&pk_1), 109),
+        Err(MemPoolRejection::RateLimitedByPolicy {
+            rule,
+            max_txs: 2,
+            window_secs: 10,
+        }) if rule == "limit-origin"
+    ));
+    // other origins are unaffected
+    assert_eq!(policy.check_tx(&make_transfer(&pk_2), 109).unwrap(), None);
+    // once the first admission leaves the window, one more is admitted
+    assert_eq!(
+        policy.check_tx(&make_transfer(&pk_1), 110).unwrap(),
+        Some(0)
+    );
+    policy.note_admitted(0, 110);
+    assert!(policy.check_tx(&make_transfer(&pk_1), 114).is_err());
+}
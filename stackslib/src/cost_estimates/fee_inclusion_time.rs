@@ -0,0 +1,299 @@
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+
+use clarity::types::sqlite::NO_PARAMS;
+use clarity::vm::costs::ExecutionCost;
+use rusqlite::{
+    params, Connection, Error as SqliteError, OpenFlags, OptionalExtension,
+    Transaction as SqlTransaction,
+};
+
+use super::fee_medians::fee_rate_and_weight_from_receipt;
+use super::metrics::CostMetric;
+use super::{EstimatorError, ExportedEstimator, FeeEstimator, FeeRateEstimate, FeeRateHistogram};
+use crate::burnchains::Txid;
+use crate::chainstate::stacks::db::{StacksBlockHeaderTypes, StacksEpochReceipt};
+use crate::chainstate::stacks::events::TransactionOrigin;
+use crate::util_lib::db::{sqlite_open, table_exists, tx_begin_immediate_sqlite, u64_to_sql};
+
+const CREATE_TABLE: &'static str = "
+CREATE TABLE inclusion_time_fee_estimator (
+    sample_key INTEGER PRIMARY KEY AUTOINCREMENT,
+    fee_rate NUMBER NOT NULL,
+    wait_secs INTEGER NOT NULL
+)";
+
+/// Name of the node's `TimeToInclusionFeeRateEstimator` DB, in its estimates directory
+pub const DB_FILENAME: &str = "fee_estimator_time_to_inclusion.sqlite";
+
+/// Name of this estimator in its exported state
+pub const ESTIMATOR_NAME: &str = "time_to_inclusion";
+
+/// Exported state of a `TimeToInclusionFeeRateEstimator`
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct TimeToInclusionFeeRateEstimatorState {
+    /// `(fee_rate, wait_secs)` samples, oldest first
+    samples: Vec<(f64, u64)>,
+}
+
+/// Default number of confirmed transactions to learn from
+pub const DEFAULT_MAX_SAMPLES: u32 = 2_000;
+
+/// Expected time between tenures, in seconds
+pub const EXPECTED_TENURE_SECS: u64 = 600;
+
+/// Number of seconds within which a transaction paying the `high`, `middle` and `low` estimate
+/// should be mined: about 1, 3 and 10 tenures. `high` targets the next block, which is at most a
+/// tenure away.
+pub const INCLUSION_TARGET_SECS: [u64; 3] = [
+    EXPECTED_TENURE_SECS,
+    3 * EXPECTED_TENURE_SECS,
+    10 * EXPECTED_TENURE_SECS,
+];
+
+/// Fraction of the sampled transactions around the estimated fee rate which must have been mined
+/// within the target wait
+pub const INCLUSION_SUCCESS_THRESHOLD: f64 = 0.85;
+
+/// Minimum number of samples needed before making any estimate
+pub const MIN_SAMPLES: usize = 10;
+
+/// Minimum number of samples in a group of fee rate buckets whose success rate is checked
+pub const MIN_GROUP_SAMPLES: u64 = 5;
+
+/// FeeEstimator which learns how long transactions paying a given fee rate wait to be mined.
+///
+/// When a block is processed, each of its transactions is looked up in the mempool, whose
+/// `accept_time` column records when the node accepted it. The number of seconds between then
+/// and the block's timestamp is stored along with the transaction's fee rate. Transactions which
+/// this node never had in its mempool are ignored.
+///
+/// For each target in `INCLUSION_TARGET_SECS`, the estimate is roughly the lowest fee rate at
+/// which `INCLUSION_SUCCESS_THRESHOLD` of the sampled transactions were mined within the target
+/// (see `fee_rate_estimate_from_inclusion_samples`). Only mined transactions are sampled, so
+/// transactions which were never mined do not count against a fee rate.
+pub struct TimeToInclusionFeeRateEstimator<M: CostMetric> {
+    db: Connection,
+    /// Path to the mempool DB, which is opened read-only on first use
+    mempool_path: PathBuf,
+    mempool_db: Option<Connection>,
+    /// We only keep the most recent `max_samples` samples.
+    max_samples: u32,
+    /// Use this cost metric in fee rate calculations.
+    metric: M,
+}
+
+impl<M: CostMetric> TimeToInclusionFeeRateEstimator<M> {
+    /// Open a fee rate estimator at the given db path. Creates if not existent.
+    pub fn open(
+        p: &Path,
+        mempool_path: &Path,
+        metric: M,
+        max_samples: u32,
+    ) -> Result<Self, SqliteError> {
+        let mut db = sqlite_open(
+            p,
+            OpenFlags::SQLITE_OPEN_CREATE | OpenFlags::SQLITE_OPEN_READ_WRITE,
+            false,
+        )?;
+
+        // check if the db needs to be instantiated regardless of whether or not
+        //  it was newly created: the db itself may be shared with other fee estimators,
+        //  which would not have created the necessary table for this estimator.
+        let tx = tx_begin_immediate_sqlite(&mut db)?;
+        if !table_exists(&tx, "inclusion_time_fee_estimator")? {
+            tx.execute(CREATE_TABLE, NO_PARAMS)?;
+        }
+        tx.commit()?;
+
+        Ok(Self {
+            db,
+            mempool_path: mempool_path.to_path_buf(),
+            mempool_db: None,
+            max_samples,
+            metric,
+        })
+    }
+
+    /// Get the time at which the mempool accepted `txid`, if it is there
+    fn get_accept_time(&mut self, txid: &Txid) -> Result<Option<u64>, SqliteError> {
+        if self.mempool_db.is_none() {
+            self.mempool_db = Some(sqlite_open(
+                &self.mempool_path,
+                OpenFlags::SQLITE_OPEN_READ_ONLY,
+                false,
+            )?);
+        }
+        let Some(mempool_db) = self.mempool_db.as_ref() else {
+            return Ok(None);
+        };
+        let accept_time: Option<i64> = mempool_db
+            .query_row(
+                "SELECT accept_time FROM mempool WHERE txid = ?",
+                params![txid],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(accept_time.and_then(|accept_time| u64::try_from(accept_time).ok()))
+    }
+
+    /// Store the `(fee_rate, wait_secs)` samples of a block, forgetting the oldest samples
+    fn record_samples(&mut self, samples: &[(f64, u64)]) -> Result<(), SqliteError> {
+        let tx = tx_begin_immediate_sqlite(&mut self.db)?;
+        Self::insert_samples(&tx, samples, self.max_samples)?;
+        tx.commit()
+    }
+
+    fn insert_samples(
+        tx: &SqlTransaction,
+        samples: &[(f64, u64)],
+        max_samples: u32,
+    ) -> Result<(), SqliteError> {
+        let insert_sql = "INSERT INTO inclusion_time_fee_estimator
+                          (fee_rate, wait_secs) VALUES (?, ?)";
+        let deletion_sql = "DELETE FROM inclusion_time_fee_estimator
+                            WHERE sample_key <= (
+                               SELECT MAX(sample_key) - ?
+                               FROM inclusion_time_fee_estimator )";
+        for (fee_rate, wait_secs) in samples.iter() {
+            let wait_secs = u64_to_sql(*wait_secs).unwrap_or(i64::MAX);
+            tx.execute(insert_sql, params![fee_rate, wait_secs])?;
+        }
+        tx.execute(deletion_sql, params![max_samples])?;
+        Ok(())
+    }
+
+    fn get_samples_from_sql(conn: &Connection) -> Result<Vec<(f64, u64)>, SqliteError> {
+        let sql = "SELECT fee_rate, wait_secs FROM inclusion_time_fee_estimator
+                   ORDER BY sample_key ASC";
+        let mut stmt = conn.prepare(sql)?;
+        let results = stmt.query_and_then::<_, SqliteError, _, _>(NO_PARAMS, |row| {
+            let fee_rate: f64 = row.get("fee_rate")?;
+            let wait_secs: i64 = row.get("wait_secs")?;
+            Ok((fee_rate, u64::try_from(wait_secs).unwrap_or(0)))
+        })?;
+        results.collect()
+    }
+}
+
+impl<M: CostMetric> FeeEstimator for TimeToInclusionFeeRateEstimator<M> {
+    fn notify_block(
+        &mut self,
+        receipt: &StacksEpochReceipt,
+        block_limit: &ExecutionCost,
+    ) -> Result<(), EstimatorError> {
+        let block_time = match receipt.header.anchored_header {
+            StacksBlockHeaderTypes::Nakamoto(ref header) => header.timestamp,
+            StacksBlockHeaderTypes::Epoch2(_) => receipt.header.burn_header_timestamp,
+        };
+        let mut samples = vec![];
+        for tx_receipt in receipt.tx_receipts.iter() {
+            let TransactionOrigin::Stacks(ref tx) = tx_receipt.transaction else {
+                continue;
+            };
+            let Some(rate_and_weight) =
+                fee_rate_and_weight_from_receipt(&self.metric, tx_receipt, block_limit)
+            else {
+                continue;
+            };
+            let accept_time = match self.get_accept_time(&tx.txid()) {
+                Ok(Some(accept_time)) => accept_time,
+                Ok(None) => continue,
+                Err(e) => {
+                    warn!("Failed to look up mempool transaction for fee estimation";
+                          "txid" => %tx.txid(),
+                          "mempool_path" => %self.mempool_path.display(),
+                          "error" => %e);
+                    // try opening the mempool DB again next time
+                    self.mempool_db = None;
+                    break;
+                }
+            };
+            let wait_secs = block_time.saturating_sub(accept_time);
+            samples.push((rate_and_weight.fee_rate, wait_secs));
+        }
+
+        if !samples.is_empty() {
+            self.record_samples(&samples)
+                .map_err(EstimatorError::SqliteError)?;
+        }
+        Ok(())
+    }
+
+    fn get_rate_estimates(&self) -> Result<FeeRateEstimate, EstimatorError> {
+        let samples = Self::get_samples_from_sql(&self.db).map_err(EstimatorError::SqliteError)?;
+        fee_rate_estimate_from_inclusion_samples(samples)
+    }
+
+    fn export_state(&self) -> Result<ExportedEstimator, EstimatorError> {
+        let samples = Self::get_samples_from_sql(&self.db)?;
+        ExportedEstimator::new(
+            ESTIMATOR_NAME,
+            &TimeToInclusionFeeRateEstimatorState { samples },
+        )
+    }
+
+    fn import_state(&mut self, state: &ExportedEstimator) -> Result<(), EstimatorError> {
+        let state: TimeToInclusionFeeRateEstimatorState = state.decode(ESTIMATOR_NAME)?;
+        let tx = tx_begin_immediate_sqlite(&mut self.db)?;
+        tx.execute("DELETE FROM inclusion_time_fee_estimator", NO_PARAMS)?;
+        Self::insert_samples(&tx, &state.samples, self.max_samples)?;
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+/// Computes a `FeeRateEstimate` from `(fee_rate, wait_secs)` samples, targeting inclusion
+/// within each of `INCLUSION_TARGET_SECS`.
+///
+/// The samples are grouped into `FeeRateHistogram` buckets, which are considered from the
+/// highest fee rate down, merging adjacent buckets until a group has at least
+/// `MIN_GROUP_SAMPLES`. The estimate is the lowest fee rate sampled in the last group, before the
+/// first which fails `INCLUSION_SUCCESS_THRESHOLD`. If even the highest group fails, the highest
+/// sampled fee rate is used.
+pub fn fee_rate_estimate_from_inclusion_samples(
+    mut samples: Vec<(f64, u64)>,
+) -> Result<FeeRateEstimate, EstimatorError> {
+    if samples.len() < MIN_SAMPLES {
+        return Err(EstimatorError::NoEstimateAvailable);
+    }
+    // highest fee rate first
+    samples.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(Ordering::Equal));
+
+    let estimate_for_target = |target_secs: u64| {
+        let mut estimate = samples[0].0;
+        let mut group_samples = 0u64;
+        let mut group_in_time = 0u64;
+        let mut i = 0;
+        while i < samples.len() {
+            // add the whole bucket to the group
+            let bucket = FeeRateHistogram::bucket_index(samples[i].0);
+            let mut group_lowest_fee_rate = samples[i].0;
+            while i < samples.len() && FeeRateHistogram::bucket_index(samples[i].0) == bucket {
+                let (fee_rate, wait_secs) = samples[i];
+                group_samples += 1;
+                if wait_secs <= target_secs {
+                    group_in_time += 1;
+                }
+                group_lowest_fee_rate = fee_rate;
+                i += 1;
+            }
+            if group_samples < MIN_GROUP_SAMPLES {
+                continue;
+            }
+            if (group_in_time as f64) / (group_samples as f64) < INCLUSION_SUCCESS_THRESHOLD {
+                break;
+            }
+            estimate = group_lowest_fee_rate;
+            group_samples = 0;
+            group_in_time = 0;
+        }
+        estimate
+    };
+
+    Ok(FeeRateEstimate {
+        high: estimate_for_target(INCLUSION_TARGET_SECS[0]),
+        middle: estimate_for_target(INCLUSION_TARGET_SECS[1]),
+        low: estimate_for_target(INCLUSION_TARGET_SECS[2]),
+    })
+}
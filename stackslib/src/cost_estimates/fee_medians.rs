@@ -1,5 +1,6 @@
 use std::cmp;
 use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::path::Path;
 
 use clarity::types::sqlite::NO_PARAMS;
@@ -12,7 +13,7 @@ use rusqlite::{
 use serde_json::Value as JsonValue;
 
 use super::metrics::{CostMetric, PROPORTION_RESOLUTION};
-use super::{EstimatorError, FeeEstimator, FeeRateEstimate};
+use super::{EstimatorError, ExportedEstimator, FeeEstimator, FeeRateEstimate, FeeRateHistogram};
 use crate::chainstate::stacks::db::StacksEpochReceipt;
 use crate::chainstate::stacks::events::TransactionOrigin;
 use crate::chainstate::stacks::TransactionPayload;
@@ -29,8 +30,40 @@ CREATE TABLE median_fee_estimator (
     low NUMBER NOT NULL
 )";
 
+const CREATE_HISTOGRAM_TABLE: &'static str = "
+CREATE TABLE median_fee_estimator_histograms (
+    measure_key INTEGER NOT NULL,
+    bucket INTEGER NOT NULL,
+    count INTEGER NOT NULL,
+    weight INTEGER NOT NULL,
+    PRIMARY KEY(measure_key, bucket)
+)";
+
 const MINIMUM_TX_FEE_RATE: f64 = 1f64;
 
+/// Name of the node's fuzzed `WeightedMedianFeeRateEstimator` DB, in its estimates directory
+pub const DB_FILENAME: &str = "fee_fuzzed_weighted_median.sqlite";
+
+/// Name of this estimator in its exported state
+pub const ESTIMATOR_NAME: &str = "weighted_median_fee_rate";
+
+/// Default number of past block measures used for estimates
+pub const DEFAULT_WINDOW_SIZE: u32 = 5;
+
+/// A block's measure, as exported by a `WeightedMedianFeeRateEstimator`
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct MedianFeeMeasure {
+    estimate: FeeRateEstimate,
+    histogram: FeeRateHistogram,
+}
+
+/// Exported state of a `WeightedMedianFeeRateEstimator`
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct WeightedMedianFeeRateEstimatorState {
+    /// Oldest first
+    measures: Vec<MedianFeeMeasure>,
+}
+
 /// FeeRateEstimator with the following properties:
 ///
 /// 1) We use a "weighted" percentile approach for calculating the percentile values. Described
@@ -92,6 +125,10 @@ impl<M: CostMetric> WeightedMedianFeeRateEstimator<M> {
         if !Self::db_already_instantiated(tx)? {
             tx.execute(CREATE_TABLE, NO_PARAMS)?;
         }
+        // fee estimator DBs created before histograms were kept won't have this table yet
+        if !table_exists(tx, "median_fee_estimator_histograms")? {
+            tx.execute(CREATE_HISTOGRAM_TABLE, NO_PARAMS)?;
+        }
 
         Ok(())
     }
@@ -151,21 +188,139 @@ impl<M: CostMetric> WeightedMedianFeeRateEstimator<M> {
         })
     }
 
-    fn update_estimate(&mut self, new_measure: FeeRateEstimate) {
-        let tx = tx_begin_immediate_sqlite(&mut self.db).expect("SQLite failure");
+    fn get_block_histograms_from_sql(
+        conn: &Connection,
+        window_size: u32,
+    ) -> Result<Vec<FeeRateHistogram>, EstimatorError> {
+        let sql = "SELECT h.measure_key, h.bucket, h.count, h.weight
+                   FROM median_fee_estimator_histograms AS h
+                   WHERE h.measure_key IN (
+                       SELECT measure_key FROM median_fee_estimator
+                       ORDER BY measure_key DESC LIMIT ?)
+                   ORDER BY h.measure_key DESC";
+        let mut stmt = conn.prepare(sql).expect("SQLite failure");
+        let results = stmt
+            .query_and_then::<_, SqliteError, _, _>(params![window_size], |row| {
+                let measure_key: i64 = row.get("measure_key")?;
+                let bucket: i64 = row.get("bucket")?;
+                let count: i64 = row.get("count")?;
+                let weight: i64 = row.get("weight")?;
+                Ok((measure_key, bucket, count, weight))
+            })
+            .expect("SQLite failure");
+
+        let mut histograms: Vec<FeeRateHistogram> = vec![];
+        let mut last_measure_key = None;
+        for result in results {
+            let (measure_key, bucket, count, weight) = result.expect("SQLite failure");
+            if last_measure_key != Some(measure_key) {
+                histograms.push(FeeRateHistogram::default());
+                last_measure_key = Some(measure_key);
+            }
+            let Some(histogram_bucket) = histograms
+                .last_mut()
+                .zip(usize::try_from(bucket).ok())
+                .and_then(|(histogram, bucket)| histogram.buckets.get_mut(bucket))
+            else {
+                warn!("Ignoring fee rate histogram entry for unknown bucket";
+                      "measure_key" => measure_key,
+                      "bucket" => bucket);
+                continue;
+            };
+            histogram_bucket.count = u64::try_from(count).unwrap_or(0);
+            histogram_bucket.weight = u64::try_from(weight).unwrap_or(0);
+        }
+
+        if histograms.is_empty() {
+            return Err(EstimatorError::NoEstimateAvailable);
+        }
+        Ok(histograms)
+    }
+
+    /// Get all of the stored measures, oldest first
+    fn get_measures_from_sql(conn: &Connection) -> Result<Vec<MedianFeeMeasure>, SqliteError> {
+        let mut histograms: HashMap<i64, FeeRateHistogram> = HashMap::new();
+        let histogram_sql = "SELECT measure_key, bucket, count, weight
+                             FROM median_fee_estimator_histograms";
+        let mut stmt = conn.prepare(histogram_sql)?;
+        let mut rows = stmt.query(NO_PARAMS)?;
+        while let Some(row) = rows.next()? {
+            let measure_key: i64 = row.get("measure_key")?;
+            let bucket: i64 = row.get("bucket")?;
+            let count: i64 = row.get("count")?;
+            let weight: i64 = row.get("weight")?;
+            let histogram = histograms.entry(measure_key).or_default();
+            if let Some(histogram_bucket) = usize::try_from(bucket)
+                .ok()
+                .and_then(|bucket| histogram.buckets.get_mut(bucket))
+            {
+                histogram_bucket.count = u64::try_from(count).unwrap_or(0);
+                histogram_bucket.weight = u64::try_from(weight).unwrap_or(0);
+            }
+        }
+
+        let sql = "SELECT measure_key, high, middle, low FROM median_fee_estimator
+                   ORDER BY measure_key ASC";
+        let mut stmt = conn.prepare(sql)?;
+        let results = stmt.query_and_then::<_, SqliteError, _, _>(NO_PARAMS, |row| {
+            let measure_key: i64 = row.get("measure_key")?;
+            let high: f64 = row.get("high")?;
+            let middle: f64 = row.get("middle")?;
+            let low: f64 = row.get("low")?;
+            Ok(MedianFeeMeasure {
+                estimate: FeeRateEstimate { high, middle, low },
+                histogram: histograms.remove(&measure_key).unwrap_or_default(),
+            })
+        })?;
+        results.collect()
+    }
+
+    /// Store a block's measure, returning its key
+    fn insert_measure(
+        tx: &SqlTransaction,
+        new_measure: &FeeRateEstimate,
+        histogram: &FeeRateHistogram,
+    ) -> Result<i64, SqliteError> {
         let insert_sql = "INSERT INTO median_fee_estimator
                           (high, middle, low) VALUES (?, ?, ?)";
+        let insert_histogram_sql = "INSERT INTO median_fee_estimator_histograms
+                                    (measure_key, bucket, count, weight) VALUES (?, ?, ?, ?)";
+        tx.execute(
+            insert_sql,
+            params![new_measure.high, new_measure.middle, new_measure.low,],
+        )?;
+        let measure_key = tx.last_insert_rowid();
+        for (bucket_index, bucket) in histogram.buckets.iter().enumerate() {
+            if bucket.count == 0 && bucket.weight == 0 {
+                continue;
+            }
+            tx.execute(
+                insert_histogram_sql,
+                params![
+                    measure_key,
+                    bucket_index as i64,
+                    i64::try_from(bucket.count).unwrap_or(i64::MAX),
+                    i64::try_from(bucket.weight).unwrap_or(i64::MAX),
+                ],
+            )?;
+        }
+        Ok(measure_key)
+    }
+
+    fn update_estimate(&mut self, new_measure: FeeRateEstimate, histogram: FeeRateHistogram) {
+        let tx = tx_begin_immediate_sqlite(&mut self.db).expect("SQLite failure");
         let deletion_sql = "DELETE FROM median_fee_estimator
                             WHERE measure_key <= (
                                SELECT MAX(measure_key) - ?
                                FROM median_fee_estimator )";
-        tx.execute(
-            insert_sql,
-            params![new_measure.high, new_measure.middle, new_measure.low,],
-        )
-        .expect("SQLite failure");
+        let histogram_deletion_sql = "DELETE FROM median_fee_estimator_histograms
+                                      WHERE measure_key NOT IN (
+                                         SELECT measure_key FROM median_fee_estimator )";
+        Self::insert_measure(&tx, &new_measure, &histogram).expect("SQLite failure");
         tx.execute(deletion_sql, params![self.window_size])
             .expect("SQLite failure");
+        tx.execute(histogram_deletion_sql, NO_PARAMS)
+            .expect("SQLite failure");
 
         let estimate = Self::get_rate_estimates_from_sql(&tx, self.window_size);
         tx.commit().expect("SQLite failure");
@@ -197,10 +352,15 @@ impl<M: CostMetric> FeeEstimator for WeightedMedianFeeRateEstimator<M> {
             .collect();
 
         // If necessary, add the "minimum" fee rate to fill the block.
+        let num_txs = working_fee_rates.len();
         maybe_add_minimum_fee_rate(&mut working_fee_rates, self.full_block_weight);
 
         // If fee rates non-empty, then compute an update.
         if working_fee_rates.len() > 0 {
+            // Must be computed before sorting, so that the padding stays after the transactions.
+            let block_histogram =
+                fee_rate_histogram_from_weighted_fees(&working_fee_rates, num_txs);
+
             // Values must be sorted.
             working_fee_rates.sort_by(|a, b| {
                 a.fee_rate
@@ -210,7 +370,7 @@ impl<M: CostMetric> FeeEstimator for WeightedMedianFeeRateEstimator<M> {
 
             // Compute the estimate and update.
             let block_estimate = fee_rate_estimate_from_sorted_weighted_fees(&working_fee_rates);
-            self.update_estimate(block_estimate);
+            self.update_estimate(block_estimate, block_histogram);
         }
 
         Ok(())
@@ -219,6 +379,45 @@ impl<M: CostMetric> FeeEstimator for WeightedMedianFeeRateEstimator<M> {
     fn get_rate_estimates(&self) -> Result<FeeRateEstimate, EstimatorError> {
         Self::get_rate_estimates_from_sql(&self.db, self.window_size)
     }
+
+    fn get_block_histograms(&self) -> Result<Vec<FeeRateHistogram>, EstimatorError> {
+        Self::get_block_histograms_from_sql(&self.db, self.window_size)
+    }
+
+    fn export_state(&self) -> Result<ExportedEstimator, EstimatorError> {
+        let measures = Self::get_measures_from_sql(&self.db)?;
+        ExportedEstimator::new(
+            ESTIMATOR_NAME,
+            &WeightedMedianFeeRateEstimatorState { measures },
+        )
+    }
+
+    fn import_state(&mut self, state: &ExportedEstimator) -> Result<(), EstimatorError> {
+        let state: WeightedMedianFeeRateEstimatorState = state.decode(ESTIMATOR_NAME)?;
+        let tx = tx_begin_immediate_sqlite(&mut self.db)?;
+        tx.execute("DELETE FROM median_fee_estimator", NO_PARAMS)?;
+        tx.execute("DELETE FROM median_fee_estimator_histograms", NO_PARAMS)?;
+        for measure in state.measures.iter() {
+            Self::insert_measure(&tx, &measure.estimate, &measure.histogram)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+/// Computes a `FeeRateHistogram` of `fee_rates`, whose first `num_txs` entries are
+/// transactions. Any later entries are padding added by `maybe_add_minimum_fee_rate`, which only
+/// adds weight.
+pub fn fee_rate_histogram_from_weighted_fees(
+    fee_rates: &[FeeRateAndWeight],
+    num_txs: usize,
+) -> FeeRateHistogram {
+    let mut histogram = FeeRateHistogram::default();
+    for (i, rate_and_weight) in fee_rates.iter().enumerate() {
+        let count = if i < num_txs { 1 } else { 0 };
+        histogram.add(rate_and_weight.fee_rate, count, rate_and_weight.weight);
+    }
+    histogram
 }
 
 /// Computes a `FeeRateEstimate` based on `sorted_fee_rates` using a "weighted percentile" method
@@ -303,7 +502,7 @@ fn maybe_add_minimum_fee_rate(working_rates: &mut Vec<FeeRateAndWeight>, full_bl
 /// Returns None if:
 ///   1) There is no fee rate for the tx.
 ///   2) Cacluated fee rate is infinite.
-fn fee_rate_and_weight_from_receipt(
+pub fn fee_rate_and_weight_from_receipt(
     metric: &dyn CostMetric,
     tx_receipt: &StacksTransactionReceipt,
     block_limit: &ExecutionCost,
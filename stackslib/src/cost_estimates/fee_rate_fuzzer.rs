@@ -3,7 +3,7 @@ use rand::distributions::{Distribution, Uniform};
 use rand::rngs::StdRng;
 use rand::{thread_rng, RngCore, SeedableRng};
 
-use super::{EstimatorError, FeeEstimator, FeeRateEstimate};
+use super::{EstimatorError, ExportedEstimator, FeeEstimator, FeeRateEstimate, FeeRateHistogram};
 use crate::chainstate::stacks::db::StacksEpochReceipt;
 
 /// The FeeRateFuzzer wraps an underlying FeeEstimator. It passes `notify_block` calls to the
@@ -86,4 +86,19 @@ impl<T: FeeEstimator> FeeEstimator for FeeRateFuzzer<T> {
         let underlying_estimate = self.underlying.get_rate_estimates()?;
         Ok(self.fuzz_estimate(underlying_estimate))
     }
+
+    /// Histograms are passed straight from `underlying`, without fuzz.
+    fn get_block_histograms(&self) -> Result<Vec<FeeRateHistogram>, EstimatorError> {
+        self.underlying.get_block_histograms()
+    }
+
+    /// The state of `underlying` is exported, since the fuzz is not learned.
+    fn export_state(&self) -> Result<ExportedEstimator, EstimatorError> {
+        self.underlying.export_state()
+    }
+
+    /// Just passes the state straight to `underlying`.
+    fn import_state(&mut self, state: &ExportedEstimator) -> Result<(), EstimatorError> {
+        self.underlying.import_state(state)
+    }
 }
@@ -12,7 +12,7 @@ use rusqlite::{
 use serde_json::Value as JsonValue;
 
 use super::metrics::CostMetric;
-use super::{EstimatorError, FeeEstimator, FeeRateEstimate};
+use super::{EstimatorError, ExportedEstimator, FeeEstimator, FeeRateEstimate};
 use crate::chainstate::stacks::db::StacksEpochReceipt;
 use crate::chainstate::stacks::events::TransactionOrigin;
 use crate::chainstate::stacks::TransactionPayload;
@@ -20,6 +20,18 @@ use crate::util_lib::db::{
     sql_pragma, sqlite_open, table_exists, tx_begin_immediate_sqlite, u64_to_sql,
 };
 
+/// Name of the node's `ScalarFeeRateEstimator` DB, in its estimates directory
+pub const DB_FILENAME: &str = "fee_estimator_scalar_rate.sqlite";
+
+/// Name of this estimator in its exported state
+pub const ESTIMATOR_NAME: &str = "scalar_fee_rate";
+
+/// Exported state of a `ScalarFeeRateEstimator`
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct ScalarFeeRateEstimatorState {
+    estimate: Option<FeeRateEstimate>,
+}
+
 const SINGLETON_ROW_ID: i64 = 1;
 const CREATE_TABLE: &'static str = "
 CREATE TABLE scalar_fee_estimator (
@@ -125,23 +137,34 @@ impl<M: CostMetric> ScalarFeeRateEstimator<M> {
                "new_estimate_middle" => next_estimate.middle,
                "new_estimate_low" => next_estimate.low);
 
+        self.store_estimate(Some(&next_estimate))
+            .expect("SQLite failure");
+    }
+
+    /// Replace the current estimate, or clear it if `estimate` is `None`
+    fn store_estimate(&mut self, estimate: Option<&FeeRateEstimate>) -> Result<(), SqliteError> {
         let sql = "INSERT OR REPLACE INTO scalar_fee_estimator
                      (estimate_key, high, middle, low) VALUES (?, ?, ?, ?)";
 
-        let tx = tx_begin_immediate_sqlite(&mut self.db).expect("SQLite failure");
+        let tx = tx_begin_immediate_sqlite(&mut self.db)?;
 
-        tx.execute(
-            sql,
-            params![
-                SINGLETON_ROW_ID,
-                next_estimate.high,
-                next_estimate.middle,
-                next_estimate.low,
-            ],
-        )
-        .expect("SQLite failure");
+        match estimate {
+            Some(estimate) => tx.execute(
+                sql,
+                params![
+                    SINGLETON_ROW_ID,
+                    estimate.high,
+                    estimate.middle,
+                    estimate.low,
+                ],
+            )?,
+            None => tx.execute(
+                "DELETE FROM scalar_fee_estimator WHERE estimate_key = ?",
+                params![SINGLETON_ROW_ID],
+            )?,
+        };
 
-        tx.commit().expect("SQLite failure");
+        tx.commit()
     }
 }
 
@@ -251,4 +274,18 @@ impl<M: CostMetric> FeeEstimator for ScalarFeeRateEstimator<M> {
             .map(|(high, middle, low)| FeeRateEstimate { high, middle, low })
             .ok_or_else(|| EstimatorError::NoEstimateAvailable)
     }
+    fn export_state(&self) -> Result<ExportedEstimator, EstimatorError> {
+        let estimate = match self.get_rate_estimates() {
+            Ok(estimate) => Some(estimate),
+            Err(EstimatorError::NoEstimateAvailable) => None,
+            Err(e) => return Err(e),
+        };
+        ExportedEstimator::new(ESTIMATOR_NAME, &ScalarFeeRateEstimatorState { estimate })
+    }
+
+    fn import_state(&mut self, state: &ExportedEstimator) -> Result<(), EstimatorError> {
+        let state: ScalarFeeRateEstimatorState = state.decode(ESTIMATOR_NAME)?;
+        self.store_estimate(state.estimate.as_ref())
+            .map_err(EstimatorError::SqliteError)
+    }
 }
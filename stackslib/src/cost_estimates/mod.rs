@@ -7,6 +7,8 @@ use std::path::Path;
 
 use clarity::vm::costs::ExecutionCost;
 use rusqlite::Error as SqliteError;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use serde_json::json;
 
 use crate::burnchains::Txid;
@@ -14,6 +16,7 @@ use crate::chainstate::stacks::db::StacksEpochReceipt;
 use crate::chainstate::stacks::events::{StacksTransactionReceipt, TransactionOrigin};
 use crate::chainstate::stacks::{StacksBlock, TransactionPayload};
 
+pub mod fee_inclusion_time;
 pub mod fee_medians;
 pub mod fee_rate_fuzzer;
 pub mod fee_scalar;
@@ -49,6 +52,21 @@ pub trait FeeEstimator {
     ) -> Result<(), EstimatorError>;
     /// Get the current estimates for fee rate
     fn get_rate_estimates(&self) -> Result<FeeRateEstimate, EstimatorError>;
+    /// Get the fee rate histograms of the recent blocks which this estimator was notified of,
+    ///  newest first. Estimators which do not keep histograms return `NoEstimateAvailable`.
+    fn get_block_histograms(&self) -> Result<Vec<FeeRateHistogram>, EstimatorError> {
+        Err(EstimatorError::NoEstimateAvailable)
+    }
+    /// Export what this estimator has learned, so that it can be loaded into another node's
+    ///  estimator with `import_state`. Estimators which keep no state return `NoEstimateAvailable`.
+    fn export_state(&self) -> Result<ExportedEstimator, EstimatorError> {
+        Err(EstimatorError::NoEstimateAvailable)
+    }
+    /// Replace what this estimator has learned with `state`, which must have been exported by
+    ///  an estimator of the same kind.
+    fn import_state(&mut self, _state: &ExportedEstimator) -> Result<(), EstimatorError> {
+        Err(EstimatorError::NoEstimateAvailable)
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
@@ -102,6 +120,98 @@ impl Add for FeeRateEstimate {
     }
 }
 
+/// Lower bounds of the buckets of a `FeeRateHistogram`. The last bucket has no upper bound.
+pub const FEE_RATE_HISTOGRAM_BUCKETS: [f64; 16] = [
+    1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 200.0, 500.0, 1_000.0, 2_000.0, 5_000.0, 10_000.0,
+    20_000.0, 50_000.0, 100_000.0,
+];
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct FeeRateHistogramBucket {
+    /// The lowest fee rate in this bucket
+    pub fee_rate: f64,
+    /// Number of transactions in this bucket
+    pub count: u64,
+    /// Total scalar cost of the transactions in this bucket
+    pub weight: u64,
+}
+
+/// The distribution of fee rates paid by a set of transactions, such as a block or the mempool.
+/// The bucket bounds are given by `FEE_RATE_HISTOGRAM_BUCKETS`. Fee rates below the first
+/// bound are counted in the first bucket.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct FeeRateHistogram {
+    pub buckets: Vec<FeeRateHistogramBucket>,
+}
+
+impl Default for FeeRateHistogram {
+    fn default() -> Self {
+        Self {
+            buckets: FEE_RATE_HISTOGRAM_BUCKETS
+                .iter()
+                .map(|fee_rate| FeeRateHistogramBucket {
+                    fee_rate: *fee_rate,
+                    count: 0,
+                    weight: 0,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl FeeRateHistogram {
+    /// Index of the bucket which `fee_rate` falls in
+    pub fn bucket_index(fee_rate: f64) -> usize {
+        FEE_RATE_HISTOGRAM_BUCKETS
+            .iter()
+            .rposition(|lower_bound| fee_rate >= *lower_bound)
+            .unwrap_or(0)
+    }
+
+    /// Count `count` transactions with total scalar cost `weight` paying `fee_rate`
+    pub fn add(&mut self, fee_rate: f64, count: u64, weight: u64) {
+        let bucket = &mut self.buckets[Self::bucket_index(fee_rate)];
+        bucket.count = bucket.count.saturating_add(count);
+        bucket.weight = bucket.weight.saturating_add(weight);
+    }
+
+    /// Add the counts of `other` to this histogram
+    pub fn merge(&mut self, other: &FeeRateHistogram) {
+        for (bucket, other_bucket) in self.buckets.iter_mut().zip(other.buckets.iter()) {
+            bucket.count = bucket.count.saturating_add(other_bucket.count);
+            bucket.weight = bucket.weight.saturating_add(other_bucket.weight);
+        }
+    }
+
+    /// The lowest bound of a non-empty bucket, if any
+    pub fn min_fee_rate(&self) -> Option<f64> {
+        self.buckets
+            .iter()
+            .find(|bucket| bucket.weight > 0 || bucket.count > 0)
+            .map(|bucket| bucket.fee_rate)
+    }
+
+    /// Estimate the probability that a transaction paying `fee_rate` is included in a block,
+    /// as the fraction of `blocks` which included transactions in the same fee rate bucket or
+    /// lower. Blocks which were not full are padded at the minimum fee rate, so they count as
+    /// including any transaction. Returns `None` if there are no blocks.
+    pub fn inclusion_probability(blocks: &[FeeRateHistogram], fee_rate: f64) -> Option<f64> {
+        if blocks.is_empty() {
+            return None;
+        }
+        let bucket_fee_rate = FEE_RATE_HISTOGRAM_BUCKETS[Self::bucket_index(fee_rate)];
+        let num_including = blocks
+            .iter()
+            .filter(|block| {
+                block
+                    .min_fee_rate()
+                    .is_some_and(|min_fee_rate| min_fee_rate <= bucket_fee_rate)
+            })
+            .count();
+        Some(num_including as f64 / blocks.len() as f64)
+    }
+}
+
 /// Given a cost estimator and a scalar metric, estimate the fee rate for
 ///  the provided transaction
 pub fn estimate_fee_rate<CE: CostEstimator + ?Sized, CM: CostMetric + ?Sized>(
@@ -179,12 +289,113 @@ pub trait CostEstimator: Send {
             }
         }
     }
+
+    /// Export what this estimator has learned, so that it can be loaded into another node's
+    /// estimator with `import_state`. Estimators which keep no state return `NoEstimateAvailable`.
+    fn export_state(&self) -> Result<ExportedEstimator, EstimatorError> {
+        Err(EstimatorError::NoEstimateAvailable)
+    }
+
+    /// Replace what this estimator has learned with `state`, which must have been exported by an
+    /// estimator of the same kind.
+    fn import_state(&mut self, _state: &ExportedEstimator) -> Result<(), EstimatorError> {
+        Err(EstimatorError::NoEstimateAvailable)
+    }
+}
+
+/// Version of the `EstimatorState` format written by this node
+pub const ESTIMATOR_STATE_VERSION: u32 = 1;
+
+/// What a single estimator has learned, as exported by `CostEstimator::export_state` or
+/// `FeeEstimator::export_state`
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct ExportedEstimator {
+    /// Name of the estimator implementation which exported this state
+    pub estimator: String,
+    /// Implementation-specific state
+    pub state: serde_json::Value,
+}
+
+impl ExportedEstimator {
+    pub fn new<T: Serialize>(estimator: &str, state: &T) -> Result<Self, EstimatorError> {
+        let state =
+            serde_json::to_value(state).map_err(|e| EstimatorError::InvalidState(e.to_string()))?;
+        Ok(Self {
+            estimator: estimator.to_string(),
+            state,
+        })
+    }
+
+    /// Decode this state, checking that it was exported by an estimator named `estimator`
+    pub fn decode<T: DeserializeOwned>(&self, estimator: &str) -> Result<T, EstimatorError> {
+        if self.estimator != estimator {
+            return Err(EstimatorError::InvalidState(format!(
+                "Cannot import the state of a {} estimator into a {estimator} estimator",
+                &self.estimator
+            )));
+        }
+        serde_json::from_value(self.state.clone())
+            .map_err(|e| EstimatorError::InvalidState(e.to_string()))
+    }
+}
+
+/// A versioned snapshot of a node's cost and fee estimators. Importing it into a freshly
+/// bootstrapped node lets it assemble blocks and estimate fees without first having to process
+/// many blocks.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct EstimatorState {
+    /// The `ESTIMATOR_STATE_VERSION` of the node which exported this snapshot
+    pub version: u32,
+    pub cost_estimator: Option<ExportedEstimator>,
+    pub fee_estimator: Option<ExportedEstimator>,
+}
+
+impl EstimatorState {
+    /// Snapshot the given estimators
+    pub fn export(
+        cost_estimator: Option<&dyn CostEstimator>,
+        fee_estimator: Option<&dyn FeeEstimator>,
+    ) -> Result<Self, EstimatorError> {
+        Ok(Self {
+            version: ESTIMATOR_STATE_VERSION,
+            cost_estimator: cost_estimator
+                .map(|estimator| estimator.export_state())
+                .transpose()?,
+            fee_estimator: fee_estimator
+                .map(|estimator| estimator.export_state())
+                .transpose()?,
+        })
+    }
+
+    /// Replace the state of the given estimators with this snapshot. An estimator whose state is
+    /// not in the snapshot is left as it is.
+    pub fn import(
+        &self,
+        cost_estimator: Option<&mut dyn CostEstimator>,
+        fee_estimator: Option<&mut dyn FeeEstimator>,
+    ) -> Result<(), EstimatorError> {
+        if self.version != ESTIMATOR_STATE_VERSION {
+            return Err(EstimatorError::InvalidState(format!(
+                "Unsupported estimator state version {} (expected {ESTIMATOR_STATE_VERSION})",
+                self.version
+            )));
+        }
+        if let (Some(estimator), Some(state)) = (cost_estimator, self.cost_estimator.as_ref()) {
+            estimator.import_state(state)?;
+        }
+        if let (Some(estimator), Some(state)) = (fee_estimator, self.fee_estimator.as_ref()) {
+            estimator.import_state(state)?;
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, PartialEq)]
 pub enum EstimatorError {
     NoEstimateAvailable,
     SqliteError(SqliteError),
+    /// Exported estimator state could not be encoded, decoded or imported
+    InvalidState(String),
 }
 
 impl Error for EstimatorError {
@@ -205,6 +416,9 @@ impl Display for EstimatorError {
             EstimatorError::SqliteError(e) => {
                 write!(f, "Sqlite error from estimator: {}", e)
             }
+            EstimatorError::InvalidState(e) => {
+                write!(f, "Invalid estimator state: {}", e)
+            }
         }
     }
 }
@@ -219,6 +433,9 @@ impl EstimatorError {
             EstimatorError::SqliteError(_) => {
                 ("DatabaseError", Some(json!({"message": self.to_string()})))
             }
+            EstimatorError::InvalidState(_) => {
+                ("InvalidState", Some(json!({"message": self.to_string()})))
+            }
         };
         let mut result = json!({
             "error": "Estimation could not be performed",
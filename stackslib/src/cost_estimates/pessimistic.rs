@@ -1,4 +1,5 @@
 use std::cmp;
+use std::collections::BTreeMap;
 use std::path::Path;
 
 use clarity::types::sqlite::NO_PARAMS;
@@ -11,7 +12,7 @@ use rusqlite::{
 use serde_json::Value as JsonValue;
 
 use super::metrics::PROPORTION_RESOLUTION;
-use super::{CostEstimator, EstimatorError};
+use super::{CostEstimator, EstimatorError, ExportedEstimator};
 use crate::chainstate::stacks::TransactionPayload;
 use crate::core::StacksEpochId;
 use crate::util_lib::db::{
@@ -36,6 +37,18 @@ struct Samples {
     items: Vec<u64>,
 }
 
+/// Name of the node's `PessimisticEstimator` DB, in its estimates directory
+pub const DB_FILENAME: &str = "cost_estimator_pessimistic.sqlite";
+
+/// Name of this estimator in its exported state
+pub const ESTIMATOR_NAME: &str = "naive_pessimistic";
+
+/// Exported state of a `PessimisticEstimator`: the samples kept for each estimate key
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct PessimisticEstimatorState {
+    samples: BTreeMap<String, Vec<u64>>,
+}
+
 const SAMPLE_SIZE: usize = 10;
 const CREATE_TABLE: &'static str = "
 CREATE TABLE pessimistic_estimator (
@@ -246,6 +259,19 @@ impl PessimisticEstimator {
 
         format!("{}:{}", &tx_descriptor, field)
     }
+
+    fn get_all_samples_sqlite(
+        conn: &Connection,
+    ) -> Result<BTreeMap<String, Vec<u64>>, SqliteError> {
+        let sql = "SELECT estimate_key, samples FROM pessimistic_estimator";
+        let mut stmt = conn.prepare(sql)?;
+        let results = stmt.query_and_then::<_, SqliteError, _, _>(NO_PARAMS, |row| {
+            let estimate_key: String = row.get("estimate_key")?;
+            let samples: Samples = row.get("samples")?;
+            Ok((estimate_key, samples.items))
+        })?;
+        results.collect()
+    }
 }
 
 impl From<SqliteError> for EstimatorError {
@@ -334,4 +360,19 @@ impl CostEstimator for PessimisticEstimator {
             write_length,
         })
     }
+    fn export_state(&self) -> Result<ExportedEstimator, EstimatorError> {
+        let samples = Self::get_all_samples_sqlite(&self.db)?;
+        ExportedEstimator::new(ESTIMATOR_NAME, &PessimisticEstimatorState { samples })
+    }
+
+    fn import_state(&mut self, state: &ExportedEstimator) -> Result<(), EstimatorError> {
+        let state: PessimisticEstimatorState = state.decode(ESTIMATOR_NAME)?;
+        let sql_tx = tx_begin_immediate_sqlite(&mut self.db)?;
+        sql_tx.execute("DELETE FROM pessimistic_estimator", NO_PARAMS)?;
+        for (key, items) in state.samples.into_iter() {
+            Samples { items }.flush_sqlite(&sql_tx, &key);
+        }
+        sql_tx.commit()?;
+        Ok(())
+    }
 }
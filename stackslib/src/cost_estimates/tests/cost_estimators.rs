@@ -25,7 +25,8 @@ use crate::cost_estimates::fee_scalar::ScalarFeeRateEstimator;
 use crate::cost_estimates::metrics::CostMetric;
 use crate::cost_estimates::tests::common::*;
 use crate::cost_estimates::{
-    CostEstimator, EstimatorError, FeeEstimator, FeeRateEstimate, PessimisticEstimator,
+    CostEstimator, EstimatorError, EstimatorState, ExportedEstimator, FeeEstimator,
+    FeeRateEstimate, PessimisticEstimator, ESTIMATOR_STATE_VERSION,
 };
 
 fn instantiate_test_db() -> PessimisticEstimator {
@@ -870,3 +871,82 @@ fn test_cost_estimator_epochs_independent() {
         cost_205.clone(),
     );
 }
+
+/// Importing an exported estimator state replaces what the importing estimator had learned.
+#[test]
+fn test_cost_estimator_export_import() {
+    let cost = ExecutionCost {
+        write_length: 10,
+        write_count: 10,
+        read_length: 10,
+        read_count: 10,
+        runtime: 10,
+    };
+    let mut estimator = instantiate_test_db();
+    estimator.notify_block(
+        &vec![
+            make_dummy_transfer_tx(),
+            make_dummy_cc_tx("contract-1", "func1", cost.clone()),
+        ],
+        &BLOCK_LIMIT_MAINNET_20,
+        &StacksEpochId::Epoch21,
+    );
+
+    let state = EstimatorState::export(Some(&estimator), None)
+        .expect("Should be able to export cost estimator");
+    assert_eq!(state.version, ESTIMATOR_STATE_VERSION);
+    assert!(state.fee_estimator.is_none());
+    let state: EstimatorState =
+        serde_json::from_str(&serde_json::to_string(&state).unwrap()).unwrap();
+
+    let mut imported_estimator = instantiate_test_db();
+    imported_estimator.notify_block(
+        &vec![make_dummy_cc_tx("contract-2", "func2", cost.clone())],
+        &BLOCK_LIMIT_MAINNET_20,
+        &StacksEpochId::Epoch21,
+    );
+    state
+        .import(Some(&mut imported_estimator), None)
+        .expect("Should be able to import cost estimator");
+
+    assert_eq!(
+        imported_estimator
+            .estimate_cost(
+                &make_dummy_cc_payload("contract-1", "func1"),
+                &StacksEpochId::Epoch21
+            )
+            .expect("Should be able to provide imported cost estimate"),
+        cost
+    );
+    assert_eq!(
+        imported_estimator
+            .estimate_cost(&make_dummy_transfer_payload(), &StacksEpochId::Epoch21)
+            .expect("Should be able to provide imported cost estimate"),
+        ExecutionCost::ZERO
+    );
+    assert_eq!(
+        imported_estimator
+            .estimate_cost(
+                &make_dummy_cc_payload("contract-2", "func2"),
+                &StacksEpochId::Epoch21
+            )
+            .expect_err("Import should replace the previous estimates"),
+        EstimatorError::NoEstimateAvailable
+    );
+
+    // other versions and other estimators' state are rejected
+    let mut future_state = state.clone();
+    future_state.version += 1;
+    assert!(matches!(
+        future_state.import(Some(&mut imported_estimator), None),
+        Err(EstimatorError::InvalidState(_))
+    ));
+    let other_state = ExportedEstimator {
+        estimator: "scalar_fee_rate".into(),
+        state: serde_json::json!({ "estimate": null }),
+    };
+    assert!(matches!(
+        imported_estimator.import_state(&other_state),
+        Err(EstimatorError::InvalidState(_))
+    ));
+}
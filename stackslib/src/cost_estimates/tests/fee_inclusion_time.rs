@@ -0,0 +1,208 @@
+use std::env;
+use std::path::PathBuf;
+
+use clarity::vm::costs::ExecutionCost;
+use clarity::vm::Value;
+use rand::Rng;
+use rusqlite::{params, Connection};
+use stacks_common::types::chainstate::StacksAddress;
+use stacks_common::util::hash::{to_hex, Hash160};
+
+use crate::chainstate::nakamoto::NakamotoBlockHeader;
+use crate::chainstate::stacks::events::StacksTransactionReceipt;
+use crate::chainstate::stacks::{
+    StacksTransaction, TransactionAuth, TransactionContractCall, TransactionPayload,
+    TransactionSpendingCondition, TransactionVersion,
+};
+use crate::cost_estimates::fee_inclusion_time::{
+    fee_rate_estimate_from_inclusion_samples, TimeToInclusionFeeRateEstimator, EXPECTED_TENURE_SECS,
+};
+use crate::cost_estimates::metrics::ProportionalDotProduct;
+use crate::cost_estimates::tests::common::*;
+use crate::cost_estimates::{EstimatorError, FeeEstimator, FeeRateEstimate};
+
+const block_limit: ExecutionCost = ExecutionCost {
+    write_length: 100,
+    write_count: 100,
+    read_length: 100,
+    read_count: 100,
+    runtime: 100,
+};
+
+const tenth_operation_cost: ExecutionCost = ExecutionCost {
+    write_length: 0,
+    write_count: 0,
+    read_length: 0,
+    read_count: 0,
+    runtime: 10,
+};
+
+// The scalar cost of `make_dummy_cc_tx(_, _)`.
+const tenth_operation_cost_basis: u64 = 1164;
+
+fn temp_db_path(prefix: &str) -> PathBuf {
+    let mut path = env::temp_dir();
+    let random_bytes = rand::thread_rng().gen::<[u8; 32]>();
+    path.push(&format!(
+        "{}_{}.sqlite",
+        prefix,
+        &to_hex(&random_bytes)[0..8]
+    ));
+    path
+}
+
+/// Make a contract-call receipt paying `fee_rate`, with a distinct txid for each `nonce`
+fn make_dummy_cc_tx(fee_rate: u64, nonce: u64) -> StacksTransactionReceipt {
+    let mut tx = StacksTransaction::new(
+        TransactionVersion::Mainnet,
+        TransactionAuth::Standard(TransactionSpendingCondition::new_initial_sighash()),
+        TransactionPayload::ContractCall(TransactionContractCall {
+            address: StacksAddress::new(0, Hash160([0; 20])),
+            contract_name: "cc-dummy".into(),
+            function_name: "func-name".into(),
+            function_args: vec![],
+        }),
+    );
+    tx.set_tx_fee(fee_rate * tenth_operation_cost_basis);
+    tx.set_origin_nonce(nonce);
+    StacksTransactionReceipt::from_contract_call(
+        tx,
+        vec![],
+        Value::okay(Value::Bool(true)).unwrap(),
+        0,
+        tenth_operation_cost.clone(),
+    )
+}
+
+#[test]
+fn test_fee_rate_estimate_from_inclusion_samples() {
+    // too few samples
+    assert_eq!(
+        fee_rate_estimate_from_inclusion_samples(vec![(10f64, 0); 9]),
+        Err(EstimatorError::NoEstimateAvailable)
+    );
+
+    // high fee rates are mined right away, and low fee rates wait
+    let mut samples = vec![];
+    samples.extend(vec![(100f64, 0); 10]);
+    samples.extend(vec![(10f64, 2 * EXPECTED_TENURE_SECS); 10]);
+    samples.extend(vec![(1f64, 8 * EXPECTED_TENURE_SECS); 10]);
+    assert_eq!(
+        fee_rate_estimate_from_inclusion_samples(samples).unwrap(),
+        FeeRateEstimate {
+            high: 100f64,
+            middle: 10f64,
+            low: 1f64,
+        }
+    );
+
+    // the lowest fee rate in the last passing bucket is used, and a bucket too small to check
+    // on its own is merged into the next one
+    let mut samples = vec![];
+    samples.extend(vec![(150f64, 0); 8]);
+    samples.extend(vec![(120f64, EXPECTED_TENURE_SECS); 2]);
+    samples.extend(vec![(60f64, EXPECTED_TENURE_SECS); 2]);
+    samples.extend(vec![(30f64, EXPECTED_TENURE_SECS); 6]);
+    samples.extend(vec![(2f64, 20 * EXPECTED_TENURE_SECS); 10]);
+    assert_eq!(
+        fee_rate_estimate_from_inclusion_samples(samples).unwrap(),
+        FeeRateEstimate {
+            high: 30f64,
+            middle: 30f64,
+            low: 30f64,
+        }
+    );
+
+    // if nothing is mined in time, fall back to the highest fee rate seen
+    assert_eq!(
+        fee_rate_estimate_from_inclusion_samples(vec![(5f64, 20 * EXPECTED_TENURE_SECS); 10])
+            .unwrap(),
+        FeeRateEstimate {
+            high: 5f64,
+            middle: 5f64,
+            low: 5f64,
+        }
+    );
+}
+
+#[test]
+fn test_estimator_learns_from_mempool() {
+    // stand-in for the mempool DB, with the columns the estimator reads
+    let mempool_path = temp_db_path("mempool_db");
+    let mempool_db = Connection::open(&mempool_path).unwrap();
+    mempool_db
+        .execute(
+            "CREATE TABLE mempool (
+                txid TEXT NOT NULL,
+                height INTEGER NOT NULL,
+                accept_time INTEGER NOT NULL
+            )",
+            params![],
+        )
+        .unwrap();
+
+    let metric = ProportionalDotProduct::new(10_000);
+    let mut estimator = TimeToInclusionFeeRateEstimator::open(
+        &temp_db_path("fee_db"),
+        &mempool_path,
+        metric,
+        1_000,
+    )
+    .expect("Test failure: could not open fee rate DB");
+
+    assert_eq!(
+        estimator
+            .get_rate_estimates()
+            .expect_err("Empty rate estimator should error."),
+        EstimatorError::NoEstimateAvailable
+    );
+
+    // all transactions were accepted at the same time, at a Stacks block height far above the
+    // coinbase height, as in Nakamoto.  Those paying 50 were mined in the next block, and those
+    // paying 5 were mined 5 tenures later.
+    let accept_time = 1_700_000_000;
+    let mut next_block = |fee_rate: u64, block_time: u64, nonces: std::ops::Range<u64>| {
+        let tx_receipts: Vec<_> = nonces
+            .map(|nonce| make_dummy_cc_tx(fee_rate, nonce))
+            .collect();
+        for tx_receipt in tx_receipts.iter() {
+            mempool_db
+                .execute(
+                    "INSERT INTO mempool (txid, height, accept_time) VALUES (?1, ?2, ?3)",
+                    params![tx_receipt.transaction.txid(), 150_000, accept_time],
+                )
+                .unwrap();
+        }
+        let mut receipt = make_block_receipt(tx_receipts);
+        receipt.header.anchored_header = NakamotoBlockHeader {
+            timestamp: block_time,
+            ..NakamotoBlockHeader::empty()
+        }
+        .into();
+        receipt.header.stacks_block_height = 150_010;
+        receipt.coinbase_height = 1_234;
+        estimator
+            .notify_block(&receipt, &block_limit)
+            .expect("Should be able to process block receipt");
+    };
+    next_block(50, accept_time + 10, 0..10);
+    next_block(5, accept_time + 5 * EXPECTED_TENURE_SECS, 10..20);
+
+    // transactions which the mempool never saw are ignored
+    let mut receipt = make_block_receipt(vec![make_dummy_cc_tx(1, 20)]);
+    receipt.header.burn_header_timestamp = accept_time + 100 * EXPECTED_TENURE_SECS;
+    estimator
+        .notify_block(&receipt, &block_limit)
+        .expect("Should be able to process block receipt");
+
+    assert_eq!(
+        estimator
+            .get_rate_estimates()
+            .expect("Should be able to create estimate now"),
+        FeeRateEstimate {
+            high: 50f64,
+            middle: 50f64,
+            low: 5f64,
+        }
+    );
+}
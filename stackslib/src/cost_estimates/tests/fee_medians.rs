@@ -21,9 +21,12 @@ use crate::core::StacksEpochId;
 use crate::cost_estimates::fee_medians::{
     fee_rate_estimate_from_sorted_weighted_fees, FeeRateAndWeight, WeightedMedianFeeRateEstimator,
 };
+use crate::cost_estimates::fee_rate_fuzzer::FeeRateFuzzer;
 use crate::cost_estimates::metrics::{CostMetric, ProportionalDotProduct};
 use crate::cost_estimates::tests::common::*;
-use crate::cost_estimates::{EstimatorError, FeeEstimator, FeeRateEstimate};
+use crate::cost_estimates::{
+    EstimatorError, EstimatorState, FeeEstimator, FeeRateEstimate, FeeRateHistogram,
+};
 
 /// Returns true iff `b` is within `0.1%` of `a`.
 fn is_close_f64(a: f64, b: f64) -> bool {
@@ -283,6 +286,117 @@ fn test_window_size_forget_something() {
     ));
 }
 
+/// Each block's fee rates are kept as a histogram, which counts the padding as weight but not as
+/// transactions. Only the most recent `window_size` histograms are kept.
+#[test]
+fn test_block_histograms() {
+    let metric = ProportionalDotProduct::new(10_000);
+    let mut estimator = instantiate_test_db(metric);
+
+    assert_eq!(
+        estimator
+            .get_block_histograms()
+            .expect_err("Empty rate estimator should error."),
+        EstimatorError::NoEstimateAvailable
+    );
+
+    let single_tx_receipt = make_block_receipt(vec![
+        StacksTransactionReceipt::from_coinbase(make_dummy_coinbase_tx()),
+        make_dummy_cc_tx(10 * tenth_operation_cost_basis, &tenth_operation_cost),
+    ]);
+    estimator
+        .notify_block(&single_tx_receipt, &block_limit)
+        .expect("Should be able to process block receipt");
+
+    let histograms = estimator
+        .get_block_histograms()
+        .expect("Should be able to get histograms now");
+    assert_eq!(histograms.len(), 1);
+    let tx_bucket = &histograms[0].buckets[FeeRateHistogram::bucket_index(10f64)];
+    assert_eq!(tx_bucket.count, 1);
+    assert_eq!(tx_bucket.weight, tenth_operation_cost_basis);
+    let padding_bucket = &histograms[0].buckets[0];
+    assert_eq!(padding_bucket.count, 0);
+    assert_eq!(padding_bucket.weight, 10_000 - tenth_operation_cost_basis);
+    assert_eq!(histograms[0].min_fee_rate(), Some(1f64));
+
+    // these blocks are full, so they have no padding
+    for i in 1..7 {
+        let single_tx_receipt = make_block_receipt(vec![
+            StacksTransactionReceipt::from_coinbase(make_dummy_coinbase_tx()),
+            make_dummy_cc_tx(i * 10 * half_operation_cost_basis, &half_operation_cost),
+            make_dummy_cc_tx(i * 10 * half_operation_cost_basis, &half_operation_cost),
+        ]);
+        estimator
+            .notify_block(&single_tx_receipt, &block_limit)
+            .expect("Should be able to process block receipt");
+    }
+
+    // the partially filled block and the block paying 10 were forgotten, and the newest block
+    // comes first
+    let histograms = estimator
+        .get_block_histograms()
+        .expect("Should be able to get histograms now");
+    assert_eq!(histograms.len(), 5);
+    assert!(histograms
+        .iter()
+        .all(|histogram| histogram.min_fee_rate() == Some(20f64)
+            || histogram.min_fee_rate() == Some(50f64)));
+    assert_eq!(
+        histograms[0].buckets[FeeRateHistogram::bucket_index(60f64)].count,
+        2
+    );
+    assert_eq!(
+        FeeRateHistogram::inclusion_probability(&histograms, 20f64),
+        Some(0.6)
+    );
+    assert_eq!(
+        FeeRateHistogram::inclusion_probability(&histograms, 50f64),
+        Some(1f64)
+    );
+}
+
+/// The exported state includes each block's histogram, and can be imported through a
+/// `FeeRateFuzzer`.
+#[test]
+fn test_export_import_state() {
+    let metric = ProportionalDotProduct::new(10_000);
+    let mut estimator = instantiate_test_db(metric);
+    for i in 1..4 {
+        let single_tx_receipt = make_block_receipt(vec![
+            StacksTransactionReceipt::from_coinbase(make_dummy_coinbase_tx()),
+            make_dummy_cc_tx(i * 10 * tenth_operation_cost_basis, &tenth_operation_cost),
+        ]);
+        estimator
+            .notify_block(&single_tx_receipt, &block_limit)
+            .expect("Should be able to process block receipt");
+    }
+
+    let state = EstimatorState::export(None, Some(&estimator))
+        .expect("Should be able to export fee estimator");
+    let state: EstimatorState =
+        serde_json::from_str(&serde_json::to_string(&state).unwrap()).unwrap();
+
+    let metric = ProportionalDotProduct::new(10_000);
+    let mut imported_estimator = FeeRateFuzzer::new(instantiate_test_db(metric), 0f64);
+    state
+        .import(None, Some(&mut imported_estimator))
+        .expect("Should be able to import fee estimator");
+
+    assert_eq!(
+        imported_estimator.get_rate_estimates(),
+        estimator.get_rate_estimates()
+    );
+    assert_eq!(
+        imported_estimator.get_block_histograms(),
+        estimator.get_block_histograms()
+    );
+    assert_eq!(
+        EstimatorState::export(None, Some(&imported_estimator)).unwrap(),
+        state
+    );
+}
+
 #[test]
 fn test_fee_rate_estimate_5_vs_95() {
     assert_eq!(
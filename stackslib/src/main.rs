@@ -65,6 +65,7 @@ use blockstack_lib::chainstate::stacks::{StacksBlockHeader, *};
 use blockstack_lib::clarity::vm::costs::ExecutionCost;
 use blockstack_lib::clarity::vm::types::StacksAddressExtensions;
 use blockstack_lib::clarity::vm::ClarityVersion;
+use blockstack_lib::core::mempool::{export_txs, MemPoolExportReader};
 use blockstack_lib::core::{MemPoolDB, *};
 use blockstack_lib::cost_estimates::metrics::UnitMetric;
 use blockstack_lib::cost_estimates::UnitEstimator;
@@ -1282,6 +1283,132 @@ simulating a miner.
         process::exit(0);
     }
 
+    if argv[1] == "export-mempool" {
+        if argv.len() < 5 {
+            eprintln!(
+                "Usage: {} export-mempool CHAINSTATE_DIR NETWORK OUTPUT_PATH
+
+Write all of the transactions in the node's mempool to OUTPUT_PATH, so that they can be
+loaded into another node's mempool with `import-mempool`.
+",
+                &argv[0]
+            );
+            process::exit(1);
+        }
+
+        let chainstate_dir = argv[2].as_str();
+        let network = argv[3].as_str();
+        let output_path = argv[4].as_str();
+
+        let (_sort_db, chain_state) = open_nakamoto_chainstate_dbs(chainstate_dir, network);
+        let mempool_db = MemPoolDB::open(
+            chain_state.mainnet,
+            chain_state.chain_id,
+            &chain_state.root_path,
+            Box::new(UnitEstimator),
+            Box::new(UnitMetric),
+        )
+        .expect("Failed to open mempool db");
+
+        let mut output = File::create(output_path)
+            .unwrap_or_else(|e| panic!("Failed to create {output_path}: {e:?}"));
+        let num_txs = export_txs(mempool_db.conn(), &mut output)
+            .unwrap_or_else(|e| panic!("Failed to export mempool: {e:?}"));
+        output.flush().expect("Failed to flush mempool export");
+
+        println!("Exported {num_txs} transactions to {output_path}");
+        process::exit(0);
+    }
+
+    if argv[1] == "import-mempool" {
+        if argv.len() < 5 {
+            eprintln!(
+                "Usage: {} import-mempool CHAINSTATE_DIR NETWORK INPUT_PATH
+
+Submit the transactions written by `export-mempool` to INPUT_PATH into the node's mempool.
+Each transaction must pass the same admission checks as one received from a peer, against
+the node's canonical chain tip.  Admitted transactions keep the accept time and fee rate
+estimate that they had in the exporting node's mempool.
+",
+                &argv[0]
+            );
+            process::exit(1);
+        }
+
+        let chainstate_dir = argv[2].as_str();
+        let network = argv[3].as_str();
+        let input_path = argv[4].as_str();
+
+        let (sort_db, mut chain_state) = open_nakamoto_chainstate_dbs(chainstate_dir, network);
+        let mut mempool_db = MemPoolDB::open(
+            chain_state.mainnet,
+            chain_state.chain_id,
+            &chain_state.root_path,
+            Box::new(UnitEstimator),
+            Box::new(UnitMetric),
+        )
+        .expect("Failed to open mempool db");
+
+        let mut reader = MemPoolExportReader::new(BufReader::new(
+            File::open(input_path).unwrap_or_else(|e| panic!("Failed to open {input_path}: {e:?}")),
+        ));
+
+        let header_tip = NakamotoChainState::get_canonical_block_header(chain_state.db(), &sort_db)
+            .unwrap()
+            .expect("No canonical Stacks chain tip");
+        let burn_tip = SortitionDB::get_canonical_burn_chain_tip(sort_db.conn())
+            .expect("Failed to get sortition chain tip");
+        let epoch = SortitionDB::get_stacks_epoch(sort_db.conn(), burn_tip.block_height)
+            .unwrap()
+            .expect("No epoch at the burnchain tip");
+
+        let mut num_txs = 0;
+        let mut admitted = HashSet::new();
+        while let Some(tx) = reader
+            .next_tx()
+            .unwrap_or_else(|e| panic!("Failed to read mempool export: {e:?}"))
+        {
+            num_txs += 1;
+            let result = mempool_db.submit(
+                &mut chain_state,
+                &sort_db,
+                &header_tip.consensus_hash,
+                &header_tip.anchored_header.block_hash(),
+                &tx,
+                None,
+                &epoch.block_limit,
+                &epoch.epoch_id,
+            );
+            match result {
+                Ok(()) => {
+                    admitted.insert(tx.txid());
+                }
+                Err(e) => eprintln!("Rejected {}: {}", tx.txid(), e.into_json(&tx.txid())),
+            }
+        }
+
+        // the metadata trailer follows all of the transactions
+        while let Some(metadata) = reader
+            .next_metadata()
+            .unwrap_or_else(|e| panic!("Failed to read mempool export: {e:?}"))
+        {
+            if !admitted.contains(&metadata.txid) {
+                continue;
+            }
+            mempool_db
+                .restore_exported_tx_metadata(&metadata)
+                .unwrap_or_else(|e| {
+                    panic!("Failed to restore metadata of {}: {e:?}", &metadata.txid)
+                });
+        }
+
+        println!(
+            "Imported {} of {num_txs} transactions from {input_path}",
+            admitted.len()
+        );
+        process::exit(0);
+    }
+
     if argv[1] == "make-shadow-block" {
         if argv.len() < 5 {
             eprintln!(
@@ -1757,6 +1884,21 @@ simulating a miner.
         process::exit(0);
     }
 
+    if argv[1] == "export-estimators" {
+        cli::command_export_estimators(&argv[1..]);
+        process::exit(0);
+    }
+
+    if argv[1] == "import-estimators" {
+        cli::command_import_estimators(&argv[1..]);
+        process::exit(0);
+    }
+
+    if argv[1] == "rebuild-estimators" {
+        cli::command_rebuild_estimators(&argv[1..], None);
+        process::exit(0);
+    }
+
     if argv.len() < 4 {
         eprintln!("Usage: {} blockchain network working_dir", argv[0]);
         process::exit(1);
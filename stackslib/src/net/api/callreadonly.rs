@@ -26,11 +26,12 @@ use clarity::vm::errors::{Error as ClarityRuntimeError, InterpreterError};
 use clarity::vm::representations::{
     CONTRACT_NAME_REGEX_STRING, PRINCIPAL_DATA_REGEX_STRING, STANDARD_PRINCIPAL_REGEX_STRING,
 };
+use clarity::vm::trace::{CallTraceFrame, CallTracer};
 use clarity::vm::types::{
     PrincipalData, QualifiedContractIdentifier, StandardPrincipalData,
     BOUND_VALUE_SERIALIZATION_HEX,
 };
-use clarity::vm::{ClarityName, ClarityVersion, ContractName, SymbolicExpression, Value};
+use clarity::vm::{ClarityName, ClarityVersion, ContractName, EvalHook, SymbolicExpression, Value};
 use regex::{Captures, Regex};
 use stacks_common::types::chainstate::{StacksAddress, StacksBlockId};
 use stacks_common::types::net::PeerHost;
@@ -73,6 +74,49 @@ pub struct CallReadOnlyResponse {
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cause: Option<String>,
+    /// The call tree, if the request asked for it with `?trace=true`
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trace: Option<CallTraceFrame>,
+}
+
+impl CallReadOnlyResponse {
+    /// Make the response for the outcome of a read-only function call
+    pub fn from_call_result(result: Result<Value, ClarityRuntimeError>) -> Result<Self, NetError> {
+        let resp = match result {
+            Ok(data) => {
+                let hex_result = data
+                    .serialize_to_hex()
+                    .map_err(|e| NetError::SerializeError(format!("{:?}", &e)))?;
+
+                CallReadOnlyResponse {
+                    okay: true,
+                    result: Some(format!("0x{}", hex_result)),
+                    cause: None,
+                    trace: None,
+                }
+            }
+            Err(e) => match e {
+                Unchecked(CheckErrors::CostBalanceExceeded(actual_cost, _))
+                    if actual_cost.write_count > 0 =>
+                {
+                    CallReadOnlyResponse {
+                        okay: false,
+                        result: None,
+                        cause: Some("NotReadOnly".to_string()),
+                        trace: None,
+                    }
+                }
+                _ => CallReadOnlyResponse {
+                    okay: false,
+                    result: None,
+                    cause: Some(e.to_string()),
+                    trace: None,
+                },
+            },
+        };
+        Ok(resp)
+    }
 }
 
 #[derive(Clone)]
@@ -220,6 +264,13 @@ impl RPCRequestHandler for RPCCallReadOnlyRequestHandler {
             .take()
             .ok_or(NetError::SendError("Missing `arguments`".into()))?;
 
+        // if asked to, record the call tree as the function runs
+        let mut tracer = match contents.get_query_arg("trace").map(|x| x.as_str()) {
+            Some("1") | Some("true") => Some(CallTracer::new()),
+            _ => None,
+        };
+        let mut execution_cost = ExecutionCost::ZERO;
+
         // run the read-only call
         let data_resp =
             node.with_node_state(|_network, sortdb, chainstate, _mempool, _rpc_args| {
@@ -260,13 +311,14 @@ impl RPCRequestHandler for RPCCallReadOnlyRequestHandler {
                                 )))
                             })?;
 
-                        clarity_tx.with_readonly_clarity_env(
+                        clarity_tx.with_readonly_clarity_env_and_hook(
                             mainnet,
                             chain_id,
                             clarity_version,
                             sender,
                             sponsor,
                             cost_track,
+                            tracer.as_mut().map(|tracer| tracer as &mut dyn EvalHook),
                             |env| {
                                 // we want to execute any function as long as no actual writes are made as
                                 // opposed to be limited to purely calling `define-read-only` functions,
@@ -274,12 +326,14 @@ impl RPCRequestHandler for RPCCallReadOnlyRequestHandler {
                                 // can be called, and also circumvents limitations on `define-read-only`
                                 // functions that can not use `contrac-call?`, even when calling other
                                 // read-only functions
-                                env.execute_contract(
+                                let result = env.execute_contract(
                                     &contract_identifier,
                                     function.as_str(),
                                     &args,
                                     false,
-                                )
+                                );
+                                execution_cost = env.global_context.cost_track.get_total();
+                                result
                             },
                         )
                     },
@@ -288,33 +342,20 @@ impl RPCRequestHandler for RPCCallReadOnlyRequestHandler {
 
         // decode the response
         let data_resp = match data_resp {
-            Ok(Some(Ok(data))) => {
-                let hex_result = data
-                    .serialize_to_hex()
-                    .map_err(|e| NetError::SerializeError(format!("{:?}", &e)))?;
-
-                CallReadOnlyResponse {
-                    okay: true,
-                    result: Some(format!("0x{}", hex_result)),
-                    cause: None,
-                }
+            Ok(Some(result)) => {
+                let trace = tracer.map(|tracer| {
+                    tracer.into_trace(
+                        &contract_identifier,
+                        function.as_str(),
+                        &arguments,
+                        &result,
+                        execution_cost,
+                    )
+                });
+                let mut resp = CallReadOnlyResponse::from_call_result(result)?;
+                resp.trace = trace;
+                resp
             }
-            Ok(Some(Err(e))) => match e {
-                Unchecked(CheckErrors::CostBalanceExceeded(actual_cost, _))
-                    if actual_cost.write_count > 0 =>
-                {
-                    CallReadOnlyResponse {
-                        okay: false,
-                        result: None,
-                        cause: Some("NotReadOnly".to_string()),
-                    }
-                }
-                _ => CallReadOnlyResponse {
-                    okay: false,
-                    result: None,
-                    cause: Some(e.to_string()),
-                },
-            },
             Ok(None) | Err(_) => {
                 return StacksHttpResponse::new_error(
                     &preamble,
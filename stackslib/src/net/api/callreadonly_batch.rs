@@ -0,0 +1,480 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use clarity::vm::analysis::CheckErrors;
+use clarity::vm::clarity::ClarityConnection;
+use clarity::vm::costs::{ExecutionCost, LimitedCostTracker};
+use clarity::vm::errors::{Error as ClarityRuntimeError, InterpreterError};
+use clarity::vm::types::{PrincipalData, QualifiedContractIdentifier};
+use clarity::vm::{ClarityName, SymbolicExpression, Value};
+use regex::{Captures, Regex};
+use stacks_common::codec::MAX_PAYLOAD_LEN;
+use stacks_common::types::chainstate::StacksBlockId;
+use stacks_common::types::net::PeerHost;
+
+use crate::clarity_vm::clarity::ClarityReadOnlyConnection;
+use crate::net::api::callreadonly::CallReadOnlyResponse;
+use crate::net::http::{
+    parse_json, Error, HttpBadRequest, HttpContentType, HttpNotFound, HttpRequest,
+    HttpRequestContents, HttpRequestPreamble, HttpResponse, HttpResponseContents,
+    HttpResponsePayload, HttpResponsePreamble, HttpServerError,
+};
+use crate::net::httpcore::{
+    HttpPreambleExtensions, HttpRequestContentsExtensions, RPCRequestHandler, StacksHttpRequest,
+    StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState, TipRequest};
+
+/// Maximum number of calls in one batch
+pub const MAX_CALL_READ_ONLY_BATCH_SIZE: usize = 100;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CallReadOnlyBatchEntry {
+    /// Fully-qualified contract identifier, e.g. `SP000000000000000000002Q6VF78.pox-4`
+    pub contract: String,
+    pub function: String,
+    pub arguments: Vec<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CallReadOnlyBatchRequestBody {
+    pub sender: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sponsor: Option<String>,
+    pub calls: Vec<CallReadOnlyBatchEntry>,
+}
+
+/// The outcome of one call in the batch
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CallReadOnlyBatchResult {
+    pub okay: bool,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cause: Option<String>,
+    /// Cost charged to the batch's budget by this call
+    pub execution_cost: ExecutionCost,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CallReadOnlyBatchResponse {
+    /// The block all of the calls were evaluated against
+    pub tip: StacksBlockId,
+    /// One result per call, in the order the calls were given
+    pub results: Vec<CallReadOnlyBatchResult>,
+    /// Total cost of all of the calls
+    pub execution_cost: ExecutionCost,
+}
+
+/// A decoded call from the request body
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReadOnlyCall {
+    pub contract_identifier: QualifiedContractIdentifier,
+    pub function: ClarityName,
+    pub arguments: Vec<Value>,
+}
+
+#[derive(Clone)]
+pub struct RPCCallReadOnlyBatchRequestHandler {
+    maximum_call_argument_size: u32,
+    read_only_call_limit: ExecutionCost,
+
+    /// Runtime fields
+    pub sender: Option<PrincipalData>,
+    pub sponsor: Option<PrincipalData>,
+    pub calls: Option<Vec<ReadOnlyCall>>,
+}
+
+impl RPCCallReadOnlyBatchRequestHandler {
+    pub fn new(maximum_call_argument_size: u32, read_only_call_limit: ExecutionCost) -> Self {
+        Self {
+            maximum_call_argument_size,
+            read_only_call_limit,
+            sender: None,
+            sponsor: None,
+            calls: None,
+        }
+    }
+
+    fn decode_call(&self, entry: CallReadOnlyBatchEntry) -> Result<ReadOnlyCall, Error> {
+        let arguments_len = entry
+            .arguments
+            .iter()
+            .fold(0usize, |total, hex| total.saturating_add(hex.len()));
+        if arguments_len >= self.maximum_call_argument_size as usize {
+            return Err(Error::DecodeError(format!(
+                "Invalid Http request: arguments to {}::{} are too big ({})",
+                &entry.contract, &entry.function, arguments_len
+            )));
+        }
+
+        let contract_identifier = QualifiedContractIdentifier::parse(&entry.contract)
+            .map_err(|_e| Error::DecodeError("Failed to parse contract identifier".into()))?;
+        let function = ClarityName::try_from(entry.function)
+            .map_err(|_e| Error::DecodeError("Failed to parse function name".into()))?;
+
+        // arguments must be valid Clarity values
+        let arguments = entry
+            .arguments
+            .into_iter()
+            .map(|hex| Value::try_deserialize_hex_untyped(&hex).ok())
+            .collect::<Option<Vec<Value>>>()
+            .ok_or_else(|| Error::DecodeError("Failed to deserialize argument value".into()))?;
+
+        Ok(ReadOnlyCall {
+            contract_identifier,
+            function,
+            arguments,
+        })
+    }
+
+    /// Run one call, charging its cost to `cost_track`.
+    fn run_call(
+        clarity_tx: &mut ClarityReadOnlyConnection,
+        mainnet: bool,
+        chain_id: u32,
+        sender: &PrincipalData,
+        sponsor: &Option<PrincipalData>,
+        cost_track: &mut LimitedCostTracker,
+        call: &ReadOnlyCall,
+    ) -> Result<Value, ClarityRuntimeError> {
+        let clarity_version = clarity_tx
+            .with_analysis_db_readonly(|analysis_db| {
+                analysis_db.get_clarity_version(&call.contract_identifier)
+            })
+            .map_err(|_| {
+                ClarityRuntimeError::from(CheckErrors::NoSuchContract(format!(
+                    "{}",
+                    &call.contract_identifier
+                )))
+            })?;
+
+        let args: Vec<_> = call
+            .arguments
+            .iter()
+            .map(|x| SymbolicExpression::atom_value(x.clone()))
+            .collect();
+
+        // the environment takes ownership of the cost tracker, so hand it a copy and take back
+        // the copy's total once the call is done
+        let (result, mut total) = clarity_tx.with_readonly_clarity_env(
+            mainnet,
+            chain_id,
+            clarity_version,
+            sender.clone(),
+            sponsor.clone(),
+            cost_track.clone(),
+            |env| {
+                // as with a single read-only call, use `read_only = false` so that any function
+                // can be called as long as it makes no writes
+                let result = env.execute_contract(
+                    &call.contract_identifier,
+                    call.function.as_str(),
+                    &args,
+                    false,
+                );
+                Ok((result, env.global_context.cost_track.get_total()))
+            },
+        )?;
+        // a call that tries to write is stopped before its write happens, and the write must not
+        // count against the (zero) write budget of the calls that follow it
+        total.write_count = 0;
+        total.write_length = 0;
+        cost_track.set_total(total);
+        result
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCCallReadOnlyBatchRequestHandler {
+    fn verb(&self) -> &'static str {
+        "POST"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(r#"^/v3/contracts/call-read-batch$"#).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v3/contracts/call-read-batch"
+    }
+
+    /// Try to decode this request.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        _captures: &Captures,
+        query: Option<&str>,
+        body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        let content_len = preamble.get_content_length();
+        if !(content_len > 0 && content_len <= MAX_PAYLOAD_LEN) {
+            return Err(Error::DecodeError(format!(
+                "Invalid Http request: invalid body length for CallReadOnlyBatch ({})",
+                content_len
+            )));
+        }
+
+        if preamble.content_type != Some(HttpContentType::JSON) {
+            return Err(Error::DecodeError(
+                "Invalid content-type: expected application/json".to_string(),
+            ));
+        }
+
+        let body: CallReadOnlyBatchRequestBody = serde_json::from_slice(body)
+            .map_err(|_e| Error::DecodeError("Failed to parse JSON body".into()))?;
+
+        if body.calls.is_empty() || body.calls.len() > MAX_CALL_READ_ONLY_BATCH_SIZE {
+            return Err(Error::DecodeError(format!(
+                "Invalid Http request: expected between 1 and {} calls, got {}",
+                MAX_CALL_READ_ONLY_BATCH_SIZE,
+                body.calls.len()
+            )));
+        }
+
+        let sender = PrincipalData::parse(&body.sender)
+            .map_err(|_e| Error::DecodeError("Failed to parse sender principal".into()))?;
+
+        let sponsor = if let Some(sponsor) = body.sponsor {
+            Some(
+                PrincipalData::parse(&sponsor)
+                    .map_err(|_e| Error::DecodeError("Failed to parse sponsor principal".into()))?,
+            )
+        } else {
+            None
+        };
+
+        let calls = body
+            .calls
+            .into_iter()
+            .map(|entry| self.decode_call(entry))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.sender = Some(sender);
+        self.sponsor = sponsor;
+        self.calls = Some(calls);
+
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+/// Handle the HTTP request
+impl RPCRequestHandler for RPCCallReadOnlyBatchRequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.sender = None;
+        self.sponsor = None;
+        self.calls = None;
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let tip = match node.load_stacks_chain_tip(&preamble, &contents) {
+            Ok(tip) => tip,
+            Err(error_resp) => {
+                return error_resp.try_into_contents().map_err(NetError::from);
+            }
+        };
+
+        let sender = self
+            .sender
+            .take()
+            .ok_or(NetError::SendError("Missing `sender`".into()))?;
+        let sponsor = self.sponsor.take();
+        let calls = self
+            .calls
+            .take()
+            .ok_or(NetError::SendError("Missing `calls`".into()))?;
+
+        // the calls share one cost budget, which is as much as the same calls would get if they
+        // were made one at a time, so a call may use budget that another call left unused.
+        // Reject batches whose budget does not fit in an `ExecutionCost`.
+        let mut cost_limit = self.read_only_call_limit.clone();
+        if cost_limit.multiply(calls.len() as u64).is_err() {
+            return StacksHttpResponse::new_error(
+                &preamble,
+                &HttpBadRequest::new(format!(
+                    "Too many calls: the cost limit for {} calls overflows",
+                    calls.len()
+                )),
+            )
+            .try_into_contents()
+            .map_err(NetError::from);
+        }
+
+        cost_limit.write_length = 0;
+        cost_limit.write_count = 0;
+
+        // run all of the calls in one read-only connection, so they all see the same state, and
+        // charge them all to one cost tracker
+        let data_resp =
+            node.with_node_state(|_network, sortdb, chainstate, _mempool, _rpc_args| {
+                let mainnet = chainstate.mainnet;
+                let chain_id = chainstate.chain_id;
+
+                chainstate.maybe_read_only_clarity_tx(
+                    &sortdb.index_handle_at_block(chainstate, &tip)?,
+                    &tip,
+                    |clarity_tx| -> Result<_, ClarityRuntimeError> {
+                        let epoch = clarity_tx.get_epoch();
+                        let mut cost_track = clarity_tx
+                            .with_clarity_db_readonly(|clarity_db| {
+                                LimitedCostTracker::new_mid_block(
+                                    mainnet, chain_id, cost_limit, clarity_db, epoch,
+                                )
+                            })
+                            .map_err(|_| {
+                                ClarityRuntimeError::from(InterpreterError::CostContractLoadFailure)
+                            })?;
+
+                        let mut results = Vec::with_capacity(calls.len());
+                        for call in calls.iter() {
+                            let cost_before = cost_track.get_total();
+                            let result = Self::run_call(
+                                clarity_tx,
+                                mainnet,
+                                chain_id,
+                                &sender,
+                                &sponsor,
+                                &mut cost_track,
+                                call,
+                            );
+                            let mut call_cost = cost_track.get_total();
+                            call_cost
+                                .sub(&cost_before)
+                                .expect("FATAL: cost tracker total decreased");
+                            results.push((result, call_cost));
+                        }
+                        Ok((results, cost_track.get_total()))
+                    },
+                )
+            });
+
+        // decode the response
+        let data_resp = match data_resp {
+            Ok(Some(Ok((call_results, execution_cost)))) => {
+                let mut results = Vec::with_capacity(call_results.len());
+                for (result, call_cost) in call_results.into_iter() {
+                    let resp = CallReadOnlyResponse::from_call_result(result)?;
+                    results.push(CallReadOnlyBatchResult {
+                        okay: resp.okay,
+                        result: resp.result,
+                        cause: resp.cause,
+                        execution_cost: call_cost,
+                    });
+                }
+                CallReadOnlyBatchResponse {
+                    tip,
+                    results,
+                    execution_cost,
+                }
+            }
+            Ok(Some(Err(e))) => {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpServerError::new(format!("Failed to run read-only calls: {:?}", &e)),
+                )
+                .try_into_contents()
+                .map_err(NetError::from);
+            }
+            Ok(None) | Err(_) => {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpNotFound::new("Chain tip not found".to_string()),
+                )
+                .try_into_contents()
+                .map_err(NetError::from);
+            }
+        };
+
+        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+        let body = HttpResponseContents::try_from_json(&data_resp)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCCallReadOnlyBatchRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let resp: CallReadOnlyBatchResponse = parse_json(preamble, body)?;
+        Ok(HttpResponsePayload::try_from_json(resp)?)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request to run a batch of read-only functions
+    pub fn new_callreadonlybatch(
+        host: PeerHost,
+        sender: PrincipalData,
+        sponsor: Option<PrincipalData>,
+        calls: Vec<ReadOnlyCall>,
+        tip_req: TipRequest,
+    ) -> StacksHttpRequest {
+        let calls = calls
+            .into_iter()
+            .map(|call| CallReadOnlyBatchEntry {
+                contract: call.contract_identifier.to_string(),
+                function: call.function.to_string(),
+                arguments: call
+                    .arguments
+                    .iter()
+                    .map(|arg| {
+                        arg.serialize_to_hex()
+                            .expect("FATAL: failed to serialize argument value")
+                    })
+                    .collect(),
+            })
+            .collect();
+        StacksHttpRequest::new_for_peer(
+            host,
+            "POST".into(),
+            "/v3/contracts/call-read-batch".to_string(),
+            HttpRequestContents::new().for_tip(tip_req).payload_json(
+                serde_json::to_value(CallReadOnlyBatchRequestBody {
+                    sender: sender.to_string(),
+                    sponsor: sponsor.map(|s| s.to_string()),
+                    calls,
+                })
+                .expect("FATAL: failed to encode infallible data"),
+            ),
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_call_readonly_batch_response(
+        self,
+    ) -> Result<CallReadOnlyBatchResponse, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let resp: CallReadOnlyBatchResponse = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(resp)
+    }
+}
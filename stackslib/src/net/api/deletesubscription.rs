@@ -0,0 +1,177 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use regex::{Captures, Regex};
+use stacks_common::types::net::PeerHost;
+
+use crate::net::http::{
+    parse_json, Error, HttpNotFound, HttpRequest, HttpRequestContents, HttpRequestPreamble,
+    HttpResponse, HttpResponseContents, HttpResponsePayload, HttpResponsePreamble,
+};
+use crate::net::httpcore::{
+    HttpPreambleExtensions, RPCRequestHandler, StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeleteSubscriptionResponse {
+    pub subscription_id: String,
+    pub deleted: bool,
+}
+
+#[derive(Clone)]
+pub struct RPCDeleteSubscriptionRequestHandler {
+    pub subscription_id: Option<String>,
+}
+
+impl RPCDeleteSubscriptionRequestHandler {
+    pub fn new() -> Self {
+        Self {
+            subscription_id: None,
+        }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCDeleteSubscriptionRequestHandler {
+    fn verb(&self) -> &'static str {
+        "DELETE"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(r#"^/v3/events/subscriptions/(?P<subscription_id>[0-9a-f]{32})$"#).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v3/events/subscriptions/:subscription_id"
+    }
+
+    /// Try to decode this request.
+    /// There's nothing to load here, so just make sure the request is well-formed.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+
+        let subscription_id = captures
+            .name("subscription_id")
+            .ok_or(Error::DecodeError(
+                "Failed to match path to subscription ID".to_string(),
+            ))?
+            .as_str()
+            .to_string();
+
+        self.subscription_id = Some(subscription_id);
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+impl RPCRequestHandler for RPCDeleteSubscriptionRequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.subscription_id = None;
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        _contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let subscription_id = self
+            .subscription_id
+            .take()
+            .ok_or(NetError::SendError("`subscription_id` not set".into()))?;
+
+        let data_resp =
+            node.with_node_state(|_network, _sortdb, _chainstate, _mempool, rpc_args| {
+                let Some(event_subscriptions) = rpc_args.event_subscriptions else {
+                    return Err(StacksHttpResponse::new_error(
+                        &preamble,
+                        &HttpNotFound::new("Event subscriptions are not enabled".to_string()),
+                    ));
+                };
+                if !event_subscriptions.unsubscribe(&subscription_id) {
+                    return Err(StacksHttpResponse::new_error(
+                        &preamble,
+                        &HttpNotFound::new("No such subscription".to_string()),
+                    ));
+                }
+                Ok(DeleteSubscriptionResponse {
+                    subscription_id,
+                    deleted: true,
+                })
+            });
+
+        let data_resp = match data_resp {
+            Ok(data) => data,
+            Err(response) => {
+                return response.try_into_contents().map_err(NetError::from);
+            }
+        };
+
+        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+        let body = HttpResponseContents::try_from_json(&data_resp)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCDeleteSubscriptionRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let resp: DeleteSubscriptionResponse = parse_json(preamble, body)?;
+        Ok(HttpResponsePayload::try_from_json(resp)?)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request to cancel an event subscription
+    pub fn new_delete_subscription(host: PeerHost, subscription_id: &str) -> StacksHttpRequest {
+        StacksHttpRequest::new_for_peer(
+            host,
+            "DELETE".into(),
+            format!("/v3/events/subscriptions/{}", subscription_id),
+            HttpRequestContents::new(),
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_delete_subscription_response(
+        self,
+    ) -> Result<DeleteSubscriptionResponse, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let response_json: serde_json::Value = contents.try_into()?;
+        let resp: DeleteSubscriptionResponse = serde_json::from_value(response_json)
+            .map_err(|_e| Error::DecodeError("Failed to decode JSON".to_string()))?;
+        Ok(resp)
+    }
+}
@@ -29,7 +29,7 @@ use stacks_common::util::hash::{to_hex, Sha256Sum};
 
 use crate::burnchains::Burnchain;
 use crate::chainstate::burn::db::sortdb::SortitionDB;
-use crate::chainstate::stacks::boot::{POX_1_NAME, POX_2_NAME, POX_3_NAME};
+use crate::chainstate::stacks::boot::{POX_1_NAME, POX_2_NAME, POX_3_NAME, POX_4_NAME};
 use crate::chainstate::stacks::db::StacksChainState;
 use crate::chainstate::stacks::Error as ChainError;
 use crate::core::mempool::MemPoolDB;
@@ -58,15 +58,62 @@ pub struct AccountEntryResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
     pub nonce_proof: Option<String>,
+    /// Name of the PoX contract which locked the `locked` amount, if any is locked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub locked_by: Option<String>,
+    /// The block the account was read from.  Only given if the request asked for the account
+    /// at a particular height.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub index_block_hash: Option<StacksBlockId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub block_height: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub burn_block_height: Option<u64>,
+}
+
+/// Which ancestor of the requested chain tip to read an account from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AccountHeightRequest {
+    /// The block at this Stacks block height
+    StacksBlockHeight(u64),
+    /// The Stacks chain tip as of this burnchain block height
+    BurnBlockHeight(u64),
 }
 
 #[derive(Clone)]
 pub struct RPCGetAccountRequestHandler {
     pub account: Option<PrincipalData>,
+    pub height: Option<AccountHeightRequest>,
 }
 impl RPCGetAccountRequestHandler {
     pub fn new() -> Self {
-        Self { account: None }
+        Self {
+            account: None,
+            height: None,
+        }
+    }
+
+    fn parse_height_arg(contents: &HttpRequestContents, key: &str) -> Result<Option<u64>, Error> {
+        contents
+            .get_query_arg(key)
+            .map(|value| value.parse::<u64>())
+            .transpose()
+            .map_err(|_| Error::DecodeError(format!("Failed to parse {}= query parameter", key)))
+    }
+}
+
+/// Name of the PoX contract which locked the tokens in `balance`, if any were locked
+fn locking_pox_contract(balance: &STXBalance) -> Option<&'static str> {
+    match balance {
+        STXBalance::Unlocked { .. } => None,
+        STXBalance::LockedPoxOne { .. } => Some(POX_1_NAME),
+        STXBalance::LockedPoxTwo { .. } => Some(POX_2_NAME),
+        STXBalance::LockedPoxThree { .. } => Some(POX_3_NAME),
+        STXBalance::LockedPoxFour { .. } => Some(POX_4_NAME),
     }
 }
 
@@ -112,9 +159,25 @@ impl HttpRequest for RPCGetAccountRequestHandler {
             ));
         };
 
+        let contents = HttpRequestContents::new().query_string(query);
+        let height = match (
+            Self::parse_height_arg(&contents, "at_height")?,
+            Self::parse_height_arg(&contents, "at_burn_height")?,
+        ) {
+            (None, None) => None,
+            (Some(height), None) => Some(AccountHeightRequest::StacksBlockHeight(height)),
+            (None, Some(height)) => Some(AccountHeightRequest::BurnBlockHeight(height)),
+            (Some(_), Some(_)) => {
+                return Err(Error::DecodeError(
+                    "At most one of at_height= and at_burn_height= may be given".into(),
+                ));
+            }
+        };
+
         self.account = Some(account);
+        self.height = height;
 
-        Ok(HttpRequestContents::new().query_string(query))
+        Ok(contents)
     }
 }
 
@@ -123,6 +186,7 @@ impl RPCRequestHandler for RPCGetAccountRequestHandler {
     /// Reset internal state
     fn restart(&mut self) {
         self.account = None;
+        self.height = None;
     }
 
     /// Make the response
@@ -144,6 +208,52 @@ impl RPCRequestHandler for RPCGetAccountRequestHandler {
             .ok_or(NetError::SendError("Missing `account`".into()))?;
         let with_proof = contents.get_with_proof();
 
+        // find the ancestor of the tip at the requested height, if one was requested
+        let header = match self.height.take() {
+            None => None,
+            Some(height) => {
+                let header_res =
+                    node.with_node_state(|_network, _sortdb, chainstate, _mempool, _rpc_args| {
+                        match height {
+                            AccountHeightRequest::StacksBlockHeight(height) => {
+                                chainstate.get_ancestor_header_at_height(&tip, height)
+                            }
+                            AccountHeightRequest::BurnBlockHeight(height) => {
+                                chainstate.get_ancestor_header_at_burn_height(&tip, height)
+                            }
+                        }
+                    });
+                match header_res {
+                    Ok(Some(header)) => Some(header),
+                    Ok(None) => {
+                        return StacksHttpResponse::new_error(
+                            &preamble,
+                            &HttpNotFound::new(format!(
+                                "No block at {:?} in the fork of '{}'",
+                                &height, &tip
+                            )),
+                        )
+                        .try_into_contents()
+                        .map_err(NetError::from);
+                    }
+                    Err(e) => {
+                        let msg = format!("Failed to load block at {:?}: {:?}", &height, &e);
+                        warn!("{}", &msg);
+                        return StacksHttpResponse::new_error(
+                            &preamble,
+                            &HttpServerError::new(msg),
+                        )
+                        .try_into_contents()
+                        .map_err(NetError::from);
+                    }
+                }
+            }
+        };
+        let tip = header
+            .as_ref()
+            .map(|header| header.index_block_hash())
+            .unwrap_or(tip);
+
         let account_opt_res =
             node.with_node_state(|_network, sortdb, chainstate, _mempool, _rpc_args| {
                 chainstate.maybe_read_only_clarity_tx(
@@ -206,6 +316,11 @@ impl RPCRequestHandler for RPCGetAccountRequestHandler {
                                 v3_unlock_height,
                             );
 
+                            let locked_by = if locked > 0 {
+                                locking_pox_contract(&balance).map(String::from)
+                            } else {
+                                None
+                            };
                             let balance = format!("0x{}", to_hex(&unlocked.to_be_bytes()));
                             let locked = format!("0x{}", to_hex(&locked.to_be_bytes()));
 
@@ -216,6 +331,16 @@ impl RPCRequestHandler for RPCGetAccountRequestHandler {
                                 nonce,
                                 balance_proof,
                                 nonce_proof,
+                                locked_by,
+                                index_block_hash: header
+                                    .as_ref()
+                                    .map(|header| header.index_block_hash()),
+                                block_height: header
+                                    .as_ref()
+                                    .map(|header| header.stacks_block_height),
+                                burn_block_height: header
+                                    .as_ref()
+                                    .map(|header| u64::from(header.burn_header_height)),
                             })
                         })
                     },
@@ -270,6 +395,30 @@ impl StacksHttpRequest {
         )
         .expect("FATAL: failed to construct request from infallible data")
     }
+
+    /// Make a new request for an account as of an ancestor of the given tip
+    pub fn new_getaccount_at_height(
+        host: PeerHost,
+        principal: PrincipalData,
+        tip_req: TipRequest,
+        height: AccountHeightRequest,
+        with_proof: bool,
+    ) -> StacksHttpRequest {
+        let (key, value) = match height {
+            AccountHeightRequest::StacksBlockHeight(height) => ("at_height", height),
+            AccountHeightRequest::BurnBlockHeight(height) => ("at_burn_height", height),
+        };
+        StacksHttpRequest::new_for_peer(
+            host,
+            "GET".into(),
+            format!("/v2/accounts/{}", &principal),
+            HttpRequestContents::new()
+                .for_tip(tip_req)
+                .query_arg("proof".into(), if with_proof { "1" } else { "0" }.into())
+                .query_arg(key.into(), value.to_string()),
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
 }
 
 impl StacksHttpResponse {
@@ -0,0 +1,454 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::fmt;
+use std::str::FromStr;
+
+use clarity::vm::representations::{CONTRACT_NAME_REGEX_STRING, STANDARD_PRINCIPAL_REGEX_STRING};
+use clarity::vm::types::QualifiedContractIdentifier;
+use clarity::vm::ContractName;
+use regex::{Captures, Regex};
+use stacks_common::types::chainstate::{StacksAddress, StacksBlockId};
+use stacks_common::types::net::PeerHost;
+
+use crate::burnchains::Txid;
+use crate::chainstate::nakamoto::NakamotoChainState;
+use crate::chainstate::stacks::db::StacksChainState;
+use crate::net::http::{
+    parse_json, Error, HttpNotFound, HttpRequest, HttpRequestContents, HttpRequestPreamble,
+    HttpResponse, HttpResponseContents, HttpResponsePayload, HttpResponsePreamble, HttpServerError,
+};
+use crate::net::httpcore::{
+    request, HttpPreambleExtensions, HttpRequestContentsExtensions, RPCRequestHandler,
+    StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState, TipRequest};
+
+/// Default number of events to return in one page
+pub const DEFAULT_CONTRACT_EVENTS_LIMIT: u32 = 50;
+/// Maximum number of events to return in one page
+pub const MAX_CONTRACT_EVENTS_LIMIT: u32 = 500;
+/// Maximum number of block heights with events from the contract which one request looks at.  If
+/// a page is not full by then, its `next_cursor` carries on from the last height looked at.
+pub const MAX_CONTRACT_EVENTS_SCAN_HEIGHTS: u32 = 500;
+
+/// Position of an event in a fork, after which the next page of events starts.  It is written as
+/// `<block_height>-<tx_index>-<event_index>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ContractEventsCursor {
+    pub block_height: u64,
+    pub tx_index: u32,
+    pub event_index: u32,
+}
+
+impl ContractEventsCursor {
+    /// Cursor after all of the events at `block_height`
+    pub fn end_of_height(block_height: u64) -> Self {
+        Self {
+            block_height,
+            tx_index: u32::MAX,
+            event_index: u32::MAX,
+        }
+    }
+}
+
+impl fmt::Display for ContractEventsCursor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}-{}-{}",
+            self.block_height, self.tx_index, self.event_index
+        )
+    }
+}
+
+impl FromStr for ContractEventsCursor {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split('-');
+        let (Some(block_height), Some(tx_index), Some(event_index), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(());
+        };
+        Ok(Self {
+            block_height: block_height.parse().map_err(|_| ())?,
+            tx_index: tx_index.parse().map_err(|_| ())?,
+            event_index: event_index.parse().map_err(|_| ())?,
+        })
+    }
+}
+
+/// Which of a contract's events to fetch
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContractEventsQuery {
+    /// only return events with this topic
+    pub topic: Option<String>,
+    /// lowest block height to consider (inclusive)
+    pub from_height: Option<u64>,
+    /// highest block height to consider (inclusive).  Defaults to the tip height.
+    pub to_height: Option<u64>,
+    /// maximum number of events to return
+    pub limit: u32,
+    /// only return events after this one
+    pub cursor: Option<ContractEventsCursor>,
+}
+
+impl Default for ContractEventsQuery {
+    fn default() -> Self {
+        Self {
+            topic: None,
+            from_height: None,
+            to_height: None,
+            limit: DEFAULT_CONTRACT_EVENTS_LIMIT,
+            cursor: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContractEvent {
+    pub txid: Txid,
+    pub index_block_hash: StacksBlockId,
+    pub block_height: u64,
+    pub tx_index: u32,
+    pub event_index: u32,
+    pub topic: String,
+    /// Hex-encoded Clarity value
+    pub value: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContractEventsResponse {
+    pub contract_identifier: String,
+    pub events: Vec<ContractEvent>,
+    /// Cursor of the next page of events, if there may be more
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct RPCGetContractEventsRequestHandler {
+    pub contract_identifier: Option<QualifiedContractIdentifier>,
+    pub query: ContractEventsQuery,
+}
+
+impl RPCGetContractEventsRequestHandler {
+    pub fn new() -> Self {
+        Self {
+            contract_identifier: None,
+            query: ContractEventsQuery::default(),
+        }
+    }
+
+    fn parse_query_arg<T: std::str::FromStr>(
+        contents: &HttpRequestContents,
+        key: &str,
+    ) -> Result<Option<T>, Error> {
+        contents
+            .get_query_arg(key)
+            .map(|value| value.parse::<T>())
+            .transpose()
+            .map_err(|_| Error::DecodeError(format!("Failed to parse {}= query parameter", key)))
+    }
+
+    /// Load a page of the contract's events which were emitted in the fork ending at `tip`.
+    /// Returns Ok(None) if the tip does not exist.
+    ///
+    /// The index holds events from all forks.  At most `MAX_CONTRACT_EVENTS_SCAN_HEIGHTS` of the
+    /// heights at which the contract emitted events are looked at, and only the events from the
+    /// block at each height which is an ancestor of the tip are loaded.
+    fn get_contract_events(
+        chainstate: &StacksChainState,
+        tip: &StacksBlockId,
+        contract_id: &QualifiedContractIdentifier,
+        query: &ContractEventsQuery,
+    ) -> Result<Option<ContractEventsResponse>, NetError> {
+        let Some(tip_header) = NakamotoChainState::get_block_header(chainstate.db(), tip)? else {
+            return Ok(None);
+        };
+        let mut from_height = query.from_height.unwrap_or(0);
+        if let Some(cursor) = query.cursor.as_ref() {
+            from_height = from_height.max(cursor.block_height);
+        }
+        let to_height = query
+            .to_height
+            .unwrap_or(tip_header.stacks_block_height)
+            .min(tip_header.stacks_block_height);
+
+        let heights = if from_height <= to_height {
+            StacksChainState::get_contract_event_heights(
+                chainstate.db(),
+                contract_id,
+                query.topic.as_deref(),
+                from_height,
+                to_height,
+                MAX_CONTRACT_EVENTS_SCAN_HEIGHTS,
+            )?
+        } else {
+            vec![]
+        };
+
+        let mut events: Vec<ContractEvent> = vec![];
+        let mut next_cursor = None;
+        'scan: for height in heights.iter() {
+            let Some(block_id) = chainstate
+                .index_conn()
+                .get_ancestor_block_hash(*height, tip)?
+            else {
+                continue;
+            };
+            let after = query
+                .cursor
+                .as_ref()
+                .filter(|cursor| cursor.block_height == *height)
+                .map(|cursor| (cursor.tx_index, cursor.event_index));
+            // load one more event than fits in the page, to find out whether there are more
+            let remaining = query.limit.saturating_sub(events.len() as u32);
+            let indexed_events = StacksChainState::get_indexed_contract_events(
+                chainstate.db(),
+                contract_id,
+                query.topic.as_deref(),
+                &block_id,
+                after,
+                remaining.saturating_add(1),
+            )?;
+            for indexed_event in indexed_events.into_iter() {
+                if events.len() >= query.limit as usize {
+                    next_cursor = events.last().map(|last| ContractEventsCursor {
+                        block_height: last.block_height,
+                        tx_index: last.tx_index,
+                        event_index: last.event_index,
+                    });
+                    break 'scan;
+                }
+                events.push(ContractEvent {
+                    txid: indexed_event.txid,
+                    index_block_hash: indexed_event.index_block_hash,
+                    block_height: indexed_event.block_height,
+                    tx_index: indexed_event.tx_index,
+                    event_index: indexed_event.event_index,
+                    topic: indexed_event.topic,
+                    value: format!("0x{}", indexed_event.value),
+                });
+            }
+        }
+
+        // if the scan stopped early, the next page carries on after the last height looked at
+        if next_cursor.is_none() && heights.len() >= MAX_CONTRACT_EVENTS_SCAN_HEIGHTS as usize {
+            next_cursor = heights
+                .last()
+                .map(|height| ContractEventsCursor::end_of_height(*height));
+        }
+
+        Ok(Some(ContractEventsResponse {
+            contract_identifier: contract_id.to_string(),
+            events,
+            next_cursor: next_cursor.map(|cursor| cursor.to_string()),
+        }))
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCGetContractEventsRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(&format!(
+            "^/v3/contracts/(?P<address>{})/(?P<contract>{})/events$",
+            *STANDARD_PRINCIPAL_REGEX_STRING, *CONTRACT_NAME_REGEX_STRING
+        ))
+        .unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v3/contracts/:principal/:contract_name/events"
+    }
+
+    /// Try to decode this request.
+    /// There's nothing to load here, so just make sure the request is well-formed.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+
+        let contract_identifier = request::get_contract_address(captures, "address", "contract")?;
+        let contents = HttpRequestContents::new().query_string(query);
+
+        let limit = Self::parse_query_arg::<u32>(&contents, "limit")?
+            .unwrap_or(DEFAULT_CONTRACT_EVENTS_LIMIT);
+        if limit == 0 || limit > MAX_CONTRACT_EVENTS_LIMIT {
+            return Err(Error::DecodeError(format!(
+                "Invalid limit= query parameter: must be between 1 and {}",
+                MAX_CONTRACT_EVENTS_LIMIT
+            )));
+        }
+
+        self.contract_identifier = Some(contract_identifier);
+        self.query = ContractEventsQuery {
+            topic: contents.get_query_arg("topic").cloned(),
+            from_height: Self::parse_query_arg(&contents, "from_height")?,
+            to_height: Self::parse_query_arg(&contents, "to_height")?,
+            limit,
+            cursor: Self::parse_query_arg(&contents, "cursor")?,
+        };
+
+        Ok(contents)
+    }
+}
+
+/// Handle the HTTP request
+impl RPCRequestHandler for RPCGetContractEventsRequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.contract_identifier = None;
+        self.query = ContractEventsQuery::default();
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let contract_identifier = self.contract_identifier.take().ok_or(NetError::SendError(
+            "`contract_identifier` not set".to_string(),
+        ))?;
+        let query = std::mem::take(&mut self.query);
+
+        let tip = match node.load_stacks_chain_tip(&preamble, &contents) {
+            Ok(tip) => tip,
+            Err(error_resp) => {
+                return error_resp.try_into_contents().map_err(NetError::from);
+            }
+        };
+
+        let event_index_enabled =
+            node.with_node_state(|_network, _sortdb, chainstate, _mempool, _rpc_args| {
+                chainstate.event_index
+            });
+        if !event_index_enabled {
+            return StacksHttpResponse::new_error(
+                &preamble,
+                &HttpNotFound::new("Contract event index is not enabled".to_string()),
+            )
+            .try_into_contents()
+            .map_err(NetError::from);
+        }
+
+        let data_resp =
+            node.with_node_state(|_network, _sortdb, chainstate, _mempool, _rpc_args| {
+                Self::get_contract_events(chainstate, &tip, &contract_identifier, &query)
+            });
+
+        let data_resp = match data_resp {
+            Ok(Some(data)) => data,
+            Ok(None) => {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpNotFound::new("Chain tip not found".to_string()),
+                )
+                .try_into_contents()
+                .map_err(NetError::from);
+            }
+            Err(e) => {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpServerError::new(format!(
+                        "Failed to load events for {}: {:?}",
+                        &contract_identifier, &e
+                    )),
+                )
+                .try_into_contents()
+                .map_err(NetError::from);
+            }
+        };
+
+        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+        let body = HttpResponseContents::try_from_json(&data_resp)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCGetContractEventsRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let events: ContractEventsResponse = parse_json(preamble, body)?;
+        Ok(HttpResponsePayload::try_from_json(events)?)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request for a page of a contract's events
+    pub fn new_getcontractevents(
+        host: PeerHost,
+        contract_addr: StacksAddress,
+        contract_name: ContractName,
+        query: &ContractEventsQuery,
+        tip_req: TipRequest,
+    ) -> StacksHttpRequest {
+        let mut contents = HttpRequestContents::new().for_tip(tip_req);
+        if let Some(topic) = query.topic.as_ref() {
+            contents = contents.query_arg("topic".into(), topic.clone());
+        }
+        if let Some(from_height) = query.from_height {
+            contents = contents.query_arg("from_height".into(), from_height.to_string());
+        }
+        if let Some(to_height) = query.to_height {
+            contents = contents.query_arg("to_height".into(), to_height.to_string());
+        }
+        contents = contents.query_arg("limit".into(), query.limit.to_string());
+        if let Some(cursor) = query.cursor.as_ref() {
+            contents = contents.query_arg("cursor".into(), cursor.to_string());
+        }
+
+        StacksHttpRequest::new_for_peer(
+            host,
+            "GET".into(),
+            format!("/v3/contracts/{}/{}/events", &contract_addr, &contract_name),
+            contents,
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_contract_events_response(self) -> Result<ContractEventsResponse, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let resp: ContractEventsResponse = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(resp)
+    }
+}
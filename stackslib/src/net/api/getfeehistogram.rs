@@ -0,0 +1,247 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use regex::{Captures, Regex};
+use stacks_common::types::net::PeerHost;
+
+use crate::core::mempool::MemPoolDB;
+use crate::cost_estimates::{EstimatorError, FeeRateHistogram};
+use crate::net::http::{
+    parse_json, Error, HttpBadRequest, HttpRequest, HttpRequestContents, HttpRequestPreamble,
+    HttpResponse, HttpResponseContents, HttpResponsePayload, HttpResponsePreamble, HttpServerError,
+};
+use crate::net::httpcore::{
+    HttpPreambleExtensions, RPCRequestHandler, StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState};
+
+/// Maximum number of mempool transactions whose fee rates are estimated per request
+pub const MAX_HISTOGRAM_TX_RATE_UPDATES: u32 = 100;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RPCFeeRateHistogramBucket {
+    /// The lowest fee rate in this bucket
+    pub fee_rate: f64,
+    /// Number of transactions in this bucket, over all recent blocks
+    pub block_tx_count: u64,
+    /// Total scalar cost of this bucket, over all recent blocks.  Includes the unused space in
+    /// blocks which were not full, which counts as paying the minimum fee rate.
+    pub block_weight: u64,
+    /// Number of mempool transactions in this bucket
+    pub mempool_tx_count: u64,
+    /// Total estimated scalar cost of the mempool transactions in this bucket
+    pub mempool_weight: u64,
+    /// Fraction of recent blocks which included transactions paying this bucket's fee rate or
+    /// less.  `None` if no recent blocks are known.
+    pub inclusion_probability: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RPCFeeRateHistogramResponse {
+    /// Number of recent blocks summarized
+    pub num_blocks: u64,
+    /// Number of mempool transactions whose fee rates have not been estimated yet, and so are
+    /// not counted in the histogram
+    pub mempool_unestimated_tx_count: u64,
+    pub buckets: Vec<RPCFeeRateHistogramBucket>,
+}
+
+impl RPCFeeRateHistogramResponse {
+    pub fn new(
+        block_histograms: &[FeeRateHistogram],
+        mempool_histogram: &FeeRateHistogram,
+        mempool_unestimated_tx_count: u64,
+    ) -> Self {
+        let mut blocks_histogram = FeeRateHistogram::default();
+        for block_histogram in block_histograms.iter() {
+            blocks_histogram.merge(block_histogram);
+        }
+        let buckets = blocks_histogram
+            .buckets
+            .iter()
+            .zip(mempool_histogram.buckets.iter())
+            .map(|(block_bucket, mempool_bucket)| RPCFeeRateHistogramBucket {
+                fee_rate: block_bucket.fee_rate,
+                block_tx_count: block_bucket.count,
+                block_weight: block_bucket.weight,
+                mempool_tx_count: mempool_bucket.count,
+                mempool_weight: mempool_bucket.weight,
+                inclusion_probability: FeeRateHistogram::inclusion_probability(
+                    block_histograms,
+                    block_bucket.fee_rate,
+                ),
+            })
+            .collect();
+        Self {
+            num_blocks: block_histograms.len() as u64,
+            mempool_unestimated_tx_count,
+            buckets,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RPCGetFeeHistogramRequestHandler {}
+
+impl RPCGetFeeHistogramRequestHandler {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCGetFeeHistogramRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(r#"^/v3/fees/histogram$"#).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v3/fees/histogram"
+    }
+
+    /// Try to decode this request.
+    /// There's nothing to load here, so just make sure the request is well-formed.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        _captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+impl RPCRequestHandler for RPCGetFeeHistogramRequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {}
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        _contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let data_resp = node.with_node_state(|_network, sortdb, _chainstate, mempool, rpc_args| {
+            let tip = self.get_canonical_burn_chain_tip(&preamble, sortdb)?;
+            let stacks_epoch = self.get_stacks_epoch(&preamble, sortdb, tip.block_height)?;
+
+            let Some((_, fee_estimator, _)) = rpc_args.get_estimators_ref() else {
+                debug!("Fee and cost estimation not configured on this stacks node");
+                return Err(StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpBadRequest::new("Fee estimation not supported on this node".to_string()),
+                ));
+            };
+
+            let block_histograms = match fee_estimator.get_block_histograms() {
+                Ok(histograms) => histograms,
+                Err(EstimatorError::NoEstimateAvailable) => vec![],
+                Err(e) => {
+                    return Err(StacksHttpResponse::new_error(
+                        &preamble,
+                        &HttpServerError::new(format!(
+                            "Failed to load fee rate histograms: {:?}",
+                            &e
+                        )),
+                    ));
+                }
+            };
+
+            // miners keep the mempool's fee rate estimates up to date, but other nodes may not
+            if let Err(e) = mempool.estimate_tx_rates(
+                MAX_HISTOGRAM_TX_RATE_UPDATES,
+                &stacks_epoch.block_limit,
+                &stacks_epoch.epoch_id,
+            ) {
+                warn!("Failed to estimate mempool transaction fee rates"; "error" => ?e);
+            }
+
+            let (mempool_histogram, mempool_unestimated_tx_count) =
+                MemPoolDB::get_fee_rate_histogram(mempool.conn()).map_err(|e| {
+                    StacksHttpResponse::new_error(
+                        &preamble,
+                        &HttpServerError::new(format!(
+                            "Failed to load mempool fee rates: {:?}",
+                            &e
+                        )),
+                    )
+                })?;
+
+            Ok(RPCFeeRateHistogramResponse::new(
+                &block_histograms,
+                &mempool_histogram,
+                mempool_unestimated_tx_count,
+            ))
+        });
+
+        let data_resp = match data_resp {
+            Ok(data) => data,
+            Err(response) => {
+                return response.try_into_contents().map_err(NetError::from);
+            }
+        };
+
+        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+        let body = HttpResponseContents::try_from_json(&data_resp)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCGetFeeHistogramRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let histogram: RPCFeeRateHistogramResponse = parse_json(preamble, body)?;
+        Ok(HttpResponsePayload::try_from_json(histogram)?)
+    }
+}
+
+impl StacksHttpRequest {
+    pub fn new_get_fee_histogram(host: PeerHost) -> StacksHttpRequest {
+        StacksHttpRequest::new_for_peer(
+            host,
+            "GET".into(),
+            "/v3/fees/histogram".into(),
+            HttpRequestContents::new(),
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_fee_histogram(self) -> Result<RPCFeeRateHistogramResponse, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let response_json: serde_json::Value = contents.try_into()?;
+        let histogram: RPCFeeRateHistogramResponse = serde_json::from_value(response_json)
+            .map_err(|_e| Error::DecodeError("Failed to decode JSON".to_string()))?;
+        Ok(histogram)
+    }
+}
@@ -0,0 +1,274 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use clarity::vm::ast::parser::v1::CLARITY_NAME_REGEX;
+use clarity::vm::clarity::ClarityConnection;
+use clarity::vm::database::{ClarityDatabase, ClaritySerializable, StoreType};
+use clarity::vm::representations::{CONTRACT_PRINCIPAL_REGEX_STRING, PRINCIPAL_DATA_REGEX_STRING};
+use clarity::vm::types::{PrincipalData, QualifiedContractIdentifier};
+use clarity::vm::ClarityName;
+use regex::{Captures, Regex};
+use stacks_common::types::net::PeerHost;
+use stacks_common::util::hash::to_hex;
+
+use crate::net::http::{
+    parse_json, Error, HttpNotFound, HttpRequest, HttpRequestContents, HttpRequestPreamble,
+    HttpResponse, HttpResponseContents, HttpResponsePayload, HttpResponsePreamble,
+};
+use crate::net::httpcore::{
+    request, HttpPreambleExtensions, HttpRequestContentsExtensions, RPCRequestHandler,
+    StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState, TipRequest};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FungibleTokenBalanceResponse {
+    /// Hex encoding of the balance as a big-endian unsigned 128-bit integer
+    pub balance: String,
+    #[serde(rename = "proof")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub marf_proof: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct RPCGetFungibleTokenBalanceRequestHandler {
+    pub contract_identifier: Option<QualifiedContractIdentifier>,
+    pub token_name: Option<ClarityName>,
+    pub principal: Option<PrincipalData>,
+}
+impl RPCGetFungibleTokenBalanceRequestHandler {
+    pub fn new() -> Self {
+        Self {
+            contract_identifier: None,
+            token_name: None,
+            principal: None,
+        }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCGetFungibleTokenBalanceRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(&format!(
+            "^/v3/ft/(?P<contract>{})/(?P<token>{})/(?P<principal>{})$",
+            *CONTRACT_PRINCIPAL_REGEX_STRING, *CLARITY_NAME_REGEX, *PRINCIPAL_DATA_REGEX_STRING
+        ))
+        .unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v3/ft/:contract/:token_name/:principal"
+    }
+
+    /// Try to decode this request.
+    /// There's nothing to load here, so just make sure the request is well-formed.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+
+        let contract_identifier = if let Some(value) = captures.name("contract") {
+            QualifiedContractIdentifier::parse(value.as_str())
+                .map_err(|_e| Error::DecodeError("Failed to parse `contract` field".to_string()))?
+        } else {
+            return Err(Error::DecodeError(
+                "Missing in request path: `contract`".into(),
+            ));
+        };
+        let token_name = request::get_clarity_name(captures, "token")?;
+        let principal = if let Some(value) = captures.name("principal") {
+            PrincipalData::parse(value.as_str())
+                .map_err(|_e| Error::DecodeError("Failed to parse `principal` field".to_string()))?
+        } else {
+            return Err(Error::DecodeError(
+                "Missing in request path: `principal`".into(),
+            ));
+        };
+
+        self.contract_identifier = Some(contract_identifier);
+        self.token_name = Some(token_name);
+        self.principal = Some(principal);
+
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+/// Handle the HTTP request
+impl RPCRequestHandler for RPCGetFungibleTokenBalanceRequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.contract_identifier = None;
+        self.token_name = None;
+        self.principal = None;
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let contract_identifier = self
+            .contract_identifier
+            .take()
+            .ok_or(NetError::SendError("`contract_identifier` not set".into()))?;
+        let token_name = self
+            .token_name
+            .take()
+            .ok_or(NetError::SendError("`token_name` not set".into()))?;
+        let principal = self
+            .principal
+            .take()
+            .ok_or(NetError::SendError("`principal` not set".into()))?;
+
+        let tip = match node.load_stacks_chain_tip(&preamble, &contents) {
+            Ok(tip) => tip,
+            Err(error_resp) => {
+                return error_resp.try_into_contents().map_err(NetError::from);
+            }
+        };
+
+        let with_proof = contents.get_with_proof();
+        let key = ClarityDatabase::make_key_for_quad(
+            &contract_identifier,
+            StoreType::FungibleToken,
+            &token_name,
+            &principal.serialize(),
+        );
+
+        let balance_opt =
+            node.with_node_state(|_network, sortdb, chainstate, _mempool, _rpc_args| {
+                chainstate.maybe_read_only_clarity_tx(
+                    &sortdb.index_handle_at_block(chainstate, &tip)?,
+                    &tip,
+                    |clarity_tx| {
+                        clarity_tx.with_clarity_db_readonly(|clarity_db| {
+                            // the token must exist, but the principal need not have ever held it
+                            clarity_db.load_ft(&contract_identifier, &token_name).ok()?;
+
+                            let (balance, marf_proof): (u128, _) = if with_proof {
+                                clarity_db
+                                    .get_data_with_proof(&key)
+                                    .ok()
+                                    .flatten()
+                                    .map(|(a, b)| (a, Some(format!("0x{}", to_hex(&b)))))
+                                    .unwrap_or_else(|| (0, Some("".into())))
+                            } else {
+                                clarity_db
+                                    .get_data(&key)
+                                    .ok()
+                                    .flatten()
+                                    .map(|a| (a, None))
+                                    .unwrap_or((0, None))
+                            };
+
+                            let balance = format!("0x{}", to_hex(&balance.to_be_bytes()));
+                            Some(FungibleTokenBalanceResponse {
+                                balance,
+                                marf_proof,
+                            })
+                        })
+                    },
+                )
+            });
+
+        let balance_resp = match balance_opt {
+            Ok(Some(Some(balance))) => balance,
+            Ok(Some(None)) => {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpNotFound::new("Fungible token not found".to_string()),
+                )
+                .try_into_contents()
+                .map_err(NetError::from);
+            }
+            Ok(None) | Err(_) => {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpNotFound::new("Chain tip not found".to_string()),
+                )
+                .try_into_contents()
+                .map_err(NetError::from);
+            }
+        };
+
+        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+        let body = HttpResponseContents::try_from_json(&balance_resp)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCGetFungibleTokenBalanceRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let balance: FungibleTokenBalanceResponse = parse_json(preamble, body)?;
+        Ok(HttpResponsePayload::try_from_json(balance)?)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request for a principal's balance of a fungible token
+    pub fn new_getftbalance(
+        host: PeerHost,
+        contract_identifier: &QualifiedContractIdentifier,
+        token_name: &ClarityName,
+        principal: &PrincipalData,
+        tip_req: TipRequest,
+        with_proof: bool,
+    ) -> StacksHttpRequest {
+        StacksHttpRequest::new_for_peer(
+            host,
+            "GET".into(),
+            format!(
+                "/v3/ft/{}/{}/{}",
+                contract_identifier, token_name, principal
+            ),
+            HttpRequestContents::new()
+                .for_tip(tip_req)
+                .query_arg("proof".into(), if with_proof { "1" } else { "0" }.into()),
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_ft_balance_response(self) -> Result<FungibleTokenBalanceResponse, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let resp: FungibleTokenBalanceResponse = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(resp)
+    }
+}
@@ -0,0 +1,256 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use clarity::vm::clarity::ClarityConnection;
+use clarity::vm::representations::STANDARD_PRINCIPAL_REGEX_STRING;
+use clarity::vm::types::StacksAddressExtensions;
+use regex::{Captures, Regex};
+use stacks_common::types::chainstate::StacksAddress;
+use stacks_common::types::net::PeerHost;
+use stacks_common::types::Address;
+
+use crate::core::mempool::MemPoolDB;
+use crate::net::http::{
+    parse_json, Error, HttpNotFound, HttpRequest, HttpRequestContents, HttpRequestPreamble,
+    HttpResponse, HttpResponseContents, HttpResponsePayload, HttpResponsePreamble, HttpServerError,
+};
+use crate::net::httpcore::{
+    HttpPreambleExtensions, HttpRequestContentsExtensions, RPCRequestHandler, StacksHttpRequest,
+    StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState, TipRequest};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MempoolNoncesResponse {
+    pub principal: String,
+    /// The account's nonce as of the chain tip
+    pub confirmed_nonce: u64,
+    /// Nonces of the account's transactions in the mempool, lowest first
+    pub pending_nonces: Vec<u64>,
+    /// Nonce to use for the account's next transaction, accounting for the run of pending
+    /// transactions which starts at the confirmed nonce
+    pub next_nonce: u64,
+    /// Nonces between the confirmed nonce and the highest pending nonce which have no pending
+    /// transaction.  Pending transactions above the first missing nonce cannot be mined until
+    /// it is filled.
+    pub missing_nonces: Vec<u64>,
+}
+
+impl MempoolNoncesResponse {
+    /// Summarize an account's pending nonces, given its confirmed nonce
+    pub fn new(principal: &StacksAddress, confirmed_nonce: u64, pending_nonces: Vec<u64>) -> Self {
+        let mut next_nonce = confirmed_nonce;
+        let mut missing_nonces = vec![];
+        let mut expected_nonce = confirmed_nonce;
+        for nonce in pending_nonces.iter().copied() {
+            if nonce < expected_nonce {
+                // already mined, but not yet garbage-collected
+                continue;
+            }
+            if nonce == next_nonce {
+                // still in the unbroken run which starts at the confirmed nonce
+                next_nonce += 1;
+            }
+            missing_nonces.extend(expected_nonce..nonce);
+            expected_nonce = nonce + 1;
+        }
+        Self {
+            principal: principal.to_string(),
+            confirmed_nonce,
+            pending_nonces,
+            next_nonce,
+            missing_nonces,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RPCGetMempoolNoncesRequestHandler {
+    pub address: Option<StacksAddress>,
+}
+
+impl RPCGetMempoolNoncesRequestHandler {
+    pub fn new() -> Self {
+        Self { address: None }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCGetMempoolNoncesRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(&format!(
+            "^/v3/mempool/accounts/(?P<principal>{})/nonces$",
+            *STANDARD_PRINCIPAL_REGEX_STRING
+        ))
+        .unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v3/mempool/accounts/:principal/nonces"
+    }
+
+    /// Try to decode this request.
+    /// There's nothing to load here, so just make sure the request is well-formed.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+
+        let address = if let Some(value) = captures.name("principal") {
+            StacksAddress::from_string(value.as_str())
+                .ok_or_else(|| Error::DecodeError("Failed to parse `principal` field".into()))?
+        } else {
+            return Err(Error::DecodeError(
+                "Missing in request path: `principal`".into(),
+            ));
+        };
+
+        self.address = Some(address);
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+/// Handle the HTTP request
+impl RPCRequestHandler for RPCGetMempoolNoncesRequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.address = None;
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let address = self
+            .address
+            .take()
+            .ok_or(NetError::SendError("`address` not set".into()))?;
+
+        let tip = match node.load_stacks_chain_tip(&preamble, &contents) {
+            Ok(tip) => tip,
+            Err(error_resp) => {
+                return error_resp.try_into_contents().map_err(NetError::from);
+            }
+        };
+
+        let principal = address.to_account_principal();
+        let confirmed_nonce_opt =
+            node.with_node_state(|_network, sortdb, chainstate, _mempool, _rpc_args| {
+                chainstate.maybe_read_only_clarity_tx(
+                    &sortdb.index_handle_at_block(chainstate, &tip)?,
+                    &tip,
+                    |clarity_tx| {
+                        clarity_tx.with_clarity_db_readonly(|clarity_db| {
+                            clarity_db.get_account_nonce(&principal).ok()
+                        })
+                    },
+                )
+            });
+
+        let confirmed_nonce = match confirmed_nonce_opt {
+            Ok(Some(Some(nonce))) => nonce,
+            Ok(Some(None)) | Ok(None) | Err(_) => {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpNotFound::new("Chain tip not found".to_string()),
+                )
+                .try_into_contents()
+                .map_err(NetError::from);
+            }
+        };
+
+        let pending_nonces_res =
+            node.with_node_state(|_network, _sortdb, _chainstate, mempool, _rpc_args| {
+                MemPoolDB::get_pending_nonces(mempool.conn(), &address)
+            });
+
+        let pending_nonces = match pending_nonces_res {
+            Ok(pending_nonces) => pending_nonces,
+            Err(e) => {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpServerError::new(format!(
+                        "Failed to load pending nonces for {}: {:?}",
+                        &address, &e
+                    )),
+                )
+                .try_into_contents()
+                .map_err(NetError::from);
+            }
+        };
+
+        let nonces_resp = MempoolNoncesResponse::new(&address, confirmed_nonce, pending_nonces);
+
+        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+        let body = HttpResponseContents::try_from_json(&nonces_resp)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCGetMempoolNoncesRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let nonces: MempoolNoncesResponse = parse_json(preamble, body)?;
+        Ok(HttpResponsePayload::try_from_json(nonces)?)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request for an account's confirmed and pending nonces
+    pub fn new_getmempoolnonces(
+        host: PeerHost,
+        address: &StacksAddress,
+        tip_req: TipRequest,
+    ) -> StacksHttpRequest {
+        StacksHttpRequest::new_for_peer(
+            host,
+            "GET".into(),
+            format!("/v3/mempool/accounts/{}/nonces", address),
+            HttpRequestContents::new().for_tip(tip_req),
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_mempool_nonces_response(self) -> Result<MempoolNoncesResponse, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let resp: MempoolNoncesResponse = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(resp)
+    }
+}
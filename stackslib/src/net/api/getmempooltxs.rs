@@ -0,0 +1,322 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use clarity::vm::types::QualifiedContractIdentifier;
+use regex::{Captures, Regex};
+use stacks_common::codec::StacksMessageCodec;
+use stacks_common::types::chainstate::StacksAddress;
+use stacks_common::types::net::PeerHost;
+use stacks_common::types::Address;
+use stacks_common::util::hash::to_hex;
+
+use crate::burnchains::Txid;
+use crate::core::mempool::{MemPoolDB, MemPoolTxFilter, MemPoolTxInfo};
+use crate::net::http::{
+    parse_json, Error, HttpRequest, HttpRequestContents, HttpRequestPreamble, HttpResponse,
+    HttpResponseContents, HttpResponsePayload, HttpResponsePreamble, HttpServerError,
+};
+use crate::net::httpcore::{
+    HttpPreambleExtensions, RPCRequestHandler, StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState};
+
+/// Default number of mempool transactions to return in one page
+pub const DEFAULT_MEMPOOL_TXS_LIMIT: u32 = 50;
+/// Maximum number of mempool transactions to return in one page
+pub const MAX_MEMPOOL_TXS_LIMIT: u32 = 200;
+
+/// Which of the mempool's transactions to list
+#[derive(Debug, Clone, PartialEq)]
+pub struct MempoolTransactionsQuery {
+    pub filter: MemPoolTxFilter,
+    /// maximum number of transactions to return
+    pub limit: u32,
+    /// number of matching transactions to skip
+    pub offset: u64,
+}
+
+impl Default for MempoolTransactionsQuery {
+    fn default() -> Self {
+        Self {
+            filter: MemPoolTxFilter::All,
+            limit: DEFAULT_MEMPOOL_TXS_LIMIT,
+            offset: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MempoolTransaction {
+    pub txid: Txid,
+    /// Hex-encoded transaction
+    pub tx: String,
+    pub origin_address: String,
+    pub origin_nonce: u64,
+    pub sponsor_address: String,
+    pub sponsor_nonce: u64,
+    pub tx_fee: u64,
+    pub length: u64,
+    /// Fee paid per byte
+    pub fee_rate: f64,
+    /// Time at which this node accepted the transaction, in seconds since the epoch
+    pub accept_time: u64,
+}
+
+impl From<MemPoolTxInfo> for MempoolTransaction {
+    fn from(tx_info: MemPoolTxInfo) -> Self {
+        let metadata = tx_info.metadata;
+        Self {
+            txid: metadata.txid,
+            tx: to_hex(&tx_info.tx.serialize_to_vec()),
+            origin_address: metadata.origin_address.to_string(),
+            origin_nonce: metadata.origin_nonce,
+            sponsor_address: metadata.sponsor_address.to_string(),
+            sponsor_nonce: metadata.sponsor_nonce,
+            tx_fee: metadata.tx_fee,
+            length: metadata.len,
+            fee_rate: metadata.tx_fee as f64 / metadata.len.max(1) as f64,
+            accept_time: metadata.accept_time,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MempoolTransactionsResponse {
+    pub transactions: Vec<MempoolTransaction>,
+    /// Offset of the next page of transactions, if there are more
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_offset: Option<u64>,
+}
+
+#[derive(Clone)]
+pub struct RPCGetMempoolTransactionsRequestHandler {
+    pub query: Option<MempoolTransactionsQuery>,
+}
+
+impl RPCGetMempoolTransactionsRequestHandler {
+    pub fn new() -> Self {
+        Self { query: None }
+    }
+
+    fn parse_query_arg<T: std::str::FromStr>(
+        contents: &HttpRequestContents,
+        key: &str,
+    ) -> Result<Option<T>, Error> {
+        contents
+            .get_query_arg(key)
+            .map(|value| value.parse::<T>())
+            .transpose()
+            .map_err(|_| Error::DecodeError(format!("Failed to parse {}= query parameter", key)))
+    }
+
+    /// Decode the filter from the `origin=`, `sponsor=` or `contract=` query parameter.
+    /// At most one may be given.
+    fn parse_filter(contents: &HttpRequestContents) -> Result<MemPoolTxFilter, Error> {
+        let origin = contents.get_query_arg("origin");
+        let sponsor = contents.get_query_arg("sponsor");
+        let contract = contents.get_query_arg("contract");
+        match (origin, sponsor, contract) {
+            (None, None, None) => Ok(MemPoolTxFilter::All),
+            (Some(addr), None, None) => StacksAddress::from_string(addr)
+                .map(MemPoolTxFilter::Origin)
+                .ok_or_else(|| Error::DecodeError("Failed to parse origin= address".into())),
+            (None, Some(addr), None) => StacksAddress::from_string(addr)
+                .map(MemPoolTxFilter::Sponsor)
+                .ok_or_else(|| Error::DecodeError("Failed to parse sponsor= address".into())),
+            (None, None, Some(contract_id)) => QualifiedContractIdentifier::parse(contract_id)
+                .map(MemPoolTxFilter::ContractCall)
+                .map_err(|_| Error::DecodeError("Failed to parse contract= identifier".into())),
+            _ => Err(Error::DecodeError(
+                "At most one of origin=, sponsor= and contract= may be given".into(),
+            )),
+        }
+    }
+
+    /// Load a page of mempool transactions
+    fn get_mempool_transactions(
+        mempool: &MemPoolDB,
+        query: &MempoolTransactionsQuery,
+    ) -> Result<MempoolTransactionsResponse, NetError> {
+        // load one extra transaction to learn whether or not there is another page
+        let mut txs = MemPoolDB::get_txs_page(
+            mempool.conn(),
+            &query.filter,
+            query.offset,
+            u64::from(query.limit) + 1,
+        )?;
+        let next_offset = if txs.len() > query.limit as usize {
+            txs.truncate(query.limit as usize);
+            Some(query.offset + u64::from(query.limit))
+        } else {
+            None
+        };
+        Ok(MempoolTransactionsResponse {
+            transactions: txs.into_iter().map(MempoolTransaction::from).collect(),
+            next_offset,
+        })
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCGetMempoolTransactionsRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(r#"^/v3/mempool/transactions$"#).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v3/mempool/transactions"
+    }
+
+    /// Try to decode this request.
+    /// There's nothing to load here, so just make sure the request is well-formed.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        _captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+
+        let contents = HttpRequestContents::new().query_string(query);
+
+        let limit =
+            Self::parse_query_arg::<u32>(&contents, "limit")?.unwrap_or(DEFAULT_MEMPOOL_TXS_LIMIT);
+        if limit > MAX_MEMPOOL_TXS_LIMIT {
+            return Err(Error::DecodeError(format!(
+                "Invalid limit= query parameter: must be at most {}",
+                MAX_MEMPOOL_TXS_LIMIT
+            )));
+        }
+
+        self.query = Some(MempoolTransactionsQuery {
+            filter: Self::parse_filter(&contents)?,
+            limit,
+            offset: Self::parse_query_arg(&contents, "offset")?.unwrap_or(0),
+        });
+
+        Ok(contents)
+    }
+}
+
+/// Handle the HTTP request
+impl RPCRequestHandler for RPCGetMempoolTransactionsRequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.query = None;
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        _contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let query = self
+            .query
+            .take()
+            .ok_or(NetError::SendError("`query` not set".into()))?;
+
+        let data_resp =
+            node.with_node_state(|_network, _sortdb, _chainstate, mempool, _rpc_args| {
+                Self::get_mempool_transactions(mempool, &query)
+            });
+
+        let data_resp = match data_resp {
+            Ok(data) => data,
+            Err(e) => {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpServerError::new(format!("Failed to query mempool: {:?}", &e)),
+                )
+                .try_into_contents()
+                .map_err(NetError::from);
+            }
+        };
+
+        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+        let body = HttpResponseContents::try_from_json(&data_resp)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCGetMempoolTransactionsRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let txs: MempoolTransactionsResponse = parse_json(preamble, body)?;
+        Ok(HttpResponsePayload::try_from_json(txs)?)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request for a page of the mempool's transactions
+    pub fn new_getmempooltxs(
+        host: PeerHost,
+        query: &MempoolTransactionsQuery,
+    ) -> StacksHttpRequest {
+        let mut contents = HttpRequestContents::new();
+        match &query.filter {
+            MemPoolTxFilter::All => {}
+            MemPoolTxFilter::Origin(addr) => {
+                contents = contents.query_arg("origin".into(), addr.to_string());
+            }
+            MemPoolTxFilter::Sponsor(addr) => {
+                contents = contents.query_arg("sponsor".into(), addr.to_string());
+            }
+            MemPoolTxFilter::ContractCall(contract_id) => {
+                contents = contents.query_arg("contract".into(), contract_id.to_string());
+            }
+        }
+        contents = contents
+            .query_arg("limit".into(), query.limit.to_string())
+            .query_arg("offset".into(), query.offset.to_string());
+
+        StacksHttpRequest::new_for_peer(
+            host,
+            "GET".into(),
+            "/v3/mempool/transactions".into(),
+            contents,
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_mempool_transactions_response(
+        self,
+    ) -> Result<MempoolTransactionsResponse, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let resp: MempoolTransactionsResponse = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(resp)
+    }
+}
@@ -0,0 +1,304 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use clarity::vm::clarity::ClarityConnection;
+use clarity::vm::types::StacksAddressExtensions;
+use regex::{Captures, Regex};
+use stacks_common::types::net::PeerHost;
+
+use crate::burnchains::Txid;
+use crate::core::mempool::{MemPoolDB, MemPoolTxMetadata, MemPoolTxNonceStatus};
+use crate::net::http::{
+    parse_json, Error, HttpNotFound, HttpRequest, HttpRequestContents, HttpRequestPreamble,
+    HttpResponse, HttpResponseContents, HttpResponsePayload, HttpResponsePreamble, HttpServerError,
+};
+use crate::net::httpcore::{
+    request, HttpPreambleExtensions, HttpRequestContentsExtensions, RPCRequestHandler,
+    StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState, TipRequest};
+use crate::util_lib::db::Error as db_error;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MempoolTxStatusResponse {
+    pub txid: Txid,
+    pub origin_address: String,
+    pub origin_nonce: u64,
+    /// The origin account's nonce as of the chain tip
+    pub confirmed_origin_nonce: u64,
+    pub sponsor_address: String,
+    pub sponsor_nonce: u64,
+    /// The sponsor account's nonce as of the chain tip
+    pub confirmed_sponsor_nonce: u64,
+    /// One of `ready`, `queued`, `blocked` or `stale`
+    pub status: String,
+    /// If `blocked`, the account whose nonce sequence has a gap
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blocked_by_address: Option<String>,
+    /// If `blocked`, the nonce which no pending transaction uses
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub missing_nonce: Option<u64>,
+    /// Highest fee per byte of the transaction together with the origin's pending transactions
+    /// at the next sequential nonces
+    pub package_fee_rate: f64,
+}
+
+impl MempoolTxStatusResponse {
+    pub fn new(
+        metadata: &MemPoolTxMetadata,
+        confirmed_origin_nonce: u64,
+        confirmed_sponsor_nonce: u64,
+        status: MemPoolTxNonceStatus,
+        package_fee_rate: f64,
+    ) -> Self {
+        let (status, blocked_by_address, missing_nonce) = match status {
+            MemPoolTxNonceStatus::Ready => ("ready", None, None),
+            MemPoolTxNonceStatus::Queued => ("queued", None, None),
+            MemPoolTxNonceStatus::Blocked {
+                address,
+                missing_nonce,
+            } => ("blocked", Some(address.to_string()), Some(missing_nonce)),
+            MemPoolTxNonceStatus::Stale { .. } => ("stale", None, None),
+        };
+        Self {
+            txid: metadata.txid.clone(),
+            origin_address: metadata.origin_address.to_string(),
+            origin_nonce: metadata.origin_nonce,
+            confirmed_origin_nonce,
+            sponsor_address: metadata.sponsor_address.to_string(),
+            sponsor_nonce: metadata.sponsor_nonce,
+            confirmed_sponsor_nonce,
+            status: status.to_string(),
+            blocked_by_address,
+            missing_nonce,
+            package_fee_rate,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RPCGetMempoolTxStatusRequestHandler {
+    pub txid: Option<Txid>,
+}
+
+impl RPCGetMempoolTxStatusRequestHandler {
+    pub fn new() -> Self {
+        Self { txid: None }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCGetMempoolTxStatusRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(r#"^/v3/mempool/transactions/(?P<txid>[0-9a-f]{64})/status$"#).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v3/mempool/transactions/:txid/status"
+    }
+
+    /// Try to decode this request.
+    /// There's nothing to load here, so just make sure the request is well-formed.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+
+        let txid = request::get_txid(captures, "txid")?;
+        self.txid = Some(txid);
+
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+/// Handle the HTTP request
+impl RPCRequestHandler for RPCGetMempoolTxStatusRequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.txid = None;
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let txid = self
+            .txid
+            .take()
+            .ok_or(NetError::SendError("`txid` not set".into()))?;
+
+        let tip = match node.load_stacks_chain_tip(&preamble, &contents) {
+            Ok(tip) => tip,
+            Err(error_resp) => {
+                return error_resp.try_into_contents().map_err(NetError::from);
+            }
+        };
+
+        let metadata =
+            match node.with_node_state(|_network, _sortdb, _chainstate, mempool, _rpc_args| {
+                MemPoolDB::get_tx(mempool.conn(), &txid)
+            }) {
+                Ok(Some(tx_info)) => tx_info.metadata,
+                Ok(None) => {
+                    return StacksHttpResponse::new_error(
+                        &preamble,
+                        &HttpNotFound::new(format!("Transaction {} not found in mempool", &txid)),
+                    )
+                    .try_into_contents()
+                    .map_err(NetError::from);
+                }
+                Err(e) => {
+                    return StacksHttpResponse::new_error(
+                        &preamble,
+                        &HttpServerError::new(format!("Failed to load {}: {:?}", &txid, &e)),
+                    )
+                    .try_into_contents()
+                    .map_err(NetError::from);
+                }
+            };
+
+        let origin = metadata.origin_address.to_account_principal();
+        let sponsor = metadata.sponsor_address.to_account_principal();
+        let confirmed_nonces_opt =
+            node.with_node_state(|_network, sortdb, chainstate, _mempool, _rpc_args| {
+                chainstate.maybe_read_only_clarity_tx(
+                    &sortdb.index_handle_at_block(chainstate, &tip)?,
+                    &tip,
+                    |clarity_tx| {
+                        clarity_tx.with_clarity_db_readonly(|clarity_db| {
+                            let origin_nonce = clarity_db.get_account_nonce(&origin).ok()?;
+                            let sponsor_nonce = clarity_db.get_account_nonce(&sponsor).ok()?;
+                            Some((origin_nonce, sponsor_nonce))
+                        })
+                    },
+                )
+            });
+
+        let (confirmed_origin_nonce, confirmed_sponsor_nonce) = match confirmed_nonces_opt {
+            Ok(Some(Some(nonces))) => nonces,
+            Ok(Some(None)) | Ok(None) | Err(_) => {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpNotFound::new("Chain tip not found".to_string()),
+                )
+                .try_into_contents()
+                .map_err(NetError::from);
+            }
+        };
+
+        let status_res =
+            node.with_node_state(|_network, _sortdb, _chainstate, mempool, _rpc_args| {
+                let status = MemPoolDB::get_tx_nonce_status(
+                    mempool.conn(),
+                    &metadata,
+                    confirmed_origin_nonce,
+                    confirmed_sponsor_nonce,
+                )?;
+                let package_fee_rate = MemPoolDB::get_package_fee_rate(
+                    mempool.conn(),
+                    &metadata.origin_address,
+                    metadata.origin_nonce,
+                    metadata.tx_fee,
+                    metadata.len,
+                )?;
+                Ok::<_, db_error>((status, package_fee_rate))
+            });
+
+        let (status, package_fee_rate) = match status_res {
+            Ok(status) => status,
+            Err(e) => {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpServerError::new(format!(
+                        "Failed to load nonce status of {}: {:?}",
+                        &txid, &e
+                    )),
+                )
+                .try_into_contents()
+                .map_err(NetError::from);
+            }
+        };
+
+        let status_resp = MempoolTxStatusResponse::new(
+            &metadata,
+            confirmed_origin_nonce,
+            confirmed_sponsor_nonce,
+            status,
+            package_fee_rate,
+        );
+
+        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+        let body = HttpResponseContents::try_from_json(&status_resp)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCGetMempoolTxStatusRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let status: MempoolTxStatusResponse = parse_json(preamble, body)?;
+        Ok(HttpResponsePayload::try_from_json(status)?)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request for whether a mempool transaction can be mined
+    pub fn new_getmempooltxstatus(
+        host: PeerHost,
+        txid: &Txid,
+        tip_req: TipRequest,
+    ) -> StacksHttpRequest {
+        StacksHttpRequest::new_for_peer(
+            host,
+            "GET".into(),
+            format!("/v3/mempool/transactions/{}/status", txid),
+            HttpRequestContents::new().for_tip(tip_req),
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_mempool_tx_status_response(self) -> Result<MempoolTxStatusResponse, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let resp: MempoolTxStatusResponse = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(resp)
+    }
+}
@@ -0,0 +1,304 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use clarity::vm::ast::parser::v1::CLARITY_NAME_REGEX;
+use clarity::vm::clarity::ClarityConnection;
+use clarity::vm::database::{ClarityDatabase, StoreType};
+use clarity::vm::representations::CONTRACT_PRINCIPAL_REGEX_STRING;
+use clarity::vm::types::{QualifiedContractIdentifier, BOUND_VALUE_SERIALIZATION_HEX};
+use clarity::vm::{ClarityName, Value};
+use regex::{Captures, Regex};
+use stacks_common::types::net::PeerHost;
+use stacks_common::util::hash::to_hex;
+
+use crate::net::http::{
+    parse_json, Error, HttpNotFound, HttpRequest, HttpRequestContents, HttpRequestPreamble,
+    HttpResponse, HttpResponseContents, HttpResponsePayload, HttpResponsePreamble,
+};
+use crate::net::httpcore::{
+    request, HttpPreambleExtensions, HttpRequestContentsExtensions, RPCRequestHandler,
+    StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState, TipRequest};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NonFungibleTokenOwnerResponse {
+    pub owner: String,
+    #[serde(rename = "proof")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub marf_proof: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct RPCGetNonFungibleTokenOwnerRequestHandler {
+    pub contract_identifier: Option<QualifiedContractIdentifier>,
+    pub token_name: Option<ClarityName>,
+    pub asset: Option<Value>,
+}
+impl RPCGetNonFungibleTokenOwnerRequestHandler {
+    pub fn new() -> Self {
+        Self {
+            contract_identifier: None,
+            token_name: None,
+            asset: None,
+        }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCGetNonFungibleTokenOwnerRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(&format!(
+            "^/v3/nft/(?P<contract>{})/(?P<token>{})/(?P<asset>(0x)?[0-9a-fA-F]+)$",
+            *CONTRACT_PRINCIPAL_REGEX_STRING, *CLARITY_NAME_REGEX
+        ))
+        .unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v3/nft/:contract/:token_name/:asset"
+    }
+
+    /// Try to decode this request.
+    /// The last path segment is the asset identifier, as a hex-encoded serialized Clarity value.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+
+        let contract_identifier = if let Some(value) = captures.name("contract") {
+            QualifiedContractIdentifier::parse(value.as_str())
+                .map_err(|_e| Error::DecodeError("Failed to parse `contract` field".to_string()))?
+        } else {
+            return Err(Error::DecodeError(
+                "Missing in request path: `contract`".into(),
+            ));
+        };
+        let token_name = request::get_clarity_name(captures, "token")?;
+        let asset = if let Some(value) = captures.name("asset") {
+            if value.as_str().len() >= BOUND_VALUE_SERIALIZATION_HEX as usize {
+                return Err(Error::DecodeError(
+                    "Invalid Http request: asset identifier is too long".to_string(),
+                ));
+            }
+            Value::try_deserialize_hex_untyped(value.as_str())
+                .map_err(|_e| Error::DecodeError("Failed to deserialize asset value".into()))?
+        } else {
+            return Err(Error::DecodeError(
+                "Missing in request path: `asset`".into(),
+            ));
+        };
+
+        self.contract_identifier = Some(contract_identifier);
+        self.token_name = Some(token_name);
+        self.asset = Some(asset);
+
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+/// Handle the HTTP request
+impl RPCRequestHandler for RPCGetNonFungibleTokenOwnerRequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.contract_identifier = None;
+        self.token_name = None;
+        self.asset = None;
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let contract_identifier = self
+            .contract_identifier
+            .take()
+            .ok_or(NetError::SendError("`contract_identifier` not set".into()))?;
+        let token_name = self
+            .token_name
+            .take()
+            .ok_or(NetError::SendError("`token_name` not set".into()))?;
+        let asset = self
+            .asset
+            .take()
+            .ok_or(NetError::SendError("`asset` not set".into()))?;
+
+        let tip = match node.load_stacks_chain_tip(&preamble, &contents) {
+            Ok(tip) => tip,
+            Err(error_resp) => {
+                return error_resp.try_into_contents().map_err(NetError::from);
+            }
+        };
+
+        let with_proof = contents.get_with_proof();
+        let key = ClarityDatabase::make_key_for_quad(
+            &contract_identifier,
+            StoreType::NonFungibleToken,
+            &token_name,
+            &asset
+                .serialize_to_hex()
+                .map_err(|e| NetError::SerializeError(format!("{:?}", &e)))?,
+        );
+
+        // Some(None) means the token exists, but this asset has no owner
+        let owner_opt =
+            node.with_node_state(|_network, sortdb, chainstate, _mempool, _rpc_args| {
+                chainstate.maybe_read_only_clarity_tx(
+                    &sortdb.index_handle_at_block(chainstate, &tip)?,
+                    &tip,
+                    |clarity_tx| {
+                        clarity_tx.with_clarity_db_readonly(|clarity_db| {
+                            let key_type = clarity_db
+                                .get_nft_key_type(&contract_identifier, &token_name)
+                                .ok()?;
+                            let epoch = clarity_db.get_clarity_epoch_version().ok()?;
+                            if !key_type.admits(&epoch, &asset).unwrap_or(false) {
+                                return Some(None);
+                            }
+
+                            let (value_hex, marf_proof): (String, _) = if with_proof {
+                                let Some((value_hex, proof)) =
+                                    clarity_db.get_data_with_proof(&key).ok().flatten()
+                                else {
+                                    return Some(None);
+                                };
+                                (value_hex, Some(format!("0x{}", to_hex(&proof))))
+                            } else {
+                                let Some(value_hex) = clarity_db.get_data(&key).ok().flatten()
+                                else {
+                                    return Some(None);
+                                };
+                                (value_hex, None)
+                            };
+
+                            // burnt assets are stored as `none`
+                            let owner = Value::try_deserialize_hex_untyped(&value_hex)
+                                .ok()?
+                                .expect_optional()
+                                .ok()?
+                                .map(|owner| owner.expect_principal())
+                                .transpose()
+                                .ok()?;
+                            Some(owner.map(|owner| NonFungibleTokenOwnerResponse {
+                                owner: owner.to_string(),
+                                marf_proof,
+                            }))
+                        })
+                    },
+                )
+            });
+
+        let owner_resp = match owner_opt {
+            Ok(Some(Some(Some(owner)))) => owner,
+            Ok(Some(Some(None))) => {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpNotFound::new("Asset not found".to_string()),
+                )
+                .try_into_contents()
+                .map_err(NetError::from);
+            }
+            Ok(Some(None)) => {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpNotFound::new("Non-fungible token not found".to_string()),
+                )
+                .try_into_contents()
+                .map_err(NetError::from);
+            }
+            Ok(None) | Err(_) => {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpNotFound::new("Chain tip not found".to_string()),
+                )
+                .try_into_contents()
+                .map_err(NetError::from);
+            }
+        };
+
+        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+        let body = HttpResponseContents::try_from_json(&owner_resp)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCGetNonFungibleTokenOwnerRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let owner: NonFungibleTokenOwnerResponse = parse_json(preamble, body)?;
+        Ok(HttpResponsePayload::try_from_json(owner)?)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request for the owner of a non-fungible token asset
+    pub fn new_getnftowner(
+        host: PeerHost,
+        contract_identifier: &QualifiedContractIdentifier,
+        token_name: &ClarityName,
+        asset: &Value,
+        tip_req: TipRequest,
+        with_proof: bool,
+    ) -> StacksHttpRequest {
+        StacksHttpRequest::new_for_peer(
+            host,
+            "GET".into(),
+            format!(
+                "/v3/nft/{}/{}/{}",
+                contract_identifier,
+                token_name,
+                asset
+                    .serialize_to_hex()
+                    .expect("FATAL: invalid asset could not be serialized")
+            ),
+            HttpRequestContents::new()
+                .for_tip(tip_req)
+                .query_arg("proof".into(), if with_proof { "1" } else { "0" }.into()),
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_nft_owner_response(self) -> Result<NonFungibleTokenOwnerResponse, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let resp: NonFungibleTokenOwnerResponse = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(resp)
+    }
+}
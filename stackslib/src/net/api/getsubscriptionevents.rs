@@ -0,0 +1,308 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use regex::{Captures, Regex};
+use stacks_common::types::net::PeerHost;
+use stacks_common::util::get_epoch_time_secs;
+
+use crate::net::http::{
+    parse_json, Error, HttpChunkGenerator, HttpNotFound, HttpRequest, HttpRequestContents,
+    HttpRequestPreamble, HttpResponse, HttpResponseContents, HttpResponsePayload,
+    HttpResponsePreamble,
+};
+use crate::net::httpcore::{
+    HttpPreambleExtensions, RPCRequestHandler, StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::{Error as NetError, EventSubscriptionRegistry, StacksNodeState};
+
+/// Default number of events returned per poll
+pub const DEFAULT_SUBSCRIPTION_EVENTS_LIMIT: usize = 100;
+/// Maximum number of events returned per poll
+pub const MAX_SUBSCRIPTION_EVENTS_LIMIT: usize = 1000;
+/// Longest time, in seconds, that a poll waits for events to arrive.  This is the default, and
+/// stays below the HTTP server's request timeout so that waiting connections are not dropped.
+pub const MAX_SUBSCRIPTION_EVENTS_WAIT_SECS: u64 = 20;
+
+/// An event queued for a subscription
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubscriptionEvent {
+    /// Sequence number of this event within its subscription
+    pub seq: u64,
+    /// The event observer endpoint this event would have been sent to (e.g. `/new_block`)
+    pub path: String,
+    /// The event, encoded the same way as it is sent to event observers
+    pub payload: serde_json::Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubscriptionEventsResponse {
+    pub subscription_id: String,
+    pub events: Vec<SubscriptionEvent>,
+    /// Number of events that were discarded because the subscription's queue was full
+    pub dropped: u64,
+}
+
+/// Streams a subscription's events once there are some, or an empty page once its deadline
+/// passes.  This lets clients long-poll for events without blocking the peer thread, which asks
+/// `is_ready()` on each pass of the HTTP server.
+pub struct SubscriptionEventsStream {
+    event_subscriptions: Box<dyn EventSubscriptionRegistry + Send>,
+    subscription_id: String,
+    after: u64,
+    limit: usize,
+    /// When to stop waiting for events, in seconds since the epoch
+    deadline: u64,
+    sent: bool,
+}
+
+impl SubscriptionEventsStream {
+    pub fn new(
+        event_subscriptions: Box<dyn EventSubscriptionRegistry + Send>,
+        subscription_id: String,
+        after: u64,
+        limit: usize,
+        wait_secs: u64,
+    ) -> Self {
+        Self {
+            event_subscriptions,
+            subscription_id,
+            after,
+            limit,
+            deadline: get_epoch_time_secs().saturating_add(wait_secs),
+            sent: false,
+        }
+    }
+}
+
+impl HttpChunkGenerator for SubscriptionEventsStream {
+    fn hint_chunk_size(&self) -> usize {
+        4096
+    }
+
+    fn is_ready(&mut self) -> bool {
+        self.sent
+            || get_epoch_time_secs() >= self.deadline
+            || self
+                .event_subscriptions
+                .has_events(&self.subscription_id, self.after)
+    }
+
+    fn generate_next_chunk(&mut self) -> Result<Vec<u8>, String> {
+        if self.sent {
+            return Ok(vec![]);
+        }
+        self.sent = true;
+
+        // the subscription may have been cancelled while the client was waiting
+        let data = self
+            .event_subscriptions
+            .poll(&self.subscription_id, self.after, self.limit)
+            .unwrap_or_else(|| SubscriptionEventsResponse {
+                subscription_id: self.subscription_id.clone(),
+                events: vec![],
+                dropped: 0,
+            });
+        serde_json::to_vec(&data).map_err(|e| e.to_string())
+    }
+}
+
+#[derive(Clone)]
+pub struct RPCGetSubscriptionEventsRequestHandler {
+    pub subscription_id: Option<String>,
+}
+
+impl RPCGetSubscriptionEventsRequestHandler {
+    pub fn new() -> Self {
+        Self {
+            subscription_id: None,
+        }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCGetSubscriptionEventsRequestHandler {
+    fn verb(&self) -> &'static str {
+        "GET"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(r#"^/v3/events/subscriptions/(?P<subscription_id>[0-9a-f]{32})$"#).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v3/events/subscriptions/:subscription_id"
+    }
+
+    /// Try to decode this request.
+    /// There's nothing to load here, so just make sure the request is well-formed.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        captures: &Captures,
+        query: Option<&str>,
+        _body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() != 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected 0-length body".to_string(),
+            ));
+        }
+
+        let subscription_id = captures
+            .name("subscription_id")
+            .ok_or(Error::DecodeError(
+                "Failed to match path to subscription ID".to_string(),
+            ))?
+            .as_str()
+            .to_string();
+
+        self.subscription_id = Some(subscription_id);
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+impl RPCRequestHandler for RPCGetSubscriptionEventsRequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.subscription_id = None;
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let subscription_id = self
+            .subscription_id
+            .take()
+            .ok_or(NetError::SendError("`subscription_id` not set".into()))?;
+
+        let after = contents
+            .get_query_arg("after")
+            .and_then(|after| after.parse::<u64>().ok())
+            .unwrap_or(0);
+        let limit = contents
+            .get_query_arg("limit")
+            .and_then(|limit| limit.parse::<usize>().ok())
+            .unwrap_or(DEFAULT_SUBSCRIPTION_EVENTS_LIMIT)
+            .min(MAX_SUBSCRIPTION_EVENTS_LIMIT);
+
+        let wait_secs = contents
+            .get_query_arg("wait")
+            .and_then(|wait| wait.parse::<u64>().ok())
+            .unwrap_or(MAX_SUBSCRIPTION_EVENTS_WAIT_SECS)
+            .min(MAX_SUBSCRIPTION_EVENTS_WAIT_SECS);
+
+        let data_resp =
+            node.with_node_state(|_network, _sortdb, _chainstate, _mempool, rpc_args| {
+                let Some(event_subscriptions) = rpc_args.event_subscriptions else {
+                    return Err(StacksHttpResponse::new_error(
+                        &preamble,
+                        &HttpNotFound::new("Event subscriptions are not enabled".to_string()),
+                    ));
+                };
+                let data = event_subscriptions
+                    .poll(&subscription_id, after, limit)
+                    .ok_or_else(|| {
+                        StacksHttpResponse::new_error(
+                            &preamble,
+                            &HttpNotFound::new("No such subscription".to_string()),
+                        )
+                    })?;
+                if data.events.is_empty() && wait_secs > 0 {
+                    // nothing to send yet, so hold the request open until there is
+                    Ok(Err(SubscriptionEventsStream::new(
+                        event_subscriptions.clone_registry(),
+                        subscription_id,
+                        after,
+                        limit,
+                        wait_secs,
+                    )))
+                } else {
+                    Ok(Ok(data))
+                }
+            });
+
+        let data_resp = match data_resp {
+            Ok(data) => data,
+            Err(response) => {
+                return response.try_into_contents().map_err(NetError::from);
+            }
+        };
+
+        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+        let body = match data_resp {
+            Ok(data) => HttpResponseContents::try_from_json(&data)?,
+            Err(stream) => HttpResponseContents::from_stream(Box::new(stream)),
+        };
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCGetSubscriptionEventsRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let events: SubscriptionEventsResponse = parse_json(preamble, body)?;
+        Ok(HttpResponsePayload::try_from_json(events)?)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request to fetch the events queued for a subscription.
+    /// Events with sequence numbers up to and including `after` are acknowledged and discarded.
+    /// If no events are queued, the node waits up to `wait_secs` seconds for some to arrive.
+    pub fn new_get_subscription_events(
+        host: PeerHost,
+        subscription_id: &str,
+        after: u64,
+        limit: Option<usize>,
+        wait_secs: Option<u64>,
+    ) -> StacksHttpRequest {
+        let mut contents = HttpRequestContents::new().query_arg("after".into(), after.to_string());
+        if let Some(limit) = limit {
+            contents = contents.query_arg("limit".into(), limit.to_string());
+        }
+        if let Some(wait_secs) = wait_secs {
+            contents = contents.query_arg("wait".into(), wait_secs.to_string());
+        }
+        StacksHttpRequest::new_for_peer(
+            host,
+            "GET".into(),
+            format!("/v3/events/subscriptions/{}", subscription_id),
+            contents,
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_subscription_events_response(
+        self,
+    ) -> Result<SubscriptionEventsResponse, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let response_json: serde_json::Value = contents.try_into()?;
+        let events: SubscriptionEventsResponse = serde_json::from_value(response_json)
+            .map_err(|_e| Error::DecodeError("Failed to decode JSON".to_string()))?;
+        Ok(events)
+    }
+}
@@ -36,6 +36,8 @@ use crate::net::Error as NetError;
 use crate::stacks_common::codec::StacksMessageCodec;
 
 pub mod callreadonly;
+pub mod callreadonly_batch;
+pub mod deletesubscription;
 pub mod get_tenures_fork_info;
 pub mod getaccount;
 pub mod getattachment;
@@ -47,16 +49,23 @@ pub mod getclaritymarfvalue;
 pub mod getclaritymetadata;
 pub mod getconstantval;
 pub mod getcontractabi;
+pub mod getcontractevents;
 pub mod getcontractsrc;
 pub mod getdatavar;
+pub mod getfeehistogram;
+pub mod getftbalance;
 pub mod getheaders;
 pub mod getinfo;
 pub mod getistraitimplemented;
 pub mod getmapentry;
+pub mod getmempoolnonces;
+pub mod getmempooltxs;
+pub mod getmempooltxstatus;
 pub mod getmicroblocks_confirmed;
 pub mod getmicroblocks_indexed;
 pub mod getmicroblocks_unconfirmed;
 pub mod getneighbors;
+pub mod getnftowner;
 pub mod getpoxinfo;
 pub mod getsigner;
 pub mod getsortition;
@@ -64,9 +73,11 @@ pub mod getstackerdbchunk;
 pub mod getstackerdbmetadata;
 pub mod getstackers;
 pub mod getstxtransfercost;
+pub mod getsubscriptionevents;
 pub mod gettenure;
 pub mod gettenureinfo;
 pub mod gettenuretip;
+pub mod gettransaction;
 pub mod gettransaction_unconfirmed;
 pub mod liststackerdbreplicas;
 pub mod postblock;
@@ -77,7 +88,9 @@ pub mod postfeerate;
 pub mod postmempoolquery;
 pub mod postmicroblock;
 pub mod poststackerdbchunk;
+pub mod postsubscription;
 pub mod posttransaction;
+pub mod posttransaction_simulate;
 
 #[cfg(test)]
 mod tests;
@@ -90,6 +103,11 @@ impl StacksHttp {
             self.maximum_call_argument_size,
             self.read_only_call_limit.clone(),
         ));
+        self.register_rpc_endpoint(callreadonly_batch::RPCCallReadOnlyBatchRequestHandler::new(
+            self.maximum_call_argument_size,
+            self.read_only_call_limit.clone(),
+        ));
+        self.register_rpc_endpoint(deletesubscription::RPCDeleteSubscriptionRequestHandler::new());
         self.register_rpc_endpoint(getaccount::RPCGetAccountRequestHandler::new());
         self.register_rpc_endpoint(getattachment::RPCGetAttachmentRequestHandler::new());
         self.register_rpc_endpoint(getattachmentsinv::RPCGetAttachmentsInvRequestHandler::new());
@@ -100,14 +118,20 @@ impl StacksHttp {
         self.register_rpc_endpoint(getclaritymetadata::RPCGetClarityMetadataRequestHandler::new());
         self.register_rpc_endpoint(getconstantval::RPCGetConstantValRequestHandler::new());
         self.register_rpc_endpoint(getcontractabi::RPCGetContractAbiRequestHandler::new());
+        self.register_rpc_endpoint(getcontractevents::RPCGetContractEventsRequestHandler::new());
         self.register_rpc_endpoint(getcontractsrc::RPCGetContractSrcRequestHandler::new());
         self.register_rpc_endpoint(getdatavar::RPCGetDataVarRequestHandler::new());
+        self.register_rpc_endpoint(getfeehistogram::RPCGetFeeHistogramRequestHandler::new());
+        self.register_rpc_endpoint(getftbalance::RPCGetFungibleTokenBalanceRequestHandler::new());
         self.register_rpc_endpoint(getheaders::RPCHeadersRequestHandler::new());
         self.register_rpc_endpoint(getinfo::RPCPeerInfoRequestHandler::new());
         self.register_rpc_endpoint(
             getistraitimplemented::RPCGetIsTraitImplementedRequestHandler::new(),
         );
         self.register_rpc_endpoint(getmapentry::RPCGetMapEntryRequestHandler::new());
+        self.register_rpc_endpoint(getmempoolnonces::RPCGetMempoolNoncesRequestHandler::new());
+        self.register_rpc_endpoint(getmempooltxs::RPCGetMempoolTransactionsRequestHandler::new());
+        self.register_rpc_endpoint(getmempooltxstatus::RPCGetMempoolTxStatusRequestHandler::new());
         self.register_rpc_endpoint(
             getmicroblocks_confirmed::RPCMicroblocksConfirmedRequestHandler::new(),
         );
@@ -118,6 +142,7 @@ impl StacksHttp {
             getmicroblocks_unconfirmed::RPCMicroblocksUnconfirmedRequestHandler::new(),
         );
         self.register_rpc_endpoint(getneighbors::RPCNeighborsRequestHandler::new());
+        self.register_rpc_endpoint(getnftowner::RPCGetNonFungibleTokenOwnerRequestHandler::new());
         self.register_rpc_endpoint(getstxtransfercost::RPCGetStxTransferCostRequestHandler::new());
         self.register_rpc_endpoint(getstackerdbchunk::RPCGetStackerDBChunkRequestHandler::new());
         self.register_rpc_endpoint(getpoxinfo::RPCPoxInfoRequestHandler::new());
@@ -126,10 +151,14 @@ impl StacksHttp {
         );
         self.register_rpc_endpoint(getstackers::GetStackersRequestHandler::default());
         self.register_rpc_endpoint(getsortition::GetSortitionHandler::new());
+        self.register_rpc_endpoint(
+            getsubscriptionevents::RPCGetSubscriptionEventsRequestHandler::new(),
+        );
         self.register_rpc_endpoint(gettenure::RPCNakamotoTenureRequestHandler::new());
         self.register_rpc_endpoint(gettenureinfo::RPCNakamotoTenureInfoRequestHandler::new());
         self.register_rpc_endpoint(gettenuretip::RPCNakamotoTenureTipRequestHandler::new());
         self.register_rpc_endpoint(get_tenures_fork_info::GetTenuresForkInfo::default());
+        self.register_rpc_endpoint(gettransaction::RPCGetTransactionRequestHandler::new());
         self.register_rpc_endpoint(
             gettransaction_unconfirmed::RPCGetTransactionUnconfirmedRequestHandler::new(),
         );
@@ -148,7 +177,11 @@ impl StacksHttp {
         self.register_rpc_endpoint(postmempoolquery::RPCMempoolQueryRequestHandler::new());
         self.register_rpc_endpoint(postmicroblock::RPCPostMicroblockRequestHandler::new());
         self.register_rpc_endpoint(poststackerdbchunk::RPCPostStackerDBChunkRequestHandler::new());
+        self.register_rpc_endpoint(postsubscription::RPCPostSubscriptionRequestHandler::new());
         self.register_rpc_endpoint(posttransaction::RPCPostTransactionRequestHandler::new());
+        self.register_rpc_endpoint(
+            posttransaction_simulate::RPCSimulateTransactionRequestHandler::new(),
+        );
     }
 }
 
@@ -0,0 +1,190 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use regex::{Captures, Regex};
+use stacks_common::codec::MAX_PAYLOAD_LEN;
+use stacks_common::types::net::PeerHost;
+
+use crate::net::http::{
+    parse_json, Error, HttpBadRequest, HttpContentType, HttpNotFound, HttpRequest,
+    HttpRequestContents, HttpRequestPreamble, HttpResponse, HttpResponseContents,
+    HttpResponsePayload, HttpResponsePreamble,
+};
+use crate::net::httpcore::{
+    HttpPreambleExtensions, RPCRequestHandler, StacksHttpRequest, StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubscriptionRequestBody {
+    /// Event keys, in the same format as an event observer's `events_keys`
+    pub events_keys: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubscriptionResponse {
+    pub subscription_id: String,
+    pub events_keys: Vec<String>,
+}
+
+#[derive(Clone)]
+pub struct RPCPostSubscriptionRequestHandler {
+    pub events_keys: Option<Vec<String>>,
+}
+
+impl RPCPostSubscriptionRequestHandler {
+    pub fn new() -> Self {
+        Self { events_keys: None }
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCPostSubscriptionRequestHandler {
+    fn verb(&self) -> &'static str {
+        "POST"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(r#"^/v3/events/subscriptions$"#).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v3/events/subscriptions"
+    }
+
+    /// Try to decode this request.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        _captures: &Captures,
+        query: Option<&str>,
+        body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        let content_len = preamble.get_content_length();
+        if !(content_len > 0 && content_len < MAX_PAYLOAD_LEN) {
+            return Err(Error::DecodeError(format!(
+                "Invalid Http request: invalid body length for Subscription ({})",
+                content_len
+            )));
+        }
+
+        if preamble.content_type != Some(HttpContentType::JSON) {
+            return Err(Error::DecodeError(
+                "Invalid content-type: expected application/json".to_string(),
+            ));
+        }
+
+        let body: SubscriptionRequestBody = serde_json::from_slice(body)
+            .map_err(|e| Error::DecodeError(format!("Failed to parse JSON body: {}", e)))?;
+
+        if body.events_keys.is_empty() {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected at least one event key".to_string(),
+            ));
+        }
+
+        self.events_keys = Some(body.events_keys);
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+impl RPCRequestHandler for RPCPostSubscriptionRequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.events_keys = None;
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        _contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let events_keys = self
+            .events_keys
+            .take()
+            .ok_or(NetError::SendError("`events_keys` not set".into()))?;
+
+        let data_resp =
+            node.with_node_state(|_network, _sortdb, _chainstate, _mempool, rpc_args| {
+                let Some(event_subscriptions) = rpc_args.event_subscriptions else {
+                    return Err(StacksHttpResponse::new_error(
+                        &preamble,
+                        &HttpNotFound::new("Event subscriptions are not enabled".to_string()),
+                    ));
+                };
+                let subscription_id =
+                    event_subscriptions.subscribe(&events_keys).map_err(|msg| {
+                        StacksHttpResponse::new_error(&preamble, &HttpBadRequest::new(msg))
+                    })?;
+                Ok(SubscriptionResponse {
+                    subscription_id,
+                    events_keys,
+                })
+            });
+
+        let data_resp = match data_resp {
+            Ok(data) => data,
+            Err(response) => {
+                return response.try_into_contents().map_err(NetError::from);
+            }
+        };
+
+        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+        let body = HttpResponseContents::try_from_json(&data_resp)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCPostSubscriptionRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let subscription: SubscriptionResponse = parse_json(preamble, body)?;
+        Ok(HttpResponsePayload::try_from_json(subscription)?)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request to subscribe to node events
+    pub fn new_post_subscription(host: PeerHost, events_keys: Vec<String>) -> StacksHttpRequest {
+        StacksHttpRequest::new_for_peer(
+            host,
+            "POST".into(),
+            "/v3/events/subscriptions".into(),
+            HttpRequestContents::new().payload_json(
+                serde_json::to_value(SubscriptionRequestBody { events_keys })
+                    .expect("FATAL: failed to encode subscription request to JSON"),
+            ),
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_subscription_response(self) -> Result<SubscriptionResponse, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let response_json: serde_json::Value = contents.try_into()?;
+        let subscription: SubscriptionResponse = serde_json::from_value(response_json)
+            .map_err(|_e| Error::DecodeError("Failed to decode JSON".to_string()))?;
+        Ok(subscription)
+    }
+}
@@ -0,0 +1,353 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use clarity::vm::clarity::ClarityConnection;
+use clarity::vm::costs::{ExecutionCost, LimitedCostTracker};
+use regex::{Captures, Regex};
+use stacks_common::codec::{Error as CodecError, StacksMessageCodec, MAX_PAYLOAD_LEN};
+use stacks_common::types::chainstate::StacksBlockId;
+use stacks_common::types::net::PeerHost;
+use stacks_common::util::hash::{hex_bytes, to_hex};
+
+use crate::burnchains::Txid;
+use crate::chainstate::burn::db::sortdb::SortitionDB;
+use crate::chainstate::stacks::db::StacksChainState;
+use crate::chainstate::stacks::StacksTransaction;
+use crate::net::http::{
+    parse_json, Error, HttpContentType, HttpNotFound, HttpRequest, HttpRequestContents,
+    HttpRequestPreamble, HttpResponse, HttpResponseContents, HttpResponsePayload,
+    HttpResponsePreamble, HttpServerError,
+};
+use crate::net::httpcore::{
+    HttpPreambleExtensions, HttpRequestContentsExtensions, RPCRequestHandler, StacksHttpRequest,
+    StacksHttpResponse,
+};
+use crate::net::{Error as NetError, StacksNodeState, TipRequest};
+
+#[derive(Serialize, Deserialize)]
+pub struct SimulateTransactionRequestBody {
+    pub tx: String,
+}
+
+/// The outcome of evaluating a transaction against a chain tip without committing it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulateTransactionResponse {
+    pub txid: Txid,
+    /// The block the transaction was evaluated on top of
+    pub tip: StacksBlockId,
+    /// Whether or not the transaction could be mined on top of `tip`
+    pub okay: bool,
+    /// Why the transaction could not be mined, if `okay` is false
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cause: Option<String>,
+    /// Hex-encoded Clarity value returned by the transaction
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<String>,
+    pub post_condition_aborted: bool,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vm_error: Option<String>,
+    /// Events, encoded the same way as they are sent to event observers
+    pub events: Vec<serde_json::Value>,
+    pub fee: u64,
+    pub stx_burned: u128,
+    pub execution_cost: ExecutionCost,
+}
+
+#[derive(Clone)]
+pub struct RPCSimulateTransactionRequestHandler {
+    pub tx: Option<StacksTransaction>,
+}
+
+impl RPCSimulateTransactionRequestHandler {
+    pub fn new() -> Self {
+        Self { tx: None }
+    }
+
+    fn decode_tx(mut body: &[u8]) -> Result<StacksTransaction, Error> {
+        StacksTransaction::consensus_deserialize(&mut body).map_err(|e| {
+            if let CodecError::DeserializeError(msg) = e {
+                Error::DecodeError(format!("Failed to deserialize transaction: {}", msg))
+            } else {
+                e.into()
+            }
+        })
+    }
+
+    /// Evaluate `tx` in an ephemeral Clarity transaction on top of `tip`, and then throw the
+    /// transaction away.  The transaction is charged against a full block budget.
+    /// Returns Ok(None) if the tip does not exist.
+    fn simulate_tx(
+        node: &mut StacksNodeState,
+        tip: &StacksBlockId,
+        tx: &StacksTransaction,
+    ) -> Result<Option<SimulateTransactionResponse>, NetError> {
+        let txid = tx.txid();
+        node.with_node_state(|network, sortdb, chainstate, _mempool, _rpc_args| {
+            let mainnet = chainstate.mainnet;
+            let chain_id = chainstate.chain_id;
+            let config = chainstate.config();
+            let ast_rules = network.ast_rules;
+
+            // evaluate on a read-only connection to `tip`, so simulating never takes the
+            // chainstate write lock.  The transaction's edits are never committed.
+            let result = chainstate.maybe_read_only_clarity_tx(
+                &sortdb.index_handle_at_block(chainstate, tip)?,
+                tip,
+                |clarity_tx| {
+                    let epoch = clarity_tx.get_epoch();
+                    let block_limit =
+                        SortitionDB::get_stacks_epoch_by_epoch_id(sortdb.conn(), &epoch)?
+                            .ok_or_else(|| {
+                                NetError::ChainstateError(format!("No such epoch {epoch}"))
+                            })?
+                            .block_limit;
+                    let cost_track = clarity_tx
+                        .with_clarity_db_readonly(|clarity_db| {
+                            LimitedCostTracker::new_mid_block(
+                                mainnet,
+                                chain_id,
+                                block_limit,
+                                clarity_db,
+                                epoch,
+                            )
+                        })
+                        .map_err(|e| NetError::ChainstateError(format!("{e:?}")))?;
+                    let mut cost_track = Some(cost_track);
+                    Ok::<_, NetError>(clarity_tx.as_ephemeral_transaction(
+                        mainnet,
+                        chain_id,
+                        &mut cost_track,
+                        |clarity_tx| {
+                            StacksChainState::process_transaction_uncommitted(
+                                clarity_tx, &config, tx, true, ast_rules,
+                            )
+                        },
+                    ))
+                },
+            )?;
+            let Some(result) = result.transpose()? else {
+                return Ok(None);
+            };
+
+            let resp = match result {
+                Ok((fee, receipt)) => {
+                    let committed = !receipt.post_condition_aborted;
+                    let events = receipt
+                        .events
+                        .iter()
+                        .enumerate()
+                        .map(|(event_index, event)| {
+                            event.json_serialize(event_index, &txid, committed)
+                        })
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(|e| NetError::SerializeError(format!("{:?}", &e)))?;
+                    let result_bytes = receipt
+                        .result
+                        .serialize_to_vec()
+                        .map_err(|e| NetError::SerializeError(format!("{:?}", &e)))?;
+
+                    SimulateTransactionResponse {
+                        txid: txid.clone(),
+                        tip: tip.clone(),
+                        okay: true,
+                        cause: None,
+                        result: Some(format!("0x{}", to_hex(&result_bytes))),
+                        post_condition_aborted: receipt.post_condition_aborted,
+                        vm_error: receipt.vm_error,
+                        events,
+                        fee,
+                        stx_burned: receipt.stx_burned,
+                        execution_cost: receipt.execution_cost,
+                    }
+                }
+                Err(e) => SimulateTransactionResponse {
+                    txid: txid.clone(),
+                    tip: tip.clone(),
+                    okay: false,
+                    cause: Some(e.to_string()),
+                    result: None,
+                    post_condition_aborted: false,
+                    vm_error: None,
+                    events: vec![],
+                    fee: 0,
+                    stx_burned: 0,
+                    execution_cost: ExecutionCost::ZERO,
+                },
+            };
+            Ok(Some(resp))
+        })
+    }
+}
+
+/// Decode the HTTP request
+impl HttpRequest for RPCSimulateTransactionRequestHandler {
+    fn verb(&self) -> &'static str {
+        "POST"
+    }
+
+    fn path_regex(&self) -> Regex {
+        Regex::new(r#"^/v3/transactions/simulate$"#).unwrap()
+    }
+
+    fn metrics_identifier(&self) -> &str {
+        "/v3/transactions/simulate"
+    }
+
+    /// Try to decode this request.
+    /// The body is either a bare transaction, or a JSON object with a hex-encoded transaction.
+    fn try_parse_request(
+        &mut self,
+        preamble: &HttpRequestPreamble,
+        _captures: &Captures,
+        query: Option<&str>,
+        body: &[u8],
+    ) -> Result<HttpRequestContents, Error> {
+        if preamble.get_content_length() == 0 {
+            return Err(Error::DecodeError(
+                "Invalid Http request: expected non-zero-length body for SimulateTransaction"
+                    .to_string(),
+            ));
+        }
+
+        if preamble.get_content_length() > MAX_PAYLOAD_LEN {
+            return Err(Error::DecodeError(
+                "Invalid Http request: SimulateTransaction body is too big".to_string(),
+            ));
+        }
+
+        let tx = match preamble.content_type {
+            None => {
+                return Err(Error::DecodeError(
+                    "Missing Content-Type for transaction".to_string(),
+                ));
+            }
+            Some(HttpContentType::Bytes) => Self::decode_tx(body)?,
+            Some(HttpContentType::JSON) => {
+                let body: SimulateTransactionRequestBody = serde_json::from_slice(body)
+                    .map_err(|_e| Error::DecodeError("Failed to parse body".into()))?;
+                let tx_bytes = hex_bytes(&body.tx)
+                    .map_err(|_e| Error::DecodeError("Failed to parse tx".into()))?;
+                Self::decode_tx(&tx_bytes)?
+            }
+            _ => {
+                return Err(Error::DecodeError(
+                    "Wrong Content-Type for transaction; expected application/json or application/octet-stream".to_string(),
+                ));
+            }
+        };
+
+        self.tx = Some(tx);
+        Ok(HttpRequestContents::new().query_string(query))
+    }
+}
+
+impl RPCRequestHandler for RPCSimulateTransactionRequestHandler {
+    /// Reset internal state
+    fn restart(&mut self) {
+        self.tx = None;
+    }
+
+    /// Make the response
+    fn try_handle_request(
+        &mut self,
+        preamble: HttpRequestPreamble,
+        contents: HttpRequestContents,
+        node: &mut StacksNodeState,
+    ) -> Result<(HttpResponsePreamble, HttpResponseContents), NetError> {
+        let tx = self
+            .tx
+            .take()
+            .ok_or(NetError::SendError("`tx` not set".into()))?;
+
+        let tip = match node.load_stacks_chain_tip(&preamble, &contents) {
+            Ok(tip) => tip,
+            Err(error_resp) => {
+                return error_resp.try_into_contents().map_err(NetError::from);
+            }
+        };
+
+        let data_resp = match Self::simulate_tx(node, &tip, &tx) {
+            Ok(Some(resp)) => resp,
+            Ok(None) => {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpNotFound::new("Chain tip not found".to_string()),
+                )
+                .try_into_contents()
+                .map_err(NetError::from);
+            }
+            Err(e) => {
+                return StacksHttpResponse::new_error(
+                    &preamble,
+                    &HttpServerError::new(format!("Failed to simulate transaction: {:?}", &e)),
+                )
+                .try_into_contents()
+                .map_err(NetError::from);
+            }
+        };
+
+        let mut preamble = HttpResponsePreamble::ok_json(&preamble);
+        preamble.set_canonical_stacks_tip_height(Some(node.canonical_stacks_tip_height()));
+        let body = HttpResponseContents::try_from_json(&data_resp)?;
+        Ok((preamble, body))
+    }
+}
+
+/// Decode the HTTP response
+impl HttpResponse for RPCSimulateTransactionRequestHandler {
+    fn try_parse_response(
+        &self,
+        preamble: &HttpResponsePreamble,
+        body: &[u8],
+    ) -> Result<HttpResponsePayload, Error> {
+        let resp: SimulateTransactionResponse = parse_json(preamble, body)?;
+        Ok(HttpResponsePayload::try_from_json(resp)?)
+    }
+}
+
+impl StacksHttpRequest {
+    /// Make a new request to simulate a transaction
+    pub fn new_simulate_transaction(
+        host: PeerHost,
+        tx: StacksTransaction,
+        tip_req: TipRequest,
+    ) -> StacksHttpRequest {
+        StacksHttpRequest::new_for_peer(
+            host,
+            "POST".into(),
+            "/v3/transactions/simulate".to_string(),
+            HttpRequestContents::new()
+                .for_tip(tip_req)
+                .payload_stacks(&tx),
+        )
+        .expect("FATAL: failed to construct request from infallible data")
+    }
+}
+
+impl StacksHttpResponse {
+    pub fn decode_simulate_transaction_response(
+        self,
+    ) -> Result<SimulateTransactionResponse, NetError> {
+        let contents = self.get_http_payload_ok()?;
+        let contents_json: serde_json::Value = contents.try_into()?;
+        let resp: SimulateTransactionResponse = serde_json::from_value(contents_json)
+            .map_err(|_e| NetError::DeserializeError("Failed to load from JSON".to_string()))?;
+        Ok(resp)
+    }
+}
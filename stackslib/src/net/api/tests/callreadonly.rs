@@ -27,6 +27,7 @@ use super::test_rpc;
 use crate::core::BLOCK_LIMIT_MAINNET_21;
 use crate::net::api::*;
 use crate::net::connection::ConnectionOptions;
+use crate::net::http::HttpRequestContents;
 use crate::net::httpcore::{
     HttpPreambleExtensions, HttpRequestContentsExtensions, RPCRequestHandler, StacksHttp,
     StacksHttpRequest,
@@ -184,6 +185,27 @@ fn test_try_make_response() {
     );
     requests.push(request);
 
+    // trace a call
+    let request = StacksHttpRequest::new_for_peer(
+        addr.into(),
+        "POST".into(),
+        "/v2/contracts/call-read/ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R/hello-world/get-bar"
+            .into(),
+        HttpRequestContents::new()
+            .for_tip(TipRequest::UseLatestAnchoredTip)
+            .query_arg("trace".into(), "true".into())
+            .payload_json(
+                serde_json::to_value(callreadonly::CallReadOnlyRequestBody {
+                    sender: "ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R".into(),
+                    sponsor: None,
+                    arguments: vec![],
+                })
+                .unwrap(),
+            ),
+    )
+    .unwrap();
+    requests.push(request);
+
     let mut responses = test_rpc(function_name!(), requests);
 
     // confirmed tip
@@ -270,4 +292,30 @@ fn test_try_make_response() {
 
     let (preamble, payload) = response.destruct();
     assert_eq!(preamble.status_code, 404);
+
+    // traced call
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    // the test node's read-only call limit only allows 3 reads, and loading the contract takes
+    // them all, so reading `bar` exceeds the limit
+    let resp = response.decode_call_readonly_response().unwrap();
+    assert!(!resp.okay);
+    assert!(resp.cause.unwrap().contains("CostBalanceExceeded"));
+
+    let trace = resp.trace.unwrap();
+    assert_eq!(
+        trace.contract,
+        "ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R.hello-world"
+    );
+    assert_eq!(trace.function, "get-bar");
+    assert!(trace.arguments.is_empty());
+    assert!(trace.result.is_none());
+    assert!(trace.error.unwrap().contains("CostBalanceExceeded"));
+    assert!(trace.reads.is_empty());
+    assert!(trace.calls.is_empty());
+    assert_eq!(trace.execution_cost.read_count, 4);
 }
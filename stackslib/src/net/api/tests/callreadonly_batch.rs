@@ -0,0 +1,231 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use clarity::vm::costs::ExecutionCost;
+use clarity::vm::types::{PrincipalData, QualifiedContractIdentifier};
+use clarity::vm::Value;
+use stacks_common::types::chainstate::StacksBlockId;
+
+use super::test_rpc;
+use crate::core::BLOCK_LIMIT_MAINNET_21;
+use crate::net::api::callreadonly_batch::ReadOnlyCall;
+use crate::net::api::*;
+use crate::net::connection::ConnectionOptions;
+use crate::net::httpcore::{
+    HttpPreambleExtensions, HttpRequestContentsExtensions, RPCRequestHandler, StacksHttp,
+    StacksHttpRequest,
+};
+use crate::net::{ProtocolFamily, TipRequest};
+
+fn make_call(contract: &str, function: &str, arguments: Vec<Value>) -> ReadOnlyCall {
+    ReadOnlyCall {
+        contract_identifier: QualifiedContractIdentifier::parse(&format!(
+            "ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R.{contract}"
+        ))
+        .unwrap(),
+        function: function.into(),
+        arguments,
+    }
+}
+
+fn sender() -> PrincipalData {
+    PrincipalData::parse("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap()
+}
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let calls = vec![
+        make_call("hello-world", "ro-confirmed", vec![]),
+        make_call("hello-world-unconfirmed", "ro-test", vec![Value::UInt(1)]),
+    ];
+    let request = StacksHttpRequest::new_callreadonlybatch(
+        addr.into(),
+        sender(),
+        None,
+        calls.clone(),
+        TipRequest::SpecificTip(StacksBlockId([0x22; 32])),
+    );
+    assert_eq!(
+        request.contents().tip_request(),
+        TipRequest::SpecificTip(StacksBlockId([0x22; 32]))
+    );
+
+    let bytes = request.try_serialize().unwrap();
+
+    debug!("Request:\n{}\n", std::str::from_utf8(&bytes).unwrap());
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler =
+        callreadonly_batch::RPCCallReadOnlyBatchRequestHandler::new(4096, BLOCK_LIMIT_MAINNET_21);
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    assert_eq!(handler.sender, Some(sender()));
+    assert_eq!(handler.sponsor, None);
+    assert_eq!(handler.calls, Some(calls));
+
+    // parsed request consumes headers that would not be in a constructed reqeuest
+    parsed_request.clear_headers();
+    let (preamble, _contents) = parsed_request.destruct();
+
+    assert_eq!(&preamble, request.preamble());
+
+    // restart clears the handler state
+    handler.restart();
+    assert!(handler.sender.is_none());
+    assert!(handler.sponsor.is_none());
+    assert!(handler.calls.is_none());
+
+    // an empty batch is rejected
+    let request = StacksHttpRequest::new_callreadonlybatch(
+        addr.into(),
+        sender(),
+        None,
+        vec![],
+        TipRequest::UseLatestAnchoredTip,
+    );
+    let bytes = request.try_serialize().unwrap();
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    assert!(http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .is_err());
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let mut requests = vec![];
+
+    // a batch with successful and failing calls
+    let request = StacksHttpRequest::new_callreadonlybatch(
+        addr.into(),
+        sender(),
+        None,
+        vec![
+            make_call("hello-world", "ro-confirmed", vec![]),
+            make_call("hello-world", "does-not-exist", vec![]),
+            make_call("does-not-exist", "ro-confirmed", vec![]),
+            make_call("hello-world", "add-unit", vec![]),
+            make_call("hello-world", "ro-confirmed", vec![]),
+            make_call("hello-world", "get-bar", vec![]),
+        ],
+        TipRequest::UseLatestAnchoredTip,
+    );
+    requests.push(request);
+
+    // query non-existent tip
+    let request = StacksHttpRequest::new_callreadonlybatch(
+        addr.into(),
+        sender(),
+        None,
+        vec![make_call("hello-world", "ro-confirmed", vec![])],
+        TipRequest::SpecificTip(StacksBlockId([0x11; 32])),
+    );
+    requests.push(request);
+
+    let mut responses = test_rpc(function_name!(), requests);
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    assert_eq!(
+        response.preamble().get_canonical_stacks_tip_height(),
+        Some(1)
+    );
+
+    let resp = response.decode_call_readonly_batch_response().unwrap();
+    assert_eq!(resp.results.len(), 6);
+
+    // u1
+    assert!(resp.results[0].okay);
+    assert_eq!(
+        resp.results[0].result.as_ref().unwrap(),
+        "0x0100000000000000000000000000000001"
+    );
+    assert!(resp.results[0].execution_cost.runtime > 0);
+
+    // non-existent function
+    assert!(!resp.results[1].okay);
+    assert!(resp.results[1].result.is_none());
+    assert!(resp.results[1]
+        .cause
+        .as_ref()
+        .unwrap()
+        .contains("UndefinedFunction"));
+
+    // non-existent contract
+    assert!(!resp.results[2].okay);
+    assert!(resp.results[2]
+        .cause
+        .as_ref()
+        .unwrap()
+        .contains("NoSuchContract"));
+
+    // writes are not allowed
+    assert!(!resp.results[3].okay);
+    assert_eq!(resp.results[3].cause.as_ref().unwrap(), "NotReadOnly");
+
+    // calls after a failed call still run
+    assert!(resp.results[4].okay);
+    assert_eq!(
+        resp.results[4].result.as_ref().unwrap(),
+        "0x0100000000000000000000000000000001"
+    );
+
+    // the calls share one budget, so reading `bar` can use the budget which the other calls
+    // left unused
+    assert!(resp.results[5].okay);
+    // (ok 0)
+    assert_eq!(
+        resp.results[5].result.as_ref().unwrap(),
+        "0x070000000000000000000000000000000000"
+    );
+
+    // the total is the sum of the per-call costs
+    let mut total = ExecutionCost::ZERO;
+    for result in resp.results.iter() {
+        total.add(&result.execution_cost).unwrap();
+    }
+    assert_eq!(resp.execution_cost, total);
+
+    // non-existent tip
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    let (preamble, _payload) = response.destruct();
+    assert_eq!(preamble.status_code, 404);
+}
@@ -0,0 +1,112 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use super::{MockEventSubscriptions, TestRPC};
+use crate::net::api::*;
+use crate::net::connection::ConnectionOptions;
+use crate::net::httpcore::{RPCRequestHandler, StacksHttp, StacksHttpRequest};
+use crate::net::ProtocolFamily;
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let subscription_id = format!("{:032x}", 0x1234);
+    let request = StacksHttpRequest::new_delete_subscription(addr.into(), &subscription_id);
+    let bytes = request.try_serialize().unwrap();
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = deletesubscription::RPCDeleteSubscriptionRequestHandler::new();
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    assert_eq!(handler.subscription_id, Some(subscription_id));
+
+    // parsed request consumes headers that would not be in a constructed reqeuest
+    parsed_request.clear_headers();
+    let (preamble, _contents) = parsed_request.destruct();
+
+    assert_eq!(&preamble, request.preamble());
+
+    handler.restart();
+    assert!(handler.subscription_id.is_none());
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let subscription_id = format!("{:032x}", 1);
+
+    let mut requests = vec![];
+
+    requests.push(StacksHttpRequest::new_post_subscription(
+        addr.into(),
+        vec!["stx".to_string()],
+    ));
+
+    // cancel it
+    requests.push(StacksHttpRequest::new_delete_subscription(
+        addr.into(),
+        &subscription_id,
+    ));
+
+    // it's gone
+    requests.push(StacksHttpRequest::new_get_subscription_events(
+        addr.into(),
+        &subscription_id,
+        0,
+        None,
+        None,
+    ));
+
+    // can't cancel it twice
+    requests.push(StacksHttpRequest::new_delete_subscription(
+        addr.into(),
+        &subscription_id,
+    ));
+
+    let event_subscriptions = MockEventSubscriptions::default();
+    let test_rpc = TestRPC::setup(function_name!());
+    let mut responses = test_rpc.run_with_subscriptions(requests, &event_subscriptions);
+
+    let response = responses.remove(0);
+    let resp = response.decode_subscription_response().unwrap();
+    assert_eq!(resp.subscription_id, subscription_id);
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    let resp = response.decode_delete_subscription_response().unwrap();
+    assert_eq!(resp.subscription_id, subscription_id);
+    assert!(resp.deleted);
+
+    for _ in 0..2 {
+        let response = responses.remove(0);
+        let (preamble, _body) = response.destruct();
+        assert_eq!(preamble.status_code, 404);
+    }
+}
@@ -24,8 +24,10 @@ use stacks_common::types::net::PeerHost;
 use stacks_common::types::Address;
 
 use super::test_rpc;
+use crate::net::api::getaccount::AccountHeightRequest;
 use crate::net::api::*;
 use crate::net::connection::ConnectionOptions;
+use crate::net::http::HttpRequestContents;
 use crate::net::httpcore::{
     HttpPreambleExtensions, HttpRequestContentsExtensions, RPCRequestHandler, StacksHttp,
     StacksHttpRequest,
@@ -71,9 +73,58 @@ fn test_try_parse_request() {
 
     assert_eq!(&preamble, request.preamble());
 
+    assert!(handler.height.is_none());
+
     // reset works
     handler.restart();
     assert!(handler.account.is_none());
+
+    // historical queries
+    for height in [
+        AccountHeightRequest::StacksBlockHeight(12),
+        AccountHeightRequest::BurnBlockHeight(34),
+    ] {
+        let request = StacksHttpRequest::new_getaccount_at_height(
+            addr.into(),
+            StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R")
+                .unwrap()
+                .to_account_principal(),
+            TipRequest::UseLatestAnchoredTip,
+            height,
+            false,
+        );
+        let bytes = request.try_serialize().unwrap();
+        let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+        http.handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+        assert_eq!(handler.height, Some(height));
+        handler.restart();
+        assert!(handler.height.is_none());
+    }
+
+    // at most one height may be given
+    let request = StacksHttpRequest::new_for_peer(
+        addr.into(),
+        "GET".into(),
+        "/v2/accounts/ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R".into(),
+        HttpRequestContents::new()
+            .query_arg("at_height".into(), "1".into())
+            .query_arg("at_burn_height".into(), "1".into()),
+    )
+    .unwrap();
+    let bytes = request.try_serialize().unwrap();
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    assert!(http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .is_err());
 }
 
 #[test]
@@ -126,6 +177,36 @@ fn test_try_make_response() {
     );
     requests.push(request);
 
+    // query existing account at the genesis block and at the tip
+    for height in [
+        AccountHeightRequest::StacksBlockHeight(0),
+        AccountHeightRequest::StacksBlockHeight(1),
+        AccountHeightRequest::BurnBlockHeight(u64::MAX),
+    ] {
+        let request = StacksHttpRequest::new_getaccount_at_height(
+            addr.into(),
+            StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R")
+                .unwrap()
+                .to_account_principal(),
+            TipRequest::UseLatestAnchoredTip,
+            height,
+            false,
+        );
+        requests.push(request);
+    }
+
+    // query at a height the chain has not reached
+    let request = StacksHttpRequest::new_getaccount_at_height(
+        addr.into(),
+        StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R")
+            .unwrap()
+            .to_account_principal(),
+        TipRequest::UseLatestAnchoredTip,
+        AccountHeightRequest::StacksBlockHeight(100),
+        false,
+    );
+    requests.push(request);
+
     let mut responses = test_rpc(function_name!(), requests);
 
     let response = responses.remove(0);
@@ -203,4 +284,36 @@ fn test_try_make_response() {
     assert_eq!(resp.nonce, 4);
     assert!(resp.balance_proof.is_some());
     assert!(resp.nonce_proof.is_some());
+
+    // at the genesis block
+    let response = responses.remove(0);
+    let resp = response.decode_account_entry_response().unwrap();
+
+    assert_eq!(resp.balance, "0x0000000000000000000000003b9aca00");
+    assert_eq!(resp.locked, "0x00000000000000000000000000000000");
+    assert_eq!(resp.nonce, 0);
+    assert!(resp.locked_by.is_none());
+    assert!(resp.index_block_hash.is_some());
+    assert_eq!(resp.block_height, Some(0));
+
+    // at the tip, by Stacks block height and by burnchain block height
+    let mut tip_block_ids = vec![];
+    for _ in 0..2 {
+        let response = responses.remove(0);
+        let resp = response.decode_account_entry_response().unwrap();
+
+        assert_eq!(resp.balance, "0x0000000000000000000000003b9aca00");
+        assert_eq!(resp.locked, "0x00000000000000000000000000000000");
+        assert_eq!(resp.nonce, 2);
+        assert!(resp.locked_by.is_none());
+        assert_eq!(resp.block_height, Some(1));
+        assert!(resp.burn_block_height.is_some());
+        tip_block_ids.push(resp.index_block_hash.unwrap());
+    }
+    assert_eq!(tip_block_ids[0], tip_block_ids[1]);
+
+    // no such ancestor
+    let response = responses.remove(0);
+    let (preamble, _body) = response.destruct();
+    assert_eq!(preamble.status_code, 404);
 }
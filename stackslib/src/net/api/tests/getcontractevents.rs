@@ -0,0 +1,311 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use clarity::vm::types::QualifiedContractIdentifier;
+use clarity::vm::ContractName;
+use stacks_common::types::chainstate::{StacksAddress, StacksBlockId};
+use stacks_common::types::Address;
+
+use super::{test_events_contract_addr, TestRPC};
+use crate::net::api::getcontractevents::{ContractEventsCursor, ContractEventsQuery};
+use crate::net::api::*;
+use crate::net::connection::ConnectionOptions;
+use crate::net::httpcore::{
+    HttpRequestContentsExtensions, RPCRequestHandler, StacksHttp, StacksHttpRequest,
+};
+use crate::net::{ProtocolFamily, TipRequest};
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let query = ContractEventsQuery {
+        topic: Some("print".into()),
+        from_height: Some(10),
+        to_height: Some(20),
+        limit: 5,
+        cursor: Some(ContractEventsCursor {
+            block_height: 12,
+            tx_index: 3,
+            event_index: 4,
+        }),
+    };
+    let request = StacksHttpRequest::new_getcontractevents(
+        addr.into(),
+        StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap(),
+        "hello-world".try_into().unwrap(),
+        &query,
+        TipRequest::SpecificTip(StacksBlockId([0x22; 32])),
+    );
+    assert_eq!(
+        request.contents().tip_request(),
+        TipRequest::SpecificTip(StacksBlockId([0x22; 32]))
+    );
+    let bytes = request.try_serialize().unwrap();
+
+    debug!("Request:\n{}\n", std::str::from_utf8(&bytes).unwrap());
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = getcontractevents::RPCGetContractEventsRequestHandler::new();
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    assert_eq!(
+        handler.contract_identifier,
+        Some(
+            QualifiedContractIdentifier::parse(
+                "ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R.hello-world"
+            )
+            .unwrap()
+        )
+    );
+    assert_eq!(handler.query, query);
+
+    // parsed request consumes headers that would not be in a constructed reqeuest
+    parsed_request.clear_headers();
+    let (preamble, _contents) = parsed_request.destruct();
+
+    assert_eq!(&preamble, request.preamble());
+
+    handler.restart();
+    assert!(handler.contract_identifier.is_none());
+    assert_eq!(handler.query, ContractEventsQuery::default());
+
+    // limit is bounded
+    for limit in [0, getcontractevents::MAX_CONTRACT_EVENTS_LIMIT + 1] {
+        let request = StacksHttpRequest::new_getcontractevents(
+            addr.into(),
+            StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap(),
+            "hello-world".try_into().unwrap(),
+            &ContractEventsQuery {
+                limit,
+                ..ContractEventsQuery::default()
+            },
+            TipRequest::UseLatestAnchoredTip,
+        );
+        let bytes = request.try_serialize().unwrap();
+        let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+        let mut handler = getcontractevents::RPCGetContractEventsRequestHandler::new();
+        assert!(http
+            .handle_try_parse_request(
+                &mut handler,
+                &parsed_preamble.expect_request(),
+                &bytes[offset..],
+            )
+            .is_err());
+    }
+}
+
+#[test]
+fn test_contract_events_cursor() {
+    let cursor = ContractEventsCursor {
+        block_height: 12,
+        tx_index: 3,
+        event_index: 4,
+    };
+    assert_eq!(cursor.to_string(), "12-3-4");
+    assert_eq!("12-3-4".parse::<ContractEventsCursor>(), Ok(cursor));
+    assert!("12-3".parse::<ContractEventsCursor>().is_err());
+    assert!("12-3-4-5".parse::<ContractEventsCursor>().is_err());
+    assert!("12-3-x".parse::<ContractEventsCursor>().is_err());
+
+    // a cursor at the end of a height comes after every event at that height
+    assert!(ContractEventsCursor::end_of_height(12) > cursor);
+    assert!(
+        ContractEventsCursor::end_of_height(12)
+            < ContractEventsCursor {
+                block_height: 13,
+                tx_index: 0,
+                event_index: 0,
+            }
+    );
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let contract_addr = test_events_contract_addr();
+    let contract_name: ContractName = "hello-events".try_into().unwrap();
+
+    let rpc_test = TestRPC::setup(function_name!());
+    let mut requests = vec![];
+
+    // all events
+    requests.push(StacksHttpRequest::new_getcontractevents(
+        addr.into(),
+        contract_addr.clone(),
+        contract_name.clone(),
+        &ContractEventsQuery::default(),
+        TipRequest::UseLatestAnchoredTip,
+    ));
+
+    // events with a topic
+    requests.push(StacksHttpRequest::new_getcontractevents(
+        addr.into(),
+        contract_addr.clone(),
+        contract_name.clone(),
+        &ContractEventsQuery {
+            topic: Some("print".into()),
+            ..ContractEventsQuery::default()
+        },
+        TipRequest::SpecificTip(rpc_test.canonical_tip.clone()),
+    ));
+
+    // events after a cursor before the event
+    requests.push(StacksHttpRequest::new_getcontractevents(
+        addr.into(),
+        contract_addr.clone(),
+        contract_name.clone(),
+        &ContractEventsQuery {
+            cursor: Some(ContractEventsCursor {
+                block_height: 1,
+                tx_index: 1,
+                event_index: 0,
+            }),
+            ..ContractEventsQuery::default()
+        },
+        TipRequest::UseLatestAnchoredTip,
+    ));
+
+    // events with a different topic
+    requests.push(StacksHttpRequest::new_getcontractevents(
+        addr.into(),
+        contract_addr.clone(),
+        contract_name.clone(),
+        &ContractEventsQuery {
+            topic: Some("nope".into()),
+            ..ContractEventsQuery::default()
+        },
+        TipRequest::UseLatestAnchoredTip,
+    ));
+
+    // events after the block that emitted them
+    requests.push(StacksHttpRequest::new_getcontractevents(
+        addr.into(),
+        contract_addr.clone(),
+        contract_name.clone(),
+        &ContractEventsQuery {
+            from_height: Some(2),
+            ..ContractEventsQuery::default()
+        },
+        TipRequest::UseLatestAnchoredTip,
+    ));
+
+    // paging past the last event
+    requests.push(StacksHttpRequest::new_getcontractevents(
+        addr.into(),
+        contract_addr.clone(),
+        contract_name.clone(),
+        &ContractEventsQuery {
+            cursor: Some(ContractEventsCursor {
+                block_height: 1,
+                tx_index: 2,
+                event_index: 0,
+            }),
+            ..ContractEventsQuery::default()
+        },
+        TipRequest::UseLatestAnchoredTip,
+    ));
+
+    // non-existent tip
+    requests.push(StacksHttpRequest::new_getcontractevents(
+        addr.into(),
+        contract_addr.clone(),
+        contract_name.clone(),
+        &ContractEventsQuery::default(),
+        TipRequest::SpecificTip(StacksBlockId([0x11; 32])),
+    ));
+
+    let canonical_tip = rpc_test.canonical_tip.clone();
+    let mut responses = rpc_test.run(requests);
+
+    for _ in 0..3 {
+        let response = responses.remove(0);
+        debug!(
+            "Response:\n{}\n",
+            std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+        );
+        let resp = response.decode_contract_events_response().unwrap();
+        assert_eq!(
+            resp.contract_identifier,
+            format!("{}.hello-events", &contract_addr)
+        );
+        assert_eq!(resp.events.len(), 1);
+        assert!(resp.next_cursor.is_none());
+
+        let event = &resp.events[0];
+        assert_eq!(event.index_block_hash, canonical_tip);
+        assert_eq!(event.block_height, 1);
+        assert_eq!(event.tx_index, 2);
+        assert_eq!(event.event_index, 0);
+        assert_eq!(event.topic, "print");
+
+        // u123
+        assert_eq!(event.value, "0x010000000000000000000000000000007b");
+    }
+
+    for _ in 0..3 {
+        let response = responses.remove(0);
+        debug!(
+            "Response:\n{}\n",
+            std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+        );
+        let resp = response.decode_contract_events_response().unwrap();
+        assert!(resp.events.is_empty());
+        assert!(resp.next_cursor.is_none());
+    }
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+    let (preamble, _body) = response.destruct();
+    assert_eq!(preamble.status_code, 404);
+}
+
+#[test]
+fn test_try_make_response_event_index_disabled() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let mut rpc_test = TestRPC::setup(function_name!());
+    rpc_test.peer_2.chainstate().event_index = false;
+
+    let requests = vec![StacksHttpRequest::new_getcontractevents(
+        addr.into(),
+        test_events_contract_addr(),
+        "hello-events".try_into().unwrap(),
+        &ContractEventsQuery::default(),
+        TipRequest::UseLatestAnchoredTip,
+    )];
+    let mut responses = rpc_test.run(requests);
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+    let (preamble, _body) = response.destruct();
+    assert_eq!(preamble.status_code, 404);
+}
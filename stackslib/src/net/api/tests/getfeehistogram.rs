@@ -0,0 +1,127 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2024 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use super::test_rpc;
+use crate::cost_estimates::FeeRateHistogram;
+use crate::net::api::*;
+use crate::net::connection::ConnectionOptions;
+use crate::net::httpcore::{RPCRequestHandler, StacksHttp, StacksHttpRequest};
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let request = StacksHttpRequest::new_get_fee_histogram(addr.into());
+    let bytes = request.try_serialize().unwrap();
+
+    debug!("Request:\n{}\n", std::str::from_utf8(&bytes).unwrap());
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = getfeehistogram::RPCGetFeeHistogramRequestHandler::new();
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    // parsed request consumes headers that would not be in a constructed reqeuest
+    parsed_request.clear_headers();
+    let (preamble, _contents) = parsed_request.destruct();
+
+    assert_eq!(&preamble, request.preamble());
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let mut requests = vec![];
+    let request = StacksHttpRequest::new_get_fee_histogram(addr.into());
+    requests.push(request);
+
+    let mut responses = test_rpc(function_name!(), requests);
+
+    // fee estimation is not configured on the test node
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    let (preamble, _body) = response.destruct();
+    assert_eq!(preamble.status_code, 400);
+}
+
+#[test]
+fn test_histogram_response() {
+    // one full block of 10-20 uSTX/cost transactions, and one half-full block
+    let mut full_block = FeeRateHistogram::default();
+    full_block.add(15.0, 4, 1000);
+    let mut half_full_block = FeeRateHistogram::default();
+    half_full_block.add(50.0, 1, 500);
+    half_full_block.add(1.0, 0, 500);
+
+    let mut mempool = FeeRateHistogram::default();
+    mempool.add(3.0, 2, 200);
+    mempool.add(12.0, 1, 100);
+
+    let response = getfeehistogram::RPCFeeRateHistogramResponse::new(
+        &[full_block, half_full_block],
+        &mempool,
+        7,
+    );
+    assert_eq!(response.num_blocks, 2);
+    assert_eq!(response.mempool_unestimated_tx_count, 7);
+
+    let bucket_at = |fee_rate: f64| {
+        response
+            .buckets
+            .iter()
+            .find(|bucket| bucket.fee_rate == fee_rate)
+            .unwrap()
+    };
+
+    // the unused half of the second block counts as paying the minimum fee rate
+    assert_eq!(bucket_at(1.0).block_tx_count, 0);
+    assert_eq!(bucket_at(1.0).block_weight, 500);
+    assert_eq!(bucket_at(1.0).inclusion_probability, Some(0.5));
+
+    assert_eq!(bucket_at(2.0).mempool_tx_count, 2);
+    assert_eq!(bucket_at(2.0).mempool_weight, 200);
+    assert_eq!(bucket_at(2.0).inclusion_probability, Some(0.5));
+
+    assert_eq!(bucket_at(10.0).block_tx_count, 4);
+    assert_eq!(bucket_at(10.0).block_weight, 1000);
+    assert_eq!(bucket_at(10.0).mempool_tx_count, 1);
+    assert_eq!(bucket_at(10.0).inclusion_probability, Some(1.0));
+
+    assert_eq!(bucket_at(50.0).block_tx_count, 1);
+    assert_eq!(bucket_at(100_000.0).inclusion_probability, Some(1.0));
+
+    // without blocks, there's nothing to base inclusion probabilities on
+    let response =
+        getfeehistogram::RPCFeeRateHistogramResponse::new(&[], &FeeRateHistogram::default(), 0);
+    assert_eq!(response.num_blocks, 0);
+    assert!(response
+        .buckets
+        .iter()
+        .all(|bucket| bucket.inclusion_probability.is_none()));
+}
@@ -0,0 +1,207 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use clarity::vm::types::{PrincipalData, QualifiedContractIdentifier};
+use stacks_common::types::chainstate::StacksBlockId;
+
+use super::test_rpc;
+use crate::net::api::*;
+use crate::net::connection::ConnectionOptions;
+use crate::net::httpcore::{
+    HttpPreambleExtensions, HttpRequestContentsExtensions, RPCRequestHandler, StacksHttp,
+    StacksHttpRequest,
+};
+use crate::net::TipRequest;
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let contract_identifier = QualifiedContractIdentifier::parse(
+        "ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R.hello-world-unconfirmed",
+    )
+    .unwrap();
+    let principal =
+        PrincipalData::parse("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R.hello-world").unwrap();
+
+    let request = StacksHttpRequest::new_getftbalance(
+        addr.into(),
+        &contract_identifier,
+        &"test-ft".into(),
+        &principal,
+        TipRequest::SpecificTip(StacksBlockId([0x22; 32])),
+        true,
+    );
+    assert_eq!(
+        request.contents().tip_request(),
+        TipRequest::SpecificTip(StacksBlockId([0x22; 32]))
+    );
+    assert_eq!(request.contents().get_with_proof(), true);
+
+    let bytes = request.try_serialize().unwrap();
+
+    debug!("Request:\n{}\n", std::str::from_utf8(&bytes).unwrap());
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = getftbalance::RPCGetFungibleTokenBalanceRequestHandler::new();
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    // parsed request consumes headers that would not be in a constructed reqeuest
+    parsed_request.clear_headers();
+    let (preamble, contents) = parsed_request.destruct();
+
+    // consumed path args
+    assert_eq!(handler.contract_identifier, Some(contract_identifier));
+    assert_eq!(handler.token_name, Some("test-ft".into()));
+    assert_eq!(handler.principal, Some(principal));
+
+    assert_eq!(&preamble, request.preamble());
+
+    handler.restart();
+    assert!(handler.contract_identifier.is_none());
+    assert!(handler.token_name.is_none());
+    assert!(handler.principal.is_none());
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let contract_identifier = QualifiedContractIdentifier::parse(
+        "ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R.hello-world-unconfirmed",
+    )
+    .unwrap();
+    let holder = PrincipalData::parse("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap();
+    let non_holder = PrincipalData::parse("STVN97YYA10MY5F6KQJHKNYJNM24C4A1AT39WRW").unwrap();
+
+    let mut requests = vec![];
+
+    // query existing balance
+    let request = StacksHttpRequest::new_getftbalance(
+        addr.into(),
+        &contract_identifier,
+        &"test-ft".into(),
+        &holder,
+        TipRequest::UseLatestUnconfirmedTip,
+        true,
+    );
+    requests.push(request);
+
+    // query existing balance without a proof
+    let request = StacksHttpRequest::new_getftbalance(
+        addr.into(),
+        &contract_identifier,
+        &"test-ft".into(),
+        &holder,
+        TipRequest::UseLatestUnconfirmedTip,
+        false,
+    );
+    requests.push(request);
+
+    // query a principal which never held the token
+    let request = StacksHttpRequest::new_getftbalance(
+        addr.into(),
+        &contract_identifier,
+        &"test-ft".into(),
+        &non_holder,
+        TipRequest::UseLatestUnconfirmedTip,
+        true,
+    );
+    requests.push(request);
+
+    // query non-existant token
+    let request = StacksHttpRequest::new_getftbalance(
+        addr.into(),
+        &contract_identifier,
+        &"does-not-exist".into(),
+        &holder,
+        TipRequest::UseLatestUnconfirmedTip,
+        true,
+    );
+    requests.push(request);
+
+    // query a token that doesn't exist yet in the confirmed chain state
+    let request = StacksHttpRequest::new_getftbalance(
+        addr.into(),
+        &contract_identifier,
+        &"test-ft".into(),
+        &holder,
+        TipRequest::UseLatestAnchoredTip,
+        true,
+    );
+    requests.push(request);
+
+    let mut responses = test_rpc(function_name!(), requests);
+
+    // existing balance
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    assert_eq!(
+        response.preamble().get_canonical_stacks_tip_height(),
+        Some(1)
+    );
+
+    let resp = response.decode_ft_balance_response().unwrap();
+    assert_eq!(resp.balance, "0x00000000000000000000000000000064");
+    assert!(resp.marf_proof.unwrap().len() > 0);
+
+    // existing balance, no proof
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    let resp = response.decode_ft_balance_response().unwrap();
+    assert_eq!(resp.balance, "0x00000000000000000000000000000064");
+    assert!(resp.marf_proof.is_none());
+
+    // no balance
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    let resp = response.decode_ft_balance_response().unwrap();
+    assert_eq!(resp.balance, "0x00000000000000000000000000000000");
+    assert_eq!(resp.marf_proof, Some("".to_string()));
+
+    // no such token
+    for _ in 0..2 {
+        let response = responses.remove(0);
+        debug!(
+            "Response:\n{}\n",
+            std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+        );
+
+        let (preamble, _body) = response.destruct();
+        assert_eq!(preamble.status_code, 404);
+    }
+}
@@ -0,0 +1,161 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use stacks_common::types::chainstate::{StacksAddress, StacksBlockId, StacksPublicKey};
+use stacks_common::types::Address;
+
+use super::TestRPC;
+use crate::net::api::getmempoolnonces::MempoolNoncesResponse;
+use crate::net::api::*;
+use crate::net::connection::ConnectionOptions;
+use crate::net::httpcore::{
+    HttpRequestContentsExtensions, RPCRequestHandler, StacksHttp, StacksHttpRequest,
+};
+use crate::net::TipRequest;
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let account = StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap();
+    let request = StacksHttpRequest::new_getmempoolnonces(
+        addr.into(),
+        &account,
+        TipRequest::SpecificTip(StacksBlockId([0x22; 32])),
+    );
+    assert_eq!(
+        request.contents().tip_request(),
+        TipRequest::SpecificTip(StacksBlockId([0x22; 32]))
+    );
+    let bytes = request.try_serialize().unwrap();
+
+    debug!("Request:\n{}\n", std::str::from_utf8(&bytes).unwrap());
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = getmempoolnonces::RPCGetMempoolNoncesRequestHandler::new();
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    assert_eq!(handler.address, Some(account));
+
+    // parsed request consumes headers that would not be in a constructed reqeuest
+    parsed_request.clear_headers();
+    let (preamble, _contents) = parsed_request.destruct();
+
+    assert_eq!(&preamble, request.preamble());
+
+    handler.restart();
+    assert!(handler.address.is_none());
+}
+
+#[test]
+fn test_nonce_gaps() {
+    let account = StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap();
+
+    // no pending transactions
+    let resp = MempoolNoncesResponse::new(&account, 3, vec![]);
+    assert_eq!(resp.next_nonce, 3);
+    assert!(resp.missing_nonces.is_empty());
+
+    // an unbroken run of pending transactions
+    let resp = MempoolNoncesResponse::new(&account, 3, vec![3, 4, 5]);
+    assert_eq!(resp.next_nonce, 6);
+    assert!(resp.missing_nonces.is_empty());
+
+    // stale and gapped pending transactions
+    let resp = MempoolNoncesResponse::new(&account, 3, vec![1, 3, 4, 6, 9]);
+    assert_eq!(resp.confirmed_nonce, 3);
+    assert_eq!(resp.pending_nonces, vec![1, 3, 4, 6, 9]);
+    assert_eq!(resp.next_nonce, 5);
+    assert_eq!(resp.missing_nonces, vec![5, 7, 8]);
+
+    // the confirmed nonce itself is missing
+    let resp = MempoolNoncesResponse::new(&account, 3, vec![5]);
+    assert_eq!(resp.next_nonce, 3);
+    assert_eq!(resp.missing_nonces, vec![3, 4]);
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let rpc_test = TestRPC::setup(function_name!());
+    let sender = StacksAddress::p2pkh(false, &StacksPublicKey::from_private(&rpc_test.privk2));
+    let idle = StacksAddress::from_string("STVN97YYA10MY5F6KQJHKNYJNM24C4A1AT39WRW").unwrap();
+
+    let mut requests = vec![];
+
+    // account with pending transactions
+    requests.push(StacksHttpRequest::new_getmempoolnonces(
+        addr.into(),
+        &sender,
+        TipRequest::UseLatestAnchoredTip,
+    ));
+
+    // account without pending transactions
+    requests.push(StacksHttpRequest::new_getmempoolnonces(
+        addr.into(),
+        &idle,
+        TipRequest::UseLatestAnchoredTip,
+    ));
+
+    // no such tip
+    requests.push(StacksHttpRequest::new_getmempoolnonces(
+        addr.into(),
+        &sender,
+        TipRequest::SpecificTip(StacksBlockId([0x22; 32])),
+    ));
+
+    let mut responses = rpc_test.run(requests);
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+    let resp = response.decode_mempool_nonces_response().unwrap();
+    assert_eq!(resp.principal, sender.to_string());
+    assert_eq!(resp.confirmed_nonce, 0);
+    assert_eq!(resp.pending_nonces, (0..10).collect::<Vec<u64>>());
+    assert_eq!(resp.next_nonce, 10);
+    assert!(resp.missing_nonces.is_empty());
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+    let resp = response.decode_mempool_nonces_response().unwrap();
+    assert_eq!(resp.principal, idle.to_string());
+    assert!(resp.pending_nonces.is_empty());
+    assert_eq!(resp.next_nonce, resp.confirmed_nonce);
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+    let (preamble, _body) = response.destruct();
+    assert_eq!(preamble.status_code, 404);
+}
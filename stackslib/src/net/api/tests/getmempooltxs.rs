@@ -0,0 +1,207 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use clarity::vm::types::QualifiedContractIdentifier;
+use stacks_common::types::chainstate::{StacksAddress, StacksPublicKey};
+use stacks_common::types::Address;
+
+use super::TestRPC;
+use crate::core::mempool::MemPoolTxFilter;
+use crate::net::api::getmempooltxs::MempoolTransactionsQuery;
+use crate::net::api::*;
+use crate::net::connection::ConnectionOptions;
+use crate::net::httpcore::{RPCRequestHandler, StacksHttp, StacksHttpRequest};
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let filters = [
+        MemPoolTxFilter::All,
+        MemPoolTxFilter::Origin(
+            StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap(),
+        ),
+        MemPoolTxFilter::Sponsor(
+            StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap(),
+        ),
+        MemPoolTxFilter::ContractCall(
+            QualifiedContractIdentifier::parse(
+                "ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R.hello-world",
+            )
+            .unwrap(),
+        ),
+    ];
+    for filter in filters.into_iter() {
+        let query = MempoolTransactionsQuery {
+            filter,
+            limit: 5,
+            offset: 15,
+        };
+        let request = StacksHttpRequest::new_getmempooltxs(addr.into(), &query);
+        let bytes = request.try_serialize().unwrap();
+
+        debug!("Request:\n{}\n", std::str::from_utf8(&bytes).unwrap());
+
+        let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+        let mut handler = getmempooltxs::RPCGetMempoolTransactionsRequestHandler::new();
+        let mut parsed_request = http
+            .handle_try_parse_request(
+                &mut handler,
+                &parsed_preamble.expect_request(),
+                &bytes[offset..],
+            )
+            .unwrap();
+
+        assert_eq!(handler.query, Some(query));
+
+        // parsed request consumes headers that would not be in a constructed reqeuest
+        parsed_request.clear_headers();
+        let (preamble, _contents) = parsed_request.destruct();
+
+        assert_eq!(&preamble, request.preamble());
+
+        handler.restart();
+        assert!(handler.query.is_none());
+    }
+
+    // limit is bounded
+    let request = StacksHttpRequest::new_getmempooltxs(
+        addr.into(),
+        &MempoolTransactionsQuery {
+            limit: getmempooltxs::MAX_MEMPOOL_TXS_LIMIT + 1,
+            ..MempoolTransactionsQuery::default()
+        },
+    );
+    let bytes = request.try_serialize().unwrap();
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = getmempooltxs::RPCGetMempoolTransactionsRequestHandler::new();
+    assert!(http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .is_err());
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let rpc_test = TestRPC::setup(function_name!());
+    let mempool_txids = rpc_test.mempool_txids.clone();
+    let sender = StacksAddress::p2pkh(false, &StacksPublicKey::from_private(&rpc_test.privk2));
+
+    let mut requests = vec![];
+
+    // first page of all transactions
+    requests.push(StacksHttpRequest::new_getmempooltxs(
+        addr.into(),
+        &MempoolTransactionsQuery {
+            limit: 4,
+            ..MempoolTransactionsQuery::default()
+        },
+    ));
+
+    // all of the sender's transactions
+    requests.push(StacksHttpRequest::new_getmempooltxs(
+        addr.into(),
+        &MempoolTransactionsQuery {
+            filter: MemPoolTxFilter::Origin(sender.clone()),
+            ..MempoolTransactionsQuery::default()
+        },
+    ));
+
+    // last page of the sender's transactions, by sponsor
+    requests.push(StacksHttpRequest::new_getmempooltxs(
+        addr.into(),
+        &MempoolTransactionsQuery {
+            filter: MemPoolTxFilter::Sponsor(sender.clone()),
+            limit: 5,
+            offset: 8,
+        },
+    ));
+
+    // no contract-calls in the mempool
+    requests.push(StacksHttpRequest::new_getmempooltxs(
+        addr.into(),
+        &MempoolTransactionsQuery {
+            filter: MemPoolTxFilter::ContractCall(
+                QualifiedContractIdentifier::parse(
+                    "ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R.hello-world",
+                )
+                .unwrap(),
+            ),
+            ..MempoolTransactionsQuery::default()
+        },
+    ));
+
+    let mut responses = rpc_test.run(requests);
+
+    // first page
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+    let resp = response.decode_mempool_transactions_response().unwrap();
+    assert_eq!(resp.transactions.len(), 4);
+    assert_eq!(resp.next_offset, Some(4));
+    for tx in resp.transactions.iter() {
+        assert!(mempool_txids.contains(&tx.txid));
+        assert_eq!(tx.tx_fee, 1000);
+        assert_eq!(tx.fee_rate, 1000.0 / tx.length as f64);
+    }
+
+    // by origin, in nonce order
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+    let resp = response.decode_mempool_transactions_response().unwrap();
+    assert_eq!(resp.next_offset, None);
+    let txids: Vec<_> = resp.transactions.iter().map(|tx| tx.txid.clone()).collect();
+    assert_eq!(txids, mempool_txids);
+    for (i, tx) in resp.transactions.iter().enumerate() {
+        assert_eq!(tx.origin_address, sender.to_string());
+        assert_eq!(tx.origin_nonce, i as u64);
+    }
+
+    // by sponsor, last page
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+    let resp = response.decode_mempool_transactions_response().unwrap();
+    assert_eq!(resp.next_offset, None);
+    let txids: Vec<_> = resp.transactions.iter().map(|tx| tx.txid.clone()).collect();
+    assert_eq!(txids, mempool_txids[8..].to_vec());
+
+    // by contract
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+    let resp = response.decode_mempool_transactions_response().unwrap();
+    assert!(resp.transactions.is_empty());
+    assert_eq!(resp.next_offset, None);
+}
@@ -0,0 +1,153 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use stacks_common::types::chainstate::{StacksAddress, StacksBlockId, StacksPublicKey};
+use stacks_common::types::Address;
+
+use super::TestRPC;
+use crate::burnchains::Txid;
+use crate::net::api::*;
+use crate::net::connection::ConnectionOptions;
+use crate::net::httpcore::{
+    HttpRequestContentsExtensions, RPCRequestHandler, StacksHttp, StacksHttpRequest,
+};
+use crate::net::TipRequest;
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let request = StacksHttpRequest::new_getmempooltxstatus(
+        addr.into(),
+        &Txid([0x11; 32]),
+        TipRequest::SpecificTip(StacksBlockId([0x22; 32])),
+    );
+    assert_eq!(
+        request.contents().tip_request(),
+        TipRequest::SpecificTip(StacksBlockId([0x22; 32]))
+    );
+    let bytes = request.try_serialize().unwrap();
+
+    debug!("Request:\n{}\n", std::str::from_utf8(&bytes).unwrap());
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = getmempooltxstatus::RPCGetMempoolTxStatusRequestHandler::new();
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    assert_eq!(handler.txid, Some(Txid([0x11; 32])));
+
+    // parsed request consumes headers that would not be in a constructed reqeuest
+    parsed_request.clear_headers();
+    let (preamble, _contents) = parsed_request.destruct();
+
+    assert_eq!(&preamble, request.preamble());
+
+    handler.restart();
+    assert!(handler.txid.is_none());
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let rpc_test = TestRPC::setup(function_name!());
+    let sender = StacksAddress::p2pkh(false, &StacksPublicKey::from_private(&rpc_test.privk2));
+
+    let mut requests = vec![];
+
+    // transaction with the sender's confirmed nonce
+    requests.push(StacksHttpRequest::new_getmempooltxstatus(
+        addr.into(),
+        &rpc_test.mempool_txids[0],
+        TipRequest::UseLatestAnchoredTip,
+    ));
+
+    // transaction behind the sender's other pending transactions
+    requests.push(StacksHttpRequest::new_getmempooltxstatus(
+        addr.into(),
+        &rpc_test.mempool_txids[9],
+        TipRequest::UseLatestAnchoredTip,
+    ));
+
+    // no such transaction
+    requests.push(StacksHttpRequest::new_getmempooltxstatus(
+        addr.into(),
+        &Txid([0x11; 32]),
+        TipRequest::UseLatestAnchoredTip,
+    ));
+
+    // no such tip
+    requests.push(StacksHttpRequest::new_getmempooltxstatus(
+        addr.into(),
+        &rpc_test.mempool_txids[0],
+        TipRequest::SpecificTip(StacksBlockId([0x22; 32])),
+    ));
+
+    let mut responses = rpc_test.run(requests);
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+    let resp = response.decode_mempool_tx_status_response().unwrap();
+    assert_eq!(resp.txid, rpc_test.mempool_txids[0]);
+    assert_eq!(resp.origin_address, sender.to_string());
+    assert_eq!(resp.origin_nonce, 0);
+    assert_eq!(resp.confirmed_origin_nonce, 0);
+    assert_eq!(resp.status, "ready");
+    assert!(resp.blocked_by_address.is_none());
+    assert!(resp.missing_nonce.is_none());
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+    let resp = response.decode_mempool_tx_status_response().unwrap();
+    assert_eq!(resp.txid, rpc_test.mempool_txids[9]);
+    assert_eq!(resp.origin_nonce, 9);
+    assert_eq!(resp.status, "queued");
+    assert!(resp.missing_nonce.is_none());
+
+    // the transaction pays a fee, so its package does too
+    assert!(resp.package_fee_rate > 0.0);
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+    let (preamble, _body) = response.destruct();
+    assert_eq!(preamble.status_code, 404);
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+    let (preamble, _body) = response.destruct();
+    assert_eq!(preamble.status_code, 404);
+}
@@ -0,0 +1,166 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use super::{MockEventSubscriptions, TestRPC};
+use crate::net::api::*;
+use crate::net::connection::ConnectionOptions;
+use crate::net::httpcore::{RPCRequestHandler, StacksHttp, StacksHttpRequest};
+use crate::net::ProtocolFamily;
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let subscription_id = format!("{:032x}", 0x1234);
+    let request = StacksHttpRequest::new_get_subscription_events(
+        addr.into(),
+        &subscription_id,
+        5,
+        Some(10),
+        Some(3),
+    );
+    let bytes = request.try_serialize().unwrap();
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = getsubscriptionevents::RPCGetSubscriptionEventsRequestHandler::new();
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    assert_eq!(handler.subscription_id, Some(subscription_id));
+
+    // parsed request consumes headers that would not be in a constructed reqeuest
+    parsed_request.clear_headers();
+    let (preamble, contents) = parsed_request.destruct();
+
+    assert_eq!(&preamble, request.preamble());
+    assert_eq!(contents.get_query_arg("after"), Some(&"5".to_string()));
+    assert_eq!(contents.get_query_arg("limit"), Some(&"10".to_string()));
+    assert_eq!(contents.get_query_arg("wait"), Some(&"3".to_string()));
+
+    handler.restart();
+    assert!(handler.subscription_id.is_none());
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let subscription_id = format!("{:032x}", 1);
+
+    let mut requests = vec![];
+
+    // subscribe to three keys, which queues three events
+    requests.push(StacksHttpRequest::new_post_subscription(
+        addr.into(),
+        vec![
+            "stx".to_string(),
+            "memtx".to_string(),
+            "burn_blocks".to_string(),
+        ],
+    ));
+
+    // fetch at most two events
+    requests.push(StacksHttpRequest::new_get_subscription_events(
+        addr.into(),
+        &subscription_id,
+        0,
+        Some(2),
+        None,
+    ));
+
+    // acknowledge the first two events, and fetch the rest
+    requests.push(StacksHttpRequest::new_get_subscription_events(
+        addr.into(),
+        &subscription_id,
+        2,
+        None,
+        None,
+    ));
+
+    // acknowledge everything, and wait briefly for events which never arrive
+    requests.push(StacksHttpRequest::new_get_subscription_events(
+        addr.into(),
+        &subscription_id,
+        3,
+        None,
+        Some(1),
+    ));
+
+    // no such subscription
+    requests.push(StacksHttpRequest::new_get_subscription_events(
+        addr.into(),
+        &format!("{:032x}", 2),
+        0,
+        None,
+        None,
+    ));
+
+    let event_subscriptions = MockEventSubscriptions::default();
+    let test_rpc = TestRPC::setup(function_name!());
+    let mut responses = test_rpc.run_with_subscriptions(requests, &event_subscriptions);
+
+    let response = responses.remove(0);
+    let resp = response.decode_subscription_response().unwrap();
+    assert_eq!(resp.subscription_id, subscription_id);
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    let resp = response.decode_subscription_events_response().unwrap();
+    assert_eq!(resp.subscription_id, subscription_id);
+    assert_eq!(resp.dropped, 0);
+    assert_eq!(resp.events.len(), 2);
+    assert_eq!(resp.events[0].seq, 1);
+    assert_eq!(resp.events[0].payload["key"], "stx");
+    assert_eq!(resp.events[1].seq, 2);
+    assert_eq!(resp.events[1].payload["key"], "memtx");
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    let resp = response.decode_subscription_events_response().unwrap();
+    assert_eq!(resp.events.len(), 1);
+    assert_eq!(resp.events[0].seq, 3);
+    assert_eq!(resp.events[0].path, "/test_event");
+    assert_eq!(resp.events[0].payload["key"], "burn_blocks");
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    let resp = response.decode_subscription_events_response().unwrap();
+    assert_eq!(resp.subscription_id, subscription_id);
+    assert!(resp.events.is_empty());
+
+    let response = responses.remove(0);
+    let (preamble, _body) = response.destruct();
+    assert_eq!(preamble.status_code, 404);
+}
@@ -0,0 +1,189 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use stacks_common::codec::StacksMessageCodec;
+use stacks_common::types::chainstate::StacksBlockId;
+use stacks_common::util::hash::to_hex;
+
+use super::TestRPC;
+use crate::burnchains::Txid;
+use crate::chainstate::stacks::db::StacksChainState;
+use crate::chainstate::stacks::TransactionPayload;
+use crate::net::api::*;
+use crate::net::connection::ConnectionOptions;
+use crate::net::httpcore::{
+    HttpRequestContentsExtensions, RPCRequestHandler, StacksHttp, StacksHttpRequest,
+};
+use crate::net::{ProtocolFamily, TipRequest};
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let request = StacksHttpRequest::new_gettransaction(
+        addr.into(),
+        Txid([0x11; 32]),
+        TipRequest::SpecificTip(StacksBlockId([0x22; 32])),
+    );
+    assert_eq!(
+        request.contents().tip_request(),
+        TipRequest::SpecificTip(StacksBlockId([0x22; 32]))
+    );
+    let bytes = request.try_serialize().unwrap();
+
+    debug!("Request:\n{}\n", std::str::from_utf8(&bytes).unwrap());
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = gettransaction::RPCGetTransactionRequestHandler::new();
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    assert_eq!(handler.txid, Some(Txid([0x11; 32])));
+
+    // parsed request consumes headers that would not be in a constructed reqeuest
+    parsed_request.clear_headers();
+    let (preamble, _contents) = parsed_request.destruct();
+
+    assert_eq!(&preamble, request.preamble());
+
+    handler.restart();
+    assert!(handler.txid.is_none());
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let mut rpc_test = TestRPC::setup(function_name!());
+    let block = StacksChainState::load_block(
+        &rpc_test.peer_2.chainstate().blocks_path,
+        &rpc_test.consensus_hash,
+        &rpc_test.tip_hash,
+    )
+    .unwrap()
+    .unwrap();
+    let tx_contract = block
+        .txs
+        .iter()
+        .find(|tx| matches!(tx.payload, TransactionPayload::SmartContract(..)))
+        .unwrap()
+        .clone();
+
+    let mut requests = vec![];
+
+    // get the confirmed contract
+    requests.push(StacksHttpRequest::new_gettransaction(
+        addr.into(),
+        tx_contract.txid(),
+        TipRequest::UseLatestAnchoredTip,
+    ));
+
+    // get the confirmed contract as of the block that mined it
+    requests.push(StacksHttpRequest::new_gettransaction(
+        addr.into(),
+        tx_contract.txid(),
+        TipRequest::SpecificTip(rpc_test.canonical_tip.clone()),
+    ));
+
+    // a mempool transaction is not confirmed
+    requests.push(StacksHttpRequest::new_gettransaction(
+        addr.into(),
+        rpc_test.mempool_txids[0].clone(),
+        TipRequest::UseLatestAnchoredTip,
+    ));
+
+    // no such transaction
+    requests.push(StacksHttpRequest::new_gettransaction(
+        addr.into(),
+        Txid([0x21; 32]),
+        TipRequest::UseLatestAnchoredTip,
+    ));
+
+    let canonical_tip = rpc_test.canonical_tip.clone();
+    let mut responses = rpc_test.run(requests);
+
+    for _ in 0..2 {
+        let response = responses.remove(0);
+        debug!(
+            "Response:\n{}\n",
+            std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+        );
+
+        let resp = response.decode_gettransaction().unwrap();
+        assert_eq!(resp.txid, tx_contract.txid());
+        assert_eq!(resp.index_block_hash, canonical_tip);
+        assert_eq!(resp.block_height, 1);
+        assert_eq!(resp.tx_index, Some(1));
+        assert_eq!(resp.tx, to_hex(&tx_contract.serialize_to_vec()));
+
+        // (ok true)
+        assert_eq!(resp.result.unwrap(), "0x0703");
+        assert_eq!(resp.post_condition_aborted, Some(false));
+        assert!(resp.vm_error.is_none());
+        assert_eq!(resp.stx_burned, Some(0));
+        assert!(resp.execution_cost.unwrap().write_count > 0);
+        assert!(resp.events.unwrap().as_array().unwrap().is_empty());
+    }
+
+    for _ in 0..2 {
+        let response = responses.remove(0);
+        debug!(
+            "Response:\n{}\n",
+            std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+        );
+        let (preamble, _body) = response.destruct();
+        assert_eq!(preamble.status_code, 404);
+    }
+}
+
+#[test]
+fn test_try_make_response_txindex_disabled() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let mut rpc_test = TestRPC::setup(function_name!());
+    rpc_test.peer_2.chainstate().txindex = false;
+
+    let block = StacksChainState::load_block(
+        &rpc_test.peer_2.chainstate().blocks_path,
+        &rpc_test.consensus_hash,
+        &rpc_test.tip_hash,
+    )
+    .unwrap()
+    .unwrap();
+
+    let requests = vec![StacksHttpRequest::new_gettransaction(
+        addr.into(),
+        block.txs[0].txid(),
+        TipRequest::UseLatestAnchoredTip,
+    )];
+    let mut responses = rpc_test.run(requests);
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+    let (preamble, _body) = response.destruct();
+    assert_eq!(preamble.status_code, 404);
+}
@@ -14,7 +14,9 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::collections::HashMap;
 use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
 
 use clarity::vm::costs::ExecutionCost;
 use clarity::vm::types::{QualifiedContractIdentifier, StacksAddressExtensions};
@@ -41,6 +43,7 @@ use crate::chainstate::stacks::{
     TransactionAuth, TransactionPayload, TransactionPostConditionMode, TransactionVersion,
 };
 use crate::core::MemPoolDB;
+use crate::net::api::getsubscriptionevents::{SubscriptionEvent, SubscriptionEventsResponse};
 use crate::net::api::{prefix_hex, prefix_opt_hex};
 use crate::net::db::PeerDB;
 use crate::net::httpcore::{StacksHttpRequest, StacksHttpResponse};
@@ -49,11 +52,13 @@ use crate::net::rpc::ConversationHttp;
 use crate::net::test::{TestEventObserver, TestPeer, TestPeerConfig};
 use crate::net::tests::inv::nakamoto::make_nakamoto_peers_from_invs_ext;
 use crate::net::{
-    Attachment, AttachmentInstance, MemPoolEventDispatcher, RPCHandlerArgs, StackerDBConfig,
-    StacksNodeState, UrlString,
+    Attachment, AttachmentInstance, EventSubscriptionRegistry, MemPoolEventDispatcher,
+    RPCHandlerArgs, StackerDBConfig, StacksNodeState, UrlString,
 };
 
 mod callreadonly;
+mod callreadonly_batch;
+mod deletesubscription;
 mod get_tenures_fork_info;
 mod getaccount;
 mod getattachment;
@@ -65,25 +70,34 @@ mod getclaritymarfvalue;
 mod getclaritymetadata;
 mod getconstantval;
 mod getcontractabi;
+mod getcontractevents;
 mod getcontractsrc;
 mod getdatavar;
+mod getfeehistogram;
+mod getftbalance;
 mod getheaders;
 mod getinfo;
 mod getistraitimplemented;
 mod getmapentry;
+mod getmempoolnonces;
+mod getmempooltxs;
+mod getmempooltxstatus;
 mod getmicroblocks_confirmed;
 mod getmicroblocks_indexed;
 mod getmicroblocks_unconfirmed;
 mod getneighbors;
+mod getnftowner;
 mod getpoxinfo;
 mod getsigner;
 mod getsortition;
 mod getstackerdbchunk;
 mod getstackerdbmetadata;
 mod getstxtransfercost;
+mod getsubscriptionevents;
 mod gettenure;
 mod gettenureinfo;
 mod gettenuretip;
+mod gettransaction;
 mod gettransaction_unconfirmed;
 mod liststackerdbreplicas;
 mod postblock;
@@ -93,7 +107,9 @@ mod postfeerate;
 mod postmempoolquery;
 mod postmicroblock;
 mod poststackerdbchunk;
+mod postsubscription;
 mod posttransaction;
+mod posttransaction_simulate;
 
 const TEST_CONTRACT: &'static str = "
     (define-trait test-trait
@@ -156,8 +172,99 @@ const TEST_CONTRACT_UNCONFIRMED: &'static str = "
 (define-map test-map-unconfirmed int int)
 (map-set test-map-unconfirmed 3 4)
 (define-public (do-test) (ok u1))
+(define-fungible-token test-ft)
+(define-non-fungible-token test-nft uint)
+(ft-mint? test-ft u100 'ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R)
+(nft-mint? test-nft u1 'ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R)
 ";
 
+/// Deployed as `hello-events` by its own account, so that the contract event index has an event
+/// to serve without changing the other fixtures
+const TEST_EVENTS_CONTRACT: &'static str = "
+(define-data-var counter uint u0)
+(print u123)
+";
+
+/// Private key of the account which deploys `TEST_EVENTS_CONTRACT`
+const TEST_EVENTS_CONTRACT_PRIVKEY: &'static str =
+    "424242424242424242424242424242424242424242424242424242424242424201";
+
+/// The address of the account which deploys `TEST_EVENTS_CONTRACT`
+pub fn test_events_contract_addr() -> StacksAddress {
+    let privk = StacksPrivateKey::from_hex(TEST_EVENTS_CONTRACT_PRIVKEY).unwrap();
+    StacksAddress::from_public_keys(
+        C32_ADDRESS_VERSION_TESTNET_SINGLESIG,
+        &AddressHashMode::SerializeP2PKH,
+        1,
+        &vec![StacksPublicKey::from_private(&privk)],
+    )
+    .unwrap()
+}
+
+/// In-memory event subscription registry for testing the subscription endpoints.
+/// Every subscription starts out with one queued event per event key.
+#[derive(Default, Clone)]
+pub struct MockEventSubscriptions {
+    subscriptions: Arc<Mutex<HashMap<String, Vec<SubscriptionEvent>>>>,
+}
+
+impl EventSubscriptionRegistry for MockEventSubscriptions {
+    fn subscribe(&self, events_keys: &[String]) -> Result<String, String> {
+        if events_keys.iter().any(|key| key == "bad-key") {
+            return Err("Invalid event key: bad-key".to_string());
+        }
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+        let subscription_id = format!("{:032x}", subscriptions.len() + 1);
+        let events = events_keys
+            .iter()
+            .enumerate()
+            .map(|(i, key)| SubscriptionEvent {
+                seq: i as u64 + 1,
+                path: "/test_event".to_string(),
+                payload: serde_json::json!({ "key": key }),
+            })
+            .collect();
+        subscriptions.insert(subscription_id.clone(), events);
+        Ok(subscription_id)
+    }
+
+    fn poll(
+        &self,
+        subscription_id: &str,
+        after: u64,
+        max_events: usize,
+    ) -> Option<SubscriptionEventsResponse> {
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+        let events = subscriptions.get_mut(subscription_id)?;
+        events.retain(|event| event.seq > after);
+        Some(SubscriptionEventsResponse {
+            subscription_id: subscription_id.to_string(),
+            events: events.iter().take(max_events).cloned().collect(),
+            dropped: 0,
+        })
+    }
+
+    fn unsubscribe(&self, subscription_id: &str) -> bool {
+        self.subscriptions
+            .lock()
+            .unwrap()
+            .remove(subscription_id)
+            .is_some()
+    }
+
+    fn has_events(&self, subscription_id: &str, after: u64) -> bool {
+        self.subscriptions
+            .lock()
+            .unwrap()
+            .get(subscription_id)
+            .map_or(true, |events| events.iter().any(|event| event.seq > after))
+    }
+
+    fn clone_registry(&self) -> Box<dyn EventSubscriptionRegistry + Send> {
+        Box::new(self.clone())
+    }
+}
+
 /// This helper function drives I/O between a sender and receiver Http conversation.
 fn convo_send_recv(sender: &mut ConversationHttp, receiver: &mut ConversationHttp) -> () {
     let (mut pipe_read, mut pipe_write) = Pipe::new();
@@ -317,6 +424,16 @@ impl<'a> TestRPC<'a> {
         let mut peer_1 = TestPeer::new(peer_1_config);
         let mut peer_2 = TestPeer::new(peer_2_config);
 
+        // index processed transactions and contract events, so they can be queried
+        peer_1.chainstate().txindex = true;
+        peer_2.chainstate().txindex = true;
+        peer_1.chainstate().event_index = true;
+        peer_2.chainstate().event_index = true;
+        peer_1.coord.chain_state_db.txindex = true;
+        peer_2.coord.chain_state_db.txindex = true;
+        peer_1.coord.chain_state_db.event_index = true;
+        peer_2.coord.chain_state_db.event_index = true;
+
         // mine one block with a contract in it
         // first the coinbase
         // make a coinbase for this miner
@@ -354,6 +471,27 @@ impl<'a> TestRPC<'a> {
         tx_signer.sign_origin(&privk1).unwrap();
         let tx_contract_signed = tx_signer.get_tx().unwrap();
 
+        // and a contract which emits an event, from its own account
+        let privk_events = StacksPrivateKey::from_hex(TEST_EVENTS_CONTRACT_PRIVKEY).unwrap();
+        let mut tx_events_contract = StacksTransaction::new(
+            TransactionVersion::Testnet,
+            TransactionAuth::from_p2pkh(&privk_events).unwrap(),
+            TransactionPayload::new_smart_contract(
+                "hello-events",
+                &TEST_EVENTS_CONTRACT.to_string(),
+                None,
+            )
+            .unwrap(),
+        );
+
+        tx_events_contract.chain_id = 0x80000000;
+        tx_events_contract.auth.set_origin_nonce(0);
+        tx_events_contract.set_tx_fee(0);
+
+        let mut tx_signer = StacksTransactionSigner::new(&tx_events_contract);
+        tx_signer.sign_origin(&privk_events).unwrap();
+        let tx_events_contract_signed = tx_signer.get_tx().unwrap();
+
         // update account and state in a microblock that will be unconfirmed
         let mut tx_cc = StacksTransaction::new(
             TransactionVersion::Testnet,
@@ -473,7 +611,11 @@ impl<'a> TestRPC<'a> {
                         block_builder,
                         chainstate,
                         &sortdb.index_handle_at_tip(),
-                        vec![tx_coinbase_signed.clone(), tx_contract_signed.clone()],
+                        vec![
+                            tx_coinbase_signed.clone(),
+                            tx_contract_signed.clone(),
+                            tx_events_contract_signed.clone(),
+                        ],
                     )
                     .unwrap();
 
@@ -953,6 +1095,26 @@ impl<'a> TestRPC<'a> {
         self,
         requests: Vec<StacksHttpRequest>,
         event_observer: Option<&dyn MemPoolEventDispatcher>,
+    ) -> Vec<StacksHttpResponse> {
+        self.run_with_rpc_args(requests, event_observer, None)
+    }
+
+    /// Run zero or more HTTP requests on this setup RPC test harness, with the given event
+    /// subscription registry.
+    /// Return the list of responses.
+    pub fn run_with_subscriptions(
+        self,
+        requests: Vec<StacksHttpRequest>,
+        event_subscriptions: &dyn EventSubscriptionRegistry,
+    ) -> Vec<StacksHttpResponse> {
+        self.run_with_rpc_args(requests, None, Some(event_subscriptions))
+    }
+
+    fn run_with_rpc_args(
+        self,
+        requests: Vec<StacksHttpRequest>,
+        event_observer: Option<&dyn MemPoolEventDispatcher>,
+        event_subscriptions: Option<&dyn EventSubscriptionRegistry>,
     ) -> Vec<StacksHttpResponse> {
         let mut peer_1 = self.peer_1;
         let mut peer_2 = self.peer_2;
@@ -989,6 +1151,7 @@ impl<'a> TestRPC<'a> {
             {
                 let mut rpc_args = RPCHandlerArgs::default();
                 rpc_args.event_observer = event_observer;
+                rpc_args.event_subscriptions = event_subscriptions;
                 let mut node_state = StacksNodeState::new(
                     &mut peer_1.network,
                     &peer_1_sortdb,
@@ -1033,6 +1196,7 @@ impl<'a> TestRPC<'a> {
             {
                 let mut rpc_args = RPCHandlerArgs::default();
                 rpc_args.event_observer = event_observer;
+                rpc_args.event_subscriptions = event_subscriptions;
                 let mut node_state = StacksNodeState::new(
                     &mut peer_2.network,
                     &peer_2_sortdb,
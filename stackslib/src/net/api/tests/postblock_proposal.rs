@@ -188,6 +188,8 @@ impl MemPoolEventDispatcher for ProposalTestObserver {
 
     fn mempool_txs_dropped(&self, txids: Vec<Txid>, reason: mempool::MemPoolDropReason) {}
 
+    fn mempool_txs_stuck(&self, stuck_txs: Vec<mempool::MemPoolStuckTx>) {}
+
     fn mined_block_event(
         &self,
         target_burn_height: u64,
@@ -0,0 +1,122 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use super::{MockEventSubscriptions, TestRPC};
+use crate::net::api::*;
+use crate::net::connection::ConnectionOptions;
+use crate::net::http::HttpContentType;
+use crate::net::httpcore::{RPCRequestHandler, StacksHttp, StacksHttpRequest};
+use crate::net::ProtocolFamily;
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    let events_keys = vec!["stx".to_string(), "burn_blocks".to_string()];
+    let request = StacksHttpRequest::new_post_subscription(addr.into(), events_keys.clone());
+    assert_eq!(request.preamble().content_type, Some(HttpContentType::JSON));
+    let bytes = request.try_serialize().unwrap();
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = postsubscription::RPCPostSubscriptionRequestHandler::new();
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    assert_eq!(handler.events_keys, Some(events_keys));
+
+    // parsed request consumes headers that would not be in a constructed reqeuest
+    parsed_request.clear_headers();
+    let (preamble, _contents) = parsed_request.destruct();
+
+    assert_eq!(&preamble, request.preamble());
+
+    handler.restart();
+    assert!(handler.events_keys.is_none());
+
+    // an empty list of keys is rejected
+    let request = StacksHttpRequest::new_post_subscription(addr.into(), vec![]);
+    let bytes = request.try_serialize().unwrap();
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = postsubscription::RPCPostSubscriptionRequestHandler::new();
+    assert!(http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .is_err());
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let mut requests = vec![];
+
+    // subscribe
+    requests.push(StacksHttpRequest::new_post_subscription(
+        addr.into(),
+        vec!["stx".to_string()],
+    ));
+
+    // subscribe with an invalid key
+    requests.push(StacksHttpRequest::new_post_subscription(
+        addr.into(),
+        vec!["bad-key".to_string()],
+    ));
+
+    let event_subscriptions = MockEventSubscriptions::default();
+    let test_rpc = TestRPC::setup(function_name!());
+    let mut responses = test_rpc.run_with_subscriptions(requests, &event_subscriptions);
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    let resp = response.decode_subscription_response().unwrap();
+    assert_eq!(resp.subscription_id, format!("{:032x}", 1));
+    assert_eq!(resp.events_keys, vec!["stx".to_string()]);
+
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    let (preamble, _body) = response.destruct();
+    assert_eq!(preamble.status_code, 400);
+
+    // subscriptions are not enabled
+    let test_rpc = TestRPC::setup(function_name!());
+    let mut responses = test_rpc.run(vec![StacksHttpRequest::new_post_subscription(
+        addr.into(),
+        vec!["stx".to_string()],
+    )]);
+
+    let response = responses.remove(0);
+    let (preamble, _body) = response.destruct();
+    assert_eq!(preamble.status_code, 404);
+}
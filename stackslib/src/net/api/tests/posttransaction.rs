@@ -25,14 +25,17 @@ use stacks_common::types::Address;
 
 use super::TestRPC;
 use crate::chainstate::stacks::{
-    StacksTransaction, StacksTransactionSigner, TransactionAuth, TransactionPayload,
-    TransactionVersion,
+    StacksTransaction, StacksTransactionSigner, TokenTransferMemo, TransactionAuth,
+    TransactionPayload, TransactionVersion,
 };
+use crate::core::mempool::{MemPoolAdmissionAction, MemPoolAdmissionPolicy, MemPoolAdmissionRule};
 use crate::core::BLOCK_LIMIT_MAINNET_21;
 use crate::net::api::*;
 use crate::net::connection::ConnectionOptions;
+use crate::net::http::HttpResponsePayload;
 use crate::net::httpcore::{
     HttpRequestContentsExtensions, RPCRequestHandler, StacksHttp, StacksHttpRequest,
+    StacksHttpResponse,
 };
 use crate::net::{Attachment, ProtocolFamily, TipRequest};
 
@@ -239,3 +242,90 @@ fn test_try_make_response() {
     let (preamble, body) = response.destruct();
     assert_eq!(preamble.status_code, 400);
 }
+
+#[test]
+fn test_try_make_response_rate_limited() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let mut rpc_test = TestRPC::setup(function_name!());
+    let privk = rpc_test.privk2.clone();
+    let origin = StacksAddress::p2pkh(false, &StacksPublicKey::from_private(&privk));
+
+    // admit at most two of the origin's transactions per hour
+    rpc_test
+        .peer_1
+        .mempool
+        .as_mut()
+        .unwrap()
+        .set_admission_policy(MemPoolAdmissionPolicy::new(
+            vec![MemPoolAdmissionRule {
+                name: "limit-origin".into(),
+                origin: Some(origin),
+                sponsor: None,
+                contract: None,
+                function: None,
+                payload_type: None,
+                action: MemPoolAdmissionAction::RateLimit {
+                    max_txs: 2,
+                    window_secs: 3600,
+                },
+            }],
+            true,
+        ));
+
+    let make_transfer = |nonce: u64, amount: u64| {
+        let mut tx = StacksTransaction::new(
+            TransactionVersion::Testnet,
+            TransactionAuth::from_p2pkh(&privk).unwrap(),
+            TransactionPayload::TokenTransfer(
+                StacksAddress::burn_address(false).to_account_principal(),
+                amount,
+                TokenTransferMemo([0u8; 34]),
+            ),
+        );
+        tx.chain_id = 0x80000000;
+        tx.auth.set_origin_nonce(nonce);
+        tx.set_tx_fee(123);
+
+        let mut tx_signer = StacksTransactionSigner::new(&tx);
+        tx_signer.sign_origin(&privk).unwrap();
+        tx_signer.get_tx().unwrap()
+    };
+
+    let txs = [
+        // admitted
+        make_transfer(0, 1000),
+        // a replacement which does not raise the fee is rejected, so it is not counted against
+        // the rate limit
+        make_transfer(0, 2000),
+        // admitted
+        make_transfer(1, 1000),
+        // rate-limited
+        make_transfer(2, 1000),
+    ];
+    let requests = txs
+        .iter()
+        .map(|tx| {
+            StacksHttpRequest::new_post_transaction_with_attachment(addr.into(), tx.clone(), None)
+        })
+        .collect();
+
+    let mut responses = rpc_test.run(requests);
+
+    let reject_reason = |response: StacksHttpResponse| {
+        let (preamble, body) = response.destruct();
+        assert_eq!(preamble.status_code, 400);
+        let HttpResponsePayload::JSON(body) = body else {
+            panic!("Expected JSON response");
+        };
+        body["reason"].as_str().unwrap().to_string()
+    };
+
+    assert_eq!(responses.remove(0).decode_txid().unwrap(), txs[0].txid());
+    assert_eq!(
+        reject_reason(responses.remove(0)),
+        "ConflictingNonceInMempool"
+    );
+    assert_eq!(responses.remove(0).decode_txid().unwrap(), txs[2].txid());
+    assert_eq!(reject_reason(responses.remove(0)), "RateLimitedByPolicy");
+}
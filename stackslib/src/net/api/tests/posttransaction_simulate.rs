@@ -0,0 +1,257 @@
+// Copyright (C) 2013-2020 Blockstack PBC, a public benefit corporation
+// Copyright (C) 2020-2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use clarity::vm::types::StacksAddressExtensions;
+use stacks_common::address::{AddressHashMode, C32_ADDRESS_VERSION_TESTNET_SINGLESIG};
+use stacks_common::types::chainstate::{
+    StacksAddress, StacksBlockId, StacksPrivateKey, StacksPublicKey,
+};
+
+use super::TestRPC;
+use crate::chainstate::stacks::{
+    StacksTransaction, StacksTransactionSigner, TokenTransferMemo, TransactionAuth,
+    TransactionPayload, TransactionVersion,
+};
+use crate::net::api::*;
+use crate::net::connection::ConnectionOptions;
+use crate::net::httpcore::{
+    HttpPreambleExtensions, HttpRequestContentsExtensions, RPCRequestHandler, StacksHttp,
+    StacksHttpRequest,
+};
+use crate::net::{ProtocolFamily, TipRequest};
+
+fn make_signed_tx(
+    privk: &StacksPrivateKey,
+    payload: TransactionPayload,
+    nonce: u64,
+) -> StacksTransaction {
+    let mut tx = StacksTransaction::new(
+        TransactionVersion::Testnet,
+        TransactionAuth::from_p2pkh(privk).unwrap(),
+        payload,
+    );
+    tx.chain_id = 0x80000000;
+    tx.auth.set_origin_nonce(nonce);
+    tx.set_tx_fee(123);
+
+    let mut tx_signer = StacksTransactionSigner::new(&tx);
+    tx_signer.sign_origin(privk).unwrap();
+    tx_signer.get_tx().unwrap()
+}
+
+fn test_addr(privk: &StacksPrivateKey) -> StacksAddress {
+    StacksAddress::from_public_keys(
+        C32_ADDRESS_VERSION_TESTNET_SINGLESIG,
+        &AddressHashMode::SerializeP2PKH,
+        1,
+        &vec![StacksPublicKey::from_private(privk)],
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_try_parse_request() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+    let mut http = StacksHttp::new(addr.clone(), &ConnectionOptions::default());
+
+    // ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R
+    let privk1 = StacksPrivateKey::from_hex(
+        "9f1f85a512a96a244e4c0d762788500687feb97481639572e3bffbd6860e6ab001",
+    )
+    .unwrap();
+    let addr1 = test_addr(&privk1);
+
+    let tx_cc = make_signed_tx(
+        &privk1,
+        TransactionPayload::new_contract_call(addr1.clone(), "hello-world", "add-unit", vec![])
+            .unwrap(),
+        2,
+    );
+
+    let request = StacksHttpRequest::new_simulate_transaction(
+        addr.into(),
+        tx_cc.clone(),
+        TipRequest::SpecificTip(StacksBlockId([0x22; 32])),
+    );
+    assert_eq!(
+        request.contents().tip_request(),
+        TipRequest::SpecificTip(StacksBlockId([0x22; 32]))
+    );
+    let bytes = request.try_serialize().unwrap();
+
+    let (parsed_preamble, offset) = http.read_preamble(&bytes).unwrap();
+    let mut handler = posttransaction_simulate::RPCSimulateTransactionRequestHandler::new();
+    let mut parsed_request = http
+        .handle_try_parse_request(
+            &mut handler,
+            &parsed_preamble.expect_request(),
+            &bytes[offset..],
+        )
+        .unwrap();
+
+    assert_eq!(handler.tx, Some(tx_cc));
+
+    // parsed request consumes headers that would not be in a constructed reqeuest
+    parsed_request.clear_headers();
+    let (preamble, _contents) = parsed_request.destruct();
+
+    assert_eq!(&preamble, request.preamble());
+
+    handler.restart();
+    assert!(handler.tx.is_none());
+}
+
+#[test]
+fn test_try_make_response() {
+    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 33333);
+
+    let rpc_test = TestRPC::setup(function_name!());
+    let addr1 = test_addr(&rpc_test.privk1);
+    let recipient = test_addr(&StacksPrivateKey::new());
+
+    let tx_stx_transfer = make_signed_tx(
+        &rpc_test.privk2,
+        TransactionPayload::TokenTransfer(
+            recipient.to_account_principal(),
+            1000,
+            TokenTransferMemo([0u8; 34]),
+        ),
+        0,
+    );
+    let tx_cc = make_signed_tx(
+        &rpc_test.privk1,
+        TransactionPayload::new_contract_call(addr1.clone(), "hello-world", "add-unit", vec![])
+            .unwrap(),
+        2,
+    );
+    let tx_bad_nonce = make_signed_tx(
+        &rpc_test.privk2,
+        TransactionPayload::TokenTransfer(
+            recipient.to_account_principal(),
+            1000,
+            TokenTransferMemo([0u8; 34]),
+        ),
+        5,
+    );
+
+    let mut requests = vec![];
+
+    // simulate a token transfer
+    requests.push(StacksHttpRequest::new_simulate_transaction(
+        addr.into(),
+        tx_stx_transfer.clone(),
+        TipRequest::UseLatestAnchoredTip,
+    ));
+
+    // simulating it again gives the same answer, since nothing was committed
+    requests.push(StacksHttpRequest::new_simulate_transaction(
+        addr.into(),
+        tx_stx_transfer.clone(),
+        TipRequest::UseLatestAnchoredTip,
+    ));
+
+    // simulate a contract-call
+    requests.push(StacksHttpRequest::new_simulate_transaction(
+        addr.into(),
+        tx_cc.clone(),
+        TipRequest::SpecificTip(rpc_test.canonical_tip.clone()),
+    ));
+
+    // simulate a transaction that cannot be mined
+    requests.push(StacksHttpRequest::new_simulate_transaction(
+        addr.into(),
+        tx_bad_nonce.clone(),
+        TipRequest::UseLatestAnchoredTip,
+    ));
+
+    // simulate on a non-existent tip
+    requests.push(StacksHttpRequest::new_simulate_transaction(
+        addr.into(),
+        tx_stx_transfer.clone(),
+        TipRequest::SpecificTip(StacksBlockId([0x11; 32])),
+    ));
+
+    let mut responses = rpc_test.run(requests);
+
+    // token transfer, twice
+    for _ in 0..2 {
+        let response = responses.remove(0);
+        debug!(
+            "Response:\n{}\n",
+            std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+        );
+        assert_eq!(
+            response.preamble().get_canonical_stacks_tip_height(),
+            Some(1)
+        );
+
+        let resp = response.decode_simulate_transaction_response().unwrap();
+        assert!(resp.okay);
+        assert!(resp.cause.is_none());
+        assert_eq!(resp.txid, tx_stx_transfer.txid());
+        assert!(!resp.post_condition_aborted);
+        assert_eq!(resp.fee, 123);
+
+        // (ok true)
+        assert_eq!(resp.result.unwrap(), "0x0703");
+        assert_eq!(resp.events.len(), 1);
+        assert_eq!(resp.events[0]["type"], "stx_transfer_event");
+        assert_eq!(resp.events[0]["stx_transfer_event"]["amount"], "1000");
+    }
+
+    // contract-call
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    let resp = response.decode_simulate_transaction_response().unwrap();
+    assert!(resp.okay);
+    assert_eq!(resp.txid, tx_cc.txid());
+
+    // (ok 1)
+    assert_eq!(
+        resp.result.unwrap(),
+        "0x070000000000000000000000000000000001"
+    );
+    assert!(resp.execution_cost.runtime > 0);
+    assert!(resp.execution_cost.write_count > 0);
+
+    // bad nonce
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    let resp = response.decode_simulate_transaction_response().unwrap();
+    assert!(!resp.okay);
+    assert!(resp.result.is_none());
+    assert!(resp.cause.unwrap().contains("Bad nonce"));
+
+    // non-existent tip
+    let response = responses.remove(0);
+    debug!(
+        "Response:\n{}\n",
+        std::str::from_utf8(&response.try_serialize().unwrap()).unwrap()
+    );
+
+    let (preamble, _payload) = response.destruct();
+    assert_eq!(preamble.status_code, 404);
+}
@@ -36,6 +36,13 @@ pub trait HttpChunkGenerator: Send {
     fn generate_next_chunk(&mut self) -> Result<Vec<u8>, String>;
     fn hint_chunk_size(&self) -> usize;
 
+    /// Is the next chunk ready to be generated?  A generator which is waiting for data that
+    /// does not exist yet returns false, and is asked again on the HTTP server's next pass
+    /// instead of blocking the thread it runs on.
+    fn is_ready(&mut self) -> bool {
+        true
+    }
+
     /// Stream one chunk to the pipe writer.  This never blocks.
     /// Returns Ok(num-bytes > 0) if there are more chunks (i.e. the caller should call this again)
     /// Returns Ok(0) if there are no more chunks (i.e. the caller should not call this again)
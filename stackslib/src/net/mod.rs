@@ -83,6 +83,7 @@ use crate::core::mempool::*;
 use crate::core::{StacksEpoch, POX_REWARD_CYCLE_LENGTH};
 use crate::cost_estimates::metrics::CostMetric;
 use crate::cost_estimates::{CostEstimator, FeeEstimator, FeeRateEstimate};
+use crate::net::api::getsubscriptionevents::SubscriptionEventsResponse;
 use crate::net::atlas::{Attachment, AttachmentInstance};
 use crate::net::dns::*;
 use crate::net::http::error::{HttpNotFound, HttpServerError};
@@ -635,6 +636,41 @@ pub struct RPCHandlerArgs<'a> {
     pub cost_metric: Option<&'a dyn CostMetric>,
     /// coordinator channels
     pub coord_comms: Option<&'a CoordinatorChannels>,
+    /// runtime event subscriptions
+    pub event_subscriptions: Option<&'a dyn EventSubscriptionRegistry>,
+}
+
+/// Registry of event subscriptions which RPC clients can create, poll, and cancel at runtime.
+/// A subscription receives the same payloads that an event observer with the same event keys
+/// would receive, but they are queued in the node until the client fetches them.
+pub trait EventSubscriptionRegistry {
+    /// Subscribe to the given event keys, which have the same format as an event observer's
+    /// `events_keys`.
+    /// Returns the new subscription's ID on success.
+    /// Returns an error message if a key is invalid or if there are too many subscriptions.
+    fn subscribe(&self, events_keys: &[String]) -> Result<String, String>;
+
+    /// Discard all of a subscription's events up to and including sequence number `after`, and
+    /// return up to `max_events` of the events that follow.
+    /// Returns None if there is no such subscription.
+    fn poll(
+        &self,
+        subscription_id: &str,
+        after: u64,
+        max_events: usize,
+    ) -> Option<SubscriptionEventsResponse>;
+
+    /// Cancel a subscription.
+    /// Returns false if there is no such subscription.
+    fn unsubscribe(&self, subscription_id: &str) -> bool;
+
+    /// Does a subscription have events with sequence numbers after `after`?
+    /// Also returns true if there is no such subscription, since there is nothing to wait for.
+    fn has_events(&self, subscription_id: &str, after: u64) -> bool;
+
+    /// Make another handle to the same subscriptions, which a long-poll can hold on to after
+    /// its request handler returns.
+    fn clone_registry(&self) -> Box<dyn EventSubscriptionRegistry + Send>;
 }
 
 impl<'a> RPCHandlerArgs<'a> {
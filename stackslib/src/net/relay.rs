@@ -15,6 +15,7 @@
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
 use std::{cmp, mem};
 
 use clarity::vm::ast::errors::{ParseError, ParseErrors};
@@ -27,12 +28,14 @@ use rand::prelude::*;
 use rand::{thread_rng, Rng};
 use stacks_common::address::public_keys_to_address_hash;
 use stacks_common::codec::MAX_PAYLOAD_LEN;
-use stacks_common::types::chainstate::{BurnchainHeaderHash, PoxId, SortitionId, StacksBlockId};
+use stacks_common::types::chainstate::{
+    BurnchainHeaderHash, PoxId, SortitionId, StacksAddress, StacksBlockId,
+};
 use stacks_common::types::{MempoolCollectionBehavior, StacksEpochId};
 use stacks_common::util::hash::Sha512Trunc256Sum;
 use stacks_common::util::{get_epoch_time_ms, get_epoch_time_secs};
 
-use crate::burnchains::{Burnchain, BurnchainView};
+use crate::burnchains::{Burnchain, BurnchainView, Txid};
 use crate::chainstate::burn::db::sortdb::{
     SortitionDB, SortitionDBConn, SortitionHandle, SortitionHandleConn,
 };
@@ -69,6 +72,11 @@ pub const MAX_RELAYER_STATS: usize = 4096;
 pub const MAX_RECENT_MESSAGES: usize = 256;
 pub const MAX_RECENT_MESSAGE_AGE: usize = 600; // seconds; equal to the expected epoch length
 pub const RELAY_DUPLICATE_INFERENCE_WARMUP: usize = 128;
+/// How often to look for mempool transactions stuck behind a missing nonce, in milliseconds
+pub const STUCK_MEMPOOL_TXS_CHECK_INTERVAL_MS: u128 = 60_000;
+/// How long one pass of the relayer may spend looking for stuck mempool transactions, in
+/// milliseconds.  A check which runs out of time carries on from where it stopped on the next pass.
+pub const STUCK_MEMPOOL_TXS_CHECK_MAX_MS: u64 = 100;
 
 #[cfg(any(test, feature = "testing"))]
 pub mod fault_injection {
@@ -126,6 +134,23 @@ pub struct Relayer {
     /// Maps to tenure ID and timestamp, so we can garbage-collect.
     /// Timestamp is in milliseconds
     recently_sent_nakamoto_blocks: HashMap<StacksBlockId, (ConsensusHash, u128)>,
+    /// Progress of the periodic check for stuck mempool transactions
+    stuck_mempool_txs: StuckMempoolTxsCheck,
+}
+
+/// State of the relayer's periodic check for mempool transactions which are stuck behind a
+/// missing nonce.  Each check goes through the mempool's accounts in order, a bounded amount of
+/// time per pass, and only transactions which were not stuck in the previous check are reported.
+#[derive(Debug, Default)]
+struct StuckMempoolTxsCheck {
+    /// When the last check started, in milliseconds since the epoch
+    last_check_start_ms: u128,
+    /// If a check is under way, the last account it looked at
+    resume_after: Option<StacksAddress>,
+    /// Stuck transactions found by the last complete check
+    reported_txids: HashSet<Txid>,
+    /// Stuck transactions found so far by the check under way
+    found_txids: HashSet<Txid>,
 }
 
 #[derive(Debug)]
@@ -591,6 +616,7 @@ impl Relayer {
             connection_opts,
             stacker_dbs,
             recently_sent_nakamoto_blocks: HashMap::new(),
+            stuck_mempool_txs: StuckMempoolTxsCheck::default(),
         }
     }
 
@@ -2897,6 +2923,74 @@ impl Relayer {
         num_new_nakamoto_blocks
     }
 
+    /// Tell mempool event observers about transactions which have become stuck behind a missing
+    /// nonce since the last check.  A check starts at most once every
+    /// `STUCK_MEMPOOL_TXS_CHECK_INTERVAL_MS`, and spends at most `STUCK_MEMPOOL_TXS_CHECK_MAX_MS`
+    /// per call, so a large mempool may take several calls to check.
+    pub fn report_stuck_mempool_txs(
+        &mut self,
+        sortdb: &SortitionDB,
+        chainstate: &mut StacksChainState,
+        mempool: &MemPoolDB,
+        event_observer: &dyn MemPoolEventDispatcher,
+    ) -> Result<(), net_error> {
+        let check = &mut self.stuck_mempool_txs;
+        let now = get_epoch_time_ms();
+        if check.resume_after.is_none() {
+            if now < check.last_check_start_ms + STUCK_MEMPOOL_TXS_CHECK_INTERVAL_MS {
+                return Ok(());
+            }
+            check.last_check_start_ms = now;
+        }
+
+        let Some(tip) = NakamotoChainState::get_canonical_block_header(chainstate.db(), sortdb)?
+        else {
+            return Ok(());
+        };
+        let tip = tip.index_block_hash();
+        let deadline = Instant::now() + Duration::from_millis(STUCK_MEMPOOL_TXS_CHECK_MAX_MS);
+        let Some(result) = chainstate.maybe_read_only_clarity_tx(
+            &sortdb.index_handle_at_block(chainstate, &tip)?,
+            &tip,
+            |clarity_tx| {
+                mempool.find_stuck_txs(
+                    clarity_tx,
+                    check.resume_after.as_ref(),
+                    MAXIMUM_MEMPOOL_STUCK_TXS_REPORTED,
+                    deadline,
+                )
+            },
+        )?
+        else {
+            return Ok(());
+        };
+        let (stuck_txs, resume_after) = result?;
+
+        let new_stuck_txs: Vec<_> = stuck_txs
+            .into_iter()
+            .filter(|stuck_tx| {
+                check.found_txids.insert(stuck_tx.txid.clone())
+                    && !check.reported_txids.contains(&stuck_tx.txid)
+            })
+            .collect();
+
+        check.resume_after = resume_after;
+        if check.resume_after.is_none() {
+            // the check is complete.  Forget the transactions which are no longer stuck, so that
+            // they are reported again if they get stuck again.
+            check.reported_txids = mem::take(&mut check.found_txids);
+        } else {
+            check
+                .reported_txids
+                .extend(new_stuck_txs.iter().map(|stuck_tx| stuck_tx.txid.clone()));
+        }
+
+        if !new_stuck_txs.is_empty() {
+            event_observer.mempool_txs_stuck(new_stuck_txs);
+        }
+        Ok(())
+    }
+
     #[cfg_attr(test, mutants::skip)]
     /// Process new transactions
     /// Returns the list of accepted txs
@@ -2932,6 +3026,17 @@ impl Relayer {
         )
         .unwrap_or(vec![]);
 
+        if let Some(event_observer) = event_observer {
+            if let Err(e) = self.report_stuck_mempool_txs(
+                sortdb,
+                chainstate,
+                mempool,
+                event_observer.as_mempool_event_dispatcher(),
+            ) {
+                warn!("Failed to find stuck mempool transactions"; "err" => ?e);
+            }
+        }
+
         if new_txs.len() > 0 {
             debug!(
                 "{:?}: Send {} transactions to neighbors",
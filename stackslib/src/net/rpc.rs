@@ -307,6 +307,11 @@ impl ConversationHttp {
             do_keep_alive = *keep_alive;
 
             while !drained_stream {
+                if !http_response.is_ready() {
+                    // the reply is waiting for its data (e.g. a long-poll), so try again on the
+                    // next pass instead of blocking
+                    break;
+                }
                 // write out the last-generated data into the write-end of the reply handle's pipe
                 if let Some(pipe_fd) = reply.inner_pipe_out() {
                     let num_written = http_response.pipe_out(pipe_fd)?;
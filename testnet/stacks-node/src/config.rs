@@ -24,6 +24,7 @@ use std::{cmp, fs, thread};
 
 use clarity::vm::costs::ExecutionCost;
 use clarity::vm::types::{AssetIdentifier, PrincipalData, QualifiedContractIdentifier};
+use clarity::vm::ClarityName;
 use lazy_static::lazy_static;
 use rand::RngCore;
 use serde::Deserialize;
@@ -36,18 +37,24 @@ use stacks::chainstate::stacks::index::marf::MARFOpenOpts;
 use stacks::chainstate::stacks::index::storage::TrieHashCalculationMode;
 use stacks::chainstate::stacks::miner::{BlockBuilderSettings, MinerStatus};
 use stacks::chainstate::stacks::MAX_BLOCK_LEN;
-use stacks::core::mempool::{MemPoolWalkSettings, MemPoolWalkTxTypes};
+use stacks::core::mempool::{
+    MemPoolAdmissionAction, MemPoolAdmissionPolicy, MemPoolAdmissionRule, MemPoolLimits,
+    MemPoolWalkSettings, MemPoolWalkStrategy, MemPoolWalkTxTypes,
+};
 use stacks::core::{
     MemPoolDB, StacksEpoch, StacksEpochExtension, StacksEpochId,
     BITCOIN_TESTNET_FIRST_BLOCK_HEIGHT, BITCOIN_TESTNET_STACKS_25_BURN_HEIGHT,
     BITCOIN_TESTNET_STACKS_25_REORGED_HEIGHT, CHAIN_ID_MAINNET, CHAIN_ID_TESTNET,
     PEER_VERSION_MAINNET, PEER_VERSION_TESTNET,
 };
-use stacks::cost_estimates::fee_medians::WeightedMedianFeeRateEstimator;
+use stacks::cost_estimates::fee_inclusion_time::{self, TimeToInclusionFeeRateEstimator};
+use stacks::cost_estimates::fee_medians::{self, WeightedMedianFeeRateEstimator};
 use stacks::cost_estimates::fee_rate_fuzzer::FeeRateFuzzer;
-use stacks::cost_estimates::fee_scalar::ScalarFeeRateEstimator;
+use stacks::cost_estimates::fee_scalar::{self, ScalarFeeRateEstimator};
 use stacks::cost_estimates::metrics::{CostMetric, ProportionalDotProduct, UnitMetric};
-use stacks::cost_estimates::{CostEstimator, FeeEstimator, PessimisticEstimator, UnitEstimator};
+use stacks::cost_estimates::{
+    pessimistic, CostEstimator, FeeEstimator, PessimisticEstimator, UnitEstimator,
+};
 use stacks::net::atlas::AtlasConfig;
 use stacks::net::connection::ConnectionOptions;
 use stacks::net::{Neighbor, NeighborAddress, NeighborKey};
@@ -559,13 +566,16 @@ impl Config {
             .make_cost_metric()
             .unwrap_or_else(|| Box::new(UnitMetric));
 
-        MemPoolDB::open(
+        let mut mempool = MemPoolDB::open(
             self.is_mainnet(),
             self.burnchain.chain_id,
             &self.get_chainstate_path_str(),
             cost_estimator,
             metric,
-        )
+        )?;
+        mempool.limits = self.node.get_mempool_limits();
+        mempool.set_admission_policy(self.node.get_mempool_admission_policy());
+        Ok(mempool)
     }
 
     /// Load up a Burnchain and apply config settings to it.
@@ -879,11 +889,13 @@ impl Config {
                         .iter()
                         .map(|e| EventKeyType::from_string(e).unwrap())
                         .collect();
+                    let sink = EventSinkType::from_config_file(&observer)?;
 
                     observers.insert(EventObserverConfig {
                         endpoint: observer.endpoint,
                         events_keys,
                         timeout_ms: observer.timeout_ms.unwrap_or(1_000),
+                        sink,
                     });
                 }
                 observers
@@ -897,6 +909,7 @@ impl Config {
                 endpoint: val,
                 events_keys: vec![EventKeyType::AnyEvent],
                 timeout_ms: 1_000,
+                sink: EventSinkType::Http,
             });
         };
 
@@ -1068,6 +1081,7 @@ impl Config {
                 filter_origins: miner_config.filter_origins,
                 tenure_cost_limit_per_block_percentage: miner_config
                     .tenure_cost_limit_per_block_percentage,
+                strategy: miner_config.mempool_walk_strategy,
             },
             miner_status,
             confirm_microblocks: false,
@@ -1110,6 +1124,7 @@ impl Config {
                 filter_origins: miner_config.filter_origins,
                 tenure_cost_limit_per_block_percentage: miner_config
                     .tenure_cost_limit_per_block_percentage,
+                strategy: miner_config.mempool_walk_strategy,
             },
             miner_status,
             confirm_microblocks: true,
@@ -1658,6 +1673,33 @@ pub struct NodeConfig {
     pub chain_liveness_poll_time_secs: u64,
     /// stacker DBs we replicate
     pub stacker_dbs: Vec<QualifiedContractIdentifier>,
+    /// Record every processed transaction and its receipt, so that it can be looked up by txid
+    /// over RPC.  Defaults to false.
+    pub txindex: bool,
+    /// Record every contract event emitted by a processed transaction, so that it can be looked
+    /// up by contract and topic over RPC.  Defaults to false.
+    pub event_index: bool,
+    /// Maximum number of event subscriptions that RPC clients may hold open at once.
+    /// Defaults to 0, which disables the event subscription endpoints.
+    pub max_event_subscriptions: usize,
+    /// Maximum number of undelivered events queued per event subscription.  The oldest events
+    /// are dropped once a subscription's queue is full.
+    pub event_subscription_max_pending: usize,
+    /// Maximum number of transactions the mempool may hold.  Once exceeded, the transactions
+    /// with the lowest fee rate are evicted.  Defaults to None (unbounded).
+    pub mempool_max_txs: Option<u64>,
+    /// Maximum total size, in bytes, of the transactions the mempool may hold.  Once exceeded,
+    /// the transactions with the lowest fee rate are evicted.  Defaults to None (unbounded).
+    pub mempool_max_bytes: Option<u64>,
+    /// Maximum number of pending transactions a single origin account may have in the mempool.
+    /// Defaults to None (unbounded).
+    pub mempool_max_txs_per_origin: Option<u64>,
+    /// Rules for which transactions received from clients and peers to admit into the mempool.
+    /// The first rule which matches a transaction applies.  Defaults to no rules.
+    pub mempool_admission_rules: Vec<MemPoolAdmissionRule>,
+    /// Whether to admit transactions which match none of `mempool_admission_rules`.
+    /// Defaults to true.
+    pub mempool_admission_default_allow: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -1669,6 +1711,7 @@ pub enum CostEstimatorName {
 pub enum FeeEstimatorName {
     ScalarFeeRate,
     FuzzedWeightedMedianFeeRate,
+    TimeToInclusion,
 }
 
 #[derive(Clone, Debug)]
@@ -1710,6 +1753,8 @@ impl FeeEstimatorName {
             FeeEstimatorName::ScalarFeeRate
         } else if &s.to_lowercase() == "fuzzed_weighted_median_fee_rate" {
             FeeEstimatorName::FuzzedWeightedMedianFeeRate
+        } else if &s.to_lowercase() == "time_to_inclusion" {
+            FeeEstimatorName::TimeToInclusion
         } else {
             panic!("Bad fee estimator name supplied in configuration file: {s}");
         }
@@ -1738,6 +1783,9 @@ pub struct FeeEstimationConfig {
     /// If using WeightedMedianFeeRateEstimator, the window size to use. See comments on
     /// WeightedMedianFeeRateEstimator.
     pub fee_rate_window_size: u64,
+    /// If using TimeToInclusionFeeRateEstimator, the number of mined transactions to sample. See
+    /// comments on TimeToInclusionFeeRateEstimator.
+    pub fee_rate_max_samples: u32,
 }
 
 impl Default for FeeEstimationConfig {
@@ -1748,7 +1796,8 @@ impl Default for FeeEstimationConfig {
             cost_metric: Some(CostMetricName::default()),
             log_error: false,
             fee_rate_fuzzer_fraction: 0.1f64,
-            fee_rate_window_size: 5u64,
+            fee_rate_window_size: fee_medians::DEFAULT_WINDOW_SIZE.into(),
+            fee_rate_max_samples: fee_inclusion_time::DEFAULT_MAX_SAMPLES,
         }
     }
 }
@@ -1763,6 +1812,7 @@ impl From<FeeEstimationConfigFile> for FeeEstimationConfig {
                 log_error: false,
                 fee_rate_fuzzer_fraction: 0f64,
                 fee_rate_window_size: 0u64,
+                fee_rate_max_samples: 0u32,
             };
         }
         let cost_estimator = f
@@ -1784,7 +1834,12 @@ impl From<FeeEstimationConfigFile> for FeeEstimationConfig {
             cost_metric: Some(cost_metric),
             log_error,
             fee_rate_fuzzer_fraction: f.fee_rate_fuzzer_fraction.unwrap_or(0.1f64),
-            fee_rate_window_size: f.fee_rate_window_size.unwrap_or(5u64),
+            fee_rate_window_size: f
+                .fee_rate_window_size
+                .unwrap_or(fee_medians::DEFAULT_WINDOW_SIZE.into()),
+            fee_rate_max_samples: f
+                .fee_rate_max_samples
+                .unwrap_or(fee_inclusion_time::DEFAULT_MAX_SAMPLES),
         }
     }
 }
@@ -1821,6 +1876,15 @@ impl Config {
             FeeEstimatorName::FuzzedWeightedMedianFeeRate => self
                 .estimation
                 .make_fuzzed_weighted_median_fee_estimator(self.get_estimates_path(), metric),
+            FeeEstimatorName::TimeToInclusion => {
+                let mempool_path = MemPoolDB::db_path(&self.get_chainstate_path_str())
+                    .expect("FATAL: failed to get mempool DB path");
+                self.estimation.make_time_to_inclusion_fee_estimator(
+                    self.get_estimates_path(),
+                    PathBuf::from(mempool_path),
+                    metric,
+                )
+            }
         };
 
         Some(fee_estimator)
@@ -1833,7 +1897,7 @@ impl FeeEstimationConfig {
         mut estimates_path: PathBuf,
     ) -> PessimisticEstimator {
         if let Some(CostEstimatorName::NaivePessimistic) = self.cost_estimator.as_ref() {
-            estimates_path.push("cost_estimator_pessimistic.sqlite");
+            estimates_path.push(pessimistic::DB_FILENAME);
             PessimisticEstimator::open(&estimates_path, self.log_error)
                 .expect("Error opening cost estimator")
         } else {
@@ -1847,7 +1911,7 @@ impl FeeEstimationConfig {
         metric: CM,
     ) -> Box<dyn FeeEstimator> {
         if let Some(FeeEstimatorName::ScalarFeeRate) = self.fee_estimator.as_ref() {
-            estimates_path.push("fee_estimator_scalar_rate.sqlite");
+            estimates_path.push(fee_scalar::DB_FILENAME);
             Box::new(
                 ScalarFeeRateEstimator::open(&estimates_path, metric)
                     .expect("Error opening fee estimator"),
@@ -1865,7 +1929,7 @@ impl FeeEstimationConfig {
         metric: CM,
     ) -> Box<dyn FeeEstimator> {
         if let Some(FeeEstimatorName::FuzzedWeightedMedianFeeRate) = self.fee_estimator.as_ref() {
-            estimates_path.push("fee_fuzzed_weighted_median.sqlite");
+            estimates_path.push(fee_medians::DB_FILENAME);
             let underlying_estimator = WeightedMedianFeeRateEstimator::open(
                 &estimates_path,
                 metric,
@@ -1882,6 +1946,30 @@ impl FeeEstimationConfig {
             panic!("BUG: Expected to configure a weighted median fee estimator");
         }
     }
+
+    /// Creates a TimeToInclusionFeeRateEstimator, which learns from the mempool DB at
+    /// `mempool_path` how long transactions wait to be mined.
+    pub fn make_time_to_inclusion_fee_estimator<CM: CostMetric + 'static>(
+        &self,
+        mut estimates_path: PathBuf,
+        mempool_path: PathBuf,
+        metric: CM,
+    ) -> Box<dyn FeeEstimator> {
+        if let Some(FeeEstimatorName::TimeToInclusion) = self.fee_estimator.as_ref() {
+            estimates_path.push(fee_inclusion_time::DB_FILENAME);
+            Box::new(
+                TimeToInclusionFeeRateEstimator::open(
+                    &estimates_path,
+                    &mempool_path,
+                    metric,
+                    self.fee_rate_max_samples,
+                )
+                .expect("Error opening fee estimator"),
+            )
+        } else {
+            panic!("BUG: Expected to configure a time-to-inclusion fee estimator");
+        }
+    }
 }
 
 impl Default for NodeConfig {
@@ -1935,11 +2023,37 @@ impl Default for NodeConfig {
             fault_injection_hide_blocks: false,
             chain_liveness_poll_time_secs: 300,
             stacker_dbs: vec![],
+            txindex: false,
+            event_index: false,
+            max_event_subscriptions: 0,
+            event_subscription_max_pending: 1000,
+            mempool_max_txs: None,
+            mempool_max_bytes: None,
+            mempool_max_txs_per_origin: None,
+            mempool_admission_rules: vec![],
+            mempool_admission_default_allow: true,
         }
     }
 }
 
 impl NodeConfig {
+    /// Get the mempool capacity limits configured for this node
+    pub fn get_mempool_limits(&self) -> MemPoolLimits {
+        MemPoolLimits {
+            max_txs: self.mempool_max_txs,
+            max_bytes: self.mempool_max_bytes,
+            max_txs_per_origin: self.mempool_max_txs_per_origin,
+        }
+    }
+
+    /// Get the mempool admission policy configured for this node
+    pub fn get_mempool_admission_policy(&self) -> MemPoolAdmissionPolicy {
+        MemPoolAdmissionPolicy::new(
+            self.mempool_admission_rules.clone(),
+            self.mempool_admission_default_allow,
+        )
+    }
+
     /// Get a SocketAddr for this node's RPC endpoint which uses the loopback address
     pub fn get_rpc_loopback(&self) -> Option<SocketAddr> {
         let rpc_port = SocketAddr::from_str(&self.rpc_bind)
@@ -2145,6 +2259,8 @@ pub struct MinerConfig {
     pub block_commit_delay: Duration,
     /// The percentage of the remaining tenure cost limit to consume each block.
     pub tenure_cost_limit_per_block_percentage: Option<u8>,
+    /// Order in which to consider mempool transactions when assembling a block
+    pub mempool_walk_strategy: MemPoolWalkStrategy,
 }
 
 impl Default for MinerConfig {
@@ -2181,6 +2297,7 @@ impl Default for MinerConfig {
             tenure_cost_limit_per_block_percentage: Some(
                 DEFAULT_TENURE_COST_LIMIT_PER_BLOCK_PERCENTAGE,
             ),
+            mempool_walk_strategy: MemPoolWalkStrategy::GlobalFeeRate,
         }
     }
 }
@@ -2424,6 +2541,24 @@ pub struct NodeConfigFile {
     pub stacker_dbs: Option<Vec<String>>,
     /// fault injection: fail to push blocks with this probability (0-100)
     pub fault_injection_block_push_fail_probability: Option<u8>,
+    /// Index processed transactions by txid
+    pub txindex: Option<bool>,
+    /// Index contract events by contract and topic
+    pub event_index: Option<bool>,
+    /// Maximum number of concurrent RPC event subscriptions (0 disables them)
+    pub max_event_subscriptions: Option<usize>,
+    /// Maximum number of undelivered events queued per RPC event subscription
+    pub event_subscription_max_pending: Option<usize>,
+    /// Maximum number of transactions held in the mempool
+    pub mempool_max_txs: Option<u64>,
+    /// Maximum total size, in bytes, of the transactions held in the mempool
+    pub mempool_max_bytes: Option<u64>,
+    /// Maximum number of pending mempool transactions per origin account
+    pub mempool_max_txs_per_origin: Option<u64>,
+    /// Rules for which transactions to admit into the mempool
+    pub mempool_admission_rules: Option<Vec<MemPoolAdmissionRuleFile>>,
+    /// Whether to admit transactions which match no admission rule: "allow" or "deny"
+    pub mempool_admission_default: Option<String>,
 }
 
 impl NodeConfigFile {
@@ -2518,11 +2653,138 @@ impl NodeConfigFile {
             } else {
                 default_node_config.fault_injection_block_push_fail_probability
             },
+            txindex: self.txindex.unwrap_or(default_node_config.txindex),
+            event_index: self.event_index.unwrap_or(default_node_config.event_index),
+            max_event_subscriptions: self
+                .max_event_subscriptions
+                .unwrap_or(default_node_config.max_event_subscriptions),
+            event_subscription_max_pending: self
+                .event_subscription_max_pending
+                .unwrap_or(default_node_config.event_subscription_max_pending),
+            mempool_max_txs: self.mempool_max_txs.or(default_node_config.mempool_max_txs),
+            mempool_max_bytes: self
+                .mempool_max_bytes
+                .or(default_node_config.mempool_max_bytes),
+            mempool_max_txs_per_origin: self
+                .mempool_max_txs_per_origin
+                .or(default_node_config.mempool_max_txs_per_origin),
+            mempool_admission_rules: match self.mempool_admission_rules {
+                Some(rules) => rules
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, rule)| rule.into_rule(index))
+                    .collect::<Result<_, _>>()?,
+                None => default_node_config.mempool_admission_rules,
+            },
+            mempool_admission_default_allow: match self.mempool_admission_default.as_deref() {
+                Some("allow") => true,
+                Some("deny") => false,
+                Some(other) => {
+                    return Err(format!(
+                        "node.mempool_admission_default must be \"allow\" or \"deny\", not \"{other}\""
+                    ));
+                }
+                None => default_node_config.mempool_admission_default_allow,
+            },
         };
         Ok(node_config)
     }
 }
 
+#[derive(Clone, Deserialize, Default, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct MemPoolAdmissionRuleFile {
+    /// Name to report when the rule rejects a transaction.  Defaults to "rule N", where N is
+    /// the rule's 0-based position.
+    pub name: Option<String>,
+    /// "allow", "deny" or "rate_limit"
+    pub action: String,
+    pub origin: Option<String>,
+    pub sponsor: Option<String>,
+    /// Fully-qualified identifier of the contract called
+    pub contract: Option<String>,
+    /// Name of the function called
+    pub function: Option<String>,
+    /// "TokenTransfer", "SmartContract" or "ContractCall"
+    pub payload_type: Option<String>,
+    /// For "rate_limit", the number of matching transactions to admit per window
+    pub max_txs: Option<u64>,
+    /// For "rate_limit", the length of the window, in seconds
+    pub window_secs: Option<u64>,
+}
+
+impl MemPoolAdmissionRuleFile {
+    fn into_rule(self, index: usize) -> Result<MemPoolAdmissionRule, String> {
+        let name = self.name.unwrap_or_else(|| format!("rule {index}"));
+        let parse_address = |field: &str, addr: Option<String>| {
+            addr.map(|addr| {
+                StacksAddress::from_string(&addr).ok_or_else(|| {
+                    format!("mempool admission rule '{name}': could not parse {field} '{addr}'")
+                })
+            })
+            .transpose()
+        };
+        let origin = parse_address("origin", self.origin)?;
+        let sponsor = parse_address("sponsor", self.sponsor)?;
+        let contract = self
+            .contract
+            .map(|contract| {
+                QualifiedContractIdentifier::parse(&contract).map_err(|e| {
+                    format!("mempool admission rule '{name}': could not parse contract '{contract}': {e}")
+                })
+            })
+            .transpose()?;
+        let function = self
+            .function
+            .map(|function| {
+                ClarityName::try_from(function.clone()).map_err(|e| {
+                    format!("mempool admission rule '{name}': could not parse function '{function}': {e}")
+                })
+            })
+            .transpose()?;
+        let payload_type = self
+            .payload_type
+            .map(|payload_type| {
+                str::parse(&payload_type).map_err(|e| {
+                    format!("mempool admission rule '{name}': could not parse payload_type '{payload_type}': {e}")
+                })
+            })
+            .transpose()?;
+        let action = match (self.action.as_str(), self.max_txs, self.window_secs) {
+            ("allow", None, None) => MemPoolAdmissionAction::Allow,
+            ("deny", None, None) => MemPoolAdmissionAction::Deny,
+            ("rate_limit", Some(max_txs), Some(window_secs)) => MemPoolAdmissionAction::RateLimit {
+                max_txs,
+                window_secs,
+            },
+            ("allow" | "deny", ..) => {
+                return Err(format!(
+                    "mempool admission rule '{name}': max_txs and window_secs only apply to \"rate_limit\" rules"
+                ));
+            }
+            ("rate_limit", ..) => {
+                return Err(format!(
+                    "mempool admission rule '{name}': \"rate_limit\" rules need max_txs and window_secs"
+                ));
+            }
+            (other, ..) => {
+                return Err(format!(
+                    "mempool admission rule '{name}': action must be \"allow\", \"deny\" or \"rate_limit\", not \"{other}\""
+                ));
+            }
+        };
+        Ok(MemPoolAdmissionRule {
+            name,
+            origin,
+            sponsor,
+            contract,
+            function,
+            payload_type,
+            action,
+        })
+    }
+}
+
 #[derive(Clone, Deserialize, Default, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct FeeEstimationConfigFile {
@@ -2533,6 +2795,7 @@ pub struct FeeEstimationConfigFile {
     pub log_error: Option<bool>,
     pub fee_rate_fuzzer_fraction: Option<f64>,
     pub fee_rate_window_size: Option<u64>,
+    pub fee_rate_max_samples: Option<u32>,
 }
 
 #[derive(Clone, Deserialize, Default, Debug)]
@@ -2566,6 +2829,7 @@ pub struct MinerConfigFile {
     pub subsequent_rejection_pause_ms: Option<u64>,
     pub block_commit_delay_ms: Option<u64>,
     pub tenure_cost_limit_per_block_percentage: Option<u8>,
+    pub mempool_walk_strategy: Option<String>,
 }
 
 impl MinerConfigFile {
@@ -2599,6 +2863,12 @@ impl MinerConfigFile {
             } else {
                 miner_default_config.tenure_cost_limit_per_block_percentage
             };
+        let mempool_walk_strategy = match &self.mempool_walk_strategy {
+            Some(strategy) => str::parse(strategy).map_err(|e| {
+                format!("could not parse miner.mempool_walk_strategy '{strategy}': {e}")
+            })?,
+            None => miner_default_config.mempool_walk_strategy,
+        };
         Ok(MinerConfig {
             first_attempt_time_ms: self
                 .first_attempt_time_ms
@@ -2706,6 +2976,7 @@ impl MinerConfigFile {
             subsequent_rejection_pause_ms: self.subsequent_rejection_pause_ms.unwrap_or(miner_default_config.subsequent_rejection_pause_ms),
             block_commit_delay: self.block_commit_delay_ms.map(Duration::from_millis).unwrap_or(miner_default_config.block_commit_delay),
             tenure_cost_limit_per_block_percentage,
+            mempool_walk_strategy,
         })
     }
 }
@@ -2745,13 +3016,77 @@ pub struct EventObserverConfigFile {
     pub endpoint: String,
     pub events_keys: Vec<String>,
     pub timeout_ms: Option<u64>,
+    /// How to deliver events: "http" (the default), "file", or "unix_socket"
+    pub sink: Option<String>,
+    /// File sinks only: start a new file once the current one would exceed this many bytes
+    pub max_file_size: Option<u64>,
+    /// File sinks only: how many rotated-out files to keep
+    pub max_rotated_files: Option<u32>,
 }
 
 #[derive(Clone, Default, Debug, Hash, PartialEq, Eq, PartialOrd)]
 pub struct EventObserverConfig {
+    /// For HTTP sinks, the `host:port` to POST events to.  For file and Unix domain socket
+    /// sinks, the path to write events to.
     pub endpoint: String,
     pub events_keys: Vec<EventKeyType>,
     pub timeout_ms: u64,
+    pub sink: EventSinkType,
+}
+
+/// How an event observer receives events
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd)]
+pub enum EventSinkType {
+    /// POST each event to an HTTP server
+    Http,
+    /// Append each event to a file as a line of JSON
+    File {
+        max_file_size: u64,
+        max_rotated_files: u32,
+    },
+    /// Write each event to a Unix domain socket as a line of JSON
+    UnixSocket,
+}
+
+impl Default for EventSinkType {
+    fn default() -> Self {
+        EventSinkType::Http
+    }
+}
+
+impl EventSinkType {
+    pub const DEFAULT_MAX_FILE_SIZE: u64 = 100 * 1024 * 1024;
+    pub const DEFAULT_MAX_ROTATED_FILES: u32 = 5;
+
+    fn from_config_file(observer: &EventObserverConfigFile) -> Result<Self, String> {
+        let sink = match observer.sink.as_deref().unwrap_or("http") {
+            "http" => EventSinkType::Http,
+            "file" => EventSinkType::File {
+                max_file_size: observer
+                    .max_file_size
+                    .unwrap_or(Self::DEFAULT_MAX_FILE_SIZE),
+                max_rotated_files: observer
+                    .max_rotated_files
+                    .unwrap_or(Self::DEFAULT_MAX_ROTATED_FILES),
+            },
+            "unix_socket" if cfg!(unix) => EventSinkType::UnixSocket,
+            other => {
+                return Err(format!(
+                    "Unsupported event observer sink '{other}' for {}",
+                    &observer.endpoint
+                ));
+            }
+        };
+        if !matches!(sink, EventSinkType::File { .. })
+            && (observer.max_file_size.is_some() || observer.max_rotated_files.is_some())
+        {
+            return Err(format!(
+                "max_file_size and max_rotated_files only apply to file event observers ({})",
+                &observer.endpoint
+            ));
+        }
+        Ok(sink)
+    }
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd)]
@@ -2770,7 +3105,7 @@ pub enum EventKeyType {
 }
 
 impl EventKeyType {
-    fn from_string(raw_key: &str) -> Option<EventKeyType> {
+    pub fn from_string(raw_key: &str) -> Option<EventKeyType> {
         if raw_key == "*" {
             return Some(EventKeyType::AnyEvent);
         }
@@ -3151,6 +3486,61 @@ mod tests {
         );
     }
 
+    #[test]
+    fn should_load_event_observer_sinks() {
+        let config = Config::from_config_file(
+            ConfigFile::from_str(
+                r#"
+                [[events_observer]]
+                endpoint = "localhost:30000"
+                events_keys = ["*"]
+
+                [[events_observer]]
+                sink = "file"
+                endpoint = "/tmp/events.ndjson"
+                max_file_size = 1024
+                events_keys = ["burn_blocks"]
+                "#,
+            )
+            .unwrap(),
+            false,
+        )
+        .expect("Expected to be able to parse event observers from file");
+
+        let mut sinks: Vec<_> = config
+            .events_observers
+            .iter()
+            .map(|observer| (observer.endpoint.as_str(), observer.sink.clone()))
+            .collect();
+        sinks.sort_by_key(|(endpoint, _)| *endpoint);
+        assert_eq!(
+            sinks,
+            vec![
+                (
+                    "/tmp/events.ndjson",
+                    EventSinkType::File {
+                        max_file_size: 1024,
+                        max_rotated_files: EventSinkType::DEFAULT_MAX_ROTATED_FILES,
+                    }
+                ),
+                ("localhost:30000", EventSinkType::Http),
+            ]
+        );
+
+        for bad_observer in [r#"sink = "carrier_pigeon""#, r#"max_file_size = 1024"#] {
+            let file = ConfigFile::from_str(&format!(
+                r#"
+                [[events_observer]]
+                endpoint = "localhost:30000"
+                events_keys = ["*"]
+                {bad_observer}
+                "#
+            ))
+            .unwrap();
+            assert!(Config::from_config_file(file, false).is_err());
+        }
+    }
+
     #[test]
     fn should_fail_to_load_invalid_affirmation_map() {
         let bad_affirmation_string = "bad_map";
@@ -47,9 +47,11 @@ use stacks::chainstate::stacks::events::{
 };
 use stacks::chainstate::stacks::miner::TransactionEvent;
 use stacks::chainstate::stacks::{
-    StacksBlock, StacksMicroblock, StacksTransaction, TransactionPayload,
+    StacksBlock, StacksMicroblock, StacksMicroblockHeader, StacksTransaction, TransactionPayload,
+};
+use stacks::core::mempool::{
+    MemPoolDropReason, MemPoolEventDispatcher, MemPoolStuckTx, ProposalCallbackReceiver,
 };
-use stacks::core::mempool::{MemPoolDropReason, MemPoolEventDispatcher, ProposalCallbackReceiver};
 use stacks::libstackerdb::StackerDBChunkData;
 use stacks::net::api::postblock_proposal::{
     BlockValidateOk, BlockValidateReject, BlockValidateResponse,
@@ -68,8 +70,13 @@ use stacks_common::util::hash::{bytes_to_hex, Sha512Trunc256Sum};
 use stacks_common::util::secp256k1::MessageSignature;
 use url::Url;
 
-use super::config::{EventKeyType, EventObserverConfig};
+use super::config::{EventKeyType, EventObserverConfig, EventSinkType};
+#[cfg(unix)]
+use crate::event_sink::UnixSocketEventSink;
+use crate::event_sink::{EventSink, FileEventSink};
+use crate::event_subscriptions::EventSubscriptions;
 
+/// An event observer that receives events over HTTP
 #[derive(Debug, Clone)]
 struct EventObserver {
     /// Path to the database where pending payloads are stored. If `None`, then
@@ -98,6 +105,7 @@ const STATUS_RESP_POST_CONDITION: &str = "abort_by_post_condition";
 pub const PATH_MICROBLOCK_SUBMIT: &str = "new_microblocks";
 pub const PATH_MEMPOOL_TX_SUBMIT: &str = "new_mempool_tx";
 pub const PATH_MEMPOOL_TX_DROP: &str = "drop_mempool_tx";
+pub const PATH_MEMPOOL_TX_STUCK: &str = "stuck_mempool_tx";
 pub const PATH_MINED_BLOCK: &str = "mined_block";
 pub const PATH_MINED_MICROBLOCK: &str = "mined_microblock";
 pub const PATH_MINED_NAKAMOTO_BLOCK: &str = "mined_nakamoto_block";
@@ -540,32 +548,6 @@ impl EventObserver {
         }
     }
 
-    /// Send the payload to the given URL.
-    /// Before sending this payload, any pending payloads in the database will be sent first.
-    pub fn send_payload(&self, payload: &serde_json::Value, path: &str) {
-        // Construct the full URL
-        let url_str = if path.starts_with('/') {
-            format!("{}{path}", &self.endpoint)
-        } else {
-            format!("{}/{path}", &self.endpoint)
-        };
-        let full_url = format!("http://{url_str}");
-
-        if let Some(db_path) = &self.db_path {
-            let conn =
-                Connection::open(db_path).expect("Failed to open database for event observer");
-
-            // Insert the new payload into the database
-            Self::insert_payload_with_retry(&conn, &full_url, payload, self.timeout);
-
-            // Process all pending payloads
-            Self::process_pending_payloads(&conn);
-        } else {
-            // No database, just send the payload
-            Self::send_payload_directly(payload, &full_url, self.timeout);
-        }
-    }
-
     fn make_new_mempool_txs_payload(transactions: Vec<StacksTransaction>) -> serde_json::Value {
         let raw_txs = transactions
             .into_iter()
@@ -708,24 +690,15 @@ impl EventObserver {
         })
     }
 
-    fn send_new_attachments(&self, payload: &serde_json::Value) {
-        self.send_payload(payload, PATH_ATTACHMENT_PROCESSED);
-    }
-
-    fn send_new_mempool_txs(&self, payload: &serde_json::Value) {
-        self.send_payload(payload, PATH_MEMPOOL_TX_SUBMIT);
-    }
-
-    /// Serializes new microblocks data into a JSON payload and sends it off to the correct path
-    fn send_new_microblocks(
-        &self,
+    /// Serializes new microblocks data into a JSON payload
+    fn make_new_microblocks_payload(
         parent_index_block_hash: StacksBlockId,
         filtered_events: Vec<(usize, &(bool, Txid, &StacksTransactionEvent))>,
         serialized_txs: &Vec<serde_json::Value>,
         burn_block_hash: BurnchainHeaderHash,
         burn_block_height: u32,
         burn_block_timestamp: u64,
-    ) {
+    ) -> serde_json::Value {
         // Serialize events to JSON
         let serialized_events: Vec<serde_json::Value> = filtered_events
             .iter()
@@ -736,45 +709,18 @@ impl EventObserver {
             })
             .collect();
 
-        let payload = json!({
+        json!({
             "parent_index_block_hash": format!("0x{parent_index_block_hash}"),
             "events": serialized_events,
             "transactions": serialized_txs,
             "burn_block_hash": format!("0x{burn_block_hash}"),
             "burn_block_height": burn_block_height,
             "burn_block_timestamp": burn_block_timestamp,
-        });
-
-        self.send_payload(&payload, PATH_MICROBLOCK_SUBMIT);
-    }
-
-    fn send_dropped_mempool_txs(&self, payload: &serde_json::Value) {
-        self.send_payload(payload, PATH_MEMPOOL_TX_DROP);
-    }
-
-    fn send_mined_block(&self, payload: &serde_json::Value) {
-        self.send_payload(payload, PATH_MINED_BLOCK);
-    }
-
-    fn send_mined_microblock(&self, payload: &serde_json::Value) {
-        self.send_payload(payload, PATH_MINED_MICROBLOCK);
-    }
-
-    fn send_mined_nakamoto_block(&self, payload: &serde_json::Value) {
-        self.send_payload(payload, PATH_MINED_NAKAMOTO_BLOCK);
-    }
-
-    fn send_stackerdb_chunks(&self, payload: &serde_json::Value) {
-        self.send_payload(payload, PATH_STACKERDB_CHUNKS);
-    }
-
-    fn send_new_burn_block(&self, payload: &serde_json::Value) {
-        self.send_payload(payload, PATH_BURN_BLOCK_SUBMIT);
+        })
     }
 
     #[allow(clippy::too_many_arguments)]
     fn make_new_block_processed_payload(
-        &self,
         filtered_events: Vec<(usize, &(bool, Txid, &StacksTransactionEvent))>,
         block: &StacksBlockEventData,
         metadata: &StacksHeaderInfo,
@@ -880,6 +826,34 @@ impl EventObserver {
     }
 }
 
+impl EventSink for EventObserver {
+    /// Send the payload to the given URL.
+    /// Before sending this payload, any pending payloads in the database will be sent first.
+    fn send_payload(&self, payload: &serde_json::Value, path: &str) {
+        // Construct the full URL
+        let url_str = if path.starts_with('/') {
+            format!("{}{path}", &self.endpoint)
+        } else {
+            format!("{}/{path}", &self.endpoint)
+        };
+        let full_url = format!("http://{url_str}");
+
+        if let Some(db_path) = &self.db_path {
+            let conn =
+                Connection::open(db_path).expect("Failed to open database for event observer");
+
+            // Insert the new payload into the database
+            Self::insert_payload_with_retry(&conn, &full_url, payload, self.timeout);
+
+            // Process all pending payloads
+            Self::process_pending_payloads(&conn);
+        } else {
+            // No database, just send the payload
+            Self::send_payload_directly(payload, &full_url, self.timeout);
+        }
+    }
+}
+
 /// Events received from block-processing.
 /// Stacks events are structured as JSON, and are grouped by topic.  An event observer can
 /// subscribe to one or more specific event streams, or the "any" stream to receive all of them.
@@ -887,7 +861,7 @@ impl EventObserver {
 pub struct EventDispatcher {
     /// List of configured event observers to which events will be posted.
     /// The fields below this contain indexes into this list.
-    registered_observers: Vec<EventObserver>,
+    registered_observers: Vec<Arc<dyn EventSink>>,
     /// Smart contract-specific events, keyed by (contract-id, event-name). Values are indexes into `registered_observers`.
     contract_events_observers_lookup: HashMap<(QualifiedContractIdentifier, String), HashSet<u16>>,
     /// Asset event observers, keyed by fully-qualified asset identifier. Values are indexes into
@@ -916,12 +890,15 @@ pub struct EventDispatcher {
     block_proposal_observers_lookup: HashSet<u16>,
     /// Channel for sending StackerDB events to the miner coordinator
     pub stackerdb_channel: Arc<Mutex<StackerDBChannel>>,
+    /// Event subscriptions created by RPC clients, if enabled
+    event_subscriptions: Option<EventSubscriptions>,
 }
 
 /// This struct is used specifically for receiving proposal responses.
 /// It's constructed separately to play nicely with threading.
 struct ProposalCallbackHandler {
-    observers: Vec<EventObserver>,
+    observers: Vec<Arc<dyn EventSink>>,
+    event_subscriptions: Option<EventSubscriptions>,
 }
 
 impl ProposalCallbackReceiver for ProposalCallbackHandler {
@@ -939,6 +916,14 @@ impl ProposalCallbackReceiver for ProposalCallbackHandler {
         for observer in self.observers.iter() {
             observer.send_payload(&response, PATH_PROPOSAL_RESPONSE);
         }
+        if let Some(subscriptions) = self.event_subscriptions.as_ref() {
+            subscriptions.publish_to_key(
+                PATH_PROPOSAL_RESPONSE,
+                &EventKeyType::BlockProposal,
+                false,
+                &response,
+            );
+        }
     }
 }
 
@@ -949,6 +934,12 @@ impl MemPoolEventDispatcher for EventDispatcher {
         }
     }
 
+    fn mempool_txs_stuck(&self, stuck_txs: Vec<MemPoolStuckTx>) {
+        if !stuck_txs.is_empty() {
+            self.process_stuck_mempool_txs(stuck_txs)
+        }
+    }
+
     fn mined_block_event(
         &self,
         target_burn_height: u64,
@@ -1018,11 +1009,13 @@ impl MemPoolEventDispatcher for EventDispatcher {
                 }
             )
             .collect();
-        if callback_receivers.is_empty() {
+        let event_subscriptions = self.active_subscriptions().cloned();
+        if callback_receivers.is_empty() && event_subscriptions.is_none() {
             return None;
         }
         let handler = ProposalCallbackHandler {
             observers: callback_receivers,
+            event_subscriptions,
         };
         Some(Box::new(handler))
     }
@@ -1099,6 +1092,121 @@ impl BlockEventDispatcher for EventDispatcher {
     }
 }
 
+/// Dispatches the events that `stacks::cli::replay_events()` re-derives from stored blocks.
+/// A block's confirmed parent microblocks are only known once the block is replayed, so they are
+/// sent as one `new_microblocks` event just before the block's `new_block` event.
+pub struct ReplayEventDispatcher<'a> {
+    dispatcher: &'a EventDispatcher,
+}
+
+impl<'a> ReplayEventDispatcher<'a> {
+    pub fn new(dispatcher: &'a EventDispatcher) -> Self {
+        Self { dispatcher }
+    }
+}
+
+impl BlockEventDispatcher for ReplayEventDispatcher<'_> {
+    fn announce_block(
+        &self,
+        block: &StacksBlockEventData,
+        metadata: &StacksHeaderInfo,
+        receipts: &[StacksTransactionReceipt],
+        parent: &StacksBlockId,
+        winner_txid: Txid,
+        mature_rewards: &[MinerReward],
+        mature_rewards_info: Option<&MinerRewardInfo>,
+        parent_burn_block_hash: BurnchainHeaderHash,
+        parent_burn_block_height: u32,
+        parent_burn_block_timestamp: u64,
+        anchored_consumed: &ExecutionCost,
+        mblock_confirmed_consumed: &ExecutionCost,
+        pox_constants: &PoxConstants,
+        reward_set_data: &Option<RewardSetData>,
+        signer_bitvec: &Option<BitVec<4000>>,
+        block_timestamp: Option<u64>,
+        coinbase_height: u64,
+    ) {
+        let mut microblocks: Vec<(u16, StacksMicroblockHeader, Vec<StacksTransactionReceipt>)> =
+            vec![];
+        for receipt in receipts.iter() {
+            let Some(microblock_header) = receipt.microblock_header.as_ref() else {
+                continue;
+            };
+            match microblocks.last_mut() {
+                Some((sequence, _, microblock_receipts))
+                    if *sequence == microblock_header.sequence =>
+                {
+                    microblock_receipts.push(receipt.clone());
+                }
+                _ => microblocks.push((
+                    microblock_header.sequence,
+                    microblock_header.clone(),
+                    vec![receipt.clone()],
+                )),
+            }
+        }
+        if !microblocks.is_empty() {
+            let microblock_receipts = microblocks.iter().flat_map(|(_, _, receipts)| receipts);
+            let total_fees = microblock_receipts
+                .clone()
+                .map(|receipt| match &receipt.transaction {
+                    TransactionOrigin::Stacks(tx) => u128::from(tx.get_tx_fee()),
+                    TransactionOrigin::Burn(_) => 0,
+                })
+                .sum();
+            let total_burns = microblock_receipts.map(|receipt| receipt.stx_burned).sum();
+            self.dispatcher.process_new_microblocks(
+                *parent,
+                ProcessedUnconfirmedState {
+                    total_burns,
+                    total_fees,
+                    receipts: microblocks,
+                    burn_block_hash: parent_burn_block_hash,
+                    burn_block_height: parent_burn_block_height,
+                    burn_block_timestamp: parent_burn_block_timestamp,
+                },
+            );
+        }
+
+        self.dispatcher.process_chain_tip(
+            block,
+            metadata,
+            receipts,
+            parent,
+            winner_txid,
+            mature_rewards,
+            mature_rewards_info,
+            parent_burn_block_hash,
+            parent_burn_block_height,
+            parent_burn_block_timestamp,
+            anchored_consumed,
+            mblock_confirmed_consumed,
+            pox_constants,
+            reward_set_data,
+            signer_bitvec,
+            block_timestamp,
+            coinbase_height,
+        );
+    }
+
+    fn announce_burn_block(
+        &self,
+        burn_block: &BurnchainHeaderHash,
+        burn_block_height: u64,
+        rewards: Vec<(PoxAddress, u64)>,
+        burns: u64,
+        recipient_info: Vec<PoxAddress>,
+    ) {
+        self.dispatcher.process_burn_block(
+            burn_block,
+            burn_block_height,
+            rewards,
+            burns,
+            recipient_info,
+        )
+    }
+}
+
 impl Default for EventDispatcher {
     fn default() -> Self {
         EventDispatcher::new()
@@ -1121,9 +1229,28 @@ impl EventDispatcher {
             mined_microblocks_observers_lookup: HashSet::new(),
             stackerdb_observers_lookup: HashSet::new(),
             block_proposal_observers_lookup: HashSet::new(),
+            event_subscriptions: None,
         }
     }
 
+    /// Allow RPC clients to subscribe to events at runtime
+    pub fn enable_subscriptions(&mut self, max_subscriptions: usize, max_pending: usize) {
+        info!("Enabling up to {max_subscriptions} RPC event subscriptions");
+        self.event_subscriptions = Some(EventSubscriptions::new(max_subscriptions, max_pending));
+    }
+
+    /// The RPC event subscriptions, if enabled
+    pub fn event_subscriptions(&self) -> Option<&EventSubscriptions> {
+        self.event_subscriptions.as_ref()
+    }
+
+    /// The RPC event subscriptions, if enabled and if there are any
+    fn active_subscriptions(&self) -> Option<&EventSubscriptions> {
+        self.event_subscriptions
+            .as_ref()
+            .filter(|subscriptions| !subscriptions.is_empty())
+    }
+
     pub fn process_burn_block(
         &self,
         burn_block: &BurnchainHeaderHash,
@@ -1134,7 +1261,8 @@ impl EventDispatcher {
     ) {
         // lazily assemble payload only if we have observers
         let interested_observers = self.filter_observers(&self.burn_block_observers_lookup, true);
-        if interested_observers.is_empty() {
+        let subscriptions = self.active_subscriptions();
+        if interested_observers.is_empty() && subscriptions.is_none() {
             return;
         }
 
@@ -1147,7 +1275,15 @@ impl EventDispatcher {
         );
 
         for observer in interested_observers.iter() {
-            observer.send_new_burn_block(&payload);
+            observer.send_payload(&payload, PATH_BURN_BLOCK_SUBMIT);
+        }
+        if let Some(subscriptions) = subscriptions {
+            subscriptions.publish_to_key(
+                PATH_BURN_BLOCK_SUBMIT,
+                &EventKeyType::BurnchainBlocks,
+                true,
+                &payload,
+            );
         }
     }
 
@@ -1274,8 +1410,9 @@ impl EventDispatcher {
     ) {
         let all_receipts = receipts.to_owned();
         let (dispatch_matrix, events) = self.create_dispatch_matrix_and_event_vector(&all_receipts);
+        let subscriptions = self.active_subscriptions();
 
-        if !dispatch_matrix.is_empty() {
+        if !dispatch_matrix.is_empty() || subscriptions.is_some() {
             let mature_rewards_vec = if let Some(rewards_info) = mature_rewards_info {
                 mature_rewards
                     .iter()
@@ -1305,8 +1442,41 @@ impl EventDispatcher {
                     .map(|event_id| (*event_id, &events[*event_id]))
                     .collect();
 
-                let payload = self.registered_observers[observer_id]
-                    .make_new_block_processed_payload(
+                let payload = EventObserver::make_new_block_processed_payload(
+                    filtered_events,
+                    block,
+                    metadata,
+                    receipts,
+                    parent_index_hash,
+                    &winner_txid,
+                    &mature_rewards,
+                    parent_burn_block_hash,
+                    parent_burn_block_height,
+                    parent_burn_block_timestamp,
+                    anchored_consumed,
+                    mblock_confirmed_consumed,
+                    pox_constants,
+                    reward_set_data,
+                    signer_bitvec,
+                    block_timestamp,
+                    coinbase_height,
+                );
+
+                // Send payload
+                self.registered_observers[observer_id].send_payload(&payload, PATH_BLOCK_PROCESSED);
+            }
+
+            // Like observers, every subscription receives every block, with its events filtered
+            if let Some(subscriptions) = subscriptions {
+                subscriptions.publish(PATH_BLOCK_PROCESSED, |events_keys| {
+                    let filtered_events: Vec<_> = events
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, (_, _, event))| {
+                            EventSubscriptions::event_matches(events_keys, event)
+                        })
+                        .collect();
+                    Some(EventObserver::make_new_block_processed_payload(
                         filtered_events,
                         block,
                         metadata,
@@ -1324,10 +1494,8 @@ impl EventDispatcher {
                         signer_bitvec,
                         block_timestamp,
                         coinbase_height,
-                    );
-
-                // Send payload
-                self.registered_observers[observer_id].send_payload(&payload, PATH_BLOCK_PROCESSED);
+                    ))
+                });
             }
         }
     }
@@ -1353,7 +1521,8 @@ impl EventDispatcher {
                     )
             })
             .collect();
-        if interested_observers.is_empty() {
+        let subscriptions = self.active_subscriptions();
+        if interested_observers.is_empty() && subscriptions.is_none() {
             return;
         }
         let flattened_receipts = processed_unconfirmed_state
@@ -1384,7 +1553,7 @@ impl EventDispatcher {
                 .map(|event_id| (*event_id, &events[*event_id]))
                 .collect();
 
-            observer.send_new_microblocks(
+            let payload = EventObserver::make_new_microblocks_payload(
                 parent_index_block_hash,
                 filtered_events,
                 &serialized_txs,
@@ -1392,10 +1561,40 @@ impl EventDispatcher {
                 processed_unconfirmed_state.burn_block_height,
                 processed_unconfirmed_state.burn_block_timestamp,
             );
+            observer.send_payload(&payload, PATH_MICROBLOCK_SUBMIT);
+        }
+
+        if let Some(subscriptions) = subscriptions {
+            subscriptions.publish(PATH_MICROBLOCK_SUBMIT, |events_keys| {
+                if !events_keys.contains(&EventKeyType::Microblocks)
+                    && !events_keys.contains(&EventKeyType::AnyEvent)
+                {
+                    return None;
+                }
+                let filtered_events: Vec<_> = events
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (_, _, event))| {
+                        EventSubscriptions::event_matches(events_keys, event)
+                    })
+                    .collect();
+                Some(EventObserver::make_new_microblocks_payload(
+                    parent_index_block_hash,
+                    filtered_events,
+                    &serialized_txs,
+                    processed_unconfirmed_state.burn_block_hash,
+                    processed_unconfirmed_state.burn_block_height,
+                    processed_unconfirmed_state.burn_block_timestamp,
+                ))
+            });
         }
     }
 
-    fn filter_observers(&self, lookup: &HashSet<u16>, include_any: bool) -> Vec<&EventObserver> {
+    fn filter_observers(
+        &self,
+        lookup: &HashSet<u16>,
+        include_any: bool,
+    ) -> Vec<&Arc<dyn EventSink>> {
         self.registered_observers
             .iter()
             .enumerate()
@@ -1415,15 +1614,24 @@ impl EventDispatcher {
     pub fn process_new_mempool_txs(&self, txs: Vec<StacksTransaction>) {
         // lazily assemble payload only if we have observers
         let interested_observers = self.filter_observers(&self.mempool_observers_lookup, true);
+        let subscriptions = self.active_subscriptions();
 
-        if interested_observers.is_empty() {
+        if interested_observers.is_empty() && subscriptions.is_none() {
             return;
         }
 
         let payload = EventObserver::make_new_mempool_txs_payload(txs);
 
         for observer in interested_observers.iter() {
-            observer.send_new_mempool_txs(&payload);
+            observer.send_payload(&payload, PATH_MEMPOOL_TX_SUBMIT);
+        }
+        if let Some(subscriptions) = subscriptions {
+            subscriptions.publish_to_key(
+                PATH_MEMPOOL_TX_SUBMIT,
+                &EventKeyType::MemPoolTransactions,
+                true,
+                &payload,
+            );
         }
     }
 
@@ -1454,7 +1662,7 @@ impl EventDispatcher {
         .unwrap();
 
         for observer in interested_observers.iter() {
-            observer.send_mined_block(&payload);
+            observer.send_payload(&payload, PATH_MINED_BLOCK);
         }
     }
 
@@ -1481,7 +1689,7 @@ impl EventDispatcher {
         .unwrap();
 
         for observer in interested_observers.iter() {
-            observer.send_mined_microblock(&payload);
+            observer.send_payload(&payload, PATH_MINED_MICROBLOCK);
         }
     }
 
@@ -1521,7 +1729,7 @@ impl EventDispatcher {
         .unwrap();
 
         for observer in interested_observers.iter() {
-            observer.send_mined_nakamoto_block(&payload);
+            observer.send_payload(&payload, PATH_MINED_NAKAMOTO_BLOCK);
         }
     }
 
@@ -1543,7 +1751,11 @@ impl EventDispatcher {
             .lock()
             .expect("FATAL: failed to lock StackerDB channel mutex");
         let interested_receiver = stackerdb_channel.is_active(&contract_id);
-        if interested_observers.is_empty() && interested_receiver.is_none() {
+        let subscriptions = self.active_subscriptions();
+        if interested_observers.is_empty()
+            && interested_receiver.is_none()
+            && subscriptions.is_none()
+        {
             return;
         }
 
@@ -1564,15 +1776,24 @@ impl EventDispatcher {
         }
 
         for observer in interested_observers.iter() {
-            observer.send_stackerdb_chunks(&payload);
+            observer.send_payload(&payload, PATH_STACKERDB_CHUNKS);
+        }
+        if let Some(subscriptions) = subscriptions {
+            subscriptions.publish_to_key(
+                PATH_STACKERDB_CHUNKS,
+                &EventKeyType::StackerDBChunks,
+                false,
+                &payload,
+            );
         }
     }
 
     pub fn process_dropped_mempool_txs(&self, txs: Vec<Txid>, reason: MemPoolDropReason) {
         // lazily assemble payload only if we have observers
         let interested_observers = self.filter_observers(&self.mempool_observers_lookup, true);
+        let subscriptions = self.active_subscriptions();
 
-        if interested_observers.is_empty() {
+        if interested_observers.is_empty() && subscriptions.is_none() {
             return;
         }
 
@@ -1587,13 +1808,59 @@ impl EventDispatcher {
         });
 
         for observer in interested_observers.iter() {
-            observer.send_dropped_mempool_txs(&payload);
+            observer.send_payload(&payload, PATH_MEMPOOL_TX_DROP);
+        }
+        if let Some(subscriptions) = subscriptions {
+            subscriptions.publish_to_key(
+                PATH_MEMPOOL_TX_DROP,
+                &EventKeyType::MemPoolTransactions,
+                true,
+                &payload,
+            );
+        }
+    }
+
+    pub fn process_stuck_mempool_txs(&self, stuck_txs: Vec<MemPoolStuckTx>) {
+        // lazily assemble payload only if we have observers
+        let interested_observers = self.filter_observers(&self.mempool_observers_lookup, true);
+        let subscriptions = self.active_subscriptions();
+
+        if interested_observers.is_empty() && subscriptions.is_none() {
+            return;
+        }
+
+        let stuck_txs: Vec<_> = stuck_txs
+            .into_iter()
+            .map(|stuck_tx| {
+                json!({
+                    "txid": format!("0x{}", stuck_tx.txid),
+                    "address": stuck_tx.address.to_string(),
+                    "missing_nonce": stuck_tx.missing_nonce,
+                })
+            })
+            .collect();
+
+        let payload = json!({
+            "stuck_txs": serde_json::Value::Array(stuck_txs),
+        });
+
+        for observer in interested_observers.iter() {
+            observer.send_payload(&payload, PATH_MEMPOOL_TX_STUCK);
+        }
+        if let Some(subscriptions) = subscriptions {
+            subscriptions.publish_to_key(
+                PATH_MEMPOOL_TX_STUCK,
+                &EventKeyType::MemPoolTransactions,
+                true,
+                &payload,
+            );
         }
     }
 
     pub fn process_new_attachments(&self, attachments: &[(AttachmentInstance, Attachment)]) {
         let interested_observers: Vec<_> = self.registered_observers.iter().enumerate().collect();
-        if interested_observers.is_empty() {
+        let subscriptions = self.active_subscriptions();
+        if interested_observers.is_empty() && subscriptions.is_none() {
             return;
         }
 
@@ -1603,8 +1870,12 @@ impl EventDispatcher {
             serialized_attachments.push(payload);
         }
 
+        let payload = json!(serialized_attachments);
         for (_, observer) in interested_observers.iter() {
-            observer.send_new_attachments(&json!(serialized_attachments));
+            observer.send_payload(&payload, PATH_ATTACHMENT_PROCESSED);
+        }
+        if let Some(subscriptions) = subscriptions {
+            subscriptions.publish(PATH_ATTACHMENT_PROCESSED, |_| Some(payload.clone()));
         }
     }
 
@@ -1621,13 +1892,34 @@ impl EventDispatcher {
         }
     }
 
-    pub fn register_observer(&mut self, conf: &EventObserverConfig, working_dir: PathBuf) {
+    /// Register an event observer.  If `working_dir` is given, an HTTP observer's payloads are
+    /// stored there until they are delivered, so they survive a restart.
+    pub fn register_observer(&mut self, conf: &EventObserverConfig, working_dir: Option<PathBuf>) {
         info!("Registering event observer at: {}", conf.endpoint);
-        let event_observer = EventObserver::new(
-            Some(working_dir),
-            conf.endpoint.clone(),
-            Duration::from_millis(conf.timeout_ms),
-        );
+        let event_observer: Arc<dyn EventSink> = match &conf.sink {
+            EventSinkType::Http => Arc::new(EventObserver::new(
+                working_dir,
+                conf.endpoint.clone(),
+                Duration::from_millis(conf.timeout_ms),
+            )),
+            EventSinkType::File {
+                max_file_size,
+                max_rotated_files,
+            } => Arc::new(FileEventSink::new(
+                PathBuf::from(&conf.endpoint),
+                *max_file_size,
+                *max_rotated_files,
+            )),
+            #[cfg(unix)]
+            EventSinkType::UnixSocket => Arc::new(UnixSocketEventSink::new(
+                PathBuf::from(&conf.endpoint),
+                Duration::from_millis(conf.timeout_ms),
+            )),
+            #[cfg(not(unix))]
+            EventSinkType::UnixSocket => {
+                panic!("Unix domain socket event observers are not supported on this platform")
+            }
+        };
 
         let observer_index = self.registered_observers.len() as u16;
 
@@ -1702,12 +1994,15 @@ mod test {
     use std::time::Instant;
 
     use clarity::vm::costs::ExecutionCost;
+    use clarity::vm::types::PrincipalData;
     use stacks::burnchains::{PoxConstants, Txid};
     use stacks::chainstate::nakamoto::{NakamotoBlock, NakamotoBlockHeader};
     use stacks::chainstate::stacks::db::{StacksBlockHeaderTypes, StacksHeaderInfo};
     use stacks::chainstate::stacks::events::StacksBlockEventData;
-    use stacks::chainstate::stacks::StacksBlock;
-    use stacks::types::chainstate::BlockHeaderHash;
+    use stacks::chainstate::stacks::{
+        StacksBlock, TokenTransferMemo, TransactionAuth, TransactionVersion,
+    };
+    use stacks::types::chainstate::{BlockHeaderHash, StacksPrivateKey};
     use stacks::util::secp256k1::MessageSignature;
     use stacks_common::bitvec::BitVec;
     use stacks_common::types::chainstate::{BurnchainHeaderHash, StacksBlockId};
@@ -1718,8 +2013,6 @@ mod test {
 
     #[test]
     fn build_block_processed_event() {
-        let observer = EventObserver::new(None, "nowhere".to_string(), Duration::from_secs(3));
-
         let filtered_events = vec![];
         let block = StacksBlock::genesis_block();
         let metadata = StacksHeaderInfo::regtest_genesis();
@@ -1737,7 +2030,7 @@ mod test {
         let block_timestamp = Some(123456);
         let coinbase_height = 1234;
 
-        let payload = observer.make_new_block_processed_payload(
+        let payload = EventObserver::make_new_block_processed_payload(
             filtered_events,
             &block.into(),
             &metadata,
@@ -1778,8 +2071,6 @@ mod test {
 
     #[test]
     fn test_block_processed_event_nakamoto() {
-        let observer = EventObserver::new(None, "nowhere".to_string(), Duration::from_secs(3));
-
         let filtered_events = vec![];
         let mut block_header = NakamotoBlockHeader::empty();
         let signer_signature = vec![
@@ -1807,7 +2098,7 @@ mod test {
         let block_timestamp = Some(123456);
         let coinbase_height = 1234;
 
-        let payload = observer.make_new_block_processed_payload(
+        let payload = EventObserver::make_new_block_processed_payload(
             filtered_events,
             &StacksBlockEventData::from((block, BlockHeaderHash([0; 32]))),
             &metadata,
@@ -2360,4 +2651,106 @@ mod test {
         rx.recv_timeout(Duration::from_secs(5))
             .expect("Server did not receive request in time");
     }
+
+    #[test]
+    fn test_replay_dispatcher_announces_confirmed_microblocks() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("events.ndjson");
+        let mut dispatcher = EventDispatcher::new();
+        dispatcher.register_observer(
+            &EventObserverConfig {
+                endpoint: path.to_str().unwrap().to_string(),
+                events_keys: vec![EventKeyType::AnyEvent],
+                timeout_ms: 1_000,
+                sink: EventSinkType::File {
+                    max_file_size: u64::MAX,
+                    max_rotated_files: 0,
+                },
+            },
+            None,
+        );
+
+        let make_receipt = |fee, microblock_sequence: Option<u16>| {
+            let mut tx = StacksTransaction::new(
+                TransactionVersion::Testnet,
+                TransactionAuth::from_p2pkh(&StacksPrivateKey::new()).unwrap(),
+                TransactionPayload::TokenTransfer(
+                    PrincipalData::from(QualifiedContractIdentifier::transient()),
+                    100,
+                    TokenTransferMemo([0; 34]),
+                ),
+            );
+            tx.set_tx_fee(fee);
+            let mut receipt = StacksTransactionReceipt::from_stx_transfer(
+                tx,
+                vec![],
+                Value::okay_true(),
+                ExecutionCost::ZERO,
+            );
+            receipt.microblock_header =
+                microblock_sequence.map(|sequence| StacksMicroblockHeader {
+                    version: 0,
+                    sequence,
+                    prev_block: BlockHeaderHash([0; 32]),
+                    tx_merkle_root: Sha512Trunc256Sum([0; 32]),
+                    signature: MessageSignature::empty(),
+                });
+            receipt
+        };
+        // two confirmed microblocks, followed by the anchored block's own transaction
+        let receipts = vec![
+            make_receipt(1, Some(0)),
+            make_receipt(2, Some(0)),
+            make_receipt(4, Some(1)),
+            make_receipt(8, None),
+        ];
+
+        let parent_index_hash = StacksBlockId([0x11; 32]);
+        ReplayEventDispatcher::new(&dispatcher).announce_block(
+            &StacksBlock::genesis_block().into(),
+            &StacksHeaderInfo::regtest_genesis(),
+            &receipts,
+            &parent_index_hash,
+            Txid([0; 32]),
+            &[],
+            None,
+            BurnchainHeaderHash([0x22; 32]),
+            100,
+            0,
+            &ExecutionCost::ZERO,
+            &ExecutionCost::ZERO,
+            &PoxConstants::testnet_default(),
+            &None,
+            &None,
+            None,
+            1,
+        );
+
+        let lines: Vec<serde_json::Value> = std::fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+
+        assert_eq!(lines[0]["path"], PATH_MICROBLOCK_SUBMIT);
+        let microblocks = &lines[0]["payload"];
+        assert_eq!(
+            microblocks["parent_index_block_hash"],
+            format!("0x{parent_index_hash}")
+        );
+        assert_eq!(microblocks["burn_block_height"], 100);
+        let microblock_txs = microblocks["transactions"].as_array().unwrap();
+        assert_eq!(microblock_txs.len(), 3);
+        assert_eq!(microblock_txs[2]["microblock_sequence"], 1);
+
+        assert_eq!(lines[1]["path"], PATH_BLOCK_PROCESSED);
+        assert_eq!(
+            lines[1]["payload"]["transactions"]
+                .as_array()
+                .unwrap()
+                .len(),
+            4
+        );
+    }
 }
@@ -0,0 +1,275 @@
+// Copyright (C) 2020-2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Destinations for the payloads produced by the `EventDispatcher`.
+//!
+//! The HTTP observer (`EventObserver`) is one kind of sink.  This module adds sinks for
+//! on-box consumers: an append-only file, and a Unix domain socket.  Both write one JSON object
+//! per line, of the form `{"path": "new_block", "payload": {...}}`, where `path` is the
+//! endpoint the HTTP observer would have POSTed the payload to.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread::sleep;
+use std::time::Duration;
+
+use serde_json::json;
+
+/// A destination for event payloads
+pub trait EventSink: Send + Sync {
+    /// Deliver `payload`, which an HTTP observer would receive at `path`.
+    /// Blocks until the payload has been delivered.
+    fn send_payload(&self, payload: &serde_json::Value, path: &str);
+}
+
+/// Encode a payload as a line of newline-delimited JSON
+fn make_payload_line(payload: &serde_json::Value, path: &str) -> String {
+    let mut line = json!({
+        "path": path.trim_start_matches('/'),
+        "payload": payload,
+    })
+    .to_string();
+    line.push('\n');
+    line
+}
+
+/// Appends payloads to a file as newline-delimited JSON.  Once the file would grow beyond
+/// `max_file_size` bytes, it is renamed to `<path>.1` (and `<path>.1` to `<path>.2`, and so on,
+/// keeping at most `max_rotated_files` old files) and a new file is started.
+pub struct FileEventSink {
+    path: PathBuf,
+    max_file_size: u64,
+    max_rotated_files: u32,
+    file: Mutex<Option<File>>,
+}
+
+impl FileEventSink {
+    pub fn new(path: PathBuf, max_file_size: u64, max_rotated_files: u32) -> Self {
+        Self {
+            path,
+            max_file_size,
+            max_rotated_files,
+            file: Mutex::new(None),
+        }
+    }
+
+    fn rotated_path(&self, index: u32) -> PathBuf {
+        let mut rotated = self.path.clone().into_os_string();
+        rotated.push(format!(".{index}"));
+        PathBuf::from(rotated)
+    }
+
+    /// Shift `<path>` to `<path>.1`, `<path>.1` to `<path>.2`, etc., discarding the oldest file
+    fn rotate(&self) -> Result<(), std::io::Error> {
+        if self.max_rotated_files == 0 {
+            return fs::remove_file(&self.path);
+        }
+        for index in (1..self.max_rotated_files).rev() {
+            let from = self.rotated_path(index);
+            if from.exists() {
+                fs::rename(&from, self.rotated_path(index + 1))?;
+            }
+        }
+        fs::rename(&self.path, self.rotated_path(1))
+    }
+
+    fn open(path: &Path) -> Result<File, std::io::Error> {
+        OpenOptions::new().create(true).append(true).open(path)
+    }
+
+    fn try_append(&self, file: &mut Option<File>, line: &str) -> Result<(), std::io::Error> {
+        let f = match file.take() {
+            Some(f) => f,
+            None => Self::open(&self.path)?,
+        };
+        let len = f.metadata()?.len();
+        let mut f = if len > 0 && len.saturating_add(line.len() as u64) > self.max_file_size {
+            drop(f);
+            self.rotate()?;
+            Self::open(&self.path)?
+        } else {
+            f
+        };
+        f.write_all(line.as_bytes())?;
+        f.flush()?;
+        file.replace(f);
+        Ok(())
+    }
+}
+
+impl EventSink for FileEventSink {
+    fn send_payload(&self, payload: &serde_json::Value, path: &str) {
+        let line = make_payload_line(payload, path);
+        let mut file = self
+            .file
+            .lock()
+            .expect("FATAL: failed to lock event sink file mutex");
+
+        let mut backoff = Duration::from_millis(100);
+        let max_backoff = Duration::from_secs(5);
+        let mut attempts = 0i64;
+        while let Err(e) = self.try_append(&mut file, &line) {
+            warn!(
+                "Event dispatcher: failed to append payload to {}: {e:?}", self.path.display();
+                "backoff" => ?backoff,
+                "attempts" => attempts
+            );
+            sleep(backoff);
+            backoff = std::cmp::min(backoff.saturating_mul(2), max_backoff);
+            attempts = attempts.saturating_add(1);
+        }
+    }
+}
+
+/// Writes payloads as newline-delimited JSON to a Unix domain socket, which the consumer
+/// listens on.  The connection is kept open between payloads, and re-established if it fails.
+#[cfg(unix)]
+pub struct UnixSocketEventSink {
+    path: PathBuf,
+    timeout: Duration,
+    stream: Mutex<Option<UnixStream>>,
+}
+
+#[cfg(unix)]
+impl UnixSocketEventSink {
+    pub fn new(path: PathBuf, timeout: Duration) -> Self {
+        Self {
+            path,
+            timeout,
+            stream: Mutex::new(None),
+        }
+    }
+
+    fn try_send(&self, stream: &mut Option<UnixStream>, line: &str) -> Result<(), std::io::Error> {
+        let mut s = match stream.take() {
+            Some(s) => s,
+            None => {
+                let s = UnixStream::connect(&self.path)?;
+                s.set_write_timeout(Some(self.timeout))?;
+                s
+            }
+        };
+        s.write_all(line.as_bytes())?;
+        s.flush()?;
+        stream.replace(s);
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+impl EventSink for UnixSocketEventSink {
+    fn send_payload(&self, payload: &serde_json::Value, path: &str) {
+        let line = make_payload_line(payload, path);
+        let mut stream = self
+            .stream
+            .lock()
+            .expect("FATAL: failed to lock event sink socket mutex");
+
+        let mut backoff = Duration::from_millis(100);
+        // Cap the backoff at 3x the timeout
+        let max_backoff = self.timeout.saturating_mul(3);
+        let mut attempts = 0i64;
+        while let Err(e) = self.try_send(&mut stream, &line) {
+            warn!(
+                "Event dispatcher: failed to send payload to {}: {e:?}", self.path.display();
+                "backoff" => ?backoff,
+                "attempts" => attempts
+            );
+            sleep(backoff);
+            backoff = std::cmp::min(backoff.saturating_mul(2), max_backoff);
+            attempts = attempts.saturating_add(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{BufRead, BufReader};
+
+    use tempfile::tempdir;
+
+    use super::*;
+
+    fn read_lines(path: &Path) -> Vec<serde_json::Value> {
+        BufReader::new(File::open(path).unwrap())
+            .lines()
+            .map(|line| serde_json::from_str(&line.unwrap()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_file_sink_append_and_rotate() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("events.ndjson");
+
+        let line_len = make_payload_line(&json!({"n": 0}), "new_block").len() as u64;
+        // room for two payloads per file, and keep two old files
+        let sink = FileEventSink::new(path.clone(), 2 * line_len, 2);
+
+        for n in 0..7 {
+            sink.send_payload(&json!({ "n": n }), "/new_block");
+        }
+
+        let current = read_lines(&path);
+        assert_eq!(current.len(), 1);
+        assert_eq!(current[0]["path"], "new_block");
+        assert_eq!(current[0]["payload"]["n"], 6);
+
+        let rotated_1 = read_lines(&sink.rotated_path(1));
+        assert_eq!(rotated_1.len(), 2);
+        assert_eq!(rotated_1[0]["payload"]["n"], 4);
+        assert_eq!(rotated_1[1]["payload"]["n"], 5);
+
+        let rotated_2 = read_lines(&sink.rotated_path(2));
+        assert_eq!(rotated_2[0]["payload"]["n"], 2);
+
+        // the oldest file was discarded
+        assert!(!sink.rotated_path(3).exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unix_socket_sink() {
+        use std::os::unix::net::UnixListener;
+        use std::thread;
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("events.sock");
+        let listener = UnixListener::bind(&path).unwrap();
+
+        let reader = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            BufReader::new(stream)
+                .lines()
+                .take(2)
+                .map(|line| serde_json::from_str::<serde_json::Value>(&line.unwrap()).unwrap())
+                .collect::<Vec<_>>()
+        });
+
+        let sink = UnixSocketEventSink::new(path, Duration::from_secs(1));
+        sink.send_payload(&json!({"n": 1}), "new_burn_block");
+        sink.send_payload(&json!({"n": 2}), "new_mempool_tx");
+
+        let received = reader.join().unwrap();
+        assert_eq!(received[0]["path"], "new_burn_block");
+        assert_eq!(received[0]["payload"]["n"], 1);
+        assert_eq!(received[1]["path"], "new_mempool_tx");
+        assert_eq!(received[1]["payload"]["n"], 2);
+    }
+}
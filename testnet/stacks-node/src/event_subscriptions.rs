@@ -0,0 +1,335 @@
+// Copyright (C) 2020-2025 Stacks Open Internet Foundation
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Event subscriptions that RPC clients create at runtime.
+//!
+//! A subscription is like an event observer that the node does not push to.  Instead, the
+//! `EventDispatcher` queues each payload that the subscription's event keys select, and the
+//! client long-polls for them over the `/v3/events/subscriptions` endpoints.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use clarity::vm::events::{FTEventType, NFTEventType, STXEventType};
+use rand::Rng;
+use stacks::chainstate::stacks::events::StacksTransactionEvent;
+use stacks::net::api::getsubscriptionevents::{SubscriptionEvent, SubscriptionEventsResponse};
+use stacks::net::EventSubscriptionRegistry;
+use stacks::util::hash::to_hex;
+use stacks_common::util::get_epoch_time_secs;
+
+use crate::config::EventKeyType;
+
+/// A subscription that has not been polled in this many seconds is dropped
+pub const EVENT_SUBSCRIPTION_IDLE_TIMEOUT_SECS: u64 = 300;
+
+struct Subscription {
+    events_keys: HashSet<EventKeyType>,
+    events: VecDeque<SubscriptionEvent>,
+    next_seq: u64,
+    /// Number of events discarded because the queue was full
+    dropped: u64,
+    last_poll: u64,
+}
+
+impl Subscription {
+    fn push(&mut self, path: &str, payload: serde_json::Value, max_pending: usize) {
+        self.next_seq += 1;
+        self.events.push_back(SubscriptionEvent {
+            seq: self.next_seq,
+            path: path.to_string(),
+            payload,
+        });
+        while self.events.len() > max_pending {
+            self.events.pop_front();
+            self.dropped += 1;
+        }
+    }
+}
+
+struct InnerEventSubscriptions {
+    subscriptions: HashMap<String, Subscription>,
+    max_subscriptions: usize,
+    max_pending: usize,
+}
+
+impl InnerEventSubscriptions {
+    /// Drop subscriptions whose clients have gone away
+    fn prune_idle(&mut self, now: u64) {
+        self.subscriptions.retain(|subscription_id, subscription| {
+            let keep = subscription.last_poll + EVENT_SUBSCRIPTION_IDLE_TIMEOUT_SECS >= now;
+            if !keep {
+                info!("Dropping idle event subscription {subscription_id}");
+            }
+            keep
+        });
+    }
+}
+
+/// Thread-safe handle to the set of RPC event subscriptions.  Clones share the same
+/// subscriptions, so the `EventDispatcher` and the RPC server can each hold one.
+#[derive(Clone)]
+pub struct EventSubscriptions {
+    inner: Arc<Mutex<InnerEventSubscriptions>>,
+}
+
+impl EventSubscriptions {
+    pub fn new(max_subscriptions: usize, max_pending: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(InnerEventSubscriptions {
+                subscriptions: HashMap::new(),
+                max_subscriptions,
+                max_pending,
+            })),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, InnerEventSubscriptions> {
+        self.inner
+            .lock()
+            .expect("FATAL: failed to lock event subscriptions mutex")
+    }
+
+    /// Are there any open subscriptions?
+    pub fn is_empty(&self) -> bool {
+        self.lock().subscriptions.is_empty()
+    }
+
+    /// Queue an event for each subscription.  `make_payload` is called with each subscription's
+    /// event keys, and returns the payload to queue for it, or None if the subscription is not
+    /// interested in this event.
+    pub fn publish<F>(&self, path: &str, mut make_payload: F)
+    where
+        F: FnMut(&HashSet<EventKeyType>) -> Option<serde_json::Value>,
+    {
+        let mut inner = self.lock();
+        inner.prune_idle(get_epoch_time_secs());
+        let max_pending = inner.max_pending;
+        for subscription in inner.subscriptions.values_mut() {
+            if let Some(payload) = make_payload(&subscription.events_keys) {
+                subscription.push(path, payload, max_pending);
+            }
+        }
+    }
+
+    /// Queue `payload` for each subscription that has the event key `key`, or the "any" key if
+    /// `include_any` is true.
+    pub fn publish_to_key(
+        &self,
+        path: &str,
+        key: &EventKeyType,
+        include_any: bool,
+        payload: &serde_json::Value,
+    ) {
+        self.publish(path, |events_keys| {
+            if events_keys.contains(key)
+                || (include_any && events_keys.contains(&EventKeyType::AnyEvent))
+            {
+                Some(payload.clone())
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Would a subscription with these event keys receive this transaction event as part of a
+    /// block or microblock payload?  This mirrors how the `EventDispatcher` filters transaction
+    /// events for event observers.
+    pub fn event_matches(
+        events_keys: &HashSet<EventKeyType>,
+        event: &StacksTransactionEvent,
+    ) -> bool {
+        if events_keys.contains(&EventKeyType::AnyEvent) {
+            return true;
+        }
+        let asset_identifier = match event {
+            StacksTransactionEvent::SmartContractEvent(event_data) => {
+                return events_keys
+                    .contains(&EventKeyType::SmartContractEvent(event_data.key.clone()));
+            }
+            StacksTransactionEvent::STXEvent(STXEventType::STXTransferEvent(_))
+            | StacksTransactionEvent::STXEvent(STXEventType::STXMintEvent(_))
+            | StacksTransactionEvent::STXEvent(STXEventType::STXBurnEvent(_))
+            | StacksTransactionEvent::STXEvent(STXEventType::STXLockEvent(_)) => {
+                return events_keys.contains(&EventKeyType::STXEvent);
+            }
+            StacksTransactionEvent::NFTEvent(NFTEventType::NFTTransferEvent(event_data)) => {
+                &event_data.asset_identifier
+            }
+            StacksTransactionEvent::NFTEvent(NFTEventType::NFTMintEvent(event_data)) => {
+                &event_data.asset_identifier
+            }
+            StacksTransactionEvent::NFTEvent(NFTEventType::NFTBurnEvent(event_data)) => {
+                &event_data.asset_identifier
+            }
+            StacksTransactionEvent::FTEvent(FTEventType::FTTransferEvent(event_data)) => {
+                &event_data.asset_identifier
+            }
+            StacksTransactionEvent::FTEvent(FTEventType::FTMintEvent(event_data)) => {
+                &event_data.asset_identifier
+            }
+            StacksTransactionEvent::FTEvent(FTEventType::FTBurnEvent(event_data)) => {
+                &event_data.asset_identifier
+            }
+        };
+        events_keys.contains(&EventKeyType::AssetEvent(asset_identifier.clone()))
+    }
+}
+
+impl EventSubscriptionRegistry for EventSubscriptions {
+    fn subscribe(&self, events_keys: &[String]) -> Result<String, String> {
+        let mut parsed_keys = HashSet::new();
+        for raw_key in events_keys.iter() {
+            let key = EventKeyType::from_string(raw_key)
+                .ok_or_else(|| format!("Invalid event key: {raw_key}"))?;
+            parsed_keys.insert(key);
+        }
+
+        let now = get_epoch_time_secs();
+        let mut inner = self.lock();
+        inner.prune_idle(now);
+        if inner.subscriptions.len() >= inner.max_subscriptions {
+            return Err("Too many event subscriptions".to_string());
+        }
+
+        let subscription_id = to_hex(&rand::thread_rng().gen::<[u8; 16]>());
+        debug!("New event subscription {subscription_id}: {events_keys:?}");
+        inner.subscriptions.insert(
+            subscription_id.clone(),
+            Subscription {
+                events_keys: parsed_keys,
+                events: VecDeque::new(),
+                next_seq: 0,
+                dropped: 0,
+                last_poll: now,
+            },
+        );
+        Ok(subscription_id)
+    }
+
+    fn poll(
+        &self,
+        subscription_id: &str,
+        after: u64,
+        max_events: usize,
+    ) -> Option<SubscriptionEventsResponse> {
+        let mut inner = self.lock();
+        let subscription = inner.subscriptions.get_mut(subscription_id)?;
+        subscription.last_poll = get_epoch_time_secs();
+        while subscription
+            .events
+            .front()
+            .is_some_and(|event| event.seq <= after)
+        {
+            subscription.events.pop_front();
+        }
+        Some(SubscriptionEventsResponse {
+            subscription_id: subscription_id.to_string(),
+            events: subscription
+                .events
+                .iter()
+                .take(max_events)
+                .cloned()
+                .collect(),
+            dropped: subscription.dropped,
+        })
+    }
+
+    fn unsubscribe(&self, subscription_id: &str) -> bool {
+        self.lock().subscriptions.remove(subscription_id).is_some()
+    }
+
+    fn has_events(&self, subscription_id: &str, after: u64) -> bool {
+        self.lock()
+            .subscriptions
+            .get(subscription_id)
+            .map_or(true, |subscription| {
+                subscription
+                    .events
+                    .back()
+                    .is_some_and(|event| event.seq > after)
+            })
+    }
+
+    fn clone_registry(&self) -> Box<dyn EventSubscriptionRegistry + Send> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_subscribe_poll_unsubscribe() {
+        let subscriptions = EventSubscriptions::new(2, 3);
+        assert!(subscriptions.is_empty());
+
+        assert!(subscriptions.subscribe(&["not-a-key".to_string()]).is_err());
+
+        let mempool_id = subscriptions.subscribe(&["memtx".to_string()]).unwrap();
+        let any_id = subscriptions.subscribe(&["*".to_string()]).unwrap();
+        assert_ne!(mempool_id, any_id);
+        assert_eq!(mempool_id.len(), 32);
+
+        // at capacity
+        assert!(subscriptions.subscribe(&["stx".to_string()]).is_err());
+
+        subscriptions.publish_to_key(
+            "new_burn_block",
+            &EventKeyType::BurnchainBlocks,
+            true,
+            &json!({"burn_block_height": 1}),
+        );
+        for i in 0..4 {
+            subscriptions.publish_to_key(
+                "new_mempool_tx",
+                &EventKeyType::MemPoolTransactions,
+                true,
+                &json!([i]),
+            );
+        }
+
+        // only the "any" subscription got the burn block, and it was pushed out of the queue
+        let resp = subscriptions.poll(&any_id, 0, 10).unwrap();
+        assert_eq!(resp.dropped, 2);
+        assert_eq!(
+            resp.events.iter().map(|e| e.seq).collect::<Vec<_>>(),
+            vec![3, 4, 5]
+        );
+
+        let resp = subscriptions.poll(&mempool_id, 0, 2).unwrap();
+        assert_eq!(resp.dropped, 1);
+        assert_eq!(resp.events.len(), 2);
+        assert_eq!(resp.events[0].seq, 2);
+        assert_eq!(resp.events[0].path, "new_mempool_tx");
+        assert_eq!(resp.events[0].payload, json!([1]));
+
+        // acknowledging events removes them
+        let resp = subscriptions.poll(&mempool_id, 3, 10).unwrap();
+        assert_eq!(resp.events.len(), 1);
+        assert_eq!(resp.events[0].seq, 4);
+
+        assert!(subscriptions.has_events(&mempool_id, 3));
+        assert!(!subscriptions.has_events(&mempool_id, 4));
+
+        assert!(subscriptions.unsubscribe(&mempool_id));
+        assert!(!subscriptions.unsubscribe(&mempool_id));
+        assert!(subscriptions.poll(&mempool_id, 0, 10).is_none());
+        assert!(subscriptions.has_events(&mempool_id, 0));
+    }
+}
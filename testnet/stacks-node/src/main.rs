@@ -19,6 +19,8 @@ pub mod burnchains;
 pub mod chain_data;
 pub mod config;
 pub mod event_dispatcher;
+pub mod event_sink;
+pub mod event_subscriptions;
 pub mod genesis_data;
 pub mod globals;
 pub mod keychain;
@@ -41,6 +43,7 @@ use stacks::chainstate::coordinator::{get_next_recipients, OnChainRewardSetProvi
 use stacks::chainstate::stacks::address::PoxAddress;
 use stacks::chainstate::stacks::db::blocks::DummyEventDispatcher;
 use stacks::chainstate::stacks::db::StacksChainState;
+use stacks::cli::replay_events;
 #[cfg(not(any(target_os = "macos", target_os = "windows", target_arch = "arm")))]
 use tikv_jemallocator::Jemalloc;
 
@@ -54,6 +57,7 @@ pub use self::node::{ChainTip, Node};
 pub use self::run_loop::{helium, neon};
 pub use self::tenure::Tenure;
 use crate::chain_data::MinerStats;
+use crate::event_dispatcher::ReplayEventDispatcher;
 use crate::neon_node::{BlockMinerThread, TipCandidate};
 use crate::run_loop::boot_nakamoto;
 
@@ -252,6 +256,61 @@ fn cli_get_miner_spend(
     spend_amount
 }
 
+/// Implementation of `replay-events` CLI option
+fn cli_replay_events(
+    config_path: &str,
+    observer_endpoint: &str,
+    first_burn_height: u64,
+    last_burn_height: u64,
+) {
+    info!("Loading config at path {config_path}");
+    let config = match ConfigFile::from_path(config_path) {
+        Ok(config_file) => Config::from_config_file(config_file, true).unwrap(),
+        Err(e) => {
+            warn!("Invalid config file: {e}");
+            process::exit(1);
+        }
+    };
+    let Some(observer) = config
+        .events_observers
+        .iter()
+        .find(|observer| observer.endpoint == observer_endpoint)
+    else {
+        warn!("No event observer with endpoint {observer_endpoint} in {config_path}");
+        process::exit(1);
+    };
+
+    // Replayed payloads are not stored in the working directory, since a node running from it
+    // would deliver them too.
+    let mut event_dispatcher = EventDispatcher::new();
+    event_dispatcher.register_observer(observer, None);
+
+    let burn_db_path = config.get_burn_db_file_path();
+    let stacks_chainstate_path = config.get_chainstate_path_str();
+    let burnchain = config.get_burnchain();
+    let (mut chainstate, _) = StacksChainState::open(
+        config.is_mainnet(),
+        config.burnchain.chain_id,
+        &stacks_chainstate_path,
+        Some(config.node.get_marf_opts()),
+    )
+    .unwrap();
+    let mut sortdb =
+        SortitionDB::open(&burn_db_path, true, burnchain.pox_constants.clone()).unwrap();
+
+    if let Err(e) = replay_events(
+        &mut sortdb,
+        &mut chainstate,
+        &burnchain,
+        first_burn_height,
+        last_burn_height,
+        &ReplayEventDispatcher::new(&event_dispatcher),
+    ) {
+        warn!("Failed to replay events: {e:?}");
+        process::exit(1);
+    }
+}
+
 fn main() {
     panic::set_hook(Box::new(|panic_info| {
         error!("Process abort due to thread panic: {panic_info}");
@@ -386,6 +445,17 @@ fn main() {
             println!("Best tip is {best_tip:?}");
             process::exit(0);
         }
+        "replay-events" => {
+            let config_path: String = args.value_from_str("--config").unwrap();
+            let observer: String = args.value_from_str("--observer").unwrap();
+            let first_burn_height: u64 = args.value_from_str("--first-burn-height").unwrap();
+            let last_burn_height: u64 = args.value_from_str("--last-burn-height").unwrap();
+            args.finish();
+
+            cli_replay_events(&config_path, &observer, first_burn_height, last_burn_height);
+            info!("Finished replaying events to {observer}");
+            process::exit(0);
+        }
         "get-spend-amount" => {
             let config_path: String = args.value_from_str("--config").unwrap();
             let at_burnchain_height: Option<u64> =
@@ -485,6 +555,14 @@ key-for-seed\tOutput the associated secret key for a burnchain signer created wi
 \t\tCan be passed a config file for the seed via the `--config <file>` option *or* by supplying the hex seed on
 \t\tthe command line directly.
 
+replay-events\tRe-send the events for a range of stored burnchain blocks, and the Stacks blocks
+\t\tthey elected, to one of the event observers in the config file.
+\t\tArguments:
+\t\t  --config: path to the config file
+\t\t  --observer: endpoint of the `[[events_observer]]` to send the events to
+\t\t  --first-burn-height: first burnchain block height to replay
+\t\t  --last-burn-height: last burnchain block height to replay
+
 replay-mock-mining\tReplay mock mined blocks from <dir>
 \t\tArguments:
 \t\t  --path: path to directory of mock mined blocks
@@ -28,7 +28,7 @@ use stacks::cost_estimates::metrics::{CostMetric, UnitMetric};
 use stacks::cost_estimates::{CostEstimator, FeeEstimator, UnitEstimator};
 use stacks::net::dns::{DNSClient, DNSResolver};
 use stacks::net::p2p::PeerNetwork;
-use stacks::net::RPCHandlerArgs;
+use stacks::net::{EventSubscriptionRegistry, RPCHandlerArgs};
 use stacks_common::util::hash::Sha256Sum;
 
 use crate::burnchains::make_bitcoin_indexer;
@@ -274,6 +274,9 @@ impl PeerThread {
                 cost_metric: Some(cost_metric.as_ref()),
                 fee_estimator: fee_estimator.map(|boxed_estimator| boxed_estimator.as_ref()),
                 coord_comms: Some(&self.globals.coord_comms),
+                event_subscriptions: event_dispatcher
+                    .event_subscriptions()
+                    .map(|subscriptions| subscriptions as &dyn EventSubscriptionRegistry),
             };
             self.net.run(
                 indexer,
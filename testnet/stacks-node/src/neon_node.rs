@@ -199,7 +199,8 @@ use stacks::net::p2p::PeerNetwork;
 use stacks::net::relay::Relayer;
 use stacks::net::stackerdb::{StackerDBConfig, StackerDBSync, StackerDBs, MINER_SLOT_COUNT};
 use stacks::net::{
-    Error as NetError, NetworkResult, PeerNetworkComms, RPCHandlerArgs, ServiceFlags,
+    Error as NetError, EventSubscriptionRegistry, NetworkResult, PeerNetworkComms, RPCHandlerArgs,
+    ServiceFlags,
 };
 use stacks::util_lib::strings::{UrlString, VecDisplay};
 use stacks::{monitoring, version_string};
@@ -360,6 +361,8 @@ pub(crate) fn open_chainstate_with_faults(
     )?;
 
     chainstate.fault_injection.hide_blocks = config.node.fault_injection_hide_blocks;
+    chainstate.txindex = config.node.txindex;
+    chainstate.event_index = config.node.event_index;
     Ok(chainstate)
 }
 
@@ -4340,14 +4343,17 @@ impl PeerThread {
             .make_cost_metric()
             .unwrap_or_else(|| Box::new(UnitMetric));
 
-        MemPoolDB::open(
+        let mut mempool = MemPoolDB::open(
             config.is_mainnet(),
             config.burnchain.chain_id,
             &config.get_chainstate_path_str(),
             cost_estimator,
             metric,
         )
-        .expect("Database failure opening mempool")
+        .expect("Database failure opening mempool");
+        mempool.limits = config.node.get_mempool_limits();
+        mempool.set_admission_policy(config.node.get_mempool_admission_policy());
+        mempool
     }
 
     /// Instantiate the p2p thread.
@@ -4499,6 +4505,9 @@ impl PeerThread {
                 cost_estimator: Some(cost_estimator.as_ref()),
                 cost_metric: Some(cost_metric.as_ref()),
                 fee_estimator: fee_estimator.map(|boxed_estimator| boxed_estimator.as_ref()),
+                event_subscriptions: event_dispatcher
+                    .event_subscriptions()
+                    .map(|subscriptions| subscriptions as &dyn EventSubscriptionRegistry),
                 ..RPCHandlerArgs::default()
             };
             p2p_thread.with_network(|_, net| {
@@ -261,7 +261,13 @@ impl RunLoop {
 
         let mut event_dispatcher = EventDispatcher::new();
         for observer in config.events_observers.iter() {
-            event_dispatcher.register_observer(observer, config.get_working_dir());
+            event_dispatcher.register_observer(observer, Some(config.get_working_dir()));
+        }
+        if config.node.max_event_subscriptions > 0 {
+            event_dispatcher.enable_subscriptions(
+                config.node.max_event_subscriptions,
+                config.node.event_subscription_max_pending,
+            );
         }
 
         Self {
@@ -570,7 +576,7 @@ impl RunLoop {
         };
 
         info!("About to call open_and_exec");
-        let (chain_state_db, receipts) = StacksChainState::open_and_exec(
+        let (mut chain_state_db, receipts) = StacksChainState::open_and_exec(
             self.config.is_mainnet(),
             self.config.burnchain.chain_id,
             &self.config.get_chainstate_path_str(),
@@ -578,6 +584,8 @@ impl RunLoop {
             Some(self.config.node.get_marf_opts()),
         )
         .unwrap();
+        chain_state_db.txindex = self.config.node.txindex;
+        chain_state_db.event_index = self.config.node.event_index;
         run_loop::announce_boot_receipts(
             &mut self.event_dispatcher,
             &chain_state_db,
@@ -83,7 +83,9 @@ use super::{
     SK_2, SK_3,
 };
 use crate::burnchains::bitcoin_regtest_controller::{self, addr2str, BitcoinRPCRequest, UTXO};
-use crate::config::{EventKeyType, EventObserverConfig, FeeEstimatorName, InitialBalance};
+use crate::config::{
+    EventKeyType, EventObserverConfig, EventSinkType, FeeEstimatorName, InitialBalance,
+};
 use crate::neon_node::RelayerThread;
 use crate::operations::BurnchainOpSigner;
 use crate::stacks_common::types::PrivateKey;
@@ -205,7 +207,7 @@ pub mod test_observer {
     use warp::Filter;
     use {tokio, warp};
 
-    use crate::config::{EventKeyType, EventObserverConfig};
+    use crate::config::{EventKeyType, EventObserverConfig, EventSinkType};
     use crate::event_dispatcher::{MinedBlockEvent, MinedMicroblockEvent, MinedNakamotoBlockEvent};
     use crate::Config;
 
@@ -220,6 +222,7 @@ pub mod test_observer {
     pub static BURN_BLOCKS: Mutex<Vec<serde_json::Value>> = Mutex::new(Vec::new());
     pub static MEMTXS: Mutex<Vec<String>> = Mutex::new(Vec::new());
     pub static MEMTXS_DROPPED: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+    pub static MEMTXS_STUCK: Mutex<Vec<serde_json::Value>> = Mutex::new(Vec::new());
     pub static ATTACHMENTS: Mutex<Vec<serde_json::Value>> = Mutex::new(Vec::new());
     pub static PROPOSAL_RESPONSES: Mutex<Vec<BlockValidateResponse>> = Mutex::new(Vec::new());
     pub static STACKER_SETS: Mutex<Vec<(StacksBlockId, u64, RewardSet)>> = Mutex::new(Vec::new());
@@ -421,6 +424,17 @@ pub mod test_observer {
         Ok(warp::http::StatusCode::OK)
     }
 
+    async fn handle_mempool_stuck_txs(
+        txs: serde_json::Value,
+    ) -> Result<impl warp::Reply, Infallible> {
+        let stuck_txs = txs.get("stuck_txs").unwrap().as_array().unwrap();
+        let mut memtxs = MEMTXS_STUCK.lock().unwrap();
+        for stuck_tx in stuck_txs {
+            memtxs.push(stuck_tx.clone());
+        }
+        Ok(warp::http::StatusCode::OK)
+    }
+
     async fn handle_attachments(
         attachments: serde_json::Value,
     ) -> Result<impl warp::Reply, Infallible> {
@@ -444,6 +458,10 @@ pub mod test_observer {
         MEMTXS_DROPPED.lock().unwrap().clone()
     }
 
+    pub fn get_memtx_stuck() -> Vec<serde_json::Value> {
+        MEMTXS_STUCK.lock().unwrap().clone()
+    }
+
     pub fn get_blocks() -> Vec<serde_json::Value> {
         NEW_BLOCKS.lock().unwrap().clone()
     }
@@ -494,6 +512,10 @@ pub mod test_observer {
             .and(warp::post())
             .and(warp::body::json())
             .and_then(handle_mempool_drop_txs);
+        let mempool_stuck_txs = warp::path!("stuck_mempool_tx")
+            .and(warp::post())
+            .and(warp::body::json())
+            .and_then(handle_mempool_stuck_txs);
         let new_burn_blocks = warp::path!("new_burn_block")
             .and(warp::post())
             .and(warp::body::json())
@@ -536,6 +558,7 @@ pub mod test_observer {
             new_blocks
                 .or(mempool_txs)
                 .or(mempool_drop_txs)
+                .or(mempool_stuck_txs)
                 .or(new_burn_blocks)
                 .or(new_attachments)
                 .or(new_microblocks)
@@ -575,6 +598,7 @@ pub mod test_observer {
         BURN_BLOCKS.lock().unwrap().clear();
         MEMTXS.lock().unwrap().clear();
         MEMTXS_DROPPED.lock().unwrap().clear();
+        MEMTXS_STUCK.lock().unwrap().clear();
         ATTACHMENTS.lock().unwrap().clear();
         PROPOSAL_RESPONSES.lock().unwrap().clear();
     }
@@ -639,6 +663,7 @@ pub mod test_observer {
             endpoint: format!("localhost:{EVENT_OBSERVER_PORT}"),
             events_keys: event_keys.to_vec(),
             timeout_ms: 1000,
+            sink: EventSinkType::Http,
         });
     }
 
@@ -7492,6 +7517,7 @@ fn atlas_integration_test() {
             endpoint: format!("localhost:{}", test_observer::EVENT_OBSERVER_PORT),
             events_keys: vec![EventKeyType::AnyEvent],
             timeout_ms: 1000,
+            sink: EventSinkType::Http,
         });
 
     conf_follower_node.node.always_use_affirmation_maps = false;
@@ -8026,6 +8052,7 @@ fn antientropy_integration_test() {
             endpoint: format!("localhost:{}", test_observer::EVENT_OBSERVER_PORT),
             events_keys: vec![EventKeyType::AnyEvent],
             timeout_ms: 1000,
+            sink: EventSinkType::Http,
         });
 
     conf_follower_node.node.mine_microblocks = true;
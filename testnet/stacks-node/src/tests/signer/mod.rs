@@ -61,7 +61,9 @@ use stacks_signer::runloop::{SignerResult, State, StateInfo};
 use stacks_signer::{Signer, SpawnedSigner};
 
 use super::nakamoto_integrations::{check_nakamoto_empty_block_heuristics, wait_for};
-use crate::config::{Config as NeonConfig, EventKeyType, EventObserverConfig, InitialBalance};
+use crate::config::{
+    Config as NeonConfig, EventKeyType, EventObserverConfig, EventSinkType, InitialBalance,
+};
 use crate::neon::{Counters, RunLoopCounter, TestFlag};
 use crate::run_loop::boot_nakamoto;
 use crate::tests::bitcoin_regtest::BitcoinCoreController;
@@ -702,6 +704,7 @@ fn setup_stx_btc_node<G: FnMut(&mut NeonConfig)>(
                 EventKeyType::BurnchainBlocks,
             ],
             timeout_ms: 1000,
+            sink: EventSinkType::Http,
         });
     }
 
@@ -717,6 +720,7 @@ fn setup_stx_btc_node<G: FnMut(&mut NeonConfig)>(
             EventKeyType::BurnchainBlocks,
         ],
         timeout_ms: 1000,
+        sink: EventSinkType::Http,
     });
 
     // The signers need some initial balances in order to pay for epoch 2.5 transaction votes